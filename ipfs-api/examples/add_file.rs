@@ -8,13 +8,11 @@
 
 #[cfg(feature = "actix")]
 extern crate actix_web;
-extern crate futures;
 #[cfg(feature = "hyper")]
 extern crate hyper;
 extern crate ipfs_api;
 
-use futures::Future;
-use ipfs_api::IpfsClient;
+use ipfs_api::prelude::*;
 use std::fs::File;
 
 // Creates an Ipfs client, and adds this source file to Ipfs.
@@ -31,11 +31,21 @@ fn main() {
         println!("  data   sent: {}", bitswap_stats.data_sent);
         println!(
             "  peers:       {}",
-            bitswap_stats.peers.join("\n               ")
+            bitswap_stats
+                .peers
+                .iter()
+                .map(|peer| peer.as_str())
+                .collect::<Vec<_>>()
+                .join("\n               ")
         );
         println!(
             "  wantlist:    {}",
-            bitswap_stats.wantlist.join("\n               ")
+            bitswap_stats
+                .wantlist
+                .iter()
+                .map(|cid| cid.to_string())
+                .collect::<Vec<_>>()
+                .join("\n               ")
         );
         println!();
     });
@@ -8,13 +8,11 @@
 
 #[cfg(feature = "actix")]
 extern crate actix_web;
-extern crate futures;
 #[cfg(feature = "hyper")]
 extern crate hyper;
 extern crate ipfs_api;
 
-use futures::Future;
-use ipfs_api::IpfsClient;
+use ipfs_api::prelude::*;
 
 // Creates an Ipfs client, and gets the version of the Ipfs server.
 //
@@ -30,11 +30,7 @@ fn main() {
     let req = client
         .swarm_peers()
         .and_then(move |connected| {
-            let peer = connected
-                .peers
-                .iter()
-                .next()
-                .expect("expected at least one peer");
+            let peer = connected.peers.first().expect("expected at least one peer");
 
             println!();
             println!("discovered peer ({})", peer.peer);
@@ -22,7 +22,7 @@ use std::{
 };
 use tokio_timer::Interval;
 
-static TOPIC: &'static str = "test";
+static TOPIC: &str = "test";
 
 fn get_client() -> IpfsClient {
     println!("connecting to localhost:5001...");
@@ -0,0 +1,371 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use response::Error;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::{fmt, ops::Deref, slice, str::FromStr, str::Split};
+
+/// Protocols that are not followed by a value.
+///
+const VALUELESS: &[&str] = &[
+    "http",
+    "https",
+    "noise",
+    "p2p-circuit",
+    "p2p-stardust",
+    "p2p-webrtc-direct",
+    "p2p-webrtc-star",
+    "p2p-websocket-star",
+    "quic",
+    "quic-v1",
+    "tls",
+    "udt",
+    "utp",
+    "webrtc",
+    "webrtc-direct",
+    "webtransport",
+    "ws",
+    "wss",
+];
+
+/// Protocols, other than the ones with their own `Protocol` variant, that
+/// are followed by a single value.
+///
+const WITH_VALUE: &[&str] = &[
+    "certhash",
+    "dccp",
+    "garlic32",
+    "garlic64",
+    "http-path",
+    "ip6zone",
+    "ipcidr",
+    "memory",
+    "onion",
+    "onion3",
+    "sctp",
+    "sni",
+];
+
+/// One component of a `Multiaddr`.
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Ip4(Ipv4Addr),
+    Ip6(Ipv6Addr),
+    Dns(String),
+    Dns4(String),
+    Dns6(String),
+    Dnsaddr(String),
+    Tcp(u16),
+    Udp(u16),
+
+    /// A peer id. The legacy `/ipfs/` name is parsed as this as well.
+    P2p(String),
+
+    /// A unix socket path, which takes up the rest of the address.
+    Unix(String),
+
+    /// Any other protocol, by name, with its value if it takes one.
+    ///
+    /// Protocols this crate doesn't know about are kept without a value, so
+    /// newer daemons don't break parsing; any value they take shows up as
+    /// its own component.
+    Other(String, Option<String>),
+}
+
+/// A parsed multiaddr, as returned by the swarm, bootstrap and dht apis.
+///
+/// The address keeps the string the daemon returned, and derefs to it.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::{Multiaddr, Protocol};
+///
+/// # fn main() {
+/// let addr: Multiaddr = "/ip4/104.131.131.82/tcp/4001/ipfs/QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(addr.iter().nth(1), Some(&Protocol::Tcp(4001)));
+/// assert_eq!(addr.peer_id(), Some("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"));
+/// assert_eq!(addr.socket_addr(), Some("104.131.131.82:4001".parse().unwrap()));
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Multiaddr {
+    addr: String,
+    components: Vec<Protocol>,
+}
+
+impl Multiaddr {
+    /// The address as a string, as it was parsed.
+    ///
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.addr
+    }
+
+    /// Iterates over the components of the address.
+    ///
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Protocol> {
+        self.components.iter()
+    }
+
+    /// The peer id at the end of the address, if there is one.
+    ///
+    pub fn peer_id(&self) -> Option<&str> {
+        match self.components.last() {
+            Some(Protocol::P2p(ref peer)) => Some(peer),
+            _ => None,
+        }
+    }
+
+    /// The ip address and tcp or udp port the address starts with, if it
+    /// starts with one.
+    ///
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        let ip = match self.components.first() {
+            Some(Protocol::Ip4(ip)) => IpAddr::V4(*ip),
+            Some(Protocol::Ip6(ip)) => IpAddr::V6(*ip),
+            _ => return None,
+        };
+
+        match self.components.get(1) {
+            Some(Protocol::Tcp(port)) | Some(Protocol::Udp(port)) => {
+                Some(SocketAddr::new(ip, *port))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Multiaddr {
+    type Item = &'a Protocol;
+
+    type IntoIter = slice::Iter<'a, Protocol>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Takes the value following the protocol `name`.
+///
+fn next_value<'a>(parts: &mut Split<'a, char>, addr: &str, name: &str) -> Result<&'a str, Error> {
+    match parts.next() {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(Error::InvalidMultiaddr(
+            addr.into(),
+            format!("missing value for '{}'", name),
+        )),
+    }
+}
+
+fn parse_value<T>(value: &str, addr: &str, name: &str) -> Result<T, Error>
+where
+    T: FromStr,
+{
+    value
+        .parse()
+        .map_err(|_| Error::InvalidMultiaddr(addr.into(), format!("invalid value for '{}'", name)))
+}
+
+impl FromStr for Multiaddr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Multiaddr, Error> {
+        if !s.starts_with('/') || s.len() == 1 {
+            return Err(Error::InvalidMultiaddr(
+                s.into(),
+                "must start with '/'".into(),
+            ));
+        }
+
+        let mut parts = s[1..].split('/');
+        let mut components = Vec::new();
+
+        while let Some(name) = parts.next() {
+            let component = match name {
+                "ip4" => Protocol::Ip4(parse_value(next_value(&mut parts, s, name)?, s, name)?),
+                "ip6" => Protocol::Ip6(parse_value(next_value(&mut parts, s, name)?, s, name)?),
+                "dns" => Protocol::Dns(next_value(&mut parts, s, name)?.into()),
+                "dns4" => Protocol::Dns4(next_value(&mut parts, s, name)?.into()),
+                "dns6" => Protocol::Dns6(next_value(&mut parts, s, name)?.into()),
+                "dnsaddr" => Protocol::Dnsaddr(next_value(&mut parts, s, name)?.into()),
+                "tcp" => Protocol::Tcp(parse_value(next_value(&mut parts, s, name)?, s, name)?),
+                "udp" => Protocol::Udp(parse_value(next_value(&mut parts, s, name)?, s, name)?),
+                "p2p" | "ipfs" => Protocol::P2p(next_value(&mut parts, s, name)?.into()),
+                "unix" => {
+                    let path: Vec<&str> = parts.by_ref().collect();
+
+                    if path.is_empty() {
+                        next_value(&mut parts, s, name)?;
+                    }
+
+                    Protocol::Unix(format!("/{}", path.join("/")))
+                }
+                name if VALUELESS.contains(&name) => Protocol::Other(name.into(), None),
+                name if WITH_VALUE.contains(&name) => {
+                    let value = next_value(&mut parts, s, name)?;
+
+                    Protocol::Other(name.into(), Some(value.into()))
+                }
+                "" => {
+                    return Err(Error::InvalidMultiaddr(
+                        s.into(),
+                        "empty protocol name".into(),
+                    ));
+                }
+                name => Protocol::Other(name.into(), None),
+            };
+
+            components.push(component);
+        }
+
+        Ok(Multiaddr {
+            addr: s.into(),
+            components,
+        })
+    }
+}
+
+impl fmt::Display for Multiaddr {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.addr)
+    }
+}
+
+impl Deref for Multiaddr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        &self.addr
+    }
+}
+
+impl AsRef<str> for Multiaddr {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.addr
+    }
+}
+
+impl From<Multiaddr> for String {
+    #[inline]
+    fn from(addr: Multiaddr) -> String {
+        addr.addr
+    }
+}
+
+impl PartialEq<str> for Multiaddr {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.addr == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Multiaddr {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.addr == *other
+    }
+}
+
+impl Serialize for Multiaddr {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.addr)
+    }
+}
+
+impl<'de> Deserialize<'de> for Multiaddr {
+    fn deserialize<D>(deserializer: D) -> Result<Multiaddr, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Multiaddr, Protocol};
+
+    #[test]
+    fn test_parse() {
+        let addr: Multiaddr =
+            "/ip6/::1/udp/4001/quic-v1/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            addr.iter().cloned().collect::<Vec<_>>(),
+            vec![
+                Protocol::Ip6("::1".parse().unwrap()),
+                Protocol::Udp(4001),
+                Protocol::Other("quic-v1".into(), None),
+                Protocol::P2p("12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN".into()),
+            ]
+        );
+        assert_eq!(addr.socket_addr(), Some("[::1]:4001".parse().unwrap()));
+
+        let addr: Multiaddr = "/dnsaddr/bootstrap.libp2p.io".parse().unwrap();
+
+        assert_eq!(addr.socket_addr(), None);
+        assert_eq!(addr.peer_id(), None);
+
+        let addr: Multiaddr = "/unix/var/run/ipfs.sock".parse().unwrap();
+
+        assert_eq!(
+            addr.iter().next(),
+            Some(&Protocol::Unix("/var/run/ipfs.sock".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".parse::<Multiaddr>().is_err());
+        assert!("/".parse::<Multiaddr>().is_err());
+        assert!("ip4/127.0.0.1".parse::<Multiaddr>().is_err());
+        assert!("/ip4/127.0.0.256".parse::<Multiaddr>().is_err());
+        assert!("/ip4/127.0.0.1/tcp".parse::<Multiaddr>().is_err());
+        assert!("/ip4/127.0.0.1/tcp/65536".parse::<Multiaddr>().is_err());
+        assert!("/ip4/127.0.0.1/tcp/4001/".parse::<Multiaddr>().is_err());
+        assert!("/ip4/127.0.0.1//tcp/4001".parse::<Multiaddr>().is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_protocol() {
+        let addr: Multiaddr = "/ip4/127.0.0.1/udp/4001/webrtc-direct-v2/p2p/QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            addr.iter().nth(2),
+            Some(&Protocol::Other("webrtc-direct-v2".into(), None))
+        );
+        assert_eq!(
+            addr.peer_id(),
+            Some("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ")
+        );
+        assert_eq!(addr.socket_addr(), Some("127.0.0.1:4001".parse().unwrap()));
+    }
+}
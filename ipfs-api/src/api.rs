@@ -0,0 +1,2173 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+#[cfg(feature = "swarm")]
+use addr::Multiaddr;
+use bandwidth::BandwidthSample;
+use bytes::Bytes;
+use cid::{Cid, ToCid};
+use client::{AsyncResponse, AsyncStreamResponse, IpfsClient};
+#[cfg(feature = "object")]
+use dagpb::DagPbNode;
+use futures::Stream;
+use monitor::{DaemonMonitor, DaemonStatusWatch};
+use path::ToIpfsPath;
+#[cfg(feature = "files")]
+use path::ToMfsPath;
+#[cfg(feature = "key")]
+use peer::PeerId;
+use peer::ToPeerId;
+#[cfg(feature = "swarm")]
+use peers::PeerEvent;
+#[cfg(feature = "pin")]
+use pinset::PinsetReport;
+use progress::Progress;
+#[cfg(feature = "pubsub")]
+use pubsub::{PubsubMessages, PubsubPublisher, PubsubRouter};
+use request;
+use response::{self, ResponseMetadata};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, Read},
+    path::Path,
+    time::Duration,
+};
+use tree::AddTreeOptions;
+use unpack::OverwritePolicy;
+#[cfg(feature = "object")]
+use walk::WalkNode;
+
+/// The set of Ipfs API endpoints.
+///
+/// `IpfsClient` implements this trait by making requests against a daemon.
+/// Code written against `IpfsApi` instead of `IpfsClient` can be exercised
+/// with a fake implementation in tests.
+///
+/// ```no_run
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::{IpfsApi, IpfsClient};
+///
+/// fn daemon_version<A: IpfsApi>(api: &A) {
+///     let req = api.version();
+/// }
+///
+/// # fn main() {
+/// daemon_version(&IpfsClient::default());
+/// # }
+/// ```
+///
+pub trait IpfsApi {
+    /// Add a file to IPFS.
+    ///
+    fn add<R>(&self, data: R) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Serializes a value as JSON and adds it to Ipfs, returning its CID.
+    ///
+    fn add_json<T>(&self, value: &T) -> AsyncResponse<Cid>
+    where
+        T: Serialize;
+
+    /// Add a file to IPFS, streaming its contents without blocking.
+    ///
+    fn add_stream<S>(&self, data: S) -> AsyncResponse<response::AddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send;
+
+    /// Add a file to IPFS with options.
+    ///
+    fn add_with_options<R>(
+        &self,
+        data: R,
+        add: &request::Add,
+    ) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Add several files to IPFS in a single request.
+    ///
+    fn add_many<I, N, R>(&self, files: I) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send;
+
+    /// Add several files to IPFS in a single request, with options.
+    ///
+    fn add_many_with_options<I, N, R>(
+        &self,
+        files: I,
+        add: &request::Add,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send;
+
+    /// Add a path to Ipfs. Can be a file or directory.
+    ///
+    fn add_path<P>(&self, path: P) -> AsyncResponse<response::AddResponse>
+    where
+        P: AsRef<Path>;
+
+    /// Add a directory to Ipfs, keeping its structure. Resolves to an entry
+    /// for every file and directory added, ending with the directory itself.
+    ///
+    fn add_tree<P>(&self, path: P) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>;
+
+    /// Add a directory to Ipfs with options for which files are included,
+    /// and how symlinks are added.
+    ///
+    fn add_tree_with_options<P>(
+        &self,
+        path: P,
+        options: &AddTreeOptions,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>;
+
+    /// Returns the current ledger for a peer.
+    ///
+    fn bitswap_ledger<P>(&self, peer: P) -> AsyncResponse<response::BitswapLedgerResponse>
+    where
+        P: ToPeerId;
+
+    /// Triggers a reprovide.
+    ///
+    fn bitswap_reprovide(&self) -> AsyncResponse<response::BitswapReprovideResponse>;
+
+    /// Returns some stats about the bitswap agent.
+    ///
+    fn bitswap_stat(&self) -> AsyncResponse<response::BitswapStatResponse>;
+
+    fn bitswap_stat_with_options(
+        &self,
+        stat: &request::BitswapStat,
+    ) -> AsyncResponse<response::BitswapStatResponse>;
+
+    /// Remove a given block from your wantlist.
+    ///
+    fn bitswap_unwant(&self, key: &str) -> AsyncResponse<response::BitswapUnwantResponse>;
+
+    /// Shows blocks on the wantlist for you or the specified peer.
+    ///
+    fn bitswap_wantlist(
+        &self,
+        peer: Option<&str>,
+    ) -> AsyncResponse<response::BitswapWantlistResponse>;
+
+    /// Gets a raw IPFS block.
+    ///
+    fn block_get<C>(&self, cid: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid;
+
+    /// Gets many raw IPFS blocks, with at most `parallelism` requests open
+    /// at the same time.
+    ///
+    fn block_get_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, response::Error>)>
+    where
+        I: IntoIterator<Item = Cid>;
+
+    /// Gets a raw IPFS block, calling `callback` with the progress of the
+    /// download as each chunk is received.
+    ///
+    fn block_get_with_progress<C, F>(&self, cid: C, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+        F: 'static + FnMut(Progress) + Send;
+
+    /// Store input as an IPFS block.
+    ///
+    fn block_put<R>(&self, data: R) -> AsyncResponse<response::BlockPutResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Store a stream of bytes as an IPFS block.
+    ///
+    fn block_put_stream<S>(&self, data: S) -> AsyncResponse<response::BlockPutResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send;
+
+    /// Removes an IPFS block.
+    ///
+    fn block_rm<C>(&self, cid: C) -> AsyncResponse<response::BlockRmResponse>
+    where
+        C: ToCid;
+
+    /// Prints information about a raw IPFS block.
+    ///
+    fn block_stat<C>(&self, cid: C) -> AsyncResponse<response::BlockStatResponse>
+    where
+        C: ToCid;
+
+    /// Add default peers to the bootstrap list.
+    ///
+    fn bootstrap_add_default(&self) -> AsyncResponse<response::BootstrapAddDefaultResponse>;
+
+    /// Lists peers in bootstrap list.
+    ///
+    fn bootstrap_list(&self) -> AsyncResponse<response::BootstrapListResponse>;
+
+    /// Removes all peers in bootstrap list.
+    ///
+    fn bootstrap_rm_all(&self) -> AsyncResponse<response::BootstrapRmAllResponse>;
+
+    /// Returns the contents of an Ipfs object.
+    ///
+    fn cat<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the whole contents of an Ipfs object, failing once more than
+    /// `max_size` bytes are received.
+    ///
+    fn cat_bytes<P>(&self, path: P, max_size: Option<usize>) -> AsyncResponse<Vec<u8>>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the whole contents of an Ipfs object as a string.
+    ///
+    fn cat_string<P>(&self, path: P) -> AsyncResponse<String>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the contents of an Ipfs object, deserialized from JSON.
+    ///
+    fn cat_json<T, P>(&self, path: P) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+        P: ToIpfsPath;
+
+    /// Returns the contents of many Ipfs objects, with at most
+    /// `parallelism` requests open at the same time.
+    ///
+    fn cat_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, response::Error>)>
+    where
+        I: IntoIterator<Item = Cid>;
+
+    /// Returns `length` bytes of an Ipfs object, starting `offset` bytes in.
+    ///
+    fn cat_range<P>(&self, path: P, offset: u64, length: u64) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the contents of an Ipfs object, downloaded as consecutive
+    /// ranges of `range_size` bytes with up to `read_ahead` ranges being
+    /// fetched ahead of the consumer.
+    ///
+    fn cat_prefetch(
+        &self,
+        path: &str,
+        range_size: u64,
+        read_ahead: usize,
+    ) -> AsyncStreamResponse<Bytes>;
+
+    /// Returns the contents of an Ipfs object, re-requested from where it
+    /// left off if the download fails with a transient error.
+    ///
+    fn cat_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the contents of an Ipfs object, once the daemon has
+    /// responded, along with the status and headers of the response.
+    ///
+    fn cat_with_metadata<P>(
+        &self,
+        path: P,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Bytes>)>
+    where
+        P: ToIpfsPath;
+
+    /// Returns the contents of an Ipfs object, calling `callback` with the
+    /// progress of the download as each chunk is received.
+    ///
+    fn cat_with_progress<P, F>(&self, path: P, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+        F: 'static + FnMut(Progress) + Send;
+
+    /// List available commands that the server accepts.
+    ///
+    fn commands(&self) -> AsyncResponse<response::CommandsResponse>;
+
+    /// Opens the config file for editing (on the server).
+    ///
+    fn config_edit(&self) -> AsyncResponse<response::ConfigEditResponse>;
+
+    /// Replace the config file.
+    ///
+    fn config_replace<R>(&self, data: R) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Replace the config with a stream of json.
+    ///
+    fn config_replace_stream<S>(&self, data: S) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send;
+
+    /// Show the current config of the server.
+    ///
+    fn config_show(&self) -> AsyncResponse<response::ConfigShowResponse>;
+
+    /// Show the current config of the server, as the raw json the daemon
+    /// returned.
+    ///
+    fn config_show_bytes(&self) -> AsyncResponse<Bytes>;
+
+    /// Exports the DAG under `root` as a CAR archive.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_export<C>(&self, root: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid;
+
+    /// Returns information about a dag node in Ipfs.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_get(&self, path: &str) -> AsyncResponse<response::DagGetResponse>;
+
+    /// Returns a dag node in Ipfs, deserialized into a type of the caller's
+    /// choosing.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_get_typed<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send;
+
+    /// Returns a dag node in Ipfs, fetched as dag-cbor and deserialized into
+    /// a type of the caller's choosing.
+    ///
+    #[cfg(feature = "dag-cbor")]
+    fn dag_get_cbor<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send;
+
+    /// Imports the blocks of a CAR archive, and pins its roots.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_import<R>(&self, data: R) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Imports the blocks of a CAR archive with options, such as whether its
+    /// roots are pinned.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_import_with_options<R>(
+        &self,
+        data: R,
+        import: &request::DagImport,
+    ) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Add a DAG node to Ipfs, from its dag-json encoding.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_put<R>(&self, data: R) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Add a DAG node to Ipfs with options, such as the codec the data is
+    /// encoded in.
+    ///
+    #[cfg(feature = "dag")]
+    fn dag_put_with_options<R>(
+        &self,
+        data: R,
+        put: &request::DagPut,
+    ) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Serializes a value as dag-cbor, and adds it to Ipfs as a DAG node.
+    ///
+    #[cfg(feature = "dag-cbor")]
+    fn dag_put_cbor<T>(&self, value: &T) -> AsyncResponse<response::DagPutResponse>
+    where
+        T: Serialize;
+
+    /// Query the DHT for all of the multiaddresses associated with a Peer ID.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_findpeer<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtFindPeerResponse>
+    where
+        P: ToPeerId;
+
+    /// Find peers in the DHT that can provide a specific value given a key.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_findprovs(&self, key: &str) -> AsyncStreamResponse<response::DhtFindProvsResponse>;
+
+    /// Query the DHT for a given key.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_get(&self, key: &str) -> AsyncStreamResponse<response::DhtGetResponse>;
+
+    /// Announce to the network that you are providing a given value.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_provide(&self, key: &str) -> AsyncStreamResponse<response::DhtProvideResponse>;
+
+    /// Write a key/value pair to the DHT.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_put(&self, key: &str, value: &str) -> AsyncStreamResponse<response::DhtPutResponse>;
+
+    /// Find the closest peer given the peer ID by querying the DHT.
+    ///
+    #[cfg(feature = "dht")]
+    fn dht_query<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtQueryResponse>
+    where
+        P: ToPeerId;
+
+    /// Clear inactive requests from the log.
+    ///
+    fn diag_cmds_clear(&self) -> AsyncResponse<response::DiagCmdsClearResponse>;
+
+    /// Set how long to keep inactive requests in the log.
+    ///
+    fn diag_cmds_set_time(&self, time: &str) -> AsyncResponse<response::DiagCmdsSetTimeResponse>;
+
+    /// Print system diagnostic information.
+    ///
+    fn diag_sys(&self) -> AsyncResponse<response::DiagSysResponse>;
+
+    /// Print system diagnostic information, as the raw bytes the daemon
+    /// returned.
+    ///
+    fn diag_sys_bytes(&self) -> AsyncResponse<Bytes>;
+
+    /// Resolve DNS link.
+    ///
+    fn dns(&self, link: &str, recursive: bool) -> AsyncResponse<response::DnsResponse>;
+
+    /// Resolve DNS link, streaming every intermediate answer on the way.
+    ///
+    fn dns_stream(&self, link: &str, recursive: bool)
+        -> AsyncStreamResponse<response::DnsResponse>;
+
+    /// List directory for Unix filesystem objects.
+    ///
+    fn file_ls(&self, path: &str) -> AsyncResponse<response::FileLsResponse>;
+
+    /// Copy files into MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_cp<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesCpResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath;
+
+    /// Flush a path's data to disk.
+    ///
+    #[cfg(feature = "files")]
+    fn files_flush(&self, path: Option<&str>) -> AsyncResponse<response::FilesFlushResponse>;
+
+    /// List directories in MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_ls(&self, path: Option<&str>) -> AsyncResponse<response::FilesLsResponse>;
+
+    /// Make directories in MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_mkdir<P>(&self, path: P, parents: bool) -> AsyncResponse<response::FilesMkdirResponse>
+    where
+        P: ToMfsPath;
+
+    /// Copy files into MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_mv<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesMvResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath;
+
+    /// Read a file in MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_read<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToMfsPath;
+
+    /// Remove a file in MFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_rm<P>(&self, path: P, recursive: bool) -> AsyncResponse<response::FilesRmResponse>
+    where
+        P: ToMfsPath;
+
+    /// Display a file's status in MDFS.
+    ///
+    #[cfg(feature = "files")]
+    fn files_stat<P>(&self, path: P) -> AsyncResponse<response::FilesStatResponse>
+    where
+        P: ToMfsPath;
+
+    /// Write to a mutable file in the filesystem.
+    ///
+    #[cfg(feature = "files")]
+    fn files_write<P, R>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: R,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        R: 'static + Read + Send;
+
+    /// Write a stream of bytes to a mutable file in the filesystem.
+    ///
+    #[cfg(feature = "files")]
+    fn files_write_stream<P, S>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: S,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send;
+
+    /// Mirror a local file or directory into MFS at `mfs_path`.
+    ///
+    #[cfg(feature = "files")]
+    fn files_write_tree<M, P>(&self, local_path: P, mfs_path: M) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>;
+
+    /// Mirror a local file or directory into MFS, only writing the files
+    /// that changed.
+    ///
+    #[cfg(feature = "files")]
+    fn files_sync<P, M>(&self, local_path: P, mfs_path: M) -> AsyncResponse<Vec<String>>
+    where
+        P: AsRef<Path>,
+        M: ToMfsPath;
+
+    /// Copy a file or directory from MFS to `local_path`.
+    ///
+    #[cfg(feature = "files")]
+    fn files_read_tree<M, P>(&self, mfs_path: M, local_path: P) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>;
+
+    /// List blocks that are both in the filestore and standard block storage.
+    ///
+    #[cfg(feature = "filestore")]
+    fn filestore_dups(&self) -> AsyncStreamResponse<response::FilestoreDupsResponse>;
+
+    /// List objects in filestore.
+    ///
+    #[cfg(feature = "filestore")]
+    fn filestore_ls(&self, cid: Option<&str>)
+        -> AsyncStreamResponse<response::FilestoreLsResponse>;
+
+    /// Verify objects in filestore.
+    ///
+    #[cfg(feature = "filestore")]
+    fn filestore_verify(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreVerifyResponse>;
+
+    /// Download Ipfs object.
+    ///
+    fn get<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath;
+
+    /// Download Ipfs object, downloading it again and skipping the bytes
+    /// already received if the download fails with a transient error.
+    ///
+    fn get_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath;
+
+    /// Download an Ipfs object, and unpack it to `dest`.
+    ///
+    fn get_to_path<I, P>(&self, path: I, dest: P, overwrite: OverwritePolicy) -> AsyncResponse<()>
+    where
+        I: ToIpfsPath,
+        P: AsRef<Path>;
+
+    /// Download Ipfs object, calling `callback` with the progress of the
+    /// download as each chunk is received.
+    ///
+    fn get_with_progress<F>(&self, path: &str, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        F: 'static + FnMut(Progress) + Send;
+
+    /// Returns information about a peer.
+    ///
+    fn id(&self, peer: Option<&str>) -> AsyncResponse<response::IdResponse>;
+
+    /// Create a new keypair.
+    ///
+    #[cfg(feature = "key")]
+    fn key_gen(
+        &self,
+        name: &str,
+        kind: request::KeyType,
+        size: i32,
+    ) -> AsyncResponse<response::KeyGenResponse>;
+
+    /// List all local keypairs.
+    ///
+    #[cfg(feature = "key")]
+    fn key_list(&self) -> AsyncResponse<response::KeyListResponse>;
+
+    /// Returns the peer id of a named key.
+    ///
+    #[cfg(feature = "key")]
+    fn key_id(&self, name: &str) -> AsyncResponse<PeerId>;
+
+    /// Rename a keypair.
+    ///
+    #[cfg(feature = "key")]
+    fn key_rename(
+        &self,
+        name: &str,
+        new: &str,
+        force: bool,
+    ) -> AsyncResponse<response::KeyRenameResponse>;
+
+    /// Remove a keypair.
+    ///
+    #[cfg(feature = "key")]
+    fn key_rm(&self, name: &str) -> AsyncResponse<response::KeyRmResponse>;
+
+    /// Change the logging level for a logger.
+    ///
+    fn log_level(
+        &self,
+        logger: request::Logger,
+        level: request::LoggingLevel,
+    ) -> AsyncResponse<response::LogLevelResponse>;
+
+    /// List all logging subsystems.
+    ///
+    fn log_ls(&self) -> AsyncResponse<response::LogLsResponse>;
+
+    /// Read the event log.
+    ///
+    fn log_tail(&self) -> AsyncStreamResponse<String>;
+
+    /// List the contents of an Ipfs multihash.
+    ///
+    fn ls(&self, path: Option<&str>) -> AsyncResponse<response::LsResponse>;
+
+    /// Check that the daemon is reachable every `interval`, by calling `id`.
+    ///
+    fn monitor_daemon(&self, interval: Duration) -> (DaemonMonitor<Self>, DaemonStatusWatch)
+    where
+        Self: Sized;
+
+    /// Publish an IPFS path to IPNS.
+    ///
+    #[cfg(feature = "name")]
+    fn name_publish(
+        &self,
+        path: &str,
+        resolve: bool,
+        lifetime: Option<&str>,
+        ttl: Option<&str>,
+        key: Option<&str>,
+    ) -> AsyncResponse<response::NamePublishResponse>;
+
+    /// Publish `cid` under the IPNS name of the key named `key`, and return
+    /// the name.
+    ///
+    #[cfg(all(feature = "key", feature = "name"))]
+    fn publish_name<C>(&self, cid: C, key: &str) -> AsyncResponse<String>
+    where
+        C: ToCid;
+
+    /// Resolve an IPNS name.
+    ///
+    #[cfg(feature = "name")]
+    fn name_resolve(
+        &self,
+        name: Option<&str>,
+        recursive: bool,
+        nocache: bool,
+    ) -> AsyncResponse<response::NameResolveResponse>;
+
+    /// Resolve an IPNS name or DNSLink domain, and cat the file it points
+    /// to.
+    ///
+    #[cfg(feature = "name")]
+    fn cat_ipns(&self, name: &str) -> AsyncStreamResponse<Bytes>;
+
+    /// Output the raw bytes of an Ipfs object.
+    ///
+    #[cfg(feature = "object")]
+    fn object_data<K>(&self, key: K) -> AsyncStreamResponse<Bytes>
+    where
+        K: ToIpfsPath;
+
+    /// Returns the diff of two Ipfs objects.
+    ///
+    #[cfg(feature = "object")]
+    fn object_diff<K0, K1>(
+        &self,
+        key0: K0,
+        key1: K1,
+    ) -> AsyncResponse<response::ObjectDiffResponse>
+    where
+        K0: ToIpfsPath,
+        K1: ToIpfsPath;
+
+    /// Returns the data in an object.
+    ///
+    #[cfg(feature = "object")]
+    fn object_get<K>(&self, key: K) -> AsyncResponse<response::ObjectGetResponse>
+    where
+        K: ToIpfsPath;
+
+    /// Returns an object in the given encoding, undecoded.
+    ///
+    #[cfg(feature = "object")]
+    fn object_get_encoded<K>(
+        &self,
+        key: K,
+        encoding: request::ObjectEncoding,
+    ) -> AsyncResponse<Bytes>
+    where
+        K: ToIpfsPath;
+
+    /// Returns an object decoded from its dag-pb bytes.
+    ///
+    #[cfg(feature = "object")]
+    fn object_get_node<K>(&self, key: K) -> AsyncResponse<DagPbNode>
+    where
+        K: ToIpfsPath;
+
+    /// Returns the links that an object points to.
+    ///
+    #[cfg(feature = "object")]
+    fn object_links<K>(&self, key: K) -> AsyncResponse<response::ObjectLinksResponse>
+    where
+        K: ToIpfsPath;
+
+    /// Walk the graph of objects under `hash`, breadth first.
+    ///
+    #[cfg(feature = "object")]
+    fn walk(&self, hash: &str, max_depth: Option<usize>) -> AsyncStreamResponse<WalkNode>;
+
+    /// Create a new object.
+    ///
+    #[cfg(feature = "object")]
+    fn object_new(
+        &self,
+        template: Option<request::ObjectTemplate>,
+    ) -> AsyncResponse<response::ObjectNewResponse>;
+
+    /// Add a link named `name` to `link` in the object `key`.
+    ///
+    #[cfg(feature = "object")]
+    fn object_patch_add_link(
+        &self,
+        key: &str,
+        name: &str,
+        link: &str,
+        create: bool,
+    ) -> AsyncResponse<response::ObjectPatchAddLinkResponse>;
+
+    /// Returns the stats for an object.
+    ///
+    #[cfg(feature = "object")]
+    fn object_stat<K>(&self, key: K) -> AsyncResponse<response::ObjectStatResponse>
+    where
+        K: ToIpfsPath;
+
+    /// Pins a new object.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_add(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinAddResponse>;
+
+    /// Pins many objects in one request.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_add_many(
+        &self,
+        keys: &[&str],
+        recursive: bool,
+    ) -> AsyncResponse<response::PinAddResponse>;
+
+    /// Pins a new object recursively, reporting the blocks fetched so far.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_add_progress(&self, key: &str) -> AsyncStreamResponse<response::PinAddEvent>;
+
+    /// Returns a list of pinned objects in local storage.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_ls(
+        &self,
+        key: Option<&str>,
+        typ: Option<&str>,
+    ) -> AsyncResponse<response::PinLsResponse>;
+
+    /// Removes a pinned object from local storage.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_rm(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinRmResponse>;
+
+    /// Pins and unpins recursively until exactly the CIDs in `desired` are
+    /// pinned recursively.
+    ///
+    #[cfg(feature = "pin")]
+    fn pin_reconcile<I>(&self, desired: I, parallelism: usize) -> AsyncResponse<PinsetReport>
+    where
+        I: IntoIterator<Item = Cid>;
+
+    /// Pings a peer.
+    ///
+    fn ping<P>(&self, peer: P, count: Option<i32>) -> AsyncStreamResponse<response::PingResponse>
+    where
+        P: ToPeerId;
+
+    /// List subscribed pubsub topics.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_ls(&self) -> AsyncResponse<response::PubsubLsResponse>;
+
+    /// List peers that are being published to.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_peers(&self, topic: Option<&str>) -> AsyncResponse<response::PubsubPeersResponse>;
+
+    /// Publish a message to a topic.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_pub(&self, topic: &str, payload: &str) -> AsyncResponse<response::PubsubPubResponse>;
+
+    /// Returns a `Sink` that publishes each message sent to it to `topic`.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_publisher(&self, topic: &str) -> PubsubPublisher<Self>
+    where
+        Self: Sized;
+
+    /// Returns a `PubsubRouter` to subscribe to many topics with, and the
+    /// stream that the messages published to all of them come from.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_router(&self) -> (PubsubRouter<Self>, PubsubMessages)
+    where
+        Self: Sized;
+
+    /// Subscribes to a pubsub topic.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_sub(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubSubResponse>;
+
+    /// Subscribes to a pubsub topic, and subscribes again whenever the
+    /// subscription is lost to a transient error.
+    ///
+    #[cfg(feature = "pubsub")]
+    fn pubsub_sub_reconnecting(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubEvent>;
+
+    /// Lists the references under a path, each once, following links
+    /// `max_depth` levels deep.
+    ///
+    fn refs<P>(
+        &self,
+        path: P,
+        max_depth: Option<u64>,
+    ) -> AsyncStreamResponse<response::RefsResponse>
+    where
+        P: ToIpfsPath;
+
+    /// Lists the CIDs under a path one level of the graph at a time.
+    ///
+    fn refs_walk<P>(&self, path: P, max_depth: Option<usize>) -> AsyncStreamResponse<Cid>
+    where
+        P: ToIpfsPath;
+
+    /// Gets a list of local references.
+    ///
+    fn refs_local(&self) -> AsyncStreamResponse<response::RefsLocalResponse>;
+
+    /// Remove blocks that are not pinned from the repo, returning each
+    /// block as it is removed.
+    ///
+    fn repo_gc(&self) -> AsyncStreamResponse<response::RepoGcResponse>;
+
+    /// Remove blocks that are not pinned from the repo, and return how many
+    /// were removed, how much space was reclaimed, and any errors.
+    ///
+    fn repo_gc_summary(&self) -> AsyncResponse<response::RepoGcSummary>;
+
+    /// Returns repo stats.
+    ///
+    fn repo_stat(&self) -> AsyncResponse<response::RepoStatResponse>;
+
+    /// Returns repo stats, with options.
+    ///
+    fn repo_stat_with_options(
+        &self,
+        stat: &request::RepoStat,
+    ) -> AsyncResponse<response::RepoStatResponse>;
+
+    /// Shutdown the Ipfs daemon.
+    ///
+    fn shutdown(&self) -> AsyncResponse<response::ShutdownResponse>;
+
+    /// Returns bitswap stats.
+    ///
+    fn stats_bitswap(&self) -> AsyncResponse<response::StatsBitswapResponse>;
+
+    /// Returns bandwidth stats.
+    ///
+    fn stats_bw(&self) -> AsyncResponse<response::StatsBwResponse>;
+
+    /// Sample `stats_bw` every `interval`, and return the bandwidth used
+    /// since the previous sample.
+    ///
+    fn bandwidth_samples(&self, interval: Duration) -> AsyncStreamResponse<BandwidthSample>;
+
+    /// Returns repo stats.
+    ///
+    fn stats_repo(&self) -> AsyncResponse<response::StatsRepoResponse>;
+
+    /// Returns repo stats, with options.
+    ///
+    fn stats_repo_with_options(
+        &self,
+        stat: &request::StatsRepo,
+    ) -> AsyncResponse<response::StatsRepoResponse>;
+
+    /// Return a list of local addresses.
+    ///
+    #[cfg(feature = "swarm")]
+    fn swarm_addrs_local(&self) -> AsyncResponse<response::SwarmAddrsLocalResponse>;
+
+    /// Open a connection to a peer at `addr`.
+    ///
+    #[cfg(feature = "swarm")]
+    fn swarm_connect(&self, addr: &str) -> AsyncResponse<response::SwarmConnectResponse>;
+
+    /// Try to connect to a peer at each of `addrs` in turn, and return the
+    /// first address that worked.
+    ///
+    #[cfg(feature = "swarm")]
+    fn swarm_connect_any<I>(&self, addrs: I) -> AsyncResponse<Multiaddr>
+    where
+        I: IntoIterator<Item = Multiaddr>;
+
+    /// Return a list of peers with open connections.
+    ///
+    #[cfg(feature = "swarm")]
+    fn swarm_peers(&self) -> AsyncResponse<response::SwarmPeersResponse>;
+
+    /// Watch the peers the daemon is connected to, by calling `swarm_peers`
+    /// every `interval`.
+    ///
+    #[cfg(feature = "swarm")]
+    fn peer_events(&self, interval: Duration) -> AsyncStreamResponse<PeerEvent>;
+
+    /// Add a tar file to Ipfs.
+    ///
+    fn tar_add<R>(&self, data: R) -> AsyncResponse<response::TarAddResponse>
+    where
+        R: 'static + Read + Send;
+
+    /// Import a tar file, streamed without blocking, into IPFS.
+    ///
+    fn tar_add_stream<S>(&self, data: S) -> AsyncResponse<response::TarAddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send;
+
+    /// Export a tar file from Ipfs.
+    ///
+    fn tar_cat(&self, path: &str) -> AsyncStreamResponse<Bytes>;
+
+    /// Returns information about the Ipfs server version.
+    ///
+    fn version(&self) -> AsyncResponse<response::VersionResponse>;
+
+    /// Waits until the daemon answers a version request, and returns its
+    /// version.
+    ///
+    fn wait_for_daemon(&self, timeout: Duration) -> AsyncResponse<response::VersionResponse>;
+}
+
+impl IpfsApi for IpfsClient {
+    #[inline]
+    fn add<R>(&self, data: R) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::add(self, data)
+    }
+
+    #[inline]
+    fn add_json<T>(&self, value: &T) -> AsyncResponse<Cid>
+    where
+        T: Serialize,
+    {
+        IpfsClient::add_json(self, value)
+    }
+
+    #[inline]
+    fn add_stream<S>(&self, data: S) -> AsyncResponse<response::AddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        IpfsClient::add_stream(self, data)
+    }
+
+    #[inline]
+    fn add_with_options<R>(
+        &self,
+        data: R,
+        add: &request::Add,
+    ) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::add_with_options(self, data, add)
+    }
+
+    #[inline]
+    fn add_many<I, N, R>(&self, files: I) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        IpfsClient::add_many(self, files)
+    }
+
+    #[inline]
+    fn add_many_with_options<I, N, R>(
+        &self,
+        files: I,
+        add: &request::Add,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        IpfsClient::add_many_with_options(self, files, add)
+    }
+
+    #[inline]
+    fn add_path<P>(&self, path: P) -> AsyncResponse<response::AddResponse>
+    where
+        P: AsRef<Path>,
+    {
+        IpfsClient::add_path(self, path)
+    }
+
+    #[inline]
+    fn add_tree<P>(&self, path: P) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        IpfsClient::add_tree(self, path)
+    }
+
+    #[inline]
+    fn add_tree_with_options<P>(
+        &self,
+        path: P,
+        options: &AddTreeOptions,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        IpfsClient::add_tree_with_options(self, path, options)
+    }
+
+    #[inline]
+    fn bitswap_ledger<P>(&self, peer: P) -> AsyncResponse<response::BitswapLedgerResponse>
+    where
+        P: ToPeerId,
+    {
+        IpfsClient::bitswap_ledger(self, peer)
+    }
+
+    #[inline]
+    fn bitswap_reprovide(&self) -> AsyncResponse<response::BitswapReprovideResponse> {
+        IpfsClient::bitswap_reprovide(self)
+    }
+
+    #[inline]
+    fn bitswap_stat(&self) -> AsyncResponse<response::BitswapStatResponse> {
+        IpfsClient::bitswap_stat(self)
+    }
+
+    #[inline]
+    fn bitswap_stat_with_options(
+        &self,
+        stat: &request::BitswapStat,
+    ) -> AsyncResponse<response::BitswapStatResponse> {
+        IpfsClient::bitswap_stat_with_options(self, stat)
+    }
+
+    #[inline]
+    fn bitswap_unwant(&self, key: &str) -> AsyncResponse<response::BitswapUnwantResponse> {
+        IpfsClient::bitswap_unwant(self, key)
+    }
+
+    #[inline]
+    fn bitswap_wantlist(
+        &self,
+        peer: Option<&str>,
+    ) -> AsyncResponse<response::BitswapWantlistResponse> {
+        IpfsClient::bitswap_wantlist(self, peer)
+    }
+
+    #[inline]
+    fn block_get<C>(&self, cid: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        IpfsClient::block_get(self, cid)
+    }
+
+    #[inline]
+    fn block_get_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, response::Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        IpfsClient::block_get_many(self, cids, parallelism)
+    }
+
+    #[inline]
+    fn block_get_with_progress<C, F>(&self, cid: C, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        IpfsClient::block_get_with_progress(self, cid, callback)
+    }
+
+    #[inline]
+    fn block_put<R>(&self, data: R) -> AsyncResponse<response::BlockPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::block_put(self, data)
+    }
+
+    #[inline]
+    fn block_put_stream<S>(&self, data: S) -> AsyncResponse<response::BlockPutResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        IpfsClient::block_put_stream(self, data)
+    }
+
+    #[inline]
+    fn block_rm<C>(&self, cid: C) -> AsyncResponse<response::BlockRmResponse>
+    where
+        C: ToCid,
+    {
+        IpfsClient::block_rm(self, cid)
+    }
+
+    #[inline]
+    fn block_stat<C>(&self, cid: C) -> AsyncResponse<response::BlockStatResponse>
+    where
+        C: ToCid,
+    {
+        IpfsClient::block_stat(self, cid)
+    }
+
+    #[inline]
+    fn bootstrap_add_default(&self) -> AsyncResponse<response::BootstrapAddDefaultResponse> {
+        IpfsClient::bootstrap_add_default(self)
+    }
+
+    #[inline]
+    fn bootstrap_list(&self) -> AsyncResponse<response::BootstrapListResponse> {
+        IpfsClient::bootstrap_list(self)
+    }
+
+    #[inline]
+    fn bootstrap_rm_all(&self) -> AsyncResponse<response::BootstrapRmAllResponse> {
+        IpfsClient::bootstrap_rm_all(self)
+    }
+
+    #[inline]
+    fn cat<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat(self, path)
+    }
+
+    #[inline]
+    fn cat_bytes<P>(&self, path: P, max_size: Option<usize>) -> AsyncResponse<Vec<u8>>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_bytes(self, path, max_size)
+    }
+
+    #[inline]
+    fn cat_string<P>(&self, path: P) -> AsyncResponse<String>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_string(self, path)
+    }
+
+    #[inline]
+    fn cat_json<T, P>(&self, path: P) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_json(self, path)
+    }
+
+    #[inline]
+    fn cat_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, response::Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        IpfsClient::cat_many(self, cids, parallelism)
+    }
+
+    #[inline]
+    fn cat_range<P>(&self, path: P, offset: u64, length: u64) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_range(self, path, offset, length)
+    }
+
+    #[inline]
+    fn cat_prefetch(
+        &self,
+        path: &str,
+        range_size: u64,
+        read_ahead: usize,
+    ) -> AsyncStreamResponse<Bytes> {
+        IpfsClient::cat_prefetch(self, path, range_size, read_ahead)
+    }
+
+    #[inline]
+    fn cat_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_resumable(self, path)
+    }
+
+    #[inline]
+    fn cat_with_metadata<P>(
+        &self,
+        path: P,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Bytes>)>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::cat_with_metadata(self, path)
+    }
+
+    #[inline]
+    fn cat_with_progress<P, F>(&self, path: P, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        IpfsClient::cat_with_progress(self, path, callback)
+    }
+
+    #[inline]
+    fn commands(&self) -> AsyncResponse<response::CommandsResponse> {
+        IpfsClient::commands(self)
+    }
+
+    #[inline]
+    fn config_edit(&self) -> AsyncResponse<response::ConfigEditResponse> {
+        IpfsClient::config_edit(self)
+    }
+
+    #[inline]
+    fn config_replace<R>(&self, data: R) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::config_replace(self, data)
+    }
+
+    #[inline]
+    fn config_replace_stream<S>(&self, data: S) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        IpfsClient::config_replace_stream(self, data)
+    }
+
+    #[inline]
+    fn config_show(&self) -> AsyncResponse<response::ConfigShowResponse> {
+        IpfsClient::config_show(self)
+    }
+
+    #[inline]
+    fn config_show_bytes(&self) -> AsyncResponse<Bytes> {
+        IpfsClient::config_show_bytes(self)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_export<C>(&self, root: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        IpfsClient::dag_export(self, root)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_get(&self, path: &str) -> AsyncResponse<response::DagGetResponse> {
+        IpfsClient::dag_get(self, path)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_get_typed<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        IpfsClient::dag_get_typed(self, path)
+    }
+
+    #[cfg(feature = "dag-cbor")]
+    #[inline]
+    fn dag_get_cbor<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        IpfsClient::dag_get_cbor(self, path)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_import<R>(&self, data: R) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::dag_import(self, data)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_import_with_options<R>(
+        &self,
+        data: R,
+        import: &request::DagImport,
+    ) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::dag_import_with_options(self, data, import)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_put<R>(&self, data: R) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::dag_put(self, data)
+    }
+
+    #[cfg(feature = "dag")]
+    #[inline]
+    fn dag_put_with_options<R>(
+        &self,
+        data: R,
+        put: &request::DagPut,
+    ) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::dag_put_with_options(self, data, put)
+    }
+
+    #[cfg(feature = "dag-cbor")]
+    #[inline]
+    fn dag_put_cbor<T>(&self, value: &T) -> AsyncResponse<response::DagPutResponse>
+    where
+        T: Serialize,
+    {
+        IpfsClient::dag_put_cbor(self, value)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_findpeer<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtFindPeerResponse>
+    where
+        P: ToPeerId,
+    {
+        IpfsClient::dht_findpeer(self, peer)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_findprovs(&self, key: &str) -> AsyncStreamResponse<response::DhtFindProvsResponse> {
+        IpfsClient::dht_findprovs(self, key)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_get(&self, key: &str) -> AsyncStreamResponse<response::DhtGetResponse> {
+        IpfsClient::dht_get(self, key)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_provide(&self, key: &str) -> AsyncStreamResponse<response::DhtProvideResponse> {
+        IpfsClient::dht_provide(self, key)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_put(&self, key: &str, value: &str) -> AsyncStreamResponse<response::DhtPutResponse> {
+        IpfsClient::dht_put(self, key, value)
+    }
+
+    #[cfg(feature = "dht")]
+    #[inline]
+    fn dht_query<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtQueryResponse>
+    where
+        P: ToPeerId,
+    {
+        IpfsClient::dht_query(self, peer)
+    }
+
+    #[inline]
+    fn diag_cmds_clear(&self) -> AsyncResponse<response::DiagCmdsClearResponse> {
+        IpfsClient::diag_cmds_clear(self)
+    }
+
+    #[inline]
+    fn diag_cmds_set_time(&self, time: &str) -> AsyncResponse<response::DiagCmdsSetTimeResponse> {
+        IpfsClient::diag_cmds_set_time(self, time)
+    }
+
+    #[inline]
+    fn diag_sys(&self) -> AsyncResponse<response::DiagSysResponse> {
+        IpfsClient::diag_sys(self)
+    }
+
+    #[inline]
+    fn diag_sys_bytes(&self) -> AsyncResponse<Bytes> {
+        IpfsClient::diag_sys_bytes(self)
+    }
+
+    #[inline]
+    fn dns(&self, link: &str, recursive: bool) -> AsyncResponse<response::DnsResponse> {
+        IpfsClient::dns(self, link, recursive)
+    }
+
+    #[inline]
+    fn dns_stream(
+        &self,
+        link: &str,
+        recursive: bool,
+    ) -> AsyncStreamResponse<response::DnsResponse> {
+        IpfsClient::dns_stream(self, link, recursive)
+    }
+
+    #[inline]
+    fn file_ls(&self, path: &str) -> AsyncResponse<response::FileLsResponse> {
+        IpfsClient::file_ls(self, path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_cp<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesCpResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath,
+    {
+        IpfsClient::files_cp(self, path, dest)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_flush(&self, path: Option<&str>) -> AsyncResponse<response::FilesFlushResponse> {
+        IpfsClient::files_flush(self, path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_ls(&self, path: Option<&str>) -> AsyncResponse<response::FilesLsResponse> {
+        IpfsClient::files_ls(self, path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_mkdir<P>(&self, path: P, parents: bool) -> AsyncResponse<response::FilesMkdirResponse>
+    where
+        P: ToMfsPath,
+    {
+        IpfsClient::files_mkdir(self, path, parents)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_mv<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesMvResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath,
+    {
+        IpfsClient::files_mv(self, path, dest)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_read<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToMfsPath,
+    {
+        IpfsClient::files_read(self, path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_rm<P>(&self, path: P, recursive: bool) -> AsyncResponse<response::FilesRmResponse>
+    where
+        P: ToMfsPath,
+    {
+        IpfsClient::files_rm(self, path, recursive)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_stat<P>(&self, path: P) -> AsyncResponse<response::FilesStatResponse>
+    where
+        P: ToMfsPath,
+    {
+        IpfsClient::files_stat(self, path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_write<P, R>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: R,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        R: 'static + Read + Send,
+    {
+        IpfsClient::files_write(self, path, create, truncate, data)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_write_stream<P, S>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: S,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        IpfsClient::files_write_stream(self, path, create, truncate, data)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_write_tree<M, P>(&self, local_path: P, mfs_path: M) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>,
+    {
+        IpfsClient::files_write_tree(self, local_path, mfs_path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_sync<P, M>(&self, local_path: P, mfs_path: M) -> AsyncResponse<Vec<String>>
+    where
+        P: AsRef<Path>,
+        M: ToMfsPath,
+    {
+        IpfsClient::files_sync(self, local_path, mfs_path)
+    }
+
+    #[cfg(feature = "files")]
+    #[inline]
+    fn files_read_tree<M, P>(&self, mfs_path: M, local_path: P) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>,
+    {
+        IpfsClient::files_read_tree(self, mfs_path, local_path)
+    }
+
+    #[cfg(feature = "filestore")]
+    #[inline]
+    fn filestore_dups(&self) -> AsyncStreamResponse<response::FilestoreDupsResponse> {
+        IpfsClient::filestore_dups(self)
+    }
+
+    #[cfg(feature = "filestore")]
+    #[inline]
+    fn filestore_ls(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreLsResponse> {
+        IpfsClient::filestore_ls(self, cid)
+    }
+
+    #[cfg(feature = "filestore")]
+    #[inline]
+    fn filestore_verify(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreVerifyResponse> {
+        IpfsClient::filestore_verify(self, cid)
+    }
+
+    #[inline]
+    fn get<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::get(self, path)
+    }
+
+    #[inline]
+    fn get_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::get_resumable(self, path)
+    }
+
+    #[inline]
+    fn get_to_path<I, P>(&self, path: I, dest: P, overwrite: OverwritePolicy) -> AsyncResponse<()>
+    where
+        I: ToIpfsPath,
+        P: AsRef<Path>,
+    {
+        IpfsClient::get_to_path(self, path, dest, overwrite)
+    }
+
+    #[inline]
+    fn get_with_progress<F>(&self, path: &str, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        F: 'static + FnMut(Progress) + Send,
+    {
+        IpfsClient::get_with_progress(self, path, callback)
+    }
+
+    #[inline]
+    fn id(&self, peer: Option<&str>) -> AsyncResponse<response::IdResponse> {
+        IpfsClient::id(self, peer)
+    }
+
+    #[cfg(feature = "key")]
+    #[inline]
+    fn key_gen(
+        &self,
+        name: &str,
+        kind: request::KeyType,
+        size: i32,
+    ) -> AsyncResponse<response::KeyGenResponse> {
+        IpfsClient::key_gen(self, name, kind, size)
+    }
+
+    #[cfg(feature = "key")]
+    #[inline]
+    fn key_list(&self) -> AsyncResponse<response::KeyListResponse> {
+        IpfsClient::key_list(self)
+    }
+
+    #[cfg(feature = "key")]
+    #[inline]
+    fn key_id(&self, name: &str) -> AsyncResponse<PeerId> {
+        IpfsClient::key_id(self, name)
+    }
+
+    #[cfg(feature = "key")]
+    #[inline]
+    fn key_rename(
+        &self,
+        name: &str,
+        new: &str,
+        force: bool,
+    ) -> AsyncResponse<response::KeyRenameResponse> {
+        IpfsClient::key_rename(self, name, new, force)
+    }
+
+    #[cfg(feature = "key")]
+    #[inline]
+    fn key_rm(&self, name: &str) -> AsyncResponse<response::KeyRmResponse> {
+        IpfsClient::key_rm(self, name)
+    }
+
+    #[inline]
+    fn log_level(
+        &self,
+        logger: request::Logger,
+        level: request::LoggingLevel,
+    ) -> AsyncResponse<response::LogLevelResponse> {
+        IpfsClient::log_level(self, logger, level)
+    }
+
+    #[inline]
+    fn log_ls(&self) -> AsyncResponse<response::LogLsResponse> {
+        IpfsClient::log_ls(self)
+    }
+
+    #[inline]
+    fn log_tail(&self) -> AsyncStreamResponse<String> {
+        IpfsClient::log_tail(self)
+    }
+
+    #[inline]
+    fn ls(&self, path: Option<&str>) -> AsyncResponse<response::LsResponse> {
+        IpfsClient::ls(self, path)
+    }
+
+    #[inline]
+    fn monitor_daemon(&self, interval: Duration) -> (DaemonMonitor<Self>, DaemonStatusWatch) {
+        IpfsClient::monitor_daemon(self, interval)
+    }
+
+    #[cfg(feature = "name")]
+    #[inline]
+    fn name_publish(
+        &self,
+        path: &str,
+        resolve: bool,
+        lifetime: Option<&str>,
+        ttl: Option<&str>,
+        key: Option<&str>,
+    ) -> AsyncResponse<response::NamePublishResponse> {
+        IpfsClient::name_publish(self, path, resolve, lifetime, ttl, key)
+    }
+
+    #[cfg(all(feature = "key", feature = "name"))]
+    #[inline]
+    fn publish_name<C>(&self, cid: C, key: &str) -> AsyncResponse<String>
+    where
+        C: ToCid,
+    {
+        IpfsClient::publish_name(self, cid, key)
+    }
+
+    #[cfg(feature = "name")]
+    #[inline]
+    fn name_resolve(
+        &self,
+        name: Option<&str>,
+        recursive: bool,
+        nocache: bool,
+    ) -> AsyncResponse<response::NameResolveResponse> {
+        IpfsClient::name_resolve(self, name, recursive, nocache)
+    }
+
+    #[cfg(feature = "name")]
+    #[inline]
+    fn cat_ipns(&self, name: &str) -> AsyncStreamResponse<Bytes> {
+        IpfsClient::cat_ipns(self, name)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_data<K>(&self, key: K) -> AsyncStreamResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_data(self, key)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_diff<K0, K1>(&self, key0: K0, key1: K1) -> AsyncResponse<response::ObjectDiffResponse>
+    where
+        K0: ToIpfsPath,
+        K1: ToIpfsPath,
+    {
+        IpfsClient::object_diff(self, key0, key1)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_get<K>(&self, key: K) -> AsyncResponse<response::ObjectGetResponse>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_get(self, key)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_get_encoded<K>(
+        &self,
+        key: K,
+        encoding: request::ObjectEncoding,
+    ) -> AsyncResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_get_encoded(self, key, encoding)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_get_node<K>(&self, key: K) -> AsyncResponse<DagPbNode>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_get_node(self, key)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_links<K>(&self, key: K) -> AsyncResponse<response::ObjectLinksResponse>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_links(self, key)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn walk(&self, hash: &str, max_depth: Option<usize>) -> AsyncStreamResponse<WalkNode> {
+        IpfsClient::walk(self, hash, max_depth)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_new(
+        &self,
+        template: Option<request::ObjectTemplate>,
+    ) -> AsyncResponse<response::ObjectNewResponse> {
+        IpfsClient::object_new(self, template)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_patch_add_link(
+        &self,
+        key: &str,
+        name: &str,
+        link: &str,
+        create: bool,
+    ) -> AsyncResponse<response::ObjectPatchAddLinkResponse> {
+        IpfsClient::object_patch_add_link(self, key, name, link, create)
+    }
+
+    #[cfg(feature = "object")]
+    #[inline]
+    fn object_stat<K>(&self, key: K) -> AsyncResponse<response::ObjectStatResponse>
+    where
+        K: ToIpfsPath,
+    {
+        IpfsClient::object_stat(self, key)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_add(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinAddResponse> {
+        IpfsClient::pin_add(self, key, recursive)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_add_many(
+        &self,
+        keys: &[&str],
+        recursive: bool,
+    ) -> AsyncResponse<response::PinAddResponse> {
+        IpfsClient::pin_add_many(self, keys, recursive)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_add_progress(&self, key: &str) -> AsyncStreamResponse<response::PinAddEvent> {
+        IpfsClient::pin_add_progress(self, key)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_ls(
+        &self,
+        key: Option<&str>,
+        typ: Option<&str>,
+    ) -> AsyncResponse<response::PinLsResponse> {
+        IpfsClient::pin_ls(self, key, typ)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_rm(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinRmResponse> {
+        IpfsClient::pin_rm(self, key, recursive)
+    }
+
+    #[cfg(feature = "pin")]
+    #[inline]
+    fn pin_reconcile<I>(&self, desired: I, parallelism: usize) -> AsyncResponse<PinsetReport>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        IpfsClient::pin_reconcile(self, desired, parallelism)
+    }
+
+    #[inline]
+    fn ping<P>(&self, peer: P, count: Option<i32>) -> AsyncStreamResponse<response::PingResponse>
+    where
+        P: ToPeerId,
+    {
+        IpfsClient::ping(self, peer, count)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_ls(&self) -> AsyncResponse<response::PubsubLsResponse> {
+        IpfsClient::pubsub_ls(self)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_peers(&self, topic: Option<&str>) -> AsyncResponse<response::PubsubPeersResponse> {
+        IpfsClient::pubsub_peers(self, topic)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_pub(&self, topic: &str, payload: &str) -> AsyncResponse<response::PubsubPubResponse> {
+        IpfsClient::pubsub_pub(self, topic, payload)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_publisher(&self, topic: &str) -> PubsubPublisher<Self> {
+        IpfsClient::pubsub_publisher(self, topic)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_router(&self) -> (PubsubRouter<Self>, PubsubMessages) {
+        IpfsClient::pubsub_router(self)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_sub(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubSubResponse> {
+        IpfsClient::pubsub_sub(self, topic, discover)
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[inline]
+    fn pubsub_sub_reconnecting(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubEvent> {
+        IpfsClient::pubsub_sub_reconnecting(self, topic, discover)
+    }
+
+    #[inline]
+    fn refs<P>(
+        &self,
+        path: P,
+        max_depth: Option<u64>,
+    ) -> AsyncStreamResponse<response::RefsResponse>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::refs(self, path, max_depth)
+    }
+
+    #[inline]
+    fn refs_walk<P>(&self, path: P, max_depth: Option<usize>) -> AsyncStreamResponse<Cid>
+    where
+        P: ToIpfsPath,
+    {
+        IpfsClient::refs_walk(self, path, max_depth)
+    }
+
+    #[inline]
+    fn refs_local(&self) -> AsyncStreamResponse<response::RefsLocalResponse> {
+        IpfsClient::refs_local(self)
+    }
+
+    #[inline]
+    fn repo_gc(&self) -> AsyncStreamResponse<response::RepoGcResponse> {
+        IpfsClient::repo_gc(self)
+    }
+
+    #[inline]
+    fn repo_gc_summary(&self) -> AsyncResponse<response::RepoGcSummary> {
+        IpfsClient::repo_gc_summary(self)
+    }
+
+    #[inline]
+    fn repo_stat(&self) -> AsyncResponse<response::RepoStatResponse> {
+        IpfsClient::repo_stat(self)
+    }
+
+    #[inline]
+    fn repo_stat_with_options(
+        &self,
+        stat: &request::RepoStat,
+    ) -> AsyncResponse<response::RepoStatResponse> {
+        IpfsClient::repo_stat_with_options(self, stat)
+    }
+
+    #[inline]
+    fn shutdown(&self) -> AsyncResponse<response::ShutdownResponse> {
+        IpfsClient::shutdown(self)
+    }
+
+    #[inline]
+    fn stats_bitswap(&self) -> AsyncResponse<response::StatsBitswapResponse> {
+        IpfsClient::stats_bitswap(self)
+    }
+
+    #[inline]
+    fn stats_bw(&self) -> AsyncResponse<response::StatsBwResponse> {
+        IpfsClient::stats_bw(self)
+    }
+
+    #[inline]
+    fn bandwidth_samples(&self, interval: Duration) -> AsyncStreamResponse<BandwidthSample> {
+        IpfsClient::bandwidth_samples(self, interval)
+    }
+
+    #[inline]
+    fn stats_repo(&self) -> AsyncResponse<response::StatsRepoResponse> {
+        IpfsClient::stats_repo(self)
+    }
+
+    #[inline]
+    fn stats_repo_with_options(
+        &self,
+        stat: &request::StatsRepo,
+    ) -> AsyncResponse<response::StatsRepoResponse> {
+        IpfsClient::stats_repo_with_options(self, stat)
+    }
+
+    #[cfg(feature = "swarm")]
+    #[inline]
+    fn swarm_addrs_local(&self) -> AsyncResponse<response::SwarmAddrsLocalResponse> {
+        IpfsClient::swarm_addrs_local(self)
+    }
+
+    #[cfg(feature = "swarm")]
+    #[inline]
+    fn swarm_connect(&self, addr: &str) -> AsyncResponse<response::SwarmConnectResponse> {
+        IpfsClient::swarm_connect(self, addr)
+    }
+
+    #[cfg(feature = "swarm")]
+    #[inline]
+    fn swarm_connect_any<I>(&self, addrs: I) -> AsyncResponse<Multiaddr>
+    where
+        I: IntoIterator<Item = Multiaddr>,
+    {
+        IpfsClient::swarm_connect_any(self, addrs)
+    }
+
+    #[cfg(feature = "swarm")]
+    #[inline]
+    fn swarm_peers(&self) -> AsyncResponse<response::SwarmPeersResponse> {
+        IpfsClient::swarm_peers(self)
+    }
+
+    #[cfg(feature = "swarm")]
+    #[inline]
+    fn peer_events(&self, interval: Duration) -> AsyncStreamResponse<PeerEvent> {
+        IpfsClient::peer_events(self, interval)
+    }
+
+    #[inline]
+    fn tar_add<R>(&self, data: R) -> AsyncResponse<response::TarAddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        IpfsClient::tar_add(self, data)
+    }
+
+    #[inline]
+    fn tar_add_stream<S>(&self, data: S) -> AsyncResponse<response::TarAddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        IpfsClient::tar_add_stream(self, data)
+    }
+
+    #[inline]
+    fn tar_cat(&self, path: &str) -> AsyncStreamResponse<Bytes> {
+        IpfsClient::tar_cat(self, path)
+    }
+
+    #[inline]
+    fn version(&self) -> AsyncResponse<response::VersionResponse> {
+        IpfsClient::version(self)
+    }
+
+    #[inline]
+    fn wait_for_daemon(&self, timeout: Duration) -> AsyncResponse<response::VersionResponse> {
+        IpfsClient::wait_for_daemon(self, timeout)
+    }
+}
@@ -0,0 +1,481 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use bytes::{Bytes, BytesMut};
+use futures::{try_ready, Async, Poll, Stream};
+use response::Error;
+use std::{
+    path::{Path, PathBuf},
+    str,
+    sync::{Arc, Mutex},
+};
+use tar::{EntryType, Header, PaxExtensions};
+
+/// The size of a tar header, and the unit that entries are padded to.
+///
+const BLOCK_SIZE: u64 = 512;
+
+/// The largest pax or GNU long name header that is read into memory.
+///
+const MAX_EXTENSION_SIZE: u64 = 1024 * 1024;
+
+/// Decodes a tar archive, as returned by `get` and `tar_cat`, into its
+/// entries as the archive is downloaded.
+///
+/// The body of an entry has to be read before the next entry is polled for,
+/// otherwise what is left of it is skipped.
+///
+/// ```no_run
+/// # extern crate futures;
+/// # extern crate ipfs_api;
+/// #
+/// use futures::{Future, Stream};
+/// use ipfs_api::{IpfsClient, TarDecodeStream};
+///
+/// # fn main() {
+/// let client = IpfsClient::default();
+/// let req = TarDecodeStream::new(client.get("/ipfs/QmFoo"))
+///     .and_then(|entry| {
+///         let path = entry.path().to_path_buf();
+///
+///         entry.into_body().concat2().map(move |body| (path, body.len()))
+///     })
+///     .for_each(|(path, len)| {
+///         println!("{}: {} bytes", path.display(), len);
+///         Ok(())
+///     });
+/// # }
+/// ```
+///
+pub struct TarDecodeStream<S> {
+    inner: Arc<Mutex<Inner<S>>>,
+
+    /// Extensions read so far for the next entry.
+    extensions: Extensions,
+
+    /// The type of an extension header whose body is still being read.
+    pending: Option<EntryType>,
+}
+
+/// A file, directory or link in a tar archive.
+///
+pub struct TarEntry<S> {
+    header: Header,
+    path: PathBuf,
+    link_name: Option<PathBuf>,
+    body: TarEntryBody<S>,
+}
+
+/// The contents of an entry in a tar archive.
+///
+pub struct TarEntryBody<S> {
+    inner: Arc<Mutex<Inner<S>>>,
+    entry: u64,
+}
+
+/// State shared between the archive stream and the body of the current
+/// entry.
+///
+struct Inner<S> {
+    stream: S,
+    buf: BytesMut,
+
+    /// The number of the current entry.
+    entry: u64,
+
+    /// Bytes left in the body of the current entry.
+    remaining: u64,
+
+    /// Bytes of padding after the body of the current entry.
+    padding: u64,
+
+    done: bool,
+}
+
+impl<S> Inner<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    /// Reads from the stream until at least `len` bytes are buffered.
+    /// Returns `false` if the stream ended before that.
+    ///
+    fn fill(&mut self, len: usize) -> Poll<bool, Error> {
+        while self.buf.len() < len {
+            match try_ready!(self.stream.poll()) {
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => return Ok(Async::Ready(false)),
+            }
+        }
+
+        Ok(Async::Ready(true))
+    }
+
+    /// Discards what is left of the current entry, including its padding.
+    ///
+    fn skip(&mut self) -> Poll<(), Error> {
+        while self.remaining + self.padding > 0 {
+            if !try_ready!(self.fill(1)) {
+                return Err(unexpected_eof());
+            }
+
+            let len = (self.remaining + self.padding).min(self.buf.len() as u64);
+
+            self.buf.advance(len as usize);
+
+            if len <= self.remaining {
+                self.remaining -= len;
+            } else {
+                self.padding -= len - self.remaining;
+                self.remaining = 0;
+            }
+        }
+
+        Ok(Async::Ready(()))
+    }
+
+    /// Reads the next header block, or `None` at the end of the archive.
+    ///
+    fn next_header(&mut self) -> Poll<Option<Header>, Error> {
+        try_ready!(self.skip());
+
+        if !try_ready!(self.fill(BLOCK_SIZE as usize)) {
+            if self.buf.is_empty() {
+                return Ok(Async::Ready(None));
+            } else {
+                return Err(unexpected_eof());
+            }
+        }
+
+        let block = self.buf.split_to(BLOCK_SIZE as usize);
+
+        // An archive ends with blocks of zeros.
+        //
+        if block.iter().all(|&b| b == 0) {
+            return Ok(Async::Ready(None));
+        }
+
+        Ok(Async::Ready(Some(Header::from_byte_slice(&block).clone())))
+    }
+
+    /// Starts the body of an entry of `size` bytes.
+    ///
+    fn start_entry(&mut self, size: u64) {
+        self.entry += 1;
+        self.remaining = size;
+        self.padding = (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE;
+    }
+
+    /// Reads the whole body of the current entry into memory.
+    ///
+    fn read_body(&mut self) -> Poll<Bytes, Error> {
+        let len = self.remaining as usize;
+
+        if !try_ready!(self.fill(len)) {
+            return Err(unexpected_eof());
+        }
+
+        self.remaining = 0;
+
+        Ok(Async::Ready(self.buf.split_to(len).freeze()))
+    }
+}
+
+#[inline]
+fn unexpected_eof() -> Error {
+    Error::Uncategorized("unexpected end of tar archive".into())
+}
+
+#[inline]
+fn path_from_bytes(bytes: &[u8]) -> Result<PathBuf, Error> {
+    str::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|_| Error::Uncategorized("tar path is not valid utf-8".into()))
+}
+
+/// Extensions to the next entry, from pax and GNU long name headers.
+///
+#[derive(Default)]
+struct Extensions {
+    path: Option<PathBuf>,
+    link_name: Option<PathBuf>,
+    size: Option<u64>,
+}
+
+impl<S> TarDecodeStream<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    #[inline]
+    pub fn new(stream: S) -> TarDecodeStream<S> {
+        TarDecodeStream {
+            inner: Arc::new(Mutex::new(Inner {
+                stream,
+                buf: BytesMut::new(),
+                entry: 0,
+                remaining: 0,
+                padding: 0,
+                done: false,
+            })),
+            extensions: Extensions::default(),
+            pending: None,
+        }
+    }
+}
+
+impl<S> Stream for TarDecodeStream<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    type Item = TarEntry<S>;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let inner = self.inner.clone();
+        let mut inner = inner.lock().unwrap();
+
+        loop {
+            if inner.done {
+                return Ok(Async::Ready(None));
+            }
+
+            if let Some(kind) = self.pending {
+                let body = try_ready!(inner.read_body());
+
+                if kind == EntryType::XHeader {
+                    for extension in PaxExtensions::new(&body) {
+                        let extension = extension?;
+
+                        match extension.key() {
+                            Ok("path") => {
+                                self.extensions.path =
+                                    Some(path_from_bytes(extension.value_bytes())?)
+                            }
+                            Ok("linkpath") => {
+                                self.extensions.link_name =
+                                    Some(path_from_bytes(extension.value_bytes())?)
+                            }
+                            Ok("size") => {
+                                self.extensions.size =
+                                    extension.value().ok().and_then(|size| size.parse().ok())
+                            }
+                            _ => (),
+                        }
+                    }
+                } else {
+                    let name = body.split(|&b| b == 0).next().unwrap_or(&[]);
+
+                    if kind == EntryType::GNULongName {
+                        self.extensions.path = Some(path_from_bytes(name)?);
+                    } else {
+                        self.extensions.link_name = Some(path_from_bytes(name)?);
+                    }
+                }
+
+                self.pending = None;
+            }
+
+            let header = match try_ready!(inner.next_header()) {
+                Some(header) => header,
+                None => {
+                    inner.done = true;
+
+                    continue;
+                }
+            };
+            let kind = header.entry_type();
+            let size = match self.extensions.size.take() {
+                Some(size) => size,
+                None => header.entry_size()?,
+            };
+
+            inner.start_entry(size);
+
+            match kind {
+                EntryType::XHeader | EntryType::GNULongName | EntryType::GNULongLink => {
+                    if size > MAX_EXTENSION_SIZE {
+                        return Err(Error::Uncategorized(
+                            "tar extension header too large".into(),
+                        ));
+                    }
+
+                    self.pending = Some(kind);
+                }
+                EntryType::XGlobalHeader => (),
+                _ => {
+                    let path = match self.extensions.path.take() {
+                        Some(path) => path,
+                        None => path_from_bytes(&header.path_bytes())?,
+                    };
+                    let link_name = match self.extensions.link_name.take() {
+                        Some(link_name) => Some(link_name),
+                        None => match header.link_name_bytes() {
+                            Some(bytes) => Some(path_from_bytes(&bytes)?),
+                            None => None,
+                        },
+                    };
+                    let body = TarEntryBody {
+                        inner: self.inner.clone(),
+                        entry: inner.entry,
+                    };
+
+                    return Ok(Async::Ready(Some(TarEntry {
+                        header,
+                        path,
+                        link_name,
+                        body,
+                    })));
+                }
+            }
+        }
+    }
+}
+
+impl<S> TarEntry<S> {
+    /// The raw header of the entry.
+    ///
+    #[inline]
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The path of the entry, including any pax or GNU long name.
+    ///
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The target of a symlink or hard link.
+    ///
+    #[inline]
+    pub fn link_name(&self) -> Option<&Path> {
+        self.link_name.as_deref()
+    }
+
+    /// Whether the entry is a file, directory, symlink, ...
+    ///
+    #[inline]
+    pub fn entry_type(&self) -> EntryType {
+        self.header.entry_type()
+    }
+
+    /// The contents of the entry.
+    ///
+    #[inline]
+    pub fn into_body(self) -> TarEntryBody<S> {
+        self.body
+    }
+}
+
+impl<S> Stream for TarEntryBody<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let mut inner = self.inner.lock().unwrap();
+
+        // The archive moved on to another entry.
+        //
+        if inner.entry != self.entry || inner.remaining == 0 {
+            return Ok(Async::Ready(None));
+        }
+
+        if !try_ready!(inner.fill(1)) {
+            return Err(unexpected_eof());
+        }
+
+        let len = inner.remaining.min(inner.buf.len() as u64);
+
+        inner.remaining -= len;
+
+        Ok(Async::Ready(Some(
+            inner.buf.split_to(len as usize).freeze(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TarDecodeStream;
+    use bytes::Bytes;
+    use futures::{stream, Future, Stream};
+    use response::Error;
+    use tar::{Builder, Header};
+
+    fn archive() -> Vec<u8> {
+        let mut builder = Builder::new(Vec::new());
+        let long = format!("QmRoot/{}.txt", "a".repeat(120));
+
+        for (path, data) in &[("QmRoot/a.txt", "Hello"), (long.as_str(), "World!")] {
+            let mut header = Header::new_gnu();
+
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            builder
+                .append_data(&mut header, path, data.as_bytes())
+                .unwrap();
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_decode_in_small_chunks() {
+        let archive = archive();
+        let chunks: Vec<Result<Bytes, Error>> = archive
+            .chunks(100)
+            .map(|chunk| Ok(Bytes::from(chunk)))
+            .collect();
+
+        let entries = TarDecodeStream::new(stream::iter_result(chunks))
+            .and_then(|entry| {
+                let path = entry.path().to_string_lossy().into_owned();
+
+                entry.into_body().concat2().map(move |body| (path, body))
+            })
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "QmRoot/a.txt");
+        assert_eq!(&entries[0].1[..], b"Hello");
+        assert_eq!(entries[1].0.len(), 7 + 120 + 4);
+        assert_eq!(&entries[1].1[..], b"World!");
+    }
+
+    #[test]
+    fn test_unread_bodies_are_skipped() {
+        let archive = Bytes::from(archive());
+
+        let paths = TarDecodeStream::new(stream::once(Ok(archive)))
+            .map(|entry| entry.path().to_path_buf())
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(paths.len(), 2);
+    }
+
+    #[test]
+    fn test_truncated_archive() {
+        let archive = Bytes::from(&archive()[..600]);
+
+        let res = TarDecodeStream::new(stream::once(Ok(archive)))
+            .and_then(|entry| entry.into_body().concat2())
+            .collect()
+            .wait();
+
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use client::AsyncResponse;
+use futures::{Async, Future, Poll, Stream};
+use response::{Error, StatsBwResponse};
+use std::time::{Duration, Instant};
+use tokio::timer::{Interval, Timeout};
+
+/// The bandwidth the daemon used between two calls to `stats_bw`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct BandwidthSample {
+    /// Bytes received per second since the previous sample.
+    pub rate_in: f64,
+
+    /// Bytes sent per second since the previous sample.
+    pub rate_out: f64,
+
+    /// Bytes received since the daemon started.
+    pub total_in: u64,
+
+    /// Bytes sent since the daemon started.
+    pub total_out: u64,
+
+    /// The time between the two calls the rates were worked out from.
+    pub elapsed: Duration,
+}
+
+/// The byte counters from one call to `stats_bw`, and when it returned.
+///
+#[derive(Clone, Copy, Debug)]
+struct Reading {
+    total_in: u64,
+    total_out: u64,
+    at: Instant,
+}
+
+impl Reading {
+    /// The sample from `prev` to this reading, or `None` if the counters went
+    /// backwards, which happens when the daemon restarts.
+    ///
+    fn since(&self, prev: &Reading) -> Option<BandwidthSample> {
+        let bytes_in = self.total_in.checked_sub(prev.total_in)?;
+        let bytes_out = self.total_out.checked_sub(prev.total_out)?;
+        let elapsed = self.at.duration_since(prev.at);
+        let secs = elapsed.as_secs_f64();
+
+        if secs <= 0.0 {
+            return None;
+        }
+
+        Some(BandwidthSample {
+            rate_in: bytes_in as f64 / secs,
+            rate_out: bytes_out as f64 / secs,
+            total_in: self.total_in,
+            total_out: self.total_out,
+            elapsed,
+        })
+    }
+}
+
+/// Calls `stats_bw` every `interval`, and returns a `BandwidthSample` with
+/// the rates since the previous call.
+///
+/// The rates are worked out from the byte counters, rather than taken from
+/// the daemon's own moving average, so each sample covers exactly the time
+/// since the one before. The first call is only used as a starting point, and
+/// a call after the counters went backwards starts over.
+///
+/// A call that fails or takes longer than `interval` is returned as an
+/// error. The stream keeps sampling on later ticks if it is polled again.
+///
+pub struct BandwidthSamples<A> {
+    api: A,
+    interval: Duration,
+    ticks: Interval,
+    last: Option<Reading>,
+    current: Option<Timeout<AsyncResponse<StatsBwResponse>>>,
+}
+
+impl<A> BandwidthSamples<A>
+where
+    A: IpfsApi,
+{
+    pub fn new(api: A, interval: Duration) -> BandwidthSamples<A> {
+        BandwidthSamples {
+            api,
+            interval,
+            ticks: Interval::new(Instant::now(), interval),
+            last: None,
+            current: None,
+        }
+    }
+}
+
+impl<A> Stream for BandwidthSamples<A>
+where
+    A: IpfsApi,
+{
+    type Item = BandwidthSample;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(ref mut check) = self.current {
+                let res = match check.poll() {
+                    Ok(Async::Ready(res)) => Ok(res),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => Err(e
+                        .into_inner()
+                        .unwrap_or_else(|| Error::Uncategorized("daemon did not respond".into()))),
+                };
+
+                self.current = None;
+
+                let stats = res?;
+                let reading = Reading {
+                    total_in: stats.total_in,
+                    total_out: stats.total_out,
+                    at: Instant::now(),
+                };
+                let sample = self.last.and_then(|last| reading.since(&last));
+
+                self.last = Some(reading);
+
+                if let Some(sample) = sample {
+                    return Ok(Async::Ready(Some(sample)));
+                }
+            }
+
+            match self.ticks.poll() {
+                Ok(Async::Ready(_)) => {
+                    self.current = Some(Timeout::new(self.api.stats_bw(), self.interval))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => return Err(Error::Uncategorized(e.to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::{BandwidthSamples, Reading};
+    use futures::Stream;
+    use mock::MockIpfsClient;
+    use response::StatsBwResponse;
+    use std::time::{Duration, Instant};
+    use tokio::runtime::current_thread::Runtime;
+
+    fn reading(total_in: u64, total_out: u64, at: Instant) -> Reading {
+        Reading {
+            total_in,
+            total_out,
+            at,
+        }
+    }
+
+    fn stats(mock: &MockIpfsClient, total_in: u64, total_out: u64) {
+        mock.push_response(
+            "stats_bw",
+            Ok(StatsBwResponse {
+                total_in,
+                total_out,
+                rate_in: 0.0,
+                rate_out: 0.0,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_sample_rates() {
+        let start = Instant::now();
+        let prev = reading(1000, 500, start);
+        let sample = reading(3000, 1500, start + Duration::from_secs(2))
+            .since(&prev)
+            .unwrap();
+
+        assert_eq!(sample.rate_in, 1000.0);
+        assert_eq!(sample.rate_out, 500.0);
+        assert_eq!(sample.total_in, 3000);
+        assert_eq!(sample.elapsed, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_sample_after_restart() {
+        let start = Instant::now();
+        let prev = reading(1000, 500, start);
+
+        assert!(reading(10, 10, start + Duration::from_secs(1))
+            .since(&prev)
+            .is_none());
+    }
+
+    #[test]
+    fn test_bandwidth_samples() {
+        let mock = MockIpfsClient::new();
+
+        stats(&mock, 100, 100);
+        stats(&mock, 200, 100);
+        stats(&mock, 50, 50);
+        stats(&mock, 150, 250);
+
+        let samples = BandwidthSamples::new(mock, Duration::from_millis(1))
+            .take(2)
+            .collect();
+        let samples = Runtime::new().unwrap().block_on(samples).unwrap();
+
+        assert_eq!(samples[0].total_in, 200);
+        assert!(samples[0].rate_in > 0.0);
+        assert_eq!(samples[0].rate_out, 0.0);
+        assert_eq!(samples[1].total_out, 250);
+        assert!(samples[1].rate_out > samples[1].rate_in);
+    }
+}
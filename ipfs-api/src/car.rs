@@ -0,0 +1,441 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! Reading and writing of CAR (v1) files, the archives of blocks the daemon
+//! imports and exports with `dag import` and `dag export`.
+//!
+//! Only the header fields a CARv1 file needs, `roots` and `version`, are
+//! understood, so no dag-cbor codec is required.
+
+use bytes::Bytes;
+use cid::Cid;
+use multibase::{read_varint, write_varint};
+use response::Error;
+use std::io::{self, Read, Write};
+
+/// The tag dag-cbor uses for links to other blocks.
+///
+const CBOR_TAG_CID: u64 = 42;
+
+/// The largest header or block section that is read, to avoid allocating
+/// whatever length a corrupt file claims.
+///
+const MAX_SECTION_LEN: u64 = 32 * 1024 * 1024;
+
+/// The header at the start of a CAR file.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CarHeader {
+    /// The CAR format version, always 1.
+    pub version: u64,
+
+    /// The roots of the DAGs the file contains.
+    pub roots: Vec<Cid>,
+}
+
+/// A block read from, or written to, a CAR file.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CarBlock {
+    pub cid: Cid,
+
+    pub data: Bytes,
+}
+
+/// Reads the header of a CAR file, then iterates over its blocks.
+///
+/// ```no_run
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::CarReader;
+/// use std::fs::File;
+///
+/// # fn main() {
+/// let car = CarReader::new(File::open("export.car").unwrap()).unwrap();
+///
+/// println!("roots: {:?}", car.header().roots);
+///
+/// for block in car {
+///     let block = block.unwrap();
+///
+///     println!("{} ({} bytes)", block.cid, block.data.len());
+/// }
+/// # }
+/// ```
+///
+pub struct CarReader<R> {
+    read: R,
+    header: CarHeader,
+}
+
+impl<R> CarReader<R>
+where
+    R: Read,
+{
+    pub fn new(mut read: R) -> Result<CarReader<R>, Error> {
+        let section = read_section(&mut read)?
+            .ok_or_else(|| Error::Uncategorized("car file is empty".into()))?;
+        let header = decode_header(&section)?;
+
+        if header.version != 1 {
+            return Err(Error::Uncategorized(format!(
+                "unsupported car version {}",
+                header.version
+            )));
+        }
+
+        Ok(CarReader { read, header })
+    }
+
+    #[inline]
+    pub fn header(&self) -> &CarHeader {
+        &self.header
+    }
+
+    /// Reads the next block, or `None` at the end of the file.
+    ///
+    pub fn next_block(&mut self) -> Result<Option<CarBlock>, Error> {
+        let section = match read_section(&mut self.read)? {
+            Some(section) => section,
+            None => return Ok(None),
+        };
+
+        let len = cid_len(&section)
+            .filter(|len| *len <= section.len())
+            .ok_or_else(|| Error::Uncategorized("invalid cid in car block".into()))?;
+        let cid = Cid::from_bytes(&section[..len])?;
+        let data = Bytes::from(section).slice_from(len);
+
+        Ok(Some(CarBlock { cid, data }))
+    }
+}
+
+impl<R> Iterator for CarReader<R>
+where
+    R: Read,
+{
+    type Item = Result<CarBlock, Error>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_block().transpose()
+    }
+}
+
+/// Writes a CAR file, the header first and then each block in turn.
+///
+/// ```no_run
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::{CarWriter, Cid};
+/// use std::fs::File;
+///
+/// # fn main() {
+/// let root: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap();
+/// let file = File::create("import.car").unwrap();
+/// let mut car = CarWriter::new(file, &[root.clone()]).unwrap();
+///
+/// car.write_block(&root, b"...").unwrap();
+/// # }
+/// ```
+///
+pub struct CarWriter<W> {
+    write: W,
+}
+
+impl<W> CarWriter<W>
+where
+    W: Write,
+{
+    pub fn new(mut write: W, roots: &[Cid]) -> Result<CarWriter<W>, Error> {
+        write_section(&mut write, &[&encode_header(roots)])?;
+
+        Ok(CarWriter { write })
+    }
+
+    /// Appends a block. The data is not checked against the CID.
+    ///
+    pub fn write_block(&mut self, cid: &Cid, data: &[u8]) -> Result<(), Error> {
+        write_section(&mut self.write, &[&cid.to_bytes(), data])
+    }
+
+    /// Flushes the file, and returns the writer it was written to.
+    ///
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.write.flush()?;
+
+        Ok(self.write)
+    }
+}
+
+/// Reads a length prefixed section, or `None` at the end of the file.
+///
+fn read_section<R>(read: &mut R) -> Result<Option<Vec<u8>>, Error>
+where
+    R: Read,
+{
+    let mut varint = Vec::new();
+    let mut byte = [0u8];
+
+    loop {
+        if read.read(&mut byte)? == 0 {
+            if varint.is_empty() {
+                return Ok(None);
+            }
+
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        varint.push(byte[0]);
+
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let len = read_varint(&mut &varint[..])
+        .filter(|len| *len <= MAX_SECTION_LEN)
+        .ok_or_else(|| Error::Uncategorized("invalid car section length".into()))?;
+    let mut section = vec![0; len as usize];
+
+    read.read_exact(&mut section)?;
+
+    Ok(Some(section))
+}
+
+fn write_section<W>(write: &mut W, parts: &[&[u8]]) -> Result<(), Error>
+where
+    W: Write,
+{
+    let mut len = Vec::new();
+
+    write_varint(parts.iter().map(|part| part.len() as u64).sum(), &mut len);
+    write.write_all(&len)?;
+
+    for part in parts {
+        write.write_all(part)?;
+    }
+
+    Ok(())
+}
+
+/// The length of the binary CID at the start of `bytes`.
+///
+fn cid_len(bytes: &[u8]) -> Option<usize> {
+    // A CIDv0 is a bare sha2-256 multihash.
+    //
+    if bytes.starts_with(&[0x12, 0x20]) {
+        return Some(34);
+    }
+
+    let mut rest = bytes;
+
+    read_varint(&mut rest)?;
+    read_varint(&mut rest)?;
+    read_varint(&mut rest)?;
+
+    let digest = read_varint(&mut rest)? as usize;
+
+    Some(bytes.len() - rest.len() + digest)
+}
+
+fn encode_header(roots: &[Cid]) -> Vec<u8> {
+    let mut header = Vec::new();
+
+    write_cbor_head(&mut header, 5, 2);
+    write_cbor_text(&mut header, "roots");
+    write_cbor_head(&mut header, 4, roots.len() as u64);
+
+    for root in roots {
+        let mut link = vec![0];
+
+        link.extend(root.to_bytes());
+
+        write_cbor_head(&mut header, 6, CBOR_TAG_CID);
+        write_cbor_head(&mut header, 2, link.len() as u64);
+        header.extend(link);
+    }
+
+    write_cbor_text(&mut header, "version");
+    write_cbor_head(&mut header, 0, 1);
+
+    header
+}
+
+fn decode_header(mut bytes: &[u8]) -> Result<CarHeader, Error> {
+    let bytes = &mut bytes;
+    let mut header = CarHeader {
+        version: 0,
+        roots: Vec::new(),
+    };
+
+    for _ in 0..read_cbor(bytes, 5)? {
+        let len = read_cbor(bytes, 3)?;
+        let key = take(bytes, len)?;
+
+        match key {
+            b"roots" => {
+                for _ in 0..read_cbor(bytes, 4)? {
+                    if read_cbor(bytes, 6)? != CBOR_TAG_CID {
+                        return Err(invalid_header());
+                    }
+
+                    let len = read_cbor(bytes, 2)?;
+
+                    match take(bytes, len)?.split_first() {
+                        Some((0, cid)) => header.roots.push(Cid::from_bytes(cid)?),
+                        _ => return Err(invalid_header()),
+                    }
+                }
+            }
+            b"version" => header.version = read_cbor(bytes, 0)?,
+            _ => return Err(invalid_header()),
+        }
+    }
+
+    Ok(header)
+}
+
+fn invalid_header() -> Error {
+    Error::Uncategorized("invalid car header".into())
+}
+
+fn write_cbor_head(bytes: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+
+    match value {
+        0..=23 => bytes.push(major | value as u8),
+        24..=0xff => bytes.extend(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            bytes.push(major | 25);
+            bytes.extend(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            bytes.push(major | 26);
+            bytes.extend(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            bytes.push(major | 27);
+            bytes.extend(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_cbor_text(bytes: &mut Vec<u8>, text: &str) {
+    write_cbor_head(bytes, 3, text.len() as u64);
+    bytes.extend(text.as_bytes());
+}
+
+/// Reads the head of a cbor item of the `major` type, returning its
+/// argument (the value, length or tag).
+///
+fn read_cbor(bytes: &mut &[u8], major: u8) -> Result<u64, Error> {
+    let (first, rest) = bytes.split_first().ok_or_else(invalid_header)?;
+
+    *bytes = rest;
+
+    if first >> 5 != major {
+        return Err(invalid_header());
+    }
+
+    let len = match first & 0x1f {
+        value @ 0..=23 => return Ok(u64::from(value)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return Err(invalid_header()),
+    };
+
+    Ok(take(bytes, len)?
+        .iter()
+        .fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: u64) -> Result<&'a [u8], Error> {
+    if len > bytes.len() as u64 {
+        return Err(invalid_header());
+    }
+
+    let (taken, rest) = bytes.split_at(len as usize);
+
+    *bytes = rest;
+
+    Ok(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CarReader, CarWriter};
+    use cid::Cid;
+    use std::{io::Cursor, slice};
+
+    #[test]
+    fn test_round_trip() {
+        let v0: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+        let v1: Cid = "bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae"
+            .parse()
+            .unwrap();
+
+        let mut car = CarWriter::new(Vec::new(), slice::from_ref(&v1)).unwrap();
+
+        car.write_block(&v1, b"root").unwrap();
+        car.write_block(&v0, b"child").unwrap();
+
+        let car = CarReader::new(Cursor::new(car.into_inner().unwrap())).unwrap();
+
+        assert_eq!(car.header().version, 1);
+        assert_eq!(car.header().roots, vec![v1.clone()]);
+
+        let blocks: Vec<_> = car.map(Result::unwrap).collect();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].cid, v1);
+        assert_eq!(blocks[0].data, "root");
+        assert_eq!(blocks[1].cid, v0);
+        assert_eq!(blocks[1].data, "child");
+    }
+
+    #[test]
+    fn test_header_bytes() {
+        // The header of a car file written by `ipfs dag export`.
+        //
+        let root: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+        let car = CarWriter::new(Vec::new(), &[root])
+            .unwrap()
+            .into_inner()
+            .unwrap();
+
+        assert_eq!(car[0] as usize, car.len() - 1);
+        assert_eq!(&car[1..9], b"\xa2\x65roots\x81");
+        assert_eq!(&car[9..14], b"\xd8\x2a\x58\x23\x00");
+        assert_eq!(&car[car.len() - 9..], b"\x67version\x01");
+    }
+
+    #[test]
+    fn test_truncated() {
+        let root: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+        let mut car = CarWriter::new(Vec::new(), slice::from_ref(&root)).unwrap();
+
+        car.write_block(&root, b"data").unwrap();
+
+        let mut bytes = car.into_inner().unwrap();
+
+        bytes.pop();
+
+        let mut car = CarReader::new(Cursor::new(bytes)).unwrap();
+
+        assert!(car.next().unwrap().is_err());
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use multibase::{
+    self, check_multihash, decode_base58, encode_base16, encode_base32, encode_base58, read_varint,
+    write_varint,
+};
+use response::Error;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::{fmt, ops::Deref, str::FromStr};
+
+/// Multihash code of sha2-256, the only hash function CIDv0 supports.
+///
+const SHA2_256: u64 = 0x12;
+
+/// Multicodec of dag-pb, implied by every CIDv0.
+///
+const DAG_PB: u64 = 0x70;
+
+/// The multibase a CIDv1 is written in.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CidBase {
+    /// `z...`, and the only encoding of a CIDv0 (`Qm...`).
+    Base58Btc,
+
+    /// `b...`, the default for CIDv1.
+    Base32,
+
+    /// `f...`
+    Base16,
+}
+
+impl CidBase {
+    /// The name of the base, as the api's `cid-base` option takes it.
+    ///
+    pub fn name(self) -> &'static str {
+        match self {
+            CidBase::Base58Btc => "base58btc",
+            CidBase::Base32 => "base32",
+            CidBase::Base16 => "base16",
+        }
+    }
+}
+
+impl Serialize for CidBase {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// A validated content identifier.
+///
+/// Both CIDv0 (base58btc, `Qm...`) and CIDv1 (base32, base58btc or base16
+/// multibase) are accepted. The CID is kept in the encoding it was parsed
+/// from, and derefs to that string.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::Cid;
+///
+/// # fn main() {
+/// let cid: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap();
+///
+/// assert_eq!(cid.version(), 0);
+/// assert!("QmNotACid".parse::<Cid>().is_err());
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cid {
+    cid: String,
+    version: u64,
+    codec: u64,
+}
+
+impl Cid {
+    /// The CID version, either 0 or 1.
+    ///
+    #[inline]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The multicodec of the content the CID points to (e.g. `0x70` for
+    /// dag-pb, `0x71` for dag-cbor).
+    ///
+    #[inline]
+    pub fn codec(&self) -> u64 {
+        self.codec
+    }
+
+    /// The CID as a string, in the encoding it was parsed from.
+    ///
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.cid
+    }
+
+    /// The CID in binary form, as it appears in CAR files and dag-cbor
+    /// links.
+    ///
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bytes = if self.version == 0 {
+            decode_base58(self.cid.as_bytes())
+        } else {
+            multibase::decode(&self.cid)
+        };
+
+        // The string was checked when the CID was parsed.
+        //
+        bytes.unwrap_or_default()
+    }
+
+    /// The multihash of the content, without the version and codec. Two
+    /// CIDs for the same block have the same multihash, whatever their
+    /// version or multibase.
+    ///
+    pub fn multihash(&self) -> Vec<u8> {
+        let bytes = self.to_bytes();
+
+        if self.version == 0 {
+            return bytes;
+        }
+
+        let mut rest = &bytes[..];
+
+        // The version and codec were checked when the CID was parsed.
+        //
+        let _ = read_varint(&mut rest);
+        let _ = read_varint(&mut rest);
+
+        rest.to_vec()
+    }
+
+    /// Reads a CID in binary form. A CIDv1 is encoded in base32.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Cid, Error> {
+        if bytes.len() == 34 && bytes[0] == SHA2_256 as u8 && bytes[1] == 32 {
+            encode_base58(bytes).parse()
+        } else {
+            format!("b{}", encode_base32(bytes)).parse()
+        }
+    }
+
+    /// The same CID written in `base`, like `ipfs cid format -b`. A CIDv1 is
+    /// re-encoded. A CIDv0 is kept as is for `Base58Btc`, and upgraded to the
+    /// equivalent CIDv1 for any other base, as the daemon does with
+    /// `--upgrade-cidv0-in-output`.
+    ///
+    /// ```
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{Cid, CidBase};
+    ///
+    /// # fn main() {
+    /// let cid: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap();
+    ///
+    /// assert!(cid.to_base(CidBase::Base32).starts_with("bafybei"));
+    /// # }
+    /// ```
+    ///
+    pub fn to_base(&self, base: CidBase) -> Cid {
+        if self.version == 0 && base == CidBase::Base58Btc {
+            return self.clone();
+        }
+
+        let bytes = if self.version == 0 {
+            let mut bytes = Vec::with_capacity(36);
+
+            write_varint(1, &mut bytes);
+            write_varint(DAG_PB, &mut bytes);
+            bytes.extend(self.to_bytes());
+            bytes
+        } else {
+            self.to_bytes()
+        };
+
+        let cid = match base {
+            CidBase::Base58Btc => format!("z{}", encode_base58(&bytes)),
+            CidBase::Base32 => format!("b{}", encode_base32(&bytes)),
+            CidBase::Base16 => format!("f{}", encode_base16(&bytes)),
+        };
+
+        Cid {
+            cid,
+            version: 1,
+            codec: self.codec,
+        }
+    }
+
+    fn invalid(cid: &str, reason: &'static str) -> Error {
+        Error::InvalidCid(cid.into(), reason)
+    }
+}
+
+impl FromStr for Cid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Cid, Error> {
+        if s.len() == 46 && s.starts_with("Qm") {
+            let bytes = decode_base58(s.as_bytes()).ok_or_else(|| Cid::invalid(s, "bad base58"))?;
+
+            return match check_multihash(&bytes) {
+                Some(SHA2_256) if bytes.len() == 34 => Ok(Cid {
+                    cid: s.into(),
+                    version: 0,
+                    codec: DAG_PB,
+                }),
+                _ => Err(Cid::invalid(s, "not a sha2-256 multihash")),
+            };
+        }
+
+        let bytes =
+            multibase::decode(s).ok_or_else(|| Cid::invalid(s, "bad multibase encoding"))?;
+
+        let mut rest = &bytes[..];
+        let version = read_varint(&mut rest).ok_or_else(|| Cid::invalid(s, "bad version"))?;
+
+        if version != 1 {
+            return Err(Cid::invalid(s, "unsupported version"));
+        }
+
+        let codec = read_varint(&mut rest).ok_or_else(|| Cid::invalid(s, "bad codec"))?;
+
+        check_multihash(rest).ok_or_else(|| Cid::invalid(s, "bad multihash"))?;
+
+        Ok(Cid {
+            cid: s.into(),
+            version,
+            codec,
+        })
+    }
+}
+
+impl fmt::Display for Cid {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.cid)
+    }
+}
+
+impl Deref for Cid {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        &self.cid
+    }
+}
+
+impl AsRef<str> for Cid {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.cid
+    }
+}
+
+impl From<Cid> for String {
+    #[inline]
+    fn from(cid: Cid) -> String {
+        cid.cid
+    }
+}
+
+impl PartialEq<str> for Cid {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.cid == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Cid {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.cid == *other
+    }
+}
+
+impl PartialEq<String> for Cid {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        &self.cid == other
+    }
+}
+
+impl Serialize for Cid {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.cid)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cid {
+    fn deserialize<D>(deserializer: D) -> Result<Cid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Values that can be used where the api expects a CID.
+///
+/// Implemented for `Cid`, and for strings, which are validated before any
+/// request is made.
+///
+pub trait ToCid {
+    fn to_cid(&self) -> Result<Cid, Error>;
+}
+
+impl ToCid for Cid {
+    #[inline]
+    fn to_cid(&self) -> Result<Cid, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl ToCid for str {
+    #[inline]
+    fn to_cid(&self) -> Result<Cid, Error> {
+        self.parse()
+    }
+}
+
+impl ToCid for String {
+    #[inline]
+    fn to_cid(&self) -> Result<Cid, Error> {
+        self.parse()
+    }
+}
+
+impl<T> ToCid for &T
+where
+    T: ToCid + ?Sized,
+{
+    #[inline]
+    fn to_cid(&self) -> Result<Cid, Error> {
+        (**self).to_cid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cid, CidBase, ToCid};
+
+    #[test]
+    fn test_bytes_round_trip() {
+        for s in &[
+            "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+        ] {
+            let cid: Cid = s.parse().unwrap();
+
+            assert_eq!(Cid::from_bytes(&cid.to_bytes()).unwrap(), cid);
+        }
+    }
+
+    #[test]
+    fn test_parse_v0() {
+        let cid: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+
+        assert_eq!(cid.version(), 0);
+        assert_eq!(cid.codec(), 0x70);
+        assert_eq!(cid, "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    }
+
+    #[test]
+    fn test_parse_v1() {
+        let base32 = "bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+        let base58 = "zdj7WWeQ43G6JJvLWQWZpyHuAMq6uYWRjkBXFad11vE2LHhQ7";
+        let cbor = "bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae";
+
+        assert_eq!(base32.parse::<Cid>().unwrap().version(), 1);
+        assert_eq!(base58.parse::<Cid>().unwrap().codec(), 0x70);
+        assert_eq!(cbor.parse::<Cid>().unwrap().codec(), 0x71);
+        assert_eq!(base32.to_uppercase().parse::<Cid>().unwrap().version(), 1);
+
+        let base32: Cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+            .parse()
+            .unwrap();
+        let base36 = "k2jmtxw8rjh1z69c6not3wtdxb0u3urbzhyll1t9jg6ox26dhi5sfi1m";
+        let base64url = "uAXASIMPEcz7Ir_0Gz56f9Q_8a80uyFphcABLtwlmnDHelDka";
+
+        assert_eq!(base36.parse::<Cid>().unwrap().to_bytes(), base32.to_bytes());
+        assert_eq!(
+            base64url.parse::<Cid>().unwrap().to_bytes(),
+            base32.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".to_cid().is_err());
+        assert!("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuF0"
+            .to_cid()
+            .is_err());
+        assert!("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwu"
+            .to_cid()
+            .is_err());
+        assert!("bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyk"
+            .to_cid()
+            .is_err());
+        assert!("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .to_cid()
+            .is_err());
+    }
+
+    #[test]
+    fn test_to_base() {
+        let v0: Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+        let v1 = v0.to_base(CidBase::Base32);
+
+        assert_eq!(v0.to_base(CidBase::Base58Btc), v0);
+        assert_eq!(v1.version(), 1);
+        assert_eq!(v1.codec(), 0x70);
+        assert_eq!(&v1.to_bytes()[2..], &v0.to_bytes()[..]);
+        assert_eq!(v1.multihash(), v0.multihash());
+
+        for base in &[CidBase::Base58Btc, CidBase::Base16, CidBase::Base32] {
+            let cid = v1.to_base(*base);
+
+            assert_eq!(cid.as_str().parse::<Cid>().unwrap(), cid);
+            assert_eq!(cid.to_base(CidBase::Base32), v1);
+        }
+    }
+}
@@ -8,47 +8,113 @@
 #[cfg(feature = "actix")]
 use actix_multipart::client::multipart;
 #[cfg(feature = "actix")]
-use actix_web::HttpMessage;
+use actix_web::{error::PayloadError, HttpMessage};
+#[cfg(feature = "swarm")]
+use addr::Multiaddr;
+use bandwidth::{BandwidthSample, BandwidthSamples};
 use bytes::Bytes;
+use cid::{Cid, CidBase, ToCid};
+#[cfg(feature = "object")]
+use dagpb::DagPbNode;
 use futures::{
     future,
     stream::{self, Stream},
     Future, IntoFuture,
 };
-use header::TRAILER;
+#[cfg(feature = "gzip")]
+use gzip;
+use header::{self, X_STREAM_ERROR};
 use http::uri::{InvalidUri, Uri};
-use http::StatusCode;
+#[cfg(feature = "hyper")]
+use http::HeaderMap;
+use http::{Method, StatusCode};
+#[cfg(feature = "hyper")]
+use hyper::body::Payload;
 #[cfg(feature = "hyper")]
 use hyper::client::{Client, HttpConnector};
 #[cfg(feature = "hyper")]
 use hyper_multipart::client::multipart;
+#[cfg(feature = "tls")]
+use hyper_tls::HttpsConnector;
+use limit::{RequestLimit, WithPermit};
+use logging::{RequestLog, RequestSpan};
+use metrics::{self, MeteredUpload, MetricsSink, RequestMeter};
+#[cfg(feature = "files")]
+use mfs;
+use monitor::{DaemonMonitor, DaemonStatusWatch};
 use multiaddr::{AddrComponent, ToMultiaddr};
-use read::{JsonLineDecoder, LineDecoder, StreamReader};
-use request::{self, ApiRequest};
-use response::{self, Error};
+#[cfg(feature = "tls")]
+use native_tls::{Certificate, Identity, TlsConnector};
+use path::ToIpfsPath;
+#[cfg(feature = "files")]
+use path::ToMfsPath;
+#[cfg(feature = "key")]
+use peer::PeerId;
+use peer::ToPeerId;
+#[cfg(feature = "swarm")]
+use peers::{PeerEvent, PeerEvents};
+#[cfg(feature = "pin")]
+use pinset::{PinsetDiff, PinsetReport};
+use prefetch::Prefetch;
+use progress::{Progress, ProgressStream};
+#[cfg(feature = "pubsub")]
+use pubsub::{self, PubsubMessages, PubsubPublisher, PubsubRouter};
+#[cfg(feature = "hyper")]
+use read::TrailerBody;
+use read::{self, ChunkDecoder, ExpectedLength, JsonLineDecoder, LimitedStream, LineDecoder};
+use refs::RefsWalk;
+use request::{self, ApiRequest, DefaultOptions, GlobalOptions};
+use resolve::DnsResolver;
+#[cfg(feature = "hyper")]
+use resolve::{ResolveError, Resolver};
+#[cfg(feature = "hyper")]
+use response::ReceivedTrailers;
+use response::{self, Error, ErrorContext, ResponseMetadata};
+use resume::{Resume, SkipBytes};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "dag-cbor")]
+use serde_cbor;
 use serde_json;
+#[cfg(feature = "pin")]
+use std::collections::HashSet;
 use std::{
+    collections::HashMap,
     fs,
-    io::Read,
-    net::{IpAddr, SocketAddr},
-    path::{Path, PathBuf},
+    io::{self, Read},
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio_codec::{Decoder, FramedRead};
+#[cfg(feature = "files")]
+use std::{io::Write, path::PathBuf};
+use tokio::timer::{Delay, Timeout};
+use tokio_codec::Decoder;
+use tree::{self, AddTreeOptions};
+use unpack::{self, OverwritePolicy};
+use upload::{self, FormBody, StreamPart, Upload};
+#[cfg(feature = "object")]
+use walk::{Walk, WalkNode};
 
 /// A response returned by the HTTP client.
 ///
+/// With the hyper backend, responses are `Send`, and can be spawned onto a
+/// multi-threaded executor. The actix client runs on a single-threaded
+/// arbiter, so its responses are not.
+///
 #[cfg(feature = "actix")]
-type AsyncResponse<T> = Box<Future<Item = T, Error = Error> + 'static>;
+pub type AsyncResponse<T> = Box<dyn Future<Item = T, Error = Error> + 'static>;
 #[cfg(feature = "hyper")]
-type AsyncResponse<T> = Box<Future<Item = T, Error = Error> + Send + 'static>;
+pub type AsyncResponse<T> = Box<dyn Future<Item = T, Error = Error> + Send + 'static>;
 
 /// A future that returns a stream of responses.
 ///
+/// `Send` with the hyper backend, like `AsyncResponse`.
+///
 #[cfg(feature = "actix")]
-type AsyncStreamResponse<T> = Box<Stream<Item = T, Error = Error> + 'static>;
+pub type AsyncStreamResponse<T> = Box<dyn Stream<Item = T, Error = Error> + 'static>;
 #[cfg(feature = "hyper")]
-type AsyncStreamResponse<T> = Box<Stream<Item = T, Error = Error> + Send + 'static>;
+pub type AsyncStreamResponse<T> = Box<dyn Stream<Item = T, Error = Error> + Send + 'static>;
 
 #[cfg(feature = "actix")]
 type Request = actix_web::client::ClientRequest;
@@ -60,13 +126,39 @@ type Response = actix_web::client::ClientResponse;
 #[cfg(feature = "hyper")]
 type Response = http::Response<hyper::Body>;
 
+#[cfg(all(feature = "hyper", not(feature = "tls")))]
+type Connector = HttpConnector<Resolver>;
+#[cfg(all(feature = "hyper", feature = "tls"))]
+type Connector = HttpsConnector<HttpConnector<Resolver>>;
+
 /// Asynchronous Ipfs client.
 ///
+/// Cloning the client is cheap: clones share the same connection pool and
+/// settings, so a single client can be handed to many tasks or threads.
+///
 #[derive(Clone)]
 pub struct IpfsClient {
+    inner: Arc<ClientInner>,
+    options: GlobalOptions,
+}
+
+/// State shared between clones of an `IpfsClient`.
+///
+struct ClientInner {
     base: Uri,
     #[cfg(feature = "hyper")]
-    client: Client<HttpConnector, hyper::Body>,
+    client: Client<Connector, hyper::Body>,
+    limit: RequestLimit,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    strict: bool,
+    max_line_length: usize,
+    upload_chunk_size: usize,
+    read_buffer_size: usize,
+    max_response_size: Option<usize>,
+    #[cfg(feature = "hyper")]
+    max_redirects: usize,
+    defaults: DefaultOptions,
+    error_context: bool,
 }
 
 impl Default for IpfsClient {
@@ -74,6 +166,63 @@ impl Default for IpfsClient {
     /// If not found, tries to connect to `localhost:5001`.
     ///
     fn default() -> IpfsClient {
+        IpfsClientBuilder::default().build().unwrap()
+    }
+}
+
+impl From<SocketAddr> for IpfsClient {
+    fn from(socket_addr: SocketAddr) -> Self {
+        IpfsClientBuilder::from(socket_addr).build().unwrap()
+    }
+}
+
+/// Builds an `IpfsClient` with custom settings.
+///
+/// ```no_run
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::IpfsClientBuilder;
+///
+/// # fn main() {
+/// let client = IpfsClientBuilder::new("localhost", 5001).build().unwrap();
+/// # }
+/// ```
+///
+#[derive(Clone)]
+pub struct IpfsClientBuilder {
+    host: String,
+    port: u16,
+    http2: bool,
+    #[cfg(feature = "tls")]
+    https: bool,
+    #[cfg(feature = "tls")]
+    identity: Option<Identity>,
+    #[cfg(feature = "tls")]
+    root_certificates: Vec<Certificate>,
+    max_in_flight: Option<usize>,
+    metrics: Option<Arc<dyn MetricsSink>>,
+    strict: bool,
+    max_line_length: usize,
+    upload_chunk_size: usize,
+    read_buffer_size: usize,
+    max_response_size: Option<usize>,
+    offline: bool,
+    cid_base: Option<CidBase>,
+    max_redirects: usize,
+    keep_alive: Option<bool>,
+    idle_timeout: Option<Duration>,
+    hosts: HashMap<String, Vec<IpAddr>>,
+    dns_resolver: Option<Arc<dyn DnsResolver>>,
+    defaults: DefaultOptions,
+    defaults_error: Option<::serde_urlencoded::ser::Error>,
+    error_context: bool,
+}
+
+impl Default for IpfsClientBuilder {
+    /// Creates a builder for the endpoint specified in ~/.ipfs/api.
+    /// If not found, uses `localhost:5001`.
+    ///
+    fn default() -> IpfsClientBuilder {
         dirs::home_dir()
             .map(|home_dir| home_dir.join(".ipfs").join("api"))
             .and_then(|multiaddr_path| fs::read_to_string(&multiaddr_path).ok())
@@ -97,495 +246,3352 @@ impl Default for IpfsClient {
                     None
                 }
             })
-            .map(IpfsClient::from)
-            .unwrap_or_else(|| IpfsClient::new("localhost", 5001).unwrap())
+            .map(IpfsClientBuilder::from)
+            .unwrap_or_else(|| IpfsClientBuilder::new("localhost", 5001))
     }
 }
 
-impl From<SocketAddr> for IpfsClient {
+impl From<SocketAddr> for IpfsClientBuilder {
     fn from(socket_addr: SocketAddr) -> Self {
-        IpfsClient::new(&socket_addr.ip().to_string(), socket_addr.port()).unwrap()
+        IpfsClientBuilder::new(&socket_addr.ip().to_string(), socket_addr.port())
     }
 }
 
-impl IpfsClient {
-    /// Creates a new `IpfsClient`.
+impl IpfsClientBuilder {
+    /// Creates a builder for a client connecting to `host` and `port`.
     ///
     #[inline]
-    pub fn new(host: &str, port: u16) -> Result<IpfsClient, InvalidUri> {
-        let base_path = IpfsClient::build_base_path(host, port)?;
-
-        Ok(IpfsClient {
-            base: base_path,
-            #[cfg(feature = "hyper")]
-            client: Client::builder().keep_alive(false).build_http(),
-        })
+    pub fn new(host: &str, port: u16) -> IpfsClientBuilder {
+        IpfsClientBuilder {
+            host: host.into(),
+            port,
+            http2: false,
+            #[cfg(feature = "tls")]
+            https: false,
+            #[cfg(feature = "tls")]
+            identity: None,
+            #[cfg(feature = "tls")]
+            root_certificates: Vec::new(),
+            max_in_flight: None,
+            metrics: None,
+            strict: false,
+            max_line_length: read::DEFAULT_MAX_LINE_LENGTH,
+            upload_chunk_size: upload::DEFAULT_CHUNK_SIZE,
+            read_buffer_size: upload::DEFAULT_READ_BUFFER_SIZE,
+            max_response_size: None,
+            offline: false,
+            cid_base: None,
+            max_redirects: 0,
+            keep_alive: None,
+            idle_timeout: None,
+            hosts: HashMap::new(),
+            dns_resolver: None,
+            defaults: DefaultOptions::default(),
+            defaults_error: None,
+            error_context: false,
+        }
     }
 
-    /// Builds the base url path for the Ipfs api.
+    /// Speaks HTTP/2 to the daemon, without first negotiating an upgrade
+    /// (h2c with prior knowledge).
     ///
-    fn build_base_path(host: &str, port: u16) -> Result<Uri, InvalidUri> {
-        format!("http://{}:{}/api/v0", host, port).parse()
-    }
-
-    /// Builds the url for an api call.
+    /// Concurrent requests are then multiplexed over a single connection,
+    /// instead of opening a socket for each one. The daemon, or a proxy in
+    /// front of it, has to accept HTTP/2. Only supported by the hyper backend.
     ///
-    fn build_base_request<Req>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> Result<Request, Error>
-    where
-        Req: ApiRequest + Serialize,
-    {
-        let url = format!(
-            "{}{}?{}",
-            self.base,
-            Req::PATH,
-            ::serde_urlencoded::to_string(req)?
-        );
-        #[cfg(feature = "hyper")]
-        let req = url.parse::<Uri>().map_err(From::from).and_then(move |url| {
-            let mut builder = http::Request::builder();
-            let mut builder = builder.method(Req::METHOD.clone()).uri(url);
-
-            let req = if let Some(form) = form {
-                form.set_body_convert::<hyper::Body, multipart::Body>(&mut builder)
-            } else {
-                builder.body(hyper::Body::empty())
-            };
-
-            req.map_err(From::from)
-        });
-        #[cfg(feature = "actix")]
-        let req = if let Some(form) = form {
-            Request::build()
-                .method(Req::METHOD.clone())
-                .uri(url)
-                .content_type(form.content_type())
-                .streaming(multipart::Body::from(form))
-                .map_err(From::from)
-        } else {
-            Request::build()
-                .method(Req::METHOD.clone())
-                .uri(url)
-                .finish()
-                .map_err(From::from)
-        };
-        req
+    #[inline]
+    pub fn http2_only(mut self, http2: bool) -> IpfsClientBuilder {
+        self.http2 = http2;
+        self
     }
 
-    /// Builds an Api error from a response body.
+    /// Keeps connections to the daemon open between requests, so they can be
+    /// reused instead of opening a socket for each request.
+    ///
+    /// Disabled by default for HTTP/1, since a connection that sat idle
+    /// behind a NAT or load balancer may have been dropped without the
+    /// client noticing, failing the next request sent over it. Enabled by
+    /// default with `http2_only`. Only supported by the hyper backend.
     ///
     #[inline]
-    fn build_error_from_body(chunk: Bytes) -> Error {
-        match serde_json::from_slice(&chunk) {
-            Ok(e) => Error::Api(e),
-            Err(_) => match String::from_utf8(chunk.to_vec()) {
-                Ok(s) => Error::Uncategorized(s),
-                Err(e) => e.into(),
-            },
-        }
+    pub fn keep_alive(mut self, keep_alive: bool) -> IpfsClientBuilder {
+        self.keep_alive = Some(keep_alive);
+        self
     }
 
-    /// Processes a response that expects a json encoded body, returning an
-    /// error or a deserialized json response.
+    /// Closes connections that have been idle for longer than `timeout`,
+    /// instead of the default of 90 seconds. Setting it below the idle
+    /// timeout of any NAT or load balancer between the client and the daemon
+    /// avoids reusing connections they have already dropped.
     ///
-    fn process_json_response<Res>(status: StatusCode, chunk: Bytes) -> Result<Res, Error>
-    where
-        for<'de> Res: 'static + Deserialize<'de>,
-    {
-        match status {
-            StatusCode::OK => serde_json::from_slice(&chunk).map_err(From::from),
-            _ => Err(Self::build_error_from_body(chunk)),
-        }
-    }
-
-    /// Processes a response that returns a stream of json deserializable
-    /// results.
+    /// Only has an effect when connections are kept alive. Only supported by
+    /// the hyper backend.
     ///
-    fn process_stream_response<D, Res>(res: Response, decoder: D) -> AsyncStreamResponse<Res>
-    where
-        D: 'static + Decoder<Item = Res, Error = Error> + Send,
-        Res: 'static,
-    {
-        #[cfg(feature = "hyper")]
-        let stream = FramedRead::new(
-            StreamReader::new(res.into_body().map(|c| c.into_bytes()).from_err()),
-            decoder,
-        );
-
-        #[cfg(feature = "actix")]
-        let stream = FramedRead::new(StreamReader::new(res.payload().from_err()), decoder);
-
-        Box::new(stream)
+    #[inline]
+    pub fn idle_timeout(mut self, timeout: Duration) -> IpfsClientBuilder {
+        self.idle_timeout = Some(timeout);
+        self
     }
 
-    /// Generates a request, and returns the unprocessed response future.
+    /// Sets options to add to every request to the api path of `req`, unless
+    /// the request sets them itself. Positional arguments (e.g. the key of a
+    /// `PinAdd`) are ignored. Calling it again for the same kind of request
+    /// replaces its defaults.
     ///
-    fn request_raw<Req>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncResponse<(StatusCode, Bytes)>
-    where
-        Req: ApiRequest + Serialize,
-    {
-        match self.build_base_request(req, form) {
-            Ok(req) => {
-                #[cfg(feature = "hyper")]
-                let res = self
-                    .client
-                    .request(req)
-                    .and_then(|res| {
-                        let status = res.status();
-
-                        res.into_body()
-                            .concat2()
-                            .map(move |chunk| (status, chunk.into_bytes()))
-                    })
-                    .from_err();
-                #[cfg(feature = "actix")]
-                let res = req
-                    .send()
-                    .timeout(std::time::Duration::from_secs(90))
-                    .from_err()
-                    .and_then(|x| {
-                        let status = x.status();
-                        x.body().map(move |body| (status, body)).from_err()
-                    });
-                Box::new(res)
-            }
-            Err(e) => Box::new(Err(e).into_future()),
-        }
-    }
-
-    /// Generic method for making a request that expects back a streaming
-    /// response.
+    /// This covers every method that sends the same request, so the defaults
+    /// for `Add` are used by `add`, `add_path`, `add_tree` and the others.
     ///
-    fn request_stream<Req, Res, F>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-        process: F,
-    ) -> AsyncStreamResponse<Res>
-    where
-        Req: ApiRequest + Serialize,
-        Res: 'static + Send,
-        F: 'static + Fn(Response) -> AsyncStreamResponse<Res> + Send,
-    {
-        #[cfg(feature = "hyper")]
-        match self.build_base_request(req, form) {
-            Ok(req) => {
-                let res = self
-                    .client
-                    .request(req)
-                    .from_err()
-                    .map(move |res| {
-                        let stream: Box<Stream<Item = Res, Error = _> + Send + 'static> =
-                            match res.status() {
-                                StatusCode::OK => process(res),
-                                // If the server responded with an error status code, the body
-                                // still needs to be read so an error can be built. This block will
-                                // read the entire body stream, then immediately return an error.
-                                //
-                                _ => Box::new(
-                                    res.into_body()
-                                        .concat2()
-                                        .from_err()
-                                        .and_then(|chunk| {
-                                            Err(Self::build_error_from_body(chunk.into_bytes()))
-                                        })
-                                        .into_stream(),
-                                ),
-                            };
-
-                        stream
-                    })
-                    .flatten_stream();
-                Box::new(res)
-            }
-            Err(e) => Box::new(stream::once(Err(e))),
-        }
-        #[cfg(feature = "actix")]
-        match self.build_base_request(req, form) {
-            Ok(req) => {
-                let res = req
-                    .send()
-                    .timeout(std::time::Duration::from_secs(90))
-                    .from_err();
-                Box::new(res.map(process).flatten_stream())
-            }
-            Err(e) => Box::new(stream::once(Err(e))),
-        }
-    }
-
-    /// Generic method for making a request to the Ipfs server, and getting
-    /// a deserializable response.
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{request, IpfsClientBuilder};
     ///
-    fn request<Req, Res>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncResponse<Res>
-    where
-        Req: ApiRequest + Serialize,
-        for<'de> Res: 'static + Deserialize<'de> + Send,
-    {
-        let res = self
-            .request_raw(req, form)
-            .and_then(|(status, chunk)| IpfsClient::process_json_response(status, chunk));
-
-        Box::new(res)
-    }
-
-    /// Generic method for making a request to the Ipfs server, and getting
-    /// back a response with no body.
+    /// # fn main() {
+    /// let client = IpfsClientBuilder::default()
+    ///     .default_options(&request::Add {
+    ///         cid_version: Some(1),
+    ///         raw_leaves: Some(true),
+    ///         ..Default::default()
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// # }
+    /// ```
     ///
-    fn request_empty<Req>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncResponse<()>
+    pub fn default_options<Req>(mut self, req: &Req) -> IpfsClientBuilder
     where
         Req: ApiRequest + Serialize,
     {
-        let res = self
-            .request_raw(req, form)
-            .and_then(|(status, chunk)| match status {
-                StatusCode::OK => Ok(()),
-                _ => Err(Self::build_error_from_body(chunk)),
-            });
+        if let Err(e) = self.defaults.set(req) {
+            self.defaults_error.get_or_insert(e);
+        }
 
-        Box::new(res)
+        self
     }
 
-    /// Generic method for making a request to the Ipfs server, and getting
-    /// back a raw String response.
+    /// Connects to `addr` whenever the client would look up `host`, without
+    /// querying DNS. Calling it again for the same host adds another address
+    /// to try, in order.
     ///
-    fn request_string<Req>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncResponse<String>
+    /// The port is still the one the client was built with. Only supported by
+    /// the hyper backend.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClientBuilder;
+    /// use std::net::Ipv4Addr;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClientBuilder::new("ipfs.internal", 5001)
+    ///     .resolve("ipfs.internal", Ipv4Addr::new(10, 0, 0, 12))
+    ///     .build()
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn resolve<A>(mut self, host: &str, addr: A) -> IpfsClientBuilder
     where
-        Req: ApiRequest + Serialize,
+        A: Into<IpAddr>,
     {
-        let res = self
-            .request_raw(req, form)
-            .and_then(|(status, chunk)| match status {
-                StatusCode::OK => String::from_utf8(chunk.to_vec()).map_err(From::from),
-                _ => Err(Self::build_error_from_body(chunk)),
-            });
-
-        Box::new(res)
+        self.hosts.entry(host.into()).or_default().push(addr.into());
+        self
     }
 
-    /// Generic method for making a request to the Ipfs server, and getting
-    /// back a raw stream of bytes.
+    /// Looks up host names with `resolver` instead of the system resolver,
+    /// for environments where the api host can only be resolved through a
+    /// particular DNS server or service registry. Hosts given to `resolve`
+    /// are never passed to it.
     ///
-    fn request_stream_bytes<Req>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncStreamResponse<Bytes>
+    /// Only supported by the hyper backend.
+    ///
+    #[inline]
+    pub fn dns_resolver<R>(mut self, resolver: R) -> IpfsClientBuilder
     where
-        Req: ApiRequest + Serialize,
+        R: 'static + DnsResolver,
     {
-        #[cfg(feature = "hyper")]
-        let res = self.request_stream(req, form, |res| {
-            Box::new(res.into_body().from_err().map(|c| c.into_bytes()))
-        });
-        #[cfg(feature = "actix")]
-        let res = self.request_stream(req, form, |res| Box::new(res.payload().from_err()));
-        res
+        self.dns_resolver = Some(Arc::new(resolver));
+        self
     }
 
-    /// Generic method to return a streaming response of deserialized json
-    /// objects delineated by new line separators.
+    /// Connects to the daemon over https.
     ///
-    fn request_stream_json<Req, Res>(
-        &self,
-        req: &Req,
-        form: Option<multipart::Form<'static>>,
-    ) -> AsyncStreamResponse<Res>
-    where
-        Req: ApiRequest + Serialize,
-        for<'de> Res: 'static + Deserialize<'de> + Send,
-    {
-        self.request_stream(req, form, |res| {
-            let parse_stream_error = if let Some(trailer) = res.headers().get(TRAILER) {
-                // Response has the Trailer header set. The StreamError trailer
-                // is used to indicate that there was an error while streaming
-                // data with Ipfs.
-                //
-                if trailer == "X-Stream-Error" {
-                    true
-                } else {
-                    let err = Error::UnrecognizedTrailerHeader(
-                        String::from_utf8_lossy(trailer.as_ref()).into(),
-                    );
-
-                    // There was an unrecognized trailer value. If that is the case,
-                    // create a stream that immediately errors.
-                    //
-                    return Box::new(stream::once(Err(err)));
-                }
-            } else {
-                false
-            };
-
-            Box::new(IpfsClient::process_stream_response(
-                res,
-                JsonLineDecoder::new(parse_stream_error),
-            ))
-        })
+    /// Requires the `tls` feature.
+    ///
+    #[cfg(feature = "tls")]
+    #[inline]
+    pub fn https(mut self, https: bool) -> IpfsClientBuilder {
+        self.https = https;
+        self
     }
-}
 
-impl IpfsClient {
-    /// Add a file to IPFS.
+    /// Authenticates with a client certificate and key (mutual TLS), for
+    /// daemons behind a proxy that requires one. Implies `https(true)`.
     ///
-    /// # Examples
+    /// Requires the `tls` feature.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
-    /// use std::io::Cursor;
+    /// use ipfs_api::{Identity, IpfsClientBuilder};
+    /// use std::fs;
     ///
     /// # fn main() {
-    /// let client = IpfsClient::default();
-    /// let data = Cursor::new("Hello World!");
-    /// let req = client.add(data);
+    /// let pkcs12 = fs::read("client.p12").unwrap();
+    /// let identity = Identity::from_pkcs12(&pkcs12, "password").unwrap();
+    /// let client = IpfsClientBuilder::new("ipfs.example.com", 443)
+    ///     .client_identity(identity)
+    ///     .build()
+    ///     .unwrap();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "tls")]
     #[inline]
-    pub fn add<R>(&self, data: R) -> AsyncResponse<response::AddResponse>
-    where
-        R: 'static + Read + Send,
-    {
-        let mut form = multipart::Form::default();
-
-        form.add_reader("path", data);
+    pub fn client_identity(mut self, identity: Identity) -> IpfsClientBuilder {
+        self.https = true;
+        self.identity = Some(identity);
+        self
+    }
 
-        self.request(&request::Add::default(), Some(form))
+    /// Trusts an additional root certificate when connecting over https,
+    /// such as the private CA of a proxy.
+    ///
+    /// Requires the `tls` feature.
+    ///
+    #[cfg(feature = "tls")]
+    #[inline]
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> IpfsClientBuilder {
+        self.root_certificates.push(certificate);
+        self
     }
 
-    /// Add a file to IPFS with options.
+    /// Limits the number of requests the client has in flight at once.
     ///
-    /// # Examples
+    /// Requests over the limit wait for an earlier one to finish before they
+    /// are sent. A request is in flight until its response body has been
+    /// read, or, for streaming responses, until the stream ends or is dropped.
+    /// By default, there is no limit. A limit of 0 is treated as 1.
     ///
-    /// ```no_run
+    #[inline]
+    pub fn max_in_flight(mut self, max: usize) -> IpfsClientBuilder {
+        self.max_in_flight = Some(max);
+        self
+    }
+
+    /// Reports metrics for every request made by the client to `sink`.
+    ///
+    #[inline]
+    pub fn metrics_sink<S>(mut self, sink: S) -> IpfsClientBuilder
+    where
+        S: 'static + MetricsSink,
+    {
+        self.metrics = Some(Arc::new(sink));
+        self
+    }
+
+    /// Makes fields in api responses that the client doesn't know about an
+    /// error (`Error::UnknownFields`), instead of ignoring them.
+    ///
+    /// This is useful for testing against a pinned daemon version, to catch
+    /// responses that have changed. By default, unknown fields are ignored,
+    /// so the client keeps working with newer daemons.
+    ///
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> IpfsClientBuilder {
+        self.strict = strict;
+        self
+    }
+
+    /// Wraps every error in `Error::Request`, which names the api path and
+    /// arguments of the request that failed (e.g.
+    /// `/files/write arg=/foo create=true: ...`), to tell which of many
+    /// requests an error came from.
+    ///
+    /// Use `Error::without_context` to match on the error itself.
+    ///
+    #[inline]
+    pub fn error_context(mut self, error_context: bool) -> IpfsClientBuilder {
+        self.error_context = error_context;
+        self
+    }
+
+    /// The longest line a streamed json response (e.g. `pin_add_progress`
+    /// or `dht_findprovs`) can have, in bytes. A longer line is returned as
+    /// `Error::LineTooLong` instead of being buffered, and the stream
+    /// continues with the next line. Defaults to 64 MiB.
+    ///
+    #[inline]
+    pub fn max_line_length(mut self, max_line_length: usize) -> IpfsClientBuilder {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// The size of the chunks data is sent to the daemon in by `add`,
+    /// `files_write`, and the other requests that upload data, in bytes.
+    /// Larger chunks can improve throughput for large uploads, at the cost
+    /// of memory: up to 8 chunks are read ahead of the request. Defaults to
+    /// 64 KiB.
+    ///
+    #[inline]
+    pub fn upload_chunk_size(mut self, upload_chunk_size: usize) -> IpfsClientBuilder {
+        self.upload_chunk_size = upload_chunk_size;
+        self
+    }
+
+    /// The size of the buffer local files are read through by `add_tree`,
+    /// `add_path` and `files_write_tree`, in bytes. Defaults to 64 KiB.
+    ///
+    #[inline]
+    pub fn read_buffer_size(mut self, read_buffer_size: usize) -> IpfsClientBuilder {
+        self.read_buffer_size = read_buffer_size;
+        self
+    }
+
+    /// The largest response body, in bytes, that is read into memory before
+    /// being returned, for requests that resolve to a single value. A larger
+    /// response fails with `Error::ResponseTooLarge` as soon as the limit is
+    /// passed. Streamed responses (e.g. `cat`) are not limited.
+    ///
+    /// By default, responses aren't limited.
+    ///
+    #[inline]
+    pub fn max_response_size(mut self, max_response_size: usize) -> IpfsClientBuilder {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
+    /// Runs every command without using the network (`offline=true`), so
+    /// e.g. `cat` and `block_get` fail instead of fetching missing blocks
+    /// from other peers. See `IpfsClient::with_offline` to change this for
+    /// a single handle.
+    ///
+    #[inline]
+    pub fn offline(mut self, offline: bool) -> IpfsClientBuilder {
+        self.offline = offline;
+        self
+    }
+
+    /// Follows up to `max` redirects (`301`, `302`, `303`, `307` and `308`),
+    /// for gateways and proxies that redirect to the canonical api host.
+    /// A `303`, or a `301` or `302` after an upload, is followed with a `GET`
+    /// without a body. Other redirects repeat the request with the same
+    /// method and headers, so they are only followed for requests without a
+    /// body (i.e. that don't upload anything). Credentials are dropped when
+    /// a redirect leads to another host. More redirects than `max` fail with
+    /// `Error::TooManyRedirects`.
+    ///
+    /// By default, redirects aren't followed, and fail like any other
+    /// unexpected status. Only supported by the hyper backend.
+    ///
+    #[inline]
+    pub fn max_redirects(mut self, max: usize) -> IpfsClientBuilder {
+        self.max_redirects = max;
+        self
+    }
+
+    /// Asks the daemon to write the CIDv1s in its responses in `base`
+    /// (`cid-base`), for the commands that support it. CIDs the daemon
+    /// returns in another encoding can be converted with `Cid::to_base`.
+    /// See `IpfsClient::with_cid_base` to change this for a single handle.
+    ///
+    #[inline]
+    pub fn cid_base(mut self, base: CidBase) -> IpfsClientBuilder {
+        self.cid_base = Some(base);
+        self
+    }
+
+    /// The url scheme used to reach the daemon.
+    ///
+    fn scheme(&self) -> &'static str {
+        #[cfg(feature = "tls")]
+        {
+            if self.https {
+                return "https";
+            }
+        }
+
+        "http"
+    }
+
+    #[cfg(feature = "hyper")]
+    fn build_resolver(&self) -> Resolver {
+        Resolver::new(self.hosts.clone(), self.dns_resolver.clone())
+    }
+
+    /// Builds the connector the http client dials the daemon with.
+    ///
+    #[cfg(all(feature = "hyper", not(feature = "tls")))]
+    fn build_connector(&self) -> Result<Connector, Error> {
+        Ok(HttpConnector::new_with_resolver(self.build_resolver()))
+    }
+
+    #[cfg(all(feature = "hyper", feature = "tls"))]
+    fn build_connector(&self) -> Result<Connector, Error> {
+        let mut http = HttpConnector::new_with_resolver(self.build_resolver());
+        let mut tls = TlsConnector::builder();
+
+        http.enforce_http(false);
+
+        if let Some(ref identity) = self.identity {
+            tls.identity(identity.clone());
+        }
+
+        for certificate in &self.root_certificates {
+            tls.add_root_certificate(certificate.clone());
+        }
+
+        Ok(HttpsConnector::from((http, tls.build()?)))
+    }
+
+    /// Creates the `IpfsClient`.
+    ///
+    pub fn build(self) -> Result<IpfsClient, Error> {
+        if let Some(e) = self.defaults_error {
+            return Err(e.into());
+        }
+
+        let base_path = IpfsClient::build_base_path(self.scheme(), &self.host, self.port)?;
+
+        Ok(IpfsClient {
+            inner: Arc::new(ClientInner {
+                base: base_path,
+                #[cfg(feature = "hyper")]
+                client: {
+                    let mut builder = Client::builder();
+
+                    if let Some(timeout) = self.idle_timeout {
+                        builder.keep_alive_timeout(timeout);
+                    }
+
+                    builder
+                        .keep_alive(self.keep_alive.unwrap_or(self.http2))
+                        .http2_only(self.http2)
+                        .build(self.build_connector()?)
+                },
+                limit: RequestLimit::new(self.max_in_flight),
+                metrics: self.metrics,
+                strict: self.strict,
+                max_line_length: self.max_line_length,
+                upload_chunk_size: self.upload_chunk_size,
+                read_buffer_size: self.read_buffer_size,
+                max_response_size: self.max_response_size,
+                #[cfg(feature = "hyper")]
+                max_redirects: self.max_redirects,
+                defaults: self.defaults,
+                error_context: self.error_context,
+            }),
+            options: GlobalOptions {
+                offline: if self.offline { Some(true) } else { None },
+                timeout: None,
+                cid_base: self.cid_base,
+            },
+        })
+    }
+}
+
+impl IpfsClient {
+    /// Creates a new `IpfsClient`.
+    ///
+    #[inline]
+    pub fn new(host: &str, port: u16) -> Result<IpfsClient, InvalidUri> {
+        match IpfsClientBuilder::new(host, port).build() {
+            Ok(client) => Ok(client),
+            Err(Error::Url(e)) => Err(e),
+            Err(e) => panic!("failed to build a plain http client: {}", e),
+        }
+    }
+
+    /// Creates a new `IpfsClient` connecting to an IPv4 or IPv6 address.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::net::Ipv6Addr;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::from_ip(Ipv6Addr::LOCALHOST, 5001);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn from_ip<A>(addr: A, port: u16) -> IpfsClient
+    where
+        A: Into<IpAddr>,
+    {
+        IpfsClient::from(SocketAddr::new(addr.into(), port))
+    }
+
+    /// Returns a handle to the same client that runs commands without using
+    /// the network (`offline=true`), or with it if `offline` is false. The
+    /// handle shares the connection pool and other settings with `self`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client
+    ///     .with_offline(true)
+    ///     .block_get("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn with_offline(&self, offline: bool) -> IpfsClient {
+        let mut client = self.clone();
+
+        client.options.offline = if offline { Some(true) } else { None };
+        client
+    }
+
+    /// Returns a handle to the same client whose commands the daemon
+    /// cancels after `timeout` (the `timeout` query parameter), failing them
+    /// with `Error::DeadlineExceeded`. This bounds slow operations, such as
+    /// DHT queries and name resolution, on the daemon's side, even while the
+    /// connection stays open.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client
+    ///     .with_timeout(Duration::from_secs(30))
+    ///     .id(Some("QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM"));
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn with_timeout(&self, timeout: Duration) -> IpfsClient {
+        let mut client = self.clone();
+
+        client.options.timeout = Some(request::format_duration(timeout));
+        client
+    }
+
+    /// Returns a handle to the same client that asks the daemon to write the
+    /// CIDv1s in its responses in `base` (`cid-base`), or in the daemon's
+    /// default if `base` is `None`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{CidBase, IpfsClient};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.with_cid_base(Some(CidBase::Base32)).refs_local();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn with_cid_base(&self, base: Option<CidBase>) -> IpfsClient {
+        let mut client = self.clone();
+
+        client.options.cid_base = base;
+        client
+    }
+
+    /// Returns a handle to the same client without a server-side timeout.
+    ///
+    #[inline]
+    pub fn without_timeout(&self) -> IpfsClient {
+        let mut client = self.clone();
+
+        client.options.timeout = None;
+        client
+    }
+
+    /// Calls an api endpoint that the client has no method for, returning
+    /// the raw response body.
+    ///
+    /// `path` is relative to `/api/v0`, and starts with a `/`. Every pair in
+    /// `params` is added to the query string; repeat a key (usually `arg`) to
+    /// pass several values. If `data` is given, it is uploaded as a file in a
+    /// multipart body, and the request is sent as a `POST`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.call_raw("/cid/base32", &[("arg", hash)], None);
+    /// # }
+    /// ```
+    ///
+    pub fn call_raw(
+        &self,
+        path: &'static str,
+        params: &[(&str, &str)],
+        data: Option<Box<dyn Read + Send>>,
+    ) -> AsyncResponse<Bytes> {
+        let res = self
+            .call_raw_with_metadata(path, params, data)
+            .map(|(_, body)| body);
+
+        Box::new(res)
+    }
+
+    /// Like `call_raw`, but also returns the status and headers of the
+    /// response.
+    ///
+    pub fn call_raw_with_metadata(
+        &self,
+        path: &'static str,
+        params: &[(&str, &str)],
+        data: Option<Box<dyn Read + Send>>,
+    ) -> AsyncResponse<(ResponseMetadata, Bytes)> {
+        let (method, form) = match data {
+            Some(data) => {
+                let mut form = multipart::Form::default();
+
+                form.add_reader("file", data);
+
+                (&Method::POST, Some(form.into()))
+            }
+            None => (&Method::GET, None),
+        };
+
+        let res = self
+            .request_raw_with_metadata(path, method, &params, form)
+            .and_then(|(metadata, chunk)| match metadata.status {
+                StatusCode::OK => Ok((metadata, chunk)),
+                status => Err(Self::build_error_from_body(status, chunk)),
+            });
+
+        Box::new(res)
+    }
+
+    /// Calls an api endpoint that the client has no method for, and
+    /// deserializes the json response into `Res`.
+    ///
+    /// Takes the same arguments as `call_raw`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # extern crate serde_json;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use serde_json::Value;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.call_json::<Value>("/repo/version", &[], None);
+    /// # }
+    /// ```
+    ///
+    pub fn call_json<Res>(
+        &self,
+        path: &'static str,
+        params: &[(&str, &str)],
+        data: Option<Box<dyn Read + Send>>,
+    ) -> AsyncResponse<Res>
+    where
+        for<'de> Res: 'static + Deserialize<'de> + Send,
+    {
+        let strict = self.inner.strict;
+        let res = self
+            .call_raw(path, params, data)
+            .and_then(move |chunk| read::from_json_slice(&chunk, strict));
+
+        Box::new(res)
+    }
+
+    /// Sends a request from the `request` module, and deserializes the json
+    /// response into `Res`, along with the status and headers of the
+    /// response.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{request, response::VersionResponse, IpfsClient};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.request_with_metadata::<_, VersionResponse>(&request::Version);
+    /// # }
+    /// ```
+    ///
+    pub fn request_with_metadata<Req, Res>(
+        &self,
+        req: &Req,
+    ) -> AsyncResponse<(ResponseMetadata, Res)>
+    where
+        Req: ApiRequest + Serialize,
+        for<'de> Res: 'static + Deserialize<'de> + Send,
+    {
+        let strict = self.inner.strict;
+        let max_response_size = self.inner.max_response_size;
+        let res = self
+            .request_stream_with_metadata(req, None, IpfsClient::process_bytes_response)
+            .and_then(move |(metadata, body)| {
+                read::concat_bytes(LimitedStream::new(body, max_response_size))
+                    .and_then(move |body| read::from_json_slice(&body, strict))
+                    .map(move |res| (metadata, res))
+            });
+
+        self.with_error_context(Req::PATH, req, Box::new(res))
+    }
+
+    /// Builds the base url path for the Ipfs api.
+    ///
+    /// IPv6 literals are wrapped in brackets, as urls require.
+    ///
+    fn build_base_path(scheme: &str, host: &str, port: u16) -> Result<Uri, InvalidUri> {
+        if host.parse::<Ipv6Addr>().is_ok() {
+            format!("{}://[{}]:{}/api/v0", scheme, host, port).parse()
+        } else {
+            format!("{}://{}:{}/api/v0", scheme, host, port).parse()
+        }
+    }
+
+    /// Builds the url for an api call.
+    ///
+    #[inline]
+    fn build_base_request<Req>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+        meter: &RequestMeter,
+    ) -> Result<Request, Error>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        self.build_request(Req::PATH, Req::METHOD, req, form, meter)
+    }
+
+    /// Builds the url for a call to an api path, with `query` serialized as
+    /// its query string. The bytes of an upload are counted by `meter` as
+    /// they are sent.
+    ///
+    fn build_request<Q>(
+        &self,
+        path: &str,
+        method: &Method,
+        query: &Q,
+        form: Option<Upload>,
+        meter: &RequestMeter,
+    ) -> Result<Request, Error>
+    where
+        Q: Serialize,
+    {
+        let mut query = self.options.encode_query(query)?;
+
+        self.inner.defaults.apply(path, &mut query);
+
+        let url = format!("{}{}?{}", self.inner.base, path, query);
+        let chunk_size = self.inner.upload_chunk_size;
+
+        #[cfg(feature = "hyper")]
+        let req = url.parse::<Uri>().map_err(From::from).and_then(move |url| {
+            let mut builder = http::Request::builder();
+            let builder = builder.method(method.clone()).uri(url);
+
+            #[cfg(feature = "gzip")]
+            builder.header(http::header::ACCEPT_ENCODING, gzip::ACCEPT_ENCODING);
+
+            let req = match form {
+                Some(Upload::Form(form)) => form
+                    .set_body_convert::<FormBody, FormBody>(builder)
+                    .map(|req| {
+                        req.map(|body| {
+                            let body = body.into_stream(chunk_size);
+
+                            hyper::Body::wrap_stream(MeteredUpload::new(body, meter.clone()))
+                        })
+                    }),
+                Some(Upload::Stream(part)) => {
+                    let (content_type, body) = part.into_body(chunk_size);
+
+                    builder
+                        .header(http::header::CONTENT_TYPE, content_type)
+                        .body(hyper::Body::wrap_stream(MeteredUpload::new(
+                            body,
+                            meter.clone(),
+                        )))
+                }
+                None => builder.body(hyper::Body::empty()),
+            };
+
+            req.map_err(From::from)
+        });
+        #[cfg(feature = "actix")]
+        let req = match form {
+            Some(Upload::Form(form)) => {
+                let content_type = form.content_type();
+                let body = FormBody::from(multipart::Body::from(form));
+
+                Request::build()
+                    .method(method.clone())
+                    .uri(url)
+                    .content_type(content_type)
+                    .streaming(MeteredUpload::new(
+                        body.into_stream(chunk_size),
+                        meter.clone(),
+                    ))
+                    .map_err(From::from)
+            }
+            Some(Upload::Stream(part)) => {
+                let (content_type, body) = part.into_body(chunk_size);
+
+                Request::build()
+                    .method(method.clone())
+                    .uri(url)
+                    .content_type(content_type)
+                    .streaming(MeteredUpload::new(body, meter.clone()))
+                    .map_err(From::from)
+            }
+            None => Request::build()
+                .method(method.clone())
+                .uri(url)
+                .finish()
+                .map_err(From::from),
+        };
+        req
+    }
+
+    /// Builds an Api error from the status and body of a response.
+    ///
+    /// A body that isn't a json api error comes from an old daemon or a
+    /// proxy, and is classified by its status.
+    ///
+    fn build_error_from_body(status: StatusCode, chunk: Bytes) -> Error {
+        if let Ok(e) = serde_json::from_slice::<response::ApiError>(&chunk) {
+            return match e.kind() {
+                response::ApiErrorKind::DeadlineExceeded => Error::DeadlineExceeded(e),
+                _ => Error::Api(e),
+            };
+        }
+
+        let body = String::from_utf8_lossy(&chunk).trim().to_string();
+
+        match status {
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+                Error::UnsupportedEndpoint { status, body }
+            }
+            StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => Error::BadGateway { status, body },
+            _ => Error::UnexpectedStatus { status, body },
+        }
+    }
+
+    /// Converts an error from sending a request. Failures to connect are
+    /// wrapped, so they name the address of the api that was contacted, and
+    /// split into refused connections, host names that didn't resolve, and
+    /// timeouts where possible.
+    ///
+    #[cfg(feature = "hyper")]
+    fn build_send_error(base: &Uri, err: hyper::Error) -> Error {
+        if !err.is_connect() {
+            return err.into();
+        }
+
+        let uri = base.to_string();
+        let (kind, is_dns) =
+            match std::error::Error::source(&err).and_then(|e| e.downcast_ref::<io::Error>()) {
+                Some(e) => (Some(e.kind()), ResolveError::is(e)),
+                None => (None, false),
+            };
+
+        match kind {
+            _ if is_dns => Error::Dns {
+                uri,
+                source: Box::new(err.into()),
+            },
+            Some(io::ErrorKind::ConnectionRefused) => Error::ConnectionRefused { uri },
+            Some(io::ErrorKind::TimedOut) => Error::Timeout { uri },
+            _ => Error::Connect {
+                uri,
+                source: Box::new(err.into()),
+            },
+        }
+    }
+
+    /// Sends a request, following up to `max_redirects` redirects.
+    ///
+    #[cfg(feature = "hyper")]
+    fn send(
+        client: Client<Connector, hyper::Body>,
+        req: Request,
+        base: Uri,
+        max_redirects: usize,
+    ) -> AsyncResponse<Response> {
+        let res = future::loop_fn((req, 0), move |(req, redirects)| {
+            let uri = req.uri().clone();
+            let replay = if max_redirects > 0 {
+                Some((
+                    req.method().clone(),
+                    req.headers().clone(),
+                    !req.body().is_end_stream(),
+                ))
+            } else {
+                None
+            };
+            let base = base.clone();
+
+            client
+                .request(req)
+                .map_err(move |e| IpfsClient::build_send_error(&base, e))
+                .and_then(move |res| {
+                    let next = replay.and_then(|(method, headers, has_body)| {
+                        IpfsClient::redirect_request(&uri, &res, method, headers, has_body)
+                    });
+
+                    match next {
+                        Some(_) if redirects == max_redirects => Err(Error::TooManyRedirects {
+                            uri: uri.to_string(),
+                            max: max_redirects,
+                        }),
+                        Some(next) => Ok(future::Loop::Continue((next, redirects + 1))),
+                        None => Ok(future::Loop::Break(res)),
+                    }
+                })
+        });
+
+        Box::new(res)
+    }
+
+    /// The request to send in place of one that was redirected by `res`, or
+    /// `None` if `res` isn't a redirect that can be followed.
+    ///
+    /// Like browsers, a `303`, or a `301` or `302` in response to a `POST`,
+    /// is followed with a `GET` without a body. Other redirects repeat the
+    /// request, which is only possible without a body, since the body has
+    /// already been sent. Credentials aren't sent on to another host.
+    ///
+    #[cfg(feature = "hyper")]
+    fn redirect_request(
+        uri: &Uri,
+        res: &Response,
+        method: Method,
+        mut headers: HeaderMap,
+        has_body: bool,
+    ) -> Option<Request> {
+        let method = match res.status() {
+            StatusCode::SEE_OTHER => Method::GET,
+            StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND if method == Method::POST => {
+                Method::GET
+            }
+            StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+                if !has_body =>
+            {
+                method
+            }
+            _ => return None,
+        };
+        let location = IpfsClient::redirect_location(uri, res)?;
+
+        if has_body {
+            headers.remove(http::header::CONTENT_TYPE);
+            headers.remove(http::header::CONTENT_LENGTH);
+            headers.remove(http::header::TRANSFER_ENCODING);
+        }
+
+        if location.authority_part() != uri.authority_part() {
+            headers.remove(http::header::AUTHORIZATION);
+            headers.remove(http::header::PROXY_AUTHORIZATION);
+            headers.remove(http::header::COOKIE);
+        }
+
+        let mut next = http::Request::new(hyper::Body::empty());
+
+        *next.method_mut() = method;
+        *next.uri_mut() = location;
+        *next.headers_mut() = headers;
+
+        Some(next)
+    }
+
+    /// The uri a redirect points to, resolving a `Location` that is only a
+    /// path against the uri of the request.
+    ///
+    #[cfg(feature = "hyper")]
+    fn redirect_location(uri: &Uri, res: &Response) -> Option<Uri> {
+        let location = res
+            .headers()
+            .get(http::header::LOCATION)?
+            .to_str()
+            .ok()?
+            .parse::<Uri>()
+            .ok()?;
+
+        if location.scheme_part().is_some() {
+            return Some(location);
+        }
+
+        let mut parts = location.into_parts();
+
+        parts.scheme = uri.scheme_part().cloned();
+        parts.authority = uri.authority_part().cloned();
+
+        Uri::from_parts(parts).ok()
+    }
+
+    #[cfg(feature = "actix")]
+    fn build_send_error(base: &Uri, err: actix_web::client::SendRequestError) -> Error {
+        use actix_web::client::{ClientConnectorError, SendRequestError};
+
+        let uri = base.to_string();
+
+        match err {
+            SendRequestError::Timeout
+            | SendRequestError::Connector(ClientConnectorError::Timeout) => Error::Timeout { uri },
+            SendRequestError::Connector(ClientConnectorError::IoError(ref e))
+                if e.kind() == io::ErrorKind::ConnectionRefused =>
+            {
+                Error::ConnectionRefused { uri }
+            }
+            SendRequestError::Connector(ClientConnectorError::Resolver(_)) => Error::Dns {
+                uri,
+                source: Box::new(err.into()),
+            },
+            SendRequestError::Connector(_) => Error::Connect {
+                uri,
+                source: Box::new(err.into()),
+            },
+            _ => err.into(),
+        }
+    }
+
+    /// Processes a response that expects a json encoded body, returning an
+    /// error or a deserialized json response.
+    ///
+    fn process_json_response<Res>(
+        status: StatusCode,
+        chunk: Bytes,
+        strict: bool,
+    ) -> Result<Res, Error>
+    where
+        for<'de> Res: 'static + Deserialize<'de>,
+    {
+        match status {
+            StatusCode::OK => read::from_json_slice(&chunk, strict),
+            _ => Err(Self::build_error_from_body(status, chunk)),
+        }
+    }
+
+    /// Processes a response that returns a stream of json deserializable
+    /// results.
+    ///
+    fn process_stream_response<D, Res>(res: Response, decoder: D) -> AsyncStreamResponse<Res>
+    where
+        D: 'static + Decoder<Item = Res, Error = Error> + Send,
+        Res: 'static,
+    {
+        #[cfg(feature = "hyper")]
+        let stream = ChunkDecoder::new(TrailerBody::from_response(res), decoder);
+
+        #[cfg(feature = "actix")]
+        let stream = ChunkDecoder::new(res.payload().from_err(), decoder);
+
+        Box::new(stream)
+    }
+
+    /// Processes a response that returns a raw stream of bytes.
+    ///
+    /// If the response can end with an `X-Stream-Error` trailer, a body that
+    /// is shorter than its `X-Content-Length` is returned as an error, since
+    /// the trailer itself is usually dropped.
+    ///
+    fn process_bytes_response(res: Response) -> AsyncStreamResponse<Bytes> {
+        let expected = if IpfsClient::has_stream_error_trailer(&res) {
+            ResponseMetadata::new(res.status(), res.headers().clone()).content_length()
+        } else {
+            None
+        };
+
+        #[cfg(feature = "hyper")]
+        let stream = TrailerBody::from_response(res);
+
+        #[cfg(feature = "actix")]
+        let stream = res.payload().from_err();
+
+        Box::new(ExpectedLength::new(stream, expected))
+    }
+
+    /// Whether a response declares that it can end with an `X-Stream-Error`
+    /// trailer.
+    ///
+    #[inline]
+    fn has_stream_error_trailer(res: &Response) -> bool {
+        header::declared_trailers(res.headers())
+            .iter()
+            .any(|trailer| trailer.eq_ignore_ascii_case(X_STREAM_ERROR))
+    }
+
+    /// Generates a request, and returns the unprocessed response future.
+    ///
+    #[inline]
+    fn request_raw<Req>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+    ) -> AsyncResponse<(StatusCode, Bytes)>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        self.request_raw_path(Req::PATH, Req::METHOD, req, form)
+    }
+
+    /// Generates a request to an api path, and returns the unprocessed
+    /// response future.
+    ///
+    #[inline]
+    fn request_raw_path<Q>(
+        &self,
+        path: &'static str,
+        method: &Method,
+        query: &Q,
+        form: Option<Upload>,
+    ) -> AsyncResponse<(StatusCode, Bytes)>
+    where
+        Q: Serialize,
+    {
+        let res = self
+            .request_raw_with_metadata(path, method, query, form)
+            .map(|(metadata, body)| (metadata.status, body));
+
+        Box::new(res)
+    }
+
+    /// The context added to the errors of a request, if the client was built
+    /// with `error_context` enabled.
+    ///
+    fn error_context<Q>(&self, path: &str, query: &Q) -> Option<ErrorContext>
+    where
+        Q: Serialize,
+    {
+        if self.inner.error_context {
+            let query = request::encode_query(query).unwrap_or_default();
+
+            Some(ErrorContext::new(path, &query))
+        } else {
+            None
+        }
+    }
+
+    /// Generates a request to an api path, and returns the unprocessed
+    /// response future, along with the status and headers of the response.
+    ///
+    fn request_raw_with_metadata<Q>(
+        &self,
+        path: &'static str,
+        method: &Method,
+        query: &Q,
+        form: Option<Upload>,
+    ) -> AsyncResponse<(ResponseMetadata, Bytes)>
+    where
+        Q: Serialize,
+    {
+        let res = self.send_raw_with_metadata(path, method, query, form);
+
+        self.with_error_context(path, query, res)
+    }
+
+    /// Adds the context of a request to the errors of `res`, if the client
+    /// was built with `error_context` enabled.
+    ///
+    fn with_error_context<Q, T>(
+        &self,
+        path: &str,
+        query: &Q,
+        res: AsyncResponse<T>,
+    ) -> AsyncResponse<T>
+    where
+        Q: Serialize,
+        T: 'static,
+    {
+        match self.error_context(path, query) {
+            Some(context) => Box::new(res.map_err(move |e| context.wrap(e))),
+            None => res,
+        }
+    }
+
+    fn send_raw_with_metadata<Q>(
+        &self,
+        path: &'static str,
+        method: &Method,
+        query: &Q,
+        form: Option<Upload>,
+    ) -> AsyncResponse<(ResponseMetadata, Bytes)>
+    where
+        Q: Serialize,
+    {
+        let meter = RequestMeter::start(self.inner.metrics.as_ref(), path);
+
+        match self.build_request(path, method, query, form, &meter) {
+            Ok(req) => {
+                let span = RequestSpan::start(path, req.uri());
+                let log = RequestLog::start(path, req.uri());
+                let err_meter = meter.clone();
+
+                let base = self.inner.base.clone();
+                let max_response_size = self.inner.max_response_size;
+
+                #[cfg(feature = "hyper")]
+                let client = self.inner.client.clone();
+                #[cfg(feature = "hyper")]
+                let max_redirects = self.inner.max_redirects;
+                #[cfg(feature = "hyper")]
+                let res = self.inner.limit.acquire().and_then(move |permit| {
+                    let res = IpfsClient::send(client, req, base, max_redirects);
+                    #[cfg(feature = "gzip")]
+                    let res = res.map(gzip::decode_response);
+
+                    res.and_then(move |res| {
+                        let (parts, body) = res.into_parts();
+
+                        log.status(parts.status);
+
+                        let body = body.map(|c| c.into_bytes()).from_err();
+
+                        read::concat_bytes(LimitedStream::new(body, max_response_size)).map(
+                            move |body| {
+                                drop(permit);
+                                metrics::record_body(&meter, parts.status, &body);
+
+                                (ResponseMetadata::new(parts.status, parts.headers), body)
+                            },
+                        )
+                    })
+                });
+                #[cfg(feature = "actix")]
+                let res = self.inner.limit.acquire().and_then(move |permit| {
+                    req.send()
+                        .timeout(std::time::Duration::from_secs(90))
+                        .map_err(move |e| IpfsClient::build_send_error(&base, e))
+                        .and_then(move |x| {
+                            let metadata = ResponseMetadata::new(x.status(), x.headers().clone());
+
+                            log.status(metadata.status);
+
+                            let body = match max_response_size {
+                                Some(limit) => x.body().limit(limit),
+                                None => x.body(),
+                            };
+
+                            body.map(move |body| {
+                                drop(permit);
+                                metrics::record_body(&meter, metadata.status, &body);
+
+                                (metadata, body)
+                            })
+                            .map_err(move |e| {
+                                match (e, max_response_size) {
+                                    (PayloadError::Overflow, Some(limit)) => {
+                                        Error::ResponseTooLarge(limit)
+                                    }
+                                    (e, _) => e.into(),
+                                }
+                            })
+                        })
+                });
+                Box::new(span.instrument(res.map_err(move |e| {
+                    log.error(&e);
+                    err_meter.error();
+                    e
+                })))
+            }
+            Err(e) => {
+                meter.error();
+
+                Box::new(Err(e).into_future())
+            }
+        }
+    }
+
+    /// Generic method for making a request that expects back a streaming
+    /// response.
+    ///
+    fn request_stream<Req, Res, F>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+        process: F,
+    ) -> AsyncStreamResponse<Res>
+    where
+        Req: ApiRequest + Serialize,
+        Res: 'static + Send,
+        F: 'static + Fn(Response) -> AsyncStreamResponse<Res> + Send,
+    {
+        let res = self
+            .request_stream_with_metadata(req, form, process)
+            .map(|(_, stream)| stream)
+            .flatten_stream();
+
+        Box::new(res)
+    }
+
+    /// Generic method for making a request that expects back a streaming
+    /// response. Resolves to the status and headers of the response once
+    /// they are received, along with the stream of the body.
+    ///
+    fn request_stream_with_metadata<Req, Res, F>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+        process: F,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Res>)>
+    where
+        Req: ApiRequest + Serialize,
+        Res: 'static + Send,
+        F: 'static + Fn(Response) -> AsyncStreamResponse<Res> + Send,
+    {
+        let res = self.send_stream_with_metadata(req, form, process);
+
+        match self.error_context(Req::PATH, req) {
+            Some(context) => {
+                let stream_context = context.clone();
+                let res = res
+                    .map(move |(metadata, stream)| {
+                        let stream: AsyncStreamResponse<Res> =
+                            Box::new(stream.map_err(move |e| stream_context.wrap(e)));
+
+                        (metadata, stream)
+                    })
+                    .map_err(move |e| context.wrap(e));
+
+                Box::new(res)
+            }
+            None => res,
+        }
+    }
+
+    fn send_stream_with_metadata<Req, Res, F>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+        process: F,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Res>)>
+    where
+        Req: ApiRequest + Serialize,
+        Res: 'static + Send,
+        F: 'static + Fn(Response) -> AsyncStreamResponse<Res> + Send,
+    {
+        let meter = RequestMeter::start(self.inner.metrics.as_ref(), Req::PATH);
+
+        #[cfg(feature = "hyper")]
+        match self.build_base_request(req, form, &meter) {
+            Ok(req) => {
+                let span = RequestSpan::start(Req::PATH, req.uri());
+                let stream_span = span.clone();
+                let log = RequestLog::start(Req::PATH, req.uri());
+                let err_meter = meter.clone();
+                let base = self.inner.base.clone();
+                let client = self.inner.client.clone();
+                let max_response_size = self.inner.max_response_size;
+                let max_redirects = self.inner.max_redirects;
+                let res = self
+                    .inner
+                    .limit
+                    .acquire()
+                    .and_then(move |permit| {
+                        let res = IpfsClient::send(client, req, base, max_redirects);
+                        #[cfg(feature = "gzip")]
+                        let res = res.map(gzip::decode_response);
+
+                        res.map(move |res| (permit, res))
+                    })
+                    .map_err(move |e| {
+                        log.error(&e);
+                        err_meter.error();
+                        e
+                    })
+                    .and_then(move |(permit, res)| {
+                        log.status(res.status());
+                        meter.status(res.status());
+
+                        let res: AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Res>)> =
+                            match res.status() {
+                                StatusCode::OK => {
+                                    let mut res = res;
+                                    let trailers = ReceivedTrailers::default();
+                                    let metadata =
+                                        ResponseMetadata::new(res.status(), res.headers().clone())
+                                            .with_trailers(trailers.clone());
+
+                                    res.extensions_mut().insert(trailers);
+
+                                    if meter.is_active() {
+                                        res.extensions_mut().insert(meter);
+                                    }
+
+                                    let stream: AsyncStreamResponse<Res> = Box::new(
+                                        stream_span
+                                            .instrument(WithPermit::new(process(res), permit)),
+                                    );
+
+                                    Box::new(future::ok((metadata, stream)))
+                                }
+                                // If the server responded with an error status code, the body
+                                // still needs to be read so an error can be built. This block will
+                                // read the entire body stream, then immediately return an error.
+                                //
+                                status => {
+                                    let body = res.into_body().map(|c| c.into_bytes()).from_err();
+
+                                    Box::new(
+                                        read::concat_bytes(LimitedStream::new(
+                                            body,
+                                            max_response_size,
+                                        ))
+                                        .then(
+                                            move |res| {
+                                                drop(permit);
+
+                                                match res {
+                                                    Ok(chunk) => {
+                                                        metrics::record_body(
+                                                            &meter, status, &chunk,
+                                                        );
+
+                                                        Err(Self::build_error_from_body(
+                                                            status, chunk,
+                                                        ))
+                                                    }
+                                                    Err(e) => Err(e),
+                                                }
+                                            },
+                                        ),
+                                    )
+                                }
+                            };
+
+                        res
+                    });
+                Box::new(span.instrument(res))
+            }
+            Err(e) => {
+                meter.error();
+
+                Box::new(Err(e).into_future())
+            }
+        }
+        #[cfg(feature = "actix")]
+        match self.build_base_request(req, form, &meter) {
+            Ok(req) => {
+                let span = RequestSpan::start(Req::PATH, req.uri());
+                let stream_span = span.clone();
+                let log = RequestLog::start(Req::PATH, req.uri());
+                let err_meter = meter.clone();
+                let base = self.inner.base.clone();
+                let res = self
+                    .inner
+                    .limit
+                    .acquire()
+                    .and_then(move |permit| {
+                        req.send()
+                            .timeout(std::time::Duration::from_secs(90))
+                            .map_err(move |e| IpfsClient::build_send_error(&base, e))
+                            .map(move |res| (permit, res))
+                    })
+                    .map_err(move |e| {
+                        log.error(&e);
+                        err_meter.error();
+                        e
+                    })
+                    .map(move |(permit, res)| {
+                        log.status(res.status());
+                        meter.status(res.status());
+
+                        let metadata = ResponseMetadata::new(res.status(), res.headers().clone());
+                        let stream: AsyncStreamResponse<Res> =
+                            Box::new(stream_span.instrument(WithPermit::new(process(res), permit)));
+
+                        (metadata, stream)
+                    });
+                Box::new(span.instrument(res))
+            }
+            Err(e) => {
+                meter.error();
+
+                Box::new(Err(e).into_future())
+            }
+        }
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// a deserializable response.
+    ///
+    fn request<Req, Res>(&self, req: &Req, form: Option<Upload>) -> AsyncResponse<Res>
+    where
+        Req: ApiRequest + Serialize,
+        for<'de> Res: 'static + Deserialize<'de> + Send,
+    {
+        let strict = self.inner.strict;
+        let res = self
+            .request_raw(req, form)
+            .and_then(move |(status, chunk)| {
+                IpfsClient::process_json_response(status, chunk, strict)
+            });
+
+        self.with_error_context(Req::PATH, req, Box::new(res))
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// back a response with no body.
+    ///
+    fn request_empty<Req>(&self, req: &Req, form: Option<Upload>) -> AsyncResponse<()>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        let res = self
+            .request_raw(req, form)
+            .and_then(|(status, chunk)| match status {
+                StatusCode::OK => Ok(()),
+                _ => Err(Self::build_error_from_body(status, chunk)),
+            });
+
+        self.with_error_context(Req::PATH, req, Box::new(res))
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// back a raw String response.
+    ///
+    fn request_string<Req>(&self, req: &Req, form: Option<Upload>) -> AsyncResponse<String>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        let res = self
+            .request_raw(req, form)
+            .and_then(|(status, chunk)| match status {
+                StatusCode::OK => String::from_utf8(chunk.to_vec()).map_err(From::from),
+                _ => Err(Self::build_error_from_body(status, chunk)),
+            });
+
+        self.with_error_context(Req::PATH, req, Box::new(res))
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// back the raw bytes of the response.
+    ///
+    fn request_bytes<Req>(&self, req: &Req, form: Option<Upload>) -> AsyncResponse<Bytes>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        let res = self
+            .request_raw(req, form)
+            .and_then(|(status, chunk)| match status {
+                StatusCode::OK => Ok(chunk),
+                _ => Err(Self::build_error_from_body(status, chunk)),
+            });
+
+        self.with_error_context(Req::PATH, req, Box::new(res))
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// back a raw stream of bytes.
+    ///
+    fn request_stream_bytes<Req>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+    ) -> AsyncStreamResponse<Bytes>
+    where
+        Req: ApiRequest + Serialize,
+    {
+        self.request_stream(req, form, IpfsClient::process_bytes_response)
+    }
+
+    /// Generic method for making a request to the Ipfs server, and getting
+    /// back a raw stream of bytes, calling `callback` with the progress of
+    /// the download as each chunk is received.
+    ///
+    fn request_stream_bytes_progress<Req, F>(
+        &self,
+        req: &Req,
+        callback: F,
+    ) -> AsyncStreamResponse<Bytes>
+    where
+        Req: ApiRequest + Serialize,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        let res = self
+            .request_stream_with_metadata(req, None, IpfsClient::process_bytes_response)
+            .map(move |(metadata, stream)| {
+                ProgressStream::new(stream, metadata.content_length(), callback)
+            })
+            .flatten_stream();
+
+        Box::new(res)
+    }
+
+    /// Generic method to return a streaming response of deserialized json
+    /// objects delineated by new line separators.
+    ///
+    fn request_stream_json<Req, Res>(
+        &self,
+        req: &Req,
+        form: Option<Upload>,
+    ) -> AsyncStreamResponse<Res>
+    where
+        Req: ApiRequest + Serialize,
+        for<'de> Res: 'static + Deserialize<'de> + Send,
+    {
+        let strict = self.inner.strict;
+        let max_line_length = self.inner.max_line_length;
+
+        self.request_stream(req, form, move |res| {
+            // The X-Stream-Error trailer is used to indicate that there was
+            // an error while streaming data with Ipfs. Other trailers are
+            // passed on through the metadata of the response.
+            //
+            let parse_stream_error = IpfsClient::has_stream_error_trailer(&res);
+
+            Box::new(IpfsClient::process_stream_response(
+                res,
+                JsonLineDecoder::new(parse_stream_error, strict)
+                    .with_max_line_length(max_line_length),
+            ))
+        })
+    }
+}
+
+impl IpfsClient {
+    /// Add a file to IPFS.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = Cursor::new("Hello World!");
+    /// let req = client.add(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add<R>(&self, data: R) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("path", data);
+
+        self.request(&request::Add::default(), Some(form.into()))
+    }
+
+    /// Serializes a value as JSON and adds it to Ipfs as a file, returning
+    /// its CID. `cat_json` reads it back.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     body: String,
+    /// }
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let post = Post {
+    ///     title: "Hello".into(),
+    ///     body: "World".into(),
+    /// };
+    /// let req = client.add_json(&post);
+    /// # }
+    /// ```
+    ///
+    pub fn add_json<T>(&self, value: &T) -> AsyncResponse<Cid>
+    where
+        T: Serialize,
+    {
+        match serde_json::to_vec(value) {
+            Ok(data) => Box::new(self.add(io::Cursor::new(data)).map(|added| added.hash)),
+            Err(e) => Box::new(future::err(e.into())),
+        }
+    }
+
+    /// Add a file to IPFS, streaming its contents from `data` without blocking.
+    ///
+    /// ```no_run
+    /// # extern crate bytes;
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = stream::once(Ok(Bytes::from("Hello World!")));
+    /// let req = client.add_stream(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add_stream<S>(&self, data: S) -> AsyncResponse<response::AddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let part = StreamPart::new("path", data);
+
+        self.request(&request::Add::default(), Some(part.into()))
+    }
+
+    /// Add a file to IPFS with options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = Cursor::new("Hello World!");
+    /// let mut add = ipfs_api::request::Add::default();
+    /// add.chunker = Some("rabin-512-1024-2048");
+    /// let req = client.add_with_options(data, &add);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add_with_options<R>(
+        &self,
+        data: R,
+        add: &request::Add,
+    ) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("path", data);
+
+        self.request(add, Some(form.into()))
+    }
+
+    /// Add several files to IPFS in a single request. Each file is a part of
+    /// the same form, named by its file name, and the response holds an entry
+    /// for each of them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let files = vec![
+    ///     ("a.txt", Cursor::new("Hello")),
+    ///     ("b.txt", Cursor::new("World!")),
+    /// ];
+    /// let req = client.add_many(files);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add_many<I, N, R>(&self, files: I) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        self.add_many_with_options(files, &request::Add::default())
+    }
+
+    /// Add several files to IPFS in a single request, with options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let files = vec![
+    ///     ("a.txt", Cursor::new("Hello")),
+    ///     ("b.txt", Cursor::new("World!")),
+    /// ];
+    /// let mut add = ipfs_api::request::Add::default();
+    /// add.pin = Some(false);
+    /// let req = client.add_many_with_options(files, &add);
+    /// # }
+    /// ```
+    ///
+    pub fn add_many_with_options<I, N, R>(
+        &self,
+        files: I,
+        add: &request::Add,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        for (name, data) in files {
+            form.add_reader_file("path", data, name);
+        }
+
+        Box::new(self.request_stream_json(add, Some(form.into())).collect())
+    }
+
+    /// Add a path to Ipfs. Can be a file or directory.
+    /// A hard limit of 128 open file descriptors is set such
+    /// that any small additional files are stored in-memory.
+    ///
+    /// Files are opened and streamed from disk, each sent with its path
+    /// (relative to the parent of `path`) as its filename. Resolves to the
+    /// entry for `path` itself, which for a directory is its root hash.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let path = "./src";
+    /// let req = client.add_path(path);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add_path<P>(&self, path: P) -> AsyncResponse<response::AddResponse>
+    where
+        P: AsRef<Path>,
+    {
+        let options = AddTreeOptions {
+            hidden: true,
+            ..Default::default()
+        };
+        let res = self
+            .add_tree_with_options(path, &options)
+            .and_then(|mut responses| {
+                responses
+                    .pop()
+                    .ok_or_else(|| Error::Uncategorized("api returned no added files".into()))
+            });
+
+        Box::new(res)
+    }
+
+    /// Add a directory to Ipfs, keeping its structure, including empty
+    /// directories. Resolves to an entry for every file and directory added,
+    /// ending with the directory itself.
+    ///
+    /// Like `ipfs add -r`, hidden files are skipped and symlinks are added as
+    /// symlinks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.add_tree("./src");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn add_tree<P>(&self, path: P) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        self.add_tree_with_options(path, &AddTreeOptions::default())
+    }
+
+    /// Add a directory to Ipfs with options for which files are included,
+    /// and how symlinks are added.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{AddTreeOptions, IpfsClient, SymlinkMode};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let mut options = AddTreeOptions::default();
+    /// options.hidden = true;
+    /// options.symlinks = SymlinkMode::Dereference;
+    /// let req = client.add_tree_with_options("./src", &options);
+    /// # }
+    /// ```
+    ///
+    pub fn add_tree_with_options<P>(
+        &self,
+        path: P,
+        options: &AddTreeOptions,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        match tree::build_form(path.as_ref(), options, self.inner.read_buffer_size) {
+            Ok(form) => Box::new(
+                self.request_stream_json(&request::Add::default(), Some(form.into()))
+                    .collect(),
+            ),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    /// Returns the current ledger for a peer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bitswap_ledger("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_ledger<P>(&self, peer: P) -> AsyncResponse<response::BitswapLedgerResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.request(
+                &request::BitswapLedger {
+                    peer: peer.as_str(),
+                },
+                None,
+            ),
+            Err(e) => Box::new(Err(e).into_future()),
+        }
+    }
+
+    /// Triggers a reprovide.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bitswap_reprovide();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_reprovide(&self) -> AsyncResponse<response::BitswapReprovideResponse> {
+        self.request_empty(&request::BitswapReprovide, None)
+    }
+
+    /// Returns some stats about the bitswap agent.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bitswap_stat();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_stat(&self) -> AsyncResponse<response::BitswapStatResponse> {
+        self.bitswap_stat_with_options(&request::BitswapStat::default())
+    }
+
+    /// Returns some stats about the bitswap agent, with options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let mut stat = ipfs_api::request::BitswapStat::default();
+    /// stat.verbose = Some(true);
+    /// let req = client.bitswap_stat_with_options(&stat);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_stat_with_options(
+        &self,
+        stat: &request::BitswapStat,
+    ) -> AsyncResponse<response::BitswapStatResponse> {
+        self.request(stat, None)
+    }
+
+    /// Remove a given block from your wantlist.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bitswap_unwant("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_unwant(&self, key: &str) -> AsyncResponse<response::BitswapUnwantResponse> {
+        self.request_empty(&request::BitswapUnwant { key }, None)
+    }
+
+    /// Shows blocks on the wantlist for you or the specified peer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bitswap_wantlist(Some("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"));
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bitswap_wantlist(
+        &self,
+        peer: Option<&str>,
+    ) -> AsyncResponse<response::BitswapWantlistResponse> {
+        self.request(&request::BitswapWantlist { peer }, None)
+    }
+
+    /// Gets a raw IPFS block.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.block_get(hash).concat2();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn block_get<C>(&self, cid: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.request_stream_bytes(&request::BlockGet { hash: cid.as_str() }, None),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    /// Gets many raw IPFS blocks, with at most `parallelism` requests open at
+    /// the same time.
+    ///
+    /// Each block is returned with its CID once it has been received in
+    /// full, in the order they finish. A block that could not be fetched is
+    /// returned with its error, and does not stop the others.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{Cid, IpfsClient};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let cids: Vec<Cid> = vec![
+    ///     "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap(),
+    /// ];
+    /// let req = client.block_get_many(cids, 4);
+    /// # }
+    /// ```
+    ///
+    pub fn block_get_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let cids: Vec<Cid> = cids.into_iter().collect();
+        let client = self.clone();
+        let res = stream::iter_ok(cids)
+            .map(move |cid| {
+                client
+                    .block_get(&cid)
+                    .concat2()
+                    .then(move |res| Ok((cid, res)))
+            })
+            .buffer_unordered(parallelism.max(1));
+
+        Box::new(res)
+    }
+
+    /// Returns the contents of a block, calling `callback` with the
+    /// progress of the download as each chunk is received.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.block_get_with_progress(hash, |progress| {
+    ///     println!("received {} bytes", progress.received)
+    /// });
+    /// # }
+    /// ```
+    ///
+    pub fn block_get_with_progress<C, F>(&self, cid: C, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self
+                .request_stream_bytes_progress(&request::BlockGet { hash: cid.as_str() }, callback),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    /// Store input as an IPFS block.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = Cursor::new("Hello World!");
+    /// let req = client.block_put(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn block_put<R>(&self, data: R) -> AsyncResponse<response::BlockPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("data", data);
+
+        self.request(&request::BlockPut, Some(form.into()))
+    }
+
+    /// Store a stream of bytes as an IPFS block.
+    ///
+    /// ```no_run
+    /// # extern crate bytes;
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = stream::once(Ok(Bytes::from("Hello World!")));
+    /// let req = client.block_put_stream(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn block_put_stream<S>(&self, data: S) -> AsyncResponse<response::BlockPutResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let part = StreamPart::new("data", data);
+
+        self.request(&request::BlockPut, Some(part.into()))
+    }
+
+    /// Removes an IPFS block.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.block_rm("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn block_rm<C>(&self, cid: C) -> AsyncResponse<response::BlockRmResponse>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.request(&request::BlockRm { hash: cid.as_str() }, None),
+            Err(e) => Box::new(Err(e).into_future()),
+        }
+    }
+
+    /// Prints information about a raw IPFS block.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn block_stat<C>(&self, cid: C) -> AsyncResponse<response::BlockStatResponse>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.request(&request::BlockStat { hash: cid.as_str() }, None),
+            Err(e) => Box::new(Err(e).into_future()),
+        }
+    }
+
+    /// Add default peers to the bootstrap list.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bootstrap_add_default();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bootstrap_add_default(&self) -> AsyncResponse<response::BootstrapAddDefaultResponse> {
+        self.request(&request::BootstrapAddDefault, None)
+    }
+
+    /// Lists peers in bootstrap list.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bootstrap_list();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bootstrap_list(&self) -> AsyncResponse<response::BootstrapListResponse> {
+        self.request(&request::BootstrapList, None)
+    }
+
+    /// Removes all peers in bootstrap list.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.bootstrap_rm_all();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bootstrap_rm_all(&self) -> AsyncResponse<response::BootstrapRmAllResponse> {
+        self.request(&request::BootstrapRmAll, None)
+    }
+
+    /// Returns the contents of an Ipfs object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat(hash).concat2();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn cat<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes(
+            &request::Cat {
+                path: &path,
+                offset: None,
+                length: None,
+            },
+            None,
+        )
+    }
+
+    /// Returns the whole contents of an Ipfs object in a single buffer.
+    ///
+    /// Fails with `Error::ResponseTooLarge` as soon as more than `max_size`
+    /// bytes are received, if a limit is given.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.cat_bytes(
+    ///     "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/readme.md",
+    ///     Some(64 * 1024),
+    /// );
+    /// # }
+    /// ```
+    ///
+    pub fn cat_bytes<P>(&self, path: P, max_size: Option<usize>) -> AsyncResponse<Vec<u8>>
+    where
+        P: ToIpfsPath,
+    {
+        let res = read::concat_bytes(LimitedStream::new(self.cat(path), max_size))
+            .map(|body| body.to_vec());
+
+        Box::new(res)
+    }
+
+    /// Returns the whole contents of an Ipfs object as a string.
+    ///
+    /// Fails with `Error::ParseUtf8` if the contents aren't valid UTF-8.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.cat_string("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/readme.md");
+    /// # }
+    /// ```
+    ///
+    pub fn cat_string<P>(&self, path: P) -> AsyncResponse<String>
+    where
+        P: ToIpfsPath,
+    {
+        let res = self
+            .cat_bytes(path, None)
+            .and_then(|body| String::from_utf8(body).map_err(Error::from));
+
+        Box::new(res)
+    }
+
+    /// Returns the contents of an Ipfs object, deserialized from JSON into a
+    /// type of the caller's choosing. The counterpart to `add_json`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     body: String,
+    /// }
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.cat_json::<Post, _>("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    pub fn cat_json<T, P>(&self, path: P) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+        P: ToIpfsPath,
+    {
+        let res = self
+            .cat(path)
+            .concat2()
+            .and_then(|body| serde_json::from_slice(&body).map_err(Error::from));
+
+        Box::new(res)
+    }
+
+    /// Returns the contents of many Ipfs objects, with at most `parallelism`
+    /// requests open at the same time.
+    ///
+    /// Each object is returned with its CID once it has been received in
+    /// full, in the order they finish. An object that could not be fetched
+    /// is returned with its error, and does not stop the others.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{Cid, IpfsClient};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let cids: Vec<Cid> = vec![
+    ///     "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap(),
+    /// ];
+    /// let req = client.cat_many(cids, 4);
+    /// # }
+    /// ```
+    ///
+    pub fn cat_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let cids: Vec<Cid> = cids.into_iter().collect();
+        let client = self.clone();
+        let res = stream::iter_ok(cids)
+            .map(move |cid| {
+                client
+                    .cat(cid.as_str())
+                    .concat2()
+                    .then(move |res| Ok((cid, res)))
+            })
+            .buffer_unordered(parallelism.max(1));
+
+        Box::new(res)
+    }
+
+    /// Returns `length` bytes of an Ipfs object, starting `offset` bytes in.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat_range(hash, 1024, 512);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn cat_range<P>(&self, path: P, offset: u64, length: u64) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes(
+            &request::Cat {
+                path: &path,
+                offset: Some(offset),
+                length: Some(length),
+            },
+            None,
+        )
+    }
+
+    /// Returns the contents of an Ipfs object, downloaded as consecutive
+    /// ranges of `range_size` bytes with up to `read_ahead` ranges being
+    /// fetched while the current one is consumed.
+    ///
+    /// This hides the latency of each request over a slow link, at the cost
+    /// of holding up to `read_ahead` ranges in memory. Each range is
+    /// returned as one chunk, in order.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat_prefetch(hash, 256 * 1024, 4);
+    /// # }
+    /// ```
+    ///
+    pub fn cat_prefetch(
+        &self,
+        path: &str,
+        range_size: u64,
+        read_ahead: usize,
+    ) -> AsyncStreamResponse<Bytes> {
+        let client = self.clone();
+        let path = path.to_string();
+        let range_size = range_size.max(1);
+        let ranges = stream::iter_ok(0..)
+            .map(move |i: u64| {
+                client
+                    .cat_range(&path, i * range_size, range_size)
+                    .concat2()
+                    .then(Ok)
+            })
+            .buffered(read_ahead.max(1));
+
+        Box::new(Prefetch::new(ranges, range_size))
+    }
+
+    /// Returns the contents of an Ipfs object, re-requesting the rest of it
+    /// from the number of bytes received so far if the download fails with
+    /// a transient error (a dropped connection, or a proxy timing out).
+    ///
+    /// Each attempt waits twice as long as the one before, starting at
+    /// 250ms. The download is given up after three failures in a row without
+    /// any bytes being received.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat_resumable(hash);
+    /// # }
+    /// ```
+    ///
+    pub fn cat_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        let client = self.clone();
+
+        Box::new(Resume::new(move |offset| {
+            client.request_stream_bytes(
+                &request::Cat {
+                    path: &path,
+                    offset: Some(offset),
+                    length: None,
+                },
+                None,
+            )
+        }))
+    }
+
+    /// Returns the contents of an Ipfs object, once the daemon has
+    /// responded, along with the status and headers of the response.
+    ///
+    /// The size of the object is available from
+    /// `ResponseMetadata::content_length`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat_with_metadata(hash);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn cat_with_metadata<P>(
+        &self,
+        path: P,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Bytes>)>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request_stream_with_metadata(
+            &request::Cat {
+                path: &path,
+                offset: None,
+                length: None,
+            },
+            None,
+            IpfsClient::process_bytes_response,
+        )
+    }
+
+    /// Returns the contents of an Ipfs object, calling `callback` with the
+    /// progress of the download as each chunk is received.
+    ///
+    /// `Progress::percent` is available when the daemon sends the size of
+    /// the object.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.cat_with_progress(hash, |progress| {
+    ///     if let Some(percent) = progress.percent() {
+    ///         println!("{:.0}%", percent);
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn cat_with_progress<P, F>(&self, path: P, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes_progress(
+            &request::Cat {
+                path: &path,
+                offset: None,
+                length: None,
+            },
+            callback,
+        )
+    }
+
+    /// List available commands that the server accepts.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.commands();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn commands(&self) -> AsyncResponse<response::CommandsResponse> {
+        self.request(&request::Commands, None)
+    }
+
+    /// Opens the config file for editing (on the server).
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.config_edit();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn config_edit(&self) -> AsyncResponse<response::ConfigEditResponse> {
+        self.request(&request::ConfigEdit, None)
+    }
+
+    /// Replace the config file.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let config = Cursor::new("{..json..}");
+    /// let req = client.config_replace(config);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn config_replace<R>(&self, data: R) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("file", data);
+
+        self.request_empty(&request::ConfigReplace, Some(form.into()))
+    }
+
+    /// Replace the config with a stream of json.
+    ///
+    /// ```no_run
+    /// # extern crate bytes;
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = stream::once(Ok(Bytes::from("Hello World!")));
+    /// let req = client.config_replace_stream(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn config_replace_stream<S>(
+        &self,
+        data: S,
+    ) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let part = StreamPart::new("file", data);
+
+        self.request_empty(&request::ConfigReplace, Some(part.into()))
+    }
+
+    /// Show the current config of the server.
+    ///
+    /// Returns an unparsed json string, due to an unclear spec.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.config_show();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn config_show(&self) -> AsyncResponse<response::ConfigShowResponse> {
+        self.request(&request::ConfigShow, None)
+    }
+
+    /// Show the current config of the server, as the raw json the daemon
+    /// returned.
+    ///
+    /// Unlike `config_show`, values that aren't valid utf-8 (e.g. in paths)
+    /// don't make the request fail.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.config_show_bytes();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn config_show_bytes(&self) -> AsyncResponse<Bytes> {
+        self.request_bytes(&request::ConfigShow, None)
+    }
+
+    /// Exports the DAG under `root` as a CAR archive, which `CarReader` can
+    /// read block by block.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client
+    ///     .dag_export("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+    ///     .concat2();
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    pub fn dag_export<C>(&self, root: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        match root.to_cid() {
+            Ok(root) => self.request_stream_bytes(
+                &request::DagExport {
+                    root: root.as_str(),
+                },
+                None,
+            ),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    /// Returns information about a dag node in Ipfs.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.dag_get("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    #[inline]
+    pub fn dag_get(&self, path: &str) -> AsyncResponse<response::DagGetResponse> {
+        self.request(
+            &request::DagGet {
+                path,
+                output_codec: None,
+            },
+            None,
+        )
+    }
+
+    /// Returns a dag node in Ipfs, deserialized into a type of the caller's
+    /// choosing.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     body: String,
+    /// }
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.dag_get_typed::<Post>("bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae");
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    #[inline]
+    pub fn dag_get_typed<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        self.request(
+            &request::DagGet {
+                path,
+                output_codec: None,
+            },
+            None,
+        )
+    }
+
+    /// Returns a dag node in Ipfs, fetched as dag-cbor and deserialized into
+    /// a type of the caller's choosing.
+    ///
+    /// Unlike `dag_get_typed`, byte strings come back as bytes rather than
+    /// through their dag-json encoding. Links are CBOR tag 42 values, which
+    /// deserialize as bytes holding the binary CID (prefixed by a zero byte).
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     body: String,
+    /// }
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.dag_get_cbor::<Post>("bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae");
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag-cbor")]
+    pub fn dag_get_cbor<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        let req = request::DagGet {
+            path,
+            output_codec: Some("dag-cbor"),
+        };
+        let res = self
+            .request_raw(&req, None)
+            .and_then(|(status, chunk)| match status {
+                StatusCode::OK => serde_cbor::from_slice(&chunk).map_err(From::from),
+                _ => Err(Self::build_error_from_body(status, chunk)),
+            });
+
+        Box::new(res)
+    }
+
+    /// Imports the blocks of a CAR archive, such as one from `dag_export`
+    /// or written with `CarWriter`, and pins its roots.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::fs::File;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = File::open("export.car").unwrap();
+    /// let req = client.dag_import(data);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    #[inline]
+    pub fn dag_import<R>(&self, data: R) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.dag_import_with_options(data, &request::DagImport::default())
+    }
+
+    /// Imports the blocks of a CAR archive with options, such as whether its
+    /// roots are pinned.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::fs::File;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = File::open("export.car").unwrap();
+    /// let mut import = ipfs_api::request::DagImport::default();
+    /// import.pin_roots = Some(false);
+    /// import.stats = Some(true);
+    /// let req = client.dag_import_with_options(data, &import);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    pub fn dag_import_with_options<R>(
+        &self,
+        data: R,
+        import: &request::DagImport,
+    ) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("file", data);
+
+        self.request_stream_json(import, Some(form.into()))
+    }
+
+    /// Add a DAG node to Ipfs, from its dag-json encoding.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::io::Cursor;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = Cursor::new(r#"{ "hello": "world" }"#);
+    /// let req = client.dag_put(data);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    #[inline]
+    pub fn dag_put<R>(&self, data: R) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.dag_put_with_options(data, &request::DagPut::default())
+    }
+
+    /// Add a DAG node to Ipfs with options, such as the codec the data is
+    /// encoded in.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::fs::File;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = File::open("node.cbor").unwrap();
+    /// let mut put = ipfs_api::request::DagPut::default();
+    /// put.input_codec = Some("dag-cbor");
+    /// let req = client.dag_put_with_options(data, &put);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag")]
+    #[inline]
+    pub fn dag_put_with_options<R>(
+        &self,
+        data: R,
+        put: &request::DagPut,
+    ) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        let mut form = multipart::Form::default();
+
+        form.add_reader("file", data);
+
+        self.request(put, Some(form.into()))
+    }
+
+    /// Serializes a value as dag-cbor, and adds it to Ipfs as a DAG node.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Post {
+    ///     title: String,
+    ///     body: String,
+    /// }
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let post = Post {
+    ///     title: "Hello".into(),
+    ///     body: "World".into(),
+    /// };
+    /// let req = client.dag_put_cbor(&post);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dag-cbor")]
+    pub fn dag_put_cbor<T>(&self, value: &T) -> AsyncResponse<response::DagPutResponse>
+    where
+        T: Serialize,
+    {
+        match serde_cbor::to_vec(value) {
+            Ok(data) => {
+                let put = request::DagPut {
+                    input_codec: Some("dag-cbor"),
+                    store_codec: Some("dag-cbor"),
+                    pin: None,
+                };
+
+                self.dag_put_with_options(std::io::Cursor::new(data), &put)
+            }
+            Err(e) => Box::new(Err(e.into()).into_future()),
+        }
+    }
+
+    // TODO /dag/resolve
+
+    /// Query the DHT for all of the multiaddresses associated with a Peer ID.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let peer = "QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM";
+    /// let req = client.dht_findpeer(peer).collect();
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dht")]
+    #[inline]
+    pub fn dht_findpeer<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtFindPeerResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.request_stream_json(
+                &request::DhtFindPeer {
+                    peer: peer.as_str(),
+                },
+                None,
+            ),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    /// Find peers in the DHT that can provide a specific value given a key.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
-    /// use std::io::Cursor;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let data = Cursor::new("Hello World!");
-    /// let mut add = ipfs_api::request::Add::default();
-    /// add.chunker = Some("rabin-512-1024-2048");
-    /// let req = client.add_with_options(data, &add);
+    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.dht_findprovs(key).collect();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "dht")]
     #[inline]
-    pub fn add_with_options<R>(&self, data: R, add: &request::Add) -> AsyncResponse<response::AddResponse>
-    where
-        R: 'static + Read + Send,
-    {
-        let mut form = multipart::Form::default();
+    pub fn dht_findprovs(&self, key: &str) -> AsyncStreamResponse<response::DhtFindProvsResponse> {
+        self.request_stream_json(&request::DhtFindProvs { key }, None)
+    }
 
-        form.add_reader("path", data);
+    /// Query the DHT for a given key.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.dht_get(key).collect();
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dht")]
+    #[inline]
+    pub fn dht_get(&self, key: &str) -> AsyncStreamResponse<response::DhtGetResponse> {
+        self.request_stream_json(&request::DhtGet { key }, None)
+    }
 
-        self.request(add, Some(form))
+    /// Announce to the network that you are providing a given value.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    /// let req = client.dht_provide(key).collect();
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dht")]
+    #[inline]
+    pub fn dht_provide(&self, key: &str) -> AsyncStreamResponse<response::DhtProvideResponse> {
+        self.request_stream_json(&request::DhtProvide { key }, None)
     }
 
-    /// Add a path to Ipfs. Can be a file or directory.
-    /// A hard limit of 128 open file descriptors is set such
-    /// that any small additional files are stored in-memory.
+    /// Write a key/value pair to the DHT.
     ///
-    /// # Examples
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.dht_put("test", "Hello World!").collect();
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "dht")]
+    #[inline]
+    pub fn dht_put(&self, key: &str, value: &str) -> AsyncStreamResponse<response::DhtPutResponse> {
+        self.request_stream_json(&request::DhtPut { key, value }, None)
+    }
+
+    /// Find the closest peer given the peer ID by querying the DHT.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let path = "./src";
-    /// let req = client.add_path(path);
+    /// let peer = "QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM";
+    /// let req = client.dht_query(peer).collect();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "dht")]
     #[inline]
-    pub fn add_path<P>(&self, path: P) -> AsyncResponse<response::AddResponse>
+    pub fn dht_query<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtQueryResponse>
     where
-        P: AsRef<Path>,
+        P: ToPeerId,
     {
-        let mut form = multipart::Form::default();
-
-        let prefix = path.as_ref().parent();
-
-        let mut paths_to_add: Vec<(PathBuf, u64)> = vec![];
-
-        for path in walkdir::WalkDir::new(path.as_ref()) {
-            match path {
-                Ok(entry) => {
-                    if entry.file_type().is_file() {
-                        let file_size =
-                            entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
-                        paths_to_add.push((entry.path().to_path_buf(), file_size));
-                    }
-                }
-                Err(err) => {
-                    return Box::new(future::err(Error::Io(err.into())));
-                }
-            }
+        match peer.to_peer_id() {
+            Ok(peer) => self.request_stream_json(
+                &request::DhtQuery {
+                    peer: peer.as_str(),
+                },
+                None,
+            ),
+            Err(e) => Box::new(stream::once(Err(e))),
         }
+    }
 
-        paths_to_add.sort_unstable_by(|(_, a), (_, b)| a.cmp(b).reverse());
-
-        let mut it = 0;
-        const FILE_DESCRIPTOR_LIMIT: usize = 127;
-
-        for (path, file_size) in paths_to_add {
-            let file = std::fs::File::open(&path);
-            if file.is_err() {
-                return Box::new(future::err(file.unwrap_err().into()));
-            }
-            let file_name = match prefix {
-                Some(prefix) => path.strip_prefix(prefix).unwrap(),
-                None => path.as_path(),
-            }
-            .to_string_lossy();
-
-            if it < FILE_DESCRIPTOR_LIMIT {
-                form.add_reader_file("path", file.unwrap(), file_name);
-                it += 1;
-            } else {
-                let mut buf = Vec::with_capacity(file_size as usize);
-                if let Err(err) = file.unwrap().read_to_end(&mut buf) {
-                    return Box::new(future::err(err.into()));
-                }
-                form.add_reader_file("path", std::io::Cursor::new(buf), file_name);
-            }
-        }
+    /// Clear inactive requests from the log.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.diag_cmds_clear();
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn diag_cmds_clear(&self) -> AsyncResponse<response::DiagCmdsClearResponse> {
+        self.request_empty(&request::DiagCmdsClear, None)
+    }
 
-        Box::new(
-            self.request_stream_json(&request::Add::default(), Some(form))
-                .collect()
-                .map(|mut responses: Vec<response::AddResponse>| responses.pop().unwrap()),
-        )
+    /// Set how long to keep inactive requests in the log.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.diag_cmds_set_time("1m");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn diag_cmds_set_time(
+        &self,
+        time: &str,
+    ) -> AsyncResponse<response::DiagCmdsSetTimeResponse> {
+        self.request_empty(&request::DiagCmdsSetTime { time }, None)
     }
 
-    /// Returns the current ledger for a peer.
+    /// Print system diagnostic information.
     ///
-    /// # Examples
+    /// Note: There isn't good documentation on what this call is supposed to return.
+    /// It might be platform dependent, but if it isn't, this can be fixed to return
+    /// an actual object.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -594,18 +3600,20 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bitswap_ledger("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ");
+    /// let req = client.diag_sys();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn bitswap_ledger(&self, peer: &str) -> AsyncResponse<response::BitswapLedgerResponse> {
-        self.request(&request::BitswapLedger { peer }, None)
+    pub fn diag_sys(&self) -> AsyncResponse<response::DiagSysResponse> {
+        self.request_string(&request::DiagSys, None)
     }
 
-    /// Triggers a reprovide.
+    /// Print system diagnostic information, as the raw bytes the daemon
+    /// returned.
     ///
-    /// # Examples
+    /// Unlike `diag_sys`, output that isn't valid utf-8 doesn't make the
+    /// request fail. Use `String::from_utf8_lossy` to read it as text.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -614,38 +3622,89 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bitswap_reprovide();
+    /// let req = client.diag_sys_bytes();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn bitswap_reprovide(&self) -> AsyncResponse<response::BitswapReprovideResponse> {
-        self.request_empty(&request::BitswapReprovide, None)
+    pub fn diag_sys_bytes(&self) -> AsyncResponse<Bytes> {
+        self.request_bytes(&request::DiagSys, None)
     }
 
-    /// Returns some stats about the bitswap agent.
+    /// Resolve DNS link.
     ///
-    /// # Examples
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.dns("ipfs.io", true);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn dns(&self, link: &str, recursive: bool) -> AsyncResponse<response::DnsResponse> {
+        let dns = request::Dns {
+            link,
+            recursive,
+            stream: None,
+        };
+
+        self.request(&dns, None)
+    }
+
+    /// Resolve DNS link, streaming every intermediate answer on the way. This
+    /// shows each step of a DNSLink chain that points at other DNSLinks.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bitswap_stat();
+    /// let req = client.dns_stream("ipfs.io", true).collect();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn bitswap_stat(&self) -> AsyncResponse<response::BitswapStatResponse> {
-        self.request(&request::BitswapStat, None)
+    pub fn dns_stream(
+        &self,
+        link: &str,
+        recursive: bool,
+    ) -> AsyncStreamResponse<response::DnsResponse> {
+        let dns = request::Dns {
+            link,
+            recursive,
+            stream: Some(true),
+        };
+
+        self.request_stream_json(&dns, None)
     }
 
-    /// Remove a given block from your wantlist.
+    /// List directory for Unix filesystem objects.
     ///
-    /// # Examples
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.file_ls("/ipns/ipfs.io");
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn file_ls(&self, path: &str) -> AsyncResponse<response::FileLsResponse> {
+        self.request(&request::FileLs { path }, None)
+    }
+
+    /// Copy files into MFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -654,18 +3713,69 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bitswap_unwant("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// let req = client.files_cp("/path/to/file", "/dest");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn bitswap_unwant(&self, key: &str) -> AsyncResponse<response::BitswapUnwantResponse> {
-        self.request_empty(&request::BitswapUnwant { key }, None)
+    pub fn files_cp<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesCpResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let dest = match dest.to_mfs_path() {
+            Ok(dest) => dest,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request_empty(
+            &request::FilesCp {
+                path: &path,
+                dest: &dest,
+            },
+            None,
+        )
+    }
+
+    /// Flush a path's data to disk, returning the CID of the flushed path
+    /// when the daemon reports it.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.files_flush(None);
+    /// let req = client.files_flush(Some("/tmp"));
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "files")]
+    #[inline]
+    pub fn files_flush(&self, path: Option<&str>) -> AsyncResponse<response::FilesFlushResponse> {
+        let strict = self.inner.strict;
+        let res = self
+            .request_raw(&request::FilesFlush { path }, None)
+            .and_then(move |(status, chunk)| {
+                if status == StatusCode::OK && chunk.iter().all(u8::is_ascii_whitespace) {
+                    Ok(response::FilesFlushResponse { cid: None })
+                } else {
+                    IpfsClient::process_json_response(status, chunk, strict)
+                }
+            });
+
+        Box::new(res)
     }
 
-    /// Shows blocks on the wantlist for you or the specified peer.
-    ///
-    /// # Examples
+    /// List directories in MFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -674,73 +3784,95 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bitswap_wantlist(Some("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"));
+    /// let req = client.files_ls(None);
+    /// let req = client.files_ls(Some("/tmp"));
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn bitswap_wantlist(
-        &self,
-        peer: Option<&str>,
-    ) -> AsyncResponse<response::BitswapWantlistResponse> {
-        self.request(&request::BitswapWantlist { peer }, None)
+    pub fn files_ls(&self, path: Option<&str>) -> AsyncResponse<response::FilesLsResponse> {
+        self.request(&request::FilesLs { path, long: false }, None)
     }
 
-    /// Gets a raw IPFS block.
-    ///
-    /// # Examples
+    /// Make directories in MFS.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
-    /// let req = client.block_get(hash).concat2();
+    /// let req = client.files_mkdir("/test", false);
+    /// let req = client.files_mkdir("/test/nested/dir", true);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn block_get(&self, hash: &str) -> AsyncStreamResponse<Bytes> {
-        self.request_stream_bytes(&request::BlockGet { hash }, None)
+    pub fn files_mkdir<P>(
+        &self,
+        path: P,
+        parents: bool,
+    ) -> AsyncResponse<response::FilesMkdirResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request_empty(
+            &request::FilesMkdir {
+                path: &path,
+                parents,
+            },
+            None,
+        )
     }
 
-    /// Store input as an IPFS block.
-    ///
-    /// # Examples
+    /// Copy files into MFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
     /// use ipfs_api::IpfsClient;
-    /// use std::io::Cursor;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let data = Cursor::new("Hello World!");
-    /// let req = client.block_put(data);
+    /// let req = client.files_mv("/test/tmp.json", "/test/file.json");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn block_put<R>(&self, data: R) -> AsyncResponse<response::BlockPutResponse>
+    pub fn files_mv<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesMvResponse>
     where
-        R: 'static + Read + Send,
+        P: ToMfsPath,
+        D: ToMfsPath,
     {
-        let mut form = multipart::Form::default();
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
 
-        form.add_reader("data", data);
+        let dest = match dest.to_mfs_path() {
+            Ok(dest) => dest,
+            Err(e) => return Box::new(future::err(e)),
+        };
 
-        self.request(&request::BlockPut, Some(form))
+        self.request_empty(
+            &request::FilesMv {
+                path: &path,
+                dest: &dest,
+            },
+            None,
+        )
     }
 
-    /// Removes an IPFS block.
-    ///
-    /// # Examples
+    /// Read a file in MFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -749,18 +3881,25 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.block_rm("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// let req = client.files_read("/test/file.json");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn block_rm(&self, hash: &str) -> AsyncResponse<response::BlockRmResponse> {
-        self.request(&request::BlockRm { hash }, None)
+    pub fn files_read<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes(&request::FilesRead { path: &path }, None)
     }
 
-    /// Prints information about a raw IPFS block.
-    ///
-    /// # Examples
+    /// Remove a file in MFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -769,18 +3908,32 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// let req = client.files_rm("/test/dir", true);
+    /// let req = client.files_rm("/test/file.json", false);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn block_stat(&self, hash: &str) -> AsyncResponse<response::BlockStatResponse> {
-        self.request(&request::BlockStat { hash }, None)
+    pub fn files_rm<P>(&self, path: P, recursive: bool) -> AsyncResponse<response::FilesRmResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request_empty(
+            &request::FilesRm {
+                path: &path,
+                recursive,
+            },
+            None,
+        )
     }
 
-    /// Add default peers to the bootstrap list.
-    ///
-    /// # Examples
+    /// Display a file's status in MDFS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -789,97 +3942,346 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bootstrap_add_default();
+    /// let req = client.files_stat("/test/file.json");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn bootstrap_add_default(&self) -> AsyncResponse<response::BootstrapAddDefaultResponse> {
-        self.request(&request::BootstrapAddDefault, None)
+    pub fn files_stat<P>(&self, path: P) -> AsyncResponse<response::FilesStatResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request(&request::FilesStat { path: &path }, None)
     }
 
-    /// Lists peers in bootstrap list.
-    ///
-    /// # Examples
+    /// Write to a mutable file in the filesystem.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
     /// use ipfs_api::IpfsClient;
+    /// use std::fs::File;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bootstrap_list();
+    /// let file = File::open("test.json").unwrap();
+    /// let req = client.files_write("/test/file.json", true, true, file);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn bootstrap_list(&self) -> AsyncResponse<response::BootstrapListResponse> {
-        self.request(&request::BootstrapList, None)
+    pub fn files_write<P, R>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: R,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        R: 'static + Read + Send,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let mut form = multipart::Form::default();
+
+        form.add_reader("data", data);
+
+        self.request_empty(
+            &request::FilesWrite {
+                path: &path,
+                create,
+                truncate,
+            },
+            Some(form.into()),
+        )
     }
 
-    /// Removes all peers in bootstrap list.
-    ///
-    /// # Examples
+    /// Write a stream of bytes to a mutable file in the filesystem.
     ///
     /// ```no_run
+    /// # extern crate bytes;
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use bytes::Bytes;
+    /// use futures::stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.bootstrap_rm_all();
+    /// let data = stream::once(Ok(Bytes::from("Hello World!")));
+    /// let req = client.files_write_stream("/test/file.json", true, true, data);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "files")]
     #[inline]
-    pub fn bootstrap_rm_all(&self) -> AsyncResponse<response::BootstrapRmAllResponse> {
-        self.request(&request::BootstrapRmAll, None)
+    pub fn files_write_stream<P, S>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: S,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let part = StreamPart::new("data", data);
+
+        self.request_empty(
+            &request::FilesWrite {
+                path: &path,
+                create,
+                truncate,
+            },
+            Some(part.into()),
+        )
     }
 
-    /// Returns the contents of an Ipfs object.
+    /// Mirror a local file or directory into MFS at `mfs_path`.
     ///
-    /// # Examples
+    /// Directories are created first, with their parents, and then files are
+    /// written, a few at a time. Files that already exist in MFS are
+    /// overwritten.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
-    /// let req = client.cat(hash).concat2();
+    /// let req = client.files_write_tree("./docs", "/website/docs");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn cat(&self, path: &str) -> AsyncStreamResponse<Bytes> {
-        self.request_stream_bytes(&request::Cat { path }, None)
+    #[cfg(feature = "files")]
+    pub fn files_write_tree<P, M>(&self, local_path: P, mfs_path: M) -> AsyncResponse<()>
+    where
+        P: AsRef<Path>,
+        M: ToMfsPath,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let tree = match mfs::local_tree(local_path.as_ref(), &mfs_path) {
+            Ok(tree) => tree,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let client = self.clone();
+        let mkdirs = stream::iter_ok(tree.dirs).for_each(move |dir| client.files_mkdir(&dir, true));
+
+        let client = self.clone();
+        let read_buffer_size = self.inner.read_buffer_size;
+        let writes = stream::iter_ok(tree.files)
+            .map(move |(local, target)| match fs::File::open(&local) {
+                Ok(file) => client.files_write(
+                    &target,
+                    true,
+                    true,
+                    io::BufReader::with_capacity(read_buffer_size, file),
+                ),
+                Err(e) => Box::new(future::err(e.into())),
+            })
+            .buffer_unordered(mfs::PARALLELISM)
+            .for_each(|_| Ok(()));
+
+        Box::new(mkdirs.and_then(|_| writes))
     }
 
-    /// List available commands that the server accepts.
+    /// Mirror a local file or directory into MFS at `mfs_path`, like
+    /// `files_write_tree`, but only writing the files that changed. Returns
+    /// the MFS paths of the files that were written.
+    ///
+    /// The directories are listed with `files_ls`. A file is unchanged if
+    /// MFS has a file of the same size at its path, with the hash `add`
+    /// would give the local file. Hashes are only computed for files whose
+    /// size matches, and nothing is added to the repo to do so. Files in MFS
+    /// that don't exist locally are left in place.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Future;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.commands();
+    /// let req = client
+    ///     .files_sync("./docs", "/website/docs")
+    ///     .map(|written| println!("{} files changed", written.len()));
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn commands(&self) -> AsyncResponse<response::CommandsResponse> {
-        self.request(&request::Commands, None)
+    #[cfg(feature = "files")]
+    pub fn files_sync<P, M>(&self, local_path: P, mfs_path: M) -> AsyncResponse<Vec<String>>
+    where
+        P: AsRef<Path>,
+        M: ToMfsPath,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let tree = match mfs::local_tree(local_path.as_ref(), &mfs_path) {
+            Ok(tree) => tree,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let mut dirs = tree.dirs;
+
+        for (_, target) in &tree.files {
+            let (dir, _) = mfs::split(target);
+
+            if !dirs.iter().any(|listed| listed == dir) {
+                dirs.push(dir.to_string());
+            }
+        }
+
+        let client = self.clone();
+        let listings = stream::iter_ok(dirs)
+            .map(move |dir| {
+                let ls = request::FilesLs {
+                    path: Some(&dir),
+                    long: true,
+                };
+
+                client
+                    .request::<_, response::FilesLsResponse>(&ls, None)
+                    .then(move |res| match res {
+                        Ok(ls) => Ok((dir, Some(ls.entries))),
+                        Err(ref e) if e.api_kind() == Some(response::ApiErrorKind::NotFound) => {
+                            Ok((dir, None))
+                        }
+                        Err(e) => Err(e),
+                    })
+            })
+            .buffered(mfs::PARALLELISM)
+            .collect();
+
+        let client = self.clone();
+        let files = tree.files;
+        let res = listings.and_then(move |listings| {
+            let mut entries = HashMap::new();
+            let mut missing = Vec::new();
+
+            for (dir, listing) in listings {
+                match listing {
+                    Some(listing) => {
+                        for entry in listing {
+                            entries.insert(mfs::join(&dir, Path::new(&entry.name)), entry);
+                        }
+                    }
+                    None => missing.push(dir),
+                }
+            }
+
+            let mkdir = client.clone();
+            let mkdirs =
+                stream::iter_ok(missing).for_each(move |dir| mkdir.files_mkdir(&dir, true));
+
+            let writes = stream::iter_ok(files)
+                .map(move |(local, target)| {
+                    let entry = entries.remove(&target);
+
+                    client.files_sync_file(local, target, entry)
+                })
+                .buffer_unordered(mfs::PARALLELISM)
+                .filter_map(|written| written)
+                .collect()
+                .map(|mut written| {
+                    written.sort();
+                    written
+                });
+
+            mkdirs.and_then(|_| writes)
+        });
+
+        Box::new(res)
     }
 
-    /// Opens the config file for editing (on the server).
+    /// Writes a local file to `target` in MFS unless `entry`, the listing of
+    /// `target`, shows the same content is already there. Returns `target`
+    /// if the file was written.
+    ///
+    #[cfg(feature = "files")]
+    fn files_sync_file(
+        &self,
+        local: PathBuf,
+        target: String,
+        entry: Option<response::FilesEntry>,
+    ) -> AsyncResponse<Option<String>> {
+        let size = match fs::metadata(&local) {
+            Ok(metadata) => metadata.len(),
+            Err(e) => return Box::new(future::err(e.into())),
+        };
+
+        let read_buffer_size = self.inner.read_buffer_size;
+        let open = move |local: &Path| {
+            fs::File::open(local).map(|file| io::BufReader::with_capacity(read_buffer_size, file))
+        };
+
+        let unchanged: AsyncResponse<bool> = match entry {
+            Some(entry) if entry.typ == response::EntryKind::File && entry.size == size => {
+                let add = request::Add {
+                    only_hash: Some(true),
+                    ..Default::default()
+                };
+
+                match open(&local) {
+                    Ok(file) => Box::new(
+                        self.add_with_options(file, &add)
+                            .map(move |added| added.hash == entry.hash),
+                    ),
+                    Err(e) => return Box::new(future::err(e.into())),
+                }
+            }
+            _ => Box::new(future::ok(false)),
+        };
+
+        let client = self.clone();
+        let res = unchanged.and_then(move |unchanged| -> AsyncResponse<Option<String>> {
+            if unchanged {
+                return Box::new(future::ok(None));
+            }
+
+            match open(&local) {
+                Ok(file) => Box::new(
+                    client
+                        .files_write(&target, true, true, file)
+                        .map(move |_| Some(target)),
+                ),
+                Err(e) => Box::new(future::err(e.into())),
+            }
+        });
+
+        Box::new(res)
+    }
+
+    /// Copy a file or directory from MFS to `local_path`, the counterpart to
+    /// `files_write_tree`.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -888,45 +4290,110 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.config_edit();
+    /// let req = client.files_read_tree("/website/docs", "./docs");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn config_edit(&self) -> AsyncResponse<response::ConfigEditResponse> {
-        self.request(&request::ConfigEdit, None)
+    #[cfg(feature = "files")]
+    pub fn files_read_tree<M, P>(&self, mfs_path: M, local_path: P) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let client = self.clone();
+        let mfs_path = String::from(mfs_path);
+        let local_path = local_path.as_ref().to_path_buf();
+
+        let res = self.files_stat(&mfs_path).and_then(move |stat| {
+            if stat.typ == "directory" {
+                client.files_read_dir(mfs_path, local_path)
+            } else {
+                client.files_read_file(&mfs_path, local_path)
+            }
+        });
+
+        Box::new(res)
     }
 
-    /// Replace the config file.
+    /// Copies the contents of an MFS directory into `local_path`.
+    ///
+    #[cfg(feature = "files")]
+    fn files_read_dir(&self, mfs_path: String, local_path: PathBuf) -> AsyncResponse<()> {
+        if let Err(e) = fs::create_dir_all(&local_path) {
+            return Box::new(future::err(e.into()));
+        }
+
+        let client = self.clone();
+        let ls = request::FilesLs {
+            path: Some(&mfs_path),
+            long: true,
+        };
+
+        let res = self
+            .request::<_, response::FilesLsResponse>(&ls, None)
+            .and_then(move |ls| {
+                stream::iter_ok(ls.entries)
+                    .map(move |entry| {
+                        let mfs_path = mfs::join(&mfs_path, Path::new(&entry.name));
+                        let local_path = local_path.join(&entry.name);
+
+                        if entry.typ == response::EntryKind::Directory {
+                            client.files_read_dir(mfs_path, local_path)
+                        } else {
+                            client.files_read_file(&mfs_path, local_path)
+                        }
+                    })
+                    .buffer_unordered(mfs::PARALLELISM)
+                    .for_each(|_| Ok(()))
+            });
+
+        Box::new(res)
+    }
+
+    /// Copies an MFS file to `local_path`.
+    ///
+    #[cfg(feature = "files")]
+    fn files_read_file(&self, mfs_path: &str, local_path: PathBuf) -> AsyncResponse<()> {
+        let file = match fs::File::create(&local_path) {
+            Ok(file) => file,
+            Err(e) => return Box::new(future::err(e.into())),
+        };
+
+        let res = self
+            .files_read(mfs_path)
+            .fold(file, |mut file, chunk| {
+                file.write_all(&chunk).map(|_| file).map_err(Error::from)
+            })
+            .map(|_| ());
+
+        Box::new(res)
+    }
+
+    /// List blocks that are both in the filestore and standard block storage.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
     /// use ipfs_api::IpfsClient;
-    /// use std::io::Cursor;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let config = Cursor::new("{..json..}");
-    /// let req = client.config_replace(config);
+    /// let req = client.filestore_dups();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "filestore")]
     #[inline]
-    pub fn config_replace<R>(&self, data: R) -> AsyncResponse<response::ConfigReplaceResponse>
-    where
-        R: 'static + Read + Send,
-    {
-        let mut form = multipart::Form::default();
-
-        form.add_reader("file", data);
-
-        self.request_empty(&request::ConfigReplace, Some(form))
+    pub fn filestore_dups(&self) -> AsyncStreamResponse<response::FilestoreDupsResponse> {
+        self.request_stream_json(&request::FilestoreDups, None)
     }
 
-    /// Show the current config of the server.
-    ///
-    /// Returns an unparsed json string, due to an unclear spec.
+    /// List objects in filestore.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -935,16 +4402,20 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.config_show();
+    /// let req = client.filestore_ls(Some("QmYPP3BovR2m8UqCZxFbdXSit6SKgExxDkFAPLqiGsap4X"));
     /// # }
     /// ```
     ///
+    #[cfg(feature = "filestore")]
     #[inline]
-    pub fn config_show(&self) -> AsyncResponse<response::ConfigShowResponse> {
-        self.request_string(&request::ConfigShow, None)
+    pub fn filestore_ls(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreLsResponse> {
+        self.request_stream_json(&request::FilestoreLs { cid }, None)
     }
 
-    /// Returns information about a dag node in Ipfs.
+    /// Verify objects in filestore.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -953,160 +4424,189 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.dag_get("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// let req = client.filestore_verify(None);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "filestore")]
     #[inline]
-    pub fn dag_get(&self, path: &str) -> AsyncResponse<response::DagGetResponse> {
-        self.request(&request::DagGet { path }, None)
-    }
-
-    // TODO /dag routes are experimental, and there isn't a whole lot of
-    // documentation available for how this route works.
-    //
-    // /// Add a DAG node to Ipfs.
-    // ///
-    // #[inline]
-    // pub fn dag_put<R>(&self, data: R) -> AsyncResponse<response::DagPutResponse>
-    // where
-    //     R: 'static + Read + Send,
-    // {
-    //     let mut form = multipart::Form::default();
-    //
-    //     form.add_reader("arg", data);
-    //
-    //     self.request(&request::DagPut, Some(form))
-    // }
-
-    // TODO /dag/resolve
+    pub fn filestore_verify(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreVerifyResponse> {
+        self.request_stream_json(&request::FilestoreVerify { cid }, None)
+    }
 
-    /// Query the DHT for all of the multiaddresses associated with a Peer ID.
+    /// Download Ipfs object.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let peer = "QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM";
-    /// let req = client.dht_findpeer(peer).collect();
+    /// let req = client.get("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json");
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn dht_findpeer(&self, peer: &str) -> AsyncStreamResponse<response::DhtFindPeerResponse> {
-        self.request_stream_json(&request::DhtFindPeer { peer }, None)
+    pub fn get<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes(&request::Get { path: &path }, None)
     }
 
-    /// Find peers in the DHT that can provide a specific value given a key.
+    /// Download Ipfs object, re-requesting it if the download fails with a
+    /// transient error.
+    ///
+    /// `get` can't start from an offset, so the archive is downloaded again
+    /// from the start, and the bytes that were already received are skipped.
+    /// Each attempt waits twice as long as the one before, starting at
+    /// 250ms. The download is given up after three failures in a row without
+    /// any new bytes being received.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
-    /// let req = client.dht_findprovs(key).collect();
+    /// let req = client.get_resumable("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn dht_findprovs(&self, key: &str) -> AsyncStreamResponse<response::DhtFindProvsResponse> {
-        self.request_stream_json(&request::DhtFindProvs { key }, None)
+    pub fn get_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        let client = self.clone();
+
+        Box::new(Resume::new(move |offset| {
+            Box::new(SkipBytes::new(client.get(&path), offset))
+        }))
     }
 
-    /// Query the DHT for a given key.
+    /// Download Ipfs object, calling `callback` with the progress of the
+    /// download as each chunk is received.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
-    /// let req = client.dht_get(key).collect();
+    /// let req = client.get_with_progress("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json", |progress| {
+    ///     println!("received {} bytes", progress.received)
+    /// });
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn dht_get(&self, key: &str) -> AsyncStreamResponse<response::DhtGetResponse> {
-        self.request_stream_json(&request::DhtGet { key }, None)
+    pub fn get_with_progress<F>(&self, path: &str, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        F: 'static + FnMut(Progress) + Send,
+    {
+        self.request_stream_bytes_progress(&request::Get { path }, callback)
     }
 
-    /// Announce to the network that you are providing a given value.
+    /// Download an Ipfs object, and unpack it to `dest`, like `ipfs get -o`.
+    ///
+    /// A file is written to `dest`, and the contents of a directory are
+    /// written inside of `dest`. `overwrite` decides what happens to files
+    /// that already exist.
+    ///
+    /// The archive is unpacked as it is downloaded, so only one chunk of it
+    /// is held in memory at a time. Entries are never written through a
+    /// symlink, so an archive can't write outside of `dest`.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
-    /// use ipfs_api::IpfsClient;
+    /// use ipfs_api::{IpfsClient, OverwritePolicy};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let key = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
-    /// let req = client.dht_provide(key).collect();
+    /// let req = client.get_to_path("/ipfs/QmFoo", "./foo", OverwritePolicy::Skip);
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn dht_provide(&self, key: &str) -> AsyncStreamResponse<response::DhtProvideResponse> {
-        self.request_stream_json(&request::DhtProvide { key }, None)
+    pub fn get_to_path<I, P>(
+        &self,
+        path: I,
+        dest: P,
+        overwrite: OverwritePolicy,
+    ) -> AsyncResponse<()>
+    where
+        I: ToIpfsPath,
+        P: AsRef<Path>,
+    {
+        Box::new(unpack::unpack(
+            self.get(path),
+            dest.as_ref().to_path_buf(),
+            overwrite,
+        ))
     }
 
-    /// Write a key/value pair to the DHT.
+    /// Returns information about a peer.
+    ///
+    /// If `peer` is `None`, returns information about you.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.dht_put("test", "Hello World!").collect();
+    /// let req = client.id(None);
+    /// let req = client.id(Some("QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM"));
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn dht_put(&self, key: &str, value: &str) -> AsyncStreamResponse<response::DhtPutResponse> {
-        self.request_stream_json(&request::DhtPut { key, value }, None)
+    pub fn id(&self, peer: Option<&str>) -> AsyncResponse<response::IdResponse> {
+        self.request(&request::Id { peer }, None)
     }
 
-    /// Find the closest peer given the peer ID by querying the DHT.
+    /// Create a new keypair.
     ///
     /// ```no_run
-    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use futures::Stream;
-    /// use ipfs_api::IpfsClient;
+    /// use ipfs_api::{IpfsClient, KeyType};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let peer = "QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM";
-    /// let req = client.dht_query(peer).collect();
+    /// let req = client.key_gen("test", KeyType::Rsa, 64);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "key")]
     #[inline]
-    pub fn dht_query(&self, peer: &str) -> AsyncStreamResponse<response::DhtQueryResponse> {
-        self.request_stream_json(&request::DhtQuery { peer }, None)
+    pub fn key_gen(
+        &self,
+        name: &str,
+        kind: request::KeyType,
+        size: i32,
+    ) -> AsyncResponse<response::KeyGenResponse> {
+        self.request(&request::KeyGen { name, kind, size }, None)
     }
 
-    /// Clear inactive requests from the log.
+    /// List all local keypairs.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1115,16 +4615,20 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.diag_cmds_clear();
+    /// let req = client.key_list();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "key")]
     #[inline]
-    pub fn diag_cmds_clear(&self) -> AsyncResponse<response::DiagCmdsClearResponse> {
-        self.request_empty(&request::DiagCmdsClear, None)
+    pub fn key_list(&self) -> AsyncResponse<response::KeyListResponse> {
+        self.request(&request::KeyList, None)
     }
 
-    /// Set how long to keep inactive requests in the log.
+    /// Returns the peer id of the key named `name`, looked up with
+    /// `key_list`. The key of the node itself is named `self`.
+    ///
+    /// Fails with `Error::KeyNotFound` if there is no such key.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1133,23 +4637,25 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.diag_cmds_set_time("1m");
+    /// let req = client.key_id("self");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn diag_cmds_set_time(
-        &self,
-        time: &str,
-    ) -> AsyncResponse<response::DiagCmdsSetTimeResponse> {
-        self.request_empty(&request::DiagCmdsSetTime { time }, None)
+    #[cfg(feature = "key")]
+    pub fn key_id(&self, name: &str) -> AsyncResponse<PeerId> {
+        let name = name.to_string();
+
+        let res = self.key_list().and_then(move |list| {
+            match list.keys.into_iter().find(|pair| pair.name == name) {
+                Some(pair) => pair.id.parse(),
+                None => Err(Error::KeyNotFound(name)),
+            }
+        });
+
+        Box::new(res)
     }
 
-    /// Print system diagnostic information.
-    ///
-    /// Note: There isn't good documentation on what this call is supposed to return.
-    /// It might be platform dependent, but if it isn't, this can be fixed to return
-    /// an actual object.
+    /// Rename a keypair.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1158,16 +4664,22 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.diag_sys();
+    /// let req = client.key_rename("key_0", "new_name", false);
     /// # }
     /// ```
     ///
+    #[cfg(feature = "key")]
     #[inline]
-    pub fn diag_sys(&self) -> AsyncResponse<response::DiagSysResponse> {
-        self.request_string(&request::DiagSys, None)
+    pub fn key_rename(
+        &self,
+        name: &str,
+        new: &str,
+        force: bool,
+    ) -> AsyncResponse<response::KeyRenameResponse> {
+        self.request(&request::KeyRename { name, new, force }, None)
     }
 
-    /// Resolve DNS link.
+    /// Remove a keypair.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1176,34 +4688,43 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.dns("ipfs.io", true);
+    /// let req = client.key_rm("key_0");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "key")]
     #[inline]
-    pub fn dns(&self, link: &str, recursive: bool) -> AsyncResponse<response::DnsResponse> {
-        self.request(&request::Dns { link, recursive }, None)
+    pub fn key_rm(&self, name: &str) -> AsyncResponse<response::KeyRmResponse> {
+        self.request(&request::KeyRm { name }, None)
     }
 
-    /// List directory for Unix filesystem objects.
+    /// Change the logging level for a logger.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
+    /// use ipfs_api::{IpfsClient, Logger, LoggingLevel};
+    /// use std::borrow::Cow;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.file_ls("/ipns/ipfs.io");
+    /// let req = client.log_level(Logger::All, LoggingLevel::Debug);
+    /// let req = client.log_level(
+    ///     Logger::Specific(Cow::Borrowed("web")),
+    ///     LoggingLevel::Warning);
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn file_ls(&self, path: &str) -> AsyncResponse<response::FileLsResponse> {
-        self.request(&request::FileLs { path }, None)
+    pub fn log_level(
+        &self,
+        logger: request::Logger,
+        level: request::LoggingLevel,
+    ) -> AsyncResponse<response::LogLevelResponse> {
+        self.request(&request::LogLevel { logger, level }, None)
     }
 
-    /// Copy files into MFS.
+    /// List all logging subsystems.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1212,16 +4733,16 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_cp("/path/to/file", "/dest");
+    /// let req = client.log_ls();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn files_cp(&self, path: &str, dest: &str) -> AsyncResponse<response::FilesCpResponse> {
-        self.request_empty(&request::FilesCp { path, dest }, None)
+    pub fn log_ls(&self) -> AsyncResponse<response::LogLsResponse> {
+        self.request(&request::LogLs, None)
     }
 
-    /// Flush a path's data to disk.
+    /// Read the event log.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1230,17 +4751,18 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_flush(None);
-    /// let req = client.files_flush(Some("/tmp"));
+    /// let req = client.log_tail();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn files_flush(&self, path: Option<&str>) -> AsyncResponse<response::FilesFlushResponse> {
-        self.request_empty(&request::FilesFlush { path }, None)
+    pub fn log_tail(&self) -> AsyncStreamResponse<String> {
+        self.request_stream(&request::LogTail, None, |res| {
+            IpfsClient::process_stream_response(res, LineDecoder)
+        })
     }
 
-    /// List directories in MFS.
+    /// List the contents of an Ipfs multihash.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1249,17 +4771,19 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_ls(None);
-    /// let req = client.files_ls(Some("/tmp"));
+    /// let req = client.ls(None);
+    /// let req = client.ls(Some("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY"));
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn files_ls(&self, path: Option<&str>) -> AsyncResponse<response::FilesLsResponse> {
-        self.request(&request::FilesLs { path }, None)
+    pub fn ls(&self, path: Option<&str>) -> AsyncResponse<response::LsResponse> {
+        self.request(&request::Ls { path }, None)
     }
 
-    /// Make directories in MFS.
+    // TODO /mount
+
+    /// Publish an IPFS path to IPNS.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1268,21 +4792,42 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_mkdir("/test", false);
-    /// let req = client.files_mkdir("/test/nested/dir", true);
+    /// let req = client.name_publish(
+    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
+    ///     false,
+    ///     Some("12h"),
+    ///     None,
+    ///     None);
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn files_mkdir(
+    #[cfg(feature = "name")]
+    pub fn name_publish(
         &self,
         path: &str,
-        parents: bool,
-    ) -> AsyncResponse<response::FilesMkdirResponse> {
-        self.request_empty(&request::FilesMkdir { path, parents }, None)
+        resolve: bool,
+        lifetime: Option<&str>,
+        ttl: Option<&str>,
+        key: Option<&str>,
+    ) -> AsyncResponse<response::NamePublishResponse> {
+        self.request(
+            &request::NamePublish {
+                path,
+                resolve,
+                lifetime,
+                ttl,
+                key,
+            },
+            None,
+        )
     }
 
-    /// Copy files into MFS.
+    /// Publish `cid` under the IPNS name of the key named `key`, and return
+    /// the name.
+    ///
+    /// The key is looked up with `key_list` first, so a missing key fails
+    /// before anything is published. The record is valid for 24 hours, and
+    /// uses the daemon's default ttl.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1291,16 +4836,35 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_mv("/test/tmp.json", "/test/file.json");
+    /// let req = client.publish_name("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", "self");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn files_mv(&self, path: &str, dest: &str) -> AsyncResponse<response::FilesMvResponse> {
-        self.request_empty(&request::FilesMv { path, dest }, None)
+    #[cfg(all(feature = "key", feature = "name"))]
+    pub fn publish_name<C>(&self, cid: C, key: &str) -> AsyncResponse<String>
+    where
+        C: ToCid,
+    {
+        const DEFAULT_LIFETIME: &str = "24h";
+
+        let path = match cid.to_cid() {
+            Ok(cid) => format!("/ipfs/{}", cid),
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let client = self.clone();
+        let key = key.to_string();
+
+        let res = self
+            .key_id(&key)
+            .and_then(move |_| {
+                client.name_publish(&path, true, Some(DEFAULT_LIFETIME), None, Some(&key))
+            })
+            .map(|res| res.name);
+
+        Box::new(res)
     }
 
-    /// Read a file in MFS.
+    /// Resolve an IPNS name.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1309,16 +4873,36 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_read("/test/file.json");
+    /// let req = client.name_resolve(
+    ///     Some("/ipns/ipfs.io"),
+    ///     true,
+    ///     false);
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn files_read(&self, path: &str) -> AsyncStreamResponse<Bytes> {
-        self.request_stream_bytes(&request::FilesRead { path }, None)
+    #[cfg(feature = "name")]
+    pub fn name_resolve(
+        &self,
+        name: Option<&str>,
+        recursive: bool,
+        nocache: bool,
+    ) -> AsyncResponse<response::NameResolveResponse> {
+        self.request(
+            &request::NameResolve {
+                name,
+                recursive,
+                nocache,
+            },
+            None,
+        )
     }
 
-    /// Remove a file in MFS.
+    /// Resolve an IPNS name or DNSLink domain, and cat the file it points
+    /// to.
+    ///
+    /// If the name can't be resolved, the stream fails with
+    /// `Error::Resolve`. Errors while fetching the file are returned as they
+    /// are by `cat`.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1327,21 +4911,25 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_rm("/test/dir", true);
-    /// let req = client.files_rm("/test/file.json", false);
+    /// let req = client.cat_ipns("/ipns/ipfs.io/index.html");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn files_rm(
-        &self,
-        path: &str,
-        recursive: bool,
-    ) -> AsyncResponse<response::FilesRmResponse> {
-        self.request_empty(&request::FilesRm { path, recursive }, None)
+    #[cfg(feature = "name")]
+    pub fn cat_ipns(&self, name: &str) -> AsyncStreamResponse<Bytes> {
+        let client = self.clone();
+        let unresolved = name.to_string();
+
+        let res = self
+            .name_resolve(Some(name), true, false)
+            .map_err(move |e| Error::Resolve(unresolved, Box::new(e)))
+            .map(move |res| client.cat(&res.path))
+            .flatten_stream();
+
+        Box::new(res)
     }
 
-    /// Display a file's status in MDFS.
+    /// Output the raw bytes of an Ipfs object.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1350,56 +4938,70 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.files_stat("/test/file.json");
+    /// let req = client.object_data("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn files_stat(&self, path: &str) -> AsyncResponse<response::FilesStatResponse> {
-        self.request(&request::FilesStat { path }, None)
+    pub fn object_data<K>(&self, key: K) -> AsyncStreamResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.request_stream_bytes(&request::ObjectData { key: &key }, None)
     }
 
-    /// Write to a mutable file in the filesystem.
+    /// Returns the diff of two Ipfs objects.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
     /// use ipfs_api::IpfsClient;
-    /// use std::fs::File;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let file = File::open("test.json").unwrap();
-    /// let req = client.files_write("/test/file.json", true, true, file);
+    /// let req = client.object_diff(
+    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
+    ///     "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn files_write<R>(
+    pub fn object_diff<K0, K1>(
         &self,
-        path: &str,
-        create: bool,
-        truncate: bool,
-        data: R,
-    ) -> AsyncResponse<response::FilesWriteResponse>
+        key0: K0,
+        key1: K1,
+    ) -> AsyncResponse<response::ObjectDiffResponse>
     where
-        R: 'static + Read + Send,
+        K0: ToIpfsPath,
+        K1: ToIpfsPath,
     {
-        let mut form = multipart::Form::default();
+        let key0 = match key0.to_ipfs_path() {
+            Ok(key0) => key0,
+            Err(e) => return Box::new(future::err(e)),
+        };
 
-        form.add_reader("data", data);
+        let key1 = match key1.to_ipfs_path() {
+            Ok(key1) => key1,
+            Err(e) => return Box::new(future::err(e)),
+        };
 
-        self.request_empty(
-            &request::FilesWrite {
-                path,
-                create,
-                truncate,
+        self.request(
+            &request::ObjectDiff {
+                key0: &key0,
+                key1: &key1,
             },
-            Some(form),
+            None,
         )
     }
 
-    /// List blocks that are both in the filestore and standard block storage.
+    /// Returns the data in an object.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1408,37 +5010,72 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.filestore_dups();
+    /// let req = client.object_get("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn filestore_dups(&self) -> AsyncStreamResponse<response::FilestoreDupsResponse> {
-        self.request_stream_json(&request::FilestoreDups, None)
+    pub fn object_get<K>(&self, key: K) -> AsyncResponse<response::ObjectGetResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let get = request::ObjectGet {
+            key: &key,
+            encoding: None,
+        };
+
+        self.request(&get, None)
     }
 
-    /// List objects in filestore.
+    /// Returns an object in the given encoding, undecoded. With
+    /// `ObjectEncoding::Protobuf`, this is the node's exact dag-pb bytes,
+    /// which `block_put` stores again under the same CID.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
+    /// use ipfs_api::{IpfsClient, ObjectEncoding};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.filestore_ls(Some("QmYPP3BovR2m8UqCZxFbdXSit6SKgExxDkFAPLqiGsap4X"));
+    /// let req = client.object_get_encoded(
+    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
+    ///     ObjectEncoding::Xml,
+    /// );
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn filestore_ls(
+    pub fn object_get_encoded<K>(
         &self,
-        cid: Option<&str>,
-    ) -> AsyncStreamResponse<response::FilestoreLsResponse> {
-        self.request_stream_json(&request::FilestoreLs { cid }, None)
+        key: K,
+        encoding: request::ObjectEncoding,
+    ) -> AsyncResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        let get = request::ObjectGet {
+            key: &key,
+            encoding: Some(encoding),
+        };
+
+        self.request_bytes(&get, None)
     }
 
-    /// Verify objects in filestore.
+    /// Returns an object decoded from its dag-pb bytes. Unlike `object_get`,
+    /// the node's data is returned as is, rather than as a string.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1447,19 +5084,26 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.filestore_verify(None);
+    /// let req = client.object_get_node("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn filestore_verify(
-        &self,
-        cid: Option<&str>,
-    ) -> AsyncStreamResponse<response::FilestoreVerifyResponse> {
-        self.request_stream_json(&request::FilestoreVerify { cid }, None)
+    #[cfg(feature = "object")]
+    pub fn object_get_node<K>(&self, key: K) -> AsyncResponse<DagPbNode>
+    where
+        K: ToIpfsPath,
+    {
+        let res = self
+            .object_get_encoded(key, request::ObjectEncoding::Protobuf)
+            .and_then(|bytes| {
+                DagPbNode::decode(&bytes)
+                    .ok_or_else(|| Error::Uncategorized("malformed dag-pb node".into()))
+            });
+
+        Box::new(res)
     }
 
-    /// Download Ipfs object.
+    /// Returns the links that an object points to.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1468,60 +5112,78 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.get("/test/file.json");
+    /// let req = client.object_links("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn get(&self, path: &str) -> AsyncStreamResponse<Bytes> {
-        self.request_stream_bytes(&request::Get { path }, None)
+    pub fn object_links<K>(&self, key: K) -> AsyncResponse<response::ObjectLinksResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request(&request::ObjectLinks { key: &key }, None)
     }
 
-    /// Returns information about a peer.
-    ///
-    /// If `peer` is `None`, returns information about you.
+    /// Walk the graph of objects under `hash`, breadth first, returning each
+    /// node with its links. Nodes that were already reached are skipped, and
+    /// links are not followed past `max_depth`.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.id(None);
-    /// let req = client.id(Some("QmSoLPppuBtQSGwKDZT2M73ULpjvfd3aZ6ha4oFGL1KrGM"));
+    /// let req = client
+    ///     .walk("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", Some(2))
+    ///     .for_each(|node| {
+    ///         println!("{} {}", node.depth, node.hash);
+    ///         Ok(())
+    ///     });
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn id(&self, peer: Option<&str>) -> AsyncResponse<response::IdResponse> {
-        self.request(&request::Id { peer }, None)
+    pub fn walk(&self, hash: &str, max_depth: Option<usize>) -> AsyncStreamResponse<WalkNode> {
+        Box::new(Walk::new(self.clone(), hash, max_depth))
     }
 
-    /// Create a new keypair.
+    /// Create a new object.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::{IpfsClient, KeyType};
+    /// use ipfs_api::{IpfsClient, ObjectTemplate};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.key_gen("test", KeyType::Rsa, 64);
+    /// let req = client.object_new(None);
+    /// let req = client.object_new(Some(ObjectTemplate::UnixFsDir));
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn key_gen(
+    pub fn object_new(
         &self,
-        name: &str,
-        kind: request::KeyType,
-        size: i32,
-    ) -> AsyncResponse<response::KeyGenResponse> {
-        self.request(&request::KeyGen { name, kind, size }, None)
+        template: Option<request::ObjectTemplate>,
+    ) -> AsyncResponse<response::ObjectNewResponse> {
+        self.request(&request::ObjectNew { template }, None)
     }
 
-    /// List all local keypairs.
+    /// Add a link named `name` to `link` in the object `key`. Resolves to
+    /// the new object. With `create`, intermediate objects in a `name` with
+    /// slashes are created as needed.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1530,16 +5192,44 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.key_list();
+    /// let req = client.object_patch_add_link(
+    ///     "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn",
+    ///     "hello.txt",
+    ///     "QmWATWQ7fVPP2EFGu71UkfnqhYXDYH566qy47CnJDgvs8u",
+    ///     false,
+    /// );
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn key_list(&self) -> AsyncResponse<response::KeyListResponse> {
-        self.request(&request::KeyList, None)
+    pub fn object_patch_add_link(
+        &self,
+        key: &str,
+        name: &str,
+        link: &str,
+        create: bool,
+    ) -> AsyncResponse<response::ObjectPatchAddLinkResponse> {
+        self.request(
+            &request::ObjectPatchAddLink {
+                key,
+                name,
+                link,
+                create,
+            },
+            None,
+        )
     }
 
-    /// Rename a keypair.
+    // TODO /object/patch/append-data
+
+    // TODO /object/patch/rm-link
+
+    // TODO /object/patch/set-data
+
+    // TODO /object/put
+
+    /// Returns the stats for an object.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1548,83 +5238,110 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.key_rename("key_0", "new_name", false);
+    /// let req = client.object_stat("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "object")]
     #[inline]
-    pub fn key_rename(
-        &self,
-        name: &str,
-        new: &str,
-        force: bool,
-    ) -> AsyncResponse<response::KeyRenameResponse> {
-        self.request(&request::KeyRename { name, new, force }, None)
+    pub fn object_stat<K>(&self, key: K) -> AsyncResponse<response::ObjectStatResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.request(&request::ObjectStat { key: &key }, None)
     }
 
-    /// Remove a keypair.
+    // TODO /p2p/listener/close
+
+    // TODO /p2p/listener/ls
+
+    // TODO /p2p/listener/open
+
+    // TODO /p2p/stream/close
+
+    // TODO /p2p/stream/dial
+
+    // TODO /p2p/stream/ls
+
+    /// Pins a new object.
     ///
-    /// ```no_run
-    /// # extern crate ipfs_api;
-    /// #
-    /// use ipfs_api::IpfsClient;
+    /// The "recursive" option tells the server whether to
+    /// pin just the top-level object, or all sub-objects
+    /// it depends on.  For most cases you want it to be `true`.
     ///
-    /// # fn main() {
-    /// let client = IpfsClient::default();
-    /// let req = client.key_rm("key_0");
-    /// # }
-    /// ```
+    /// Does not yet implement the "progress" agument because
+    /// reading it is kinda squirrelly.
     ///
-    #[inline]
-    pub fn key_rm(&self, name: &str) -> AsyncResponse<response::KeyRmResponse> {
-        self.request(&request::KeyRm { name }, None)
-    }
-
-    /// Change the logging level for a logger.
+    /// # Examples
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::{IpfsClient, Logger, LoggingLevel};
-    /// use std::borrow::Cow;
+    /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.log_level(Logger::All, LoggingLevel::Debug);
-    /// let req = client.log_level(
-    ///     Logger::Specific(Cow::Borrowed("web")),
-    ///     LoggingLevel::Warning);
+    /// let req = client.pin_add("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ", true);
     /// # }
     /// ```
-    ///
+    #[cfg(feature = "pin")]
     #[inline]
-    pub fn log_level(
-        &self,
-        logger: request::Logger,
-        level: request::LoggingLevel,
-    ) -> AsyncResponse<response::LogLevelResponse> {
-        self.request(&request::LogLevel { logger, level }, None)
+    pub fn pin_add(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinAddResponse> {
+        self.request(
+            &request::PinAdd {
+                key,
+                recursive: Some(recursive),
+                progress: false,
+            },
+            None,
+        )
     }
 
-    /// List all logging subsystems.
+    /// Pins a new object recursively, reporting how many blocks have been
+    /// fetched while the daemon pins it. The last event is `Pinned`.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
+    /// use futures::Stream;
+    /// use ipfs_api::{response::PinAddEvent, IpfsClient};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.log_ls();
+    /// let req = client
+    ///     .pin_add_progress("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn")
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             PinAddEvent::Progress(blocks) => println!("fetched {} blocks", blocks),
+    ///             PinAddEvent::Pinned(pins) => println!("pinned {:?}", pins),
+    ///         }
+    ///         Ok(())
+    ///     });
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pin")]
     #[inline]
-    pub fn log_ls(&self) -> AsyncResponse<response::LogLsResponse> {
-        self.request(&request::LogLs, None)
+    pub fn pin_add_progress(&self, key: &str) -> AsyncStreamResponse<response::PinAddEvent> {
+        let req = request::PinAdd {
+            key,
+            recursive: Some(true),
+            progress: true,
+        };
+
+        Box::new(
+            self.request_stream_json::<_, response::PinAddResponse>(&req, None)
+                .map(From::from),
+        )
     }
 
-    /// Read the event log.
+    /// Pins many objects in one request.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1633,34 +5350,34 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.log_tail();
+    /// let req = client.pin_add_many(
+    ///     &[
+    ///         "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+    ///         "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ",
+    ///     ],
+    ///     true,
+    /// );
     /// # }
     /// ```
     ///
-    pub fn log_tail(&self) -> AsyncStreamResponse<String> {
-        #[cfg(feature = "hyper")]
-        let res = self
-            .build_base_request(&request::LogTail, None)
-            .map(|req| self.client.request(req).from_err())
-            .into_future()
-            .flatten()
-            .map(|res| IpfsClient::process_stream_response(res, LineDecoder))
-            .flatten_stream();
-        #[cfg(feature = "actix")]
-        let res = self
-            .build_base_request(&request::LogTail, None)
-            .into_future()
-            .and_then(|req| {
-                req.send()
-                    .timeout(std::time::Duration::from_secs(90))
-                    .from_err()
-            })
-            .map(|res| IpfsClient::process_stream_response(res, LineDecoder))
-            .flatten_stream();
-        Box::new(res)
+    #[cfg(feature = "pin")]
+    #[inline]
+    pub fn pin_add_many(
+        &self,
+        keys: &[&str],
+        recursive: bool,
+    ) -> AsyncResponse<response::PinAddResponse> {
+        self.request(
+            &request::PinAddMany {
+                keys: request::Args(keys),
+                recursive: Some(recursive),
+                progress: false,
+            },
+            None,
+        )
     }
 
-    /// List the contents of an Ipfs multihash.
+    /// Returns a list of pinned objects in local storage.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1669,19 +5386,25 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.ls(None);
-    /// let req = client.ls(Some("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY"));
+    /// let req = client.pin_ls(None, None);
+    /// let req = client.pin_ls(
+    ///     Some("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY"),
+    ///     None);
+    /// let req = client.pin_ls(None, Some("direct"));
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pin")]
     #[inline]
-    pub fn ls(&self, path: Option<&str>) -> AsyncResponse<response::LsResponse> {
-        self.request(&request::Ls { path }, None)
+    pub fn pin_ls(
+        &self,
+        key: Option<&str>,
+        typ: Option<&str>,
+    ) -> AsyncResponse<response::PinLsResponse> {
+        self.request(&request::PinLs { key, typ }, None)
     }
 
-    // TODO /mount
-
-    /// Publish an IPFS path to IPNS.
+    /// Removes a pinned object from local storage.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1690,68 +5413,112 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.name_publish(
+    /// let req = client.pin_rm(
     ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
-    ///     false,
-    ///     Some("12h"),
-    ///     None,
-    ///     None);
+    ///     false);
+    /// let req = client.pin_rm(
+    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
+    ///     true);
     /// # }
     /// ```
     ///
-    pub fn name_publish(
-        &self,
-        path: &str,
-        resolve: bool,
-        lifetime: Option<&str>,
-        ttl: Option<&str>,
-        key: Option<&str>,
-    ) -> AsyncResponse<response::NamePublishResponse> {
-        self.request(
-            &request::NamePublish {
-                path,
-                resolve,
-                lifetime,
-                ttl,
-                key,
-            },
-            None,
-        )
+    #[cfg(feature = "pin")]
+    #[inline]
+    pub fn pin_rm(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinRmResponse> {
+        self.request(&request::PinRm { key, recursive }, None)
     }
 
-    /// Resolve an IPNS name.
+    /// Makes the recursive pins match `desired`: CIDs that aren't pinned
+    /// are pinned recursively, and recursive pins that aren't in `desired`
+    /// are removed. Direct and indirect pins are left alone.
+    ///
+    /// CIDs are compared by multihash, so content pinned as a CIDv0 matches
+    /// the same content given as a CIDv1 in `desired`, and is left pinned.
+    ///
+    /// At most `parallelism` pins are added or removed at the same time. A
+    /// pin that fails doesn't stop the others, and is listed in the report
+    /// with its error. Pinned keys that aren't valid CIDs are left alone,
+    /// and listed in the report's `unparsed`.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
+    /// use futures::Future;
+    /// use ipfs_api::{Cid, IpfsClient};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.name_resolve(
-    ///     Some("/ipns/ipfs.io"),
-    ///     true,
-    ///     false);
+    /// let desired: Vec<Cid> = vec![
+    ///     "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap(),
+    ///     "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ".parse().unwrap(),
+    /// ];
+    /// let req = client.pin_reconcile(desired, 4).map(|report| {
+    ///     println!("+{} -{}", report.added.len(), report.removed.len());
+    /// });
     /// # }
     /// ```
     ///
-    pub fn name_resolve(
-        &self,
-        name: Option<&str>,
-        recursive: bool,
-        nocache: bool,
-    ) -> AsyncResponse<response::NameResolveResponse> {
-        self.request(
-            &request::NameResolve {
-                name,
-                recursive,
-                nocache,
-            },
-            None,
-        )
+    #[cfg(feature = "pin")]
+    pub fn pin_reconcile<I>(&self, desired: I, parallelism: usize) -> AsyncResponse<PinsetReport>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let desired: HashSet<Cid> = desired.into_iter().collect();
+        let client = self.clone();
+
+        let res = self.pin_ls(None, Some("recursive")).and_then(move |pins| {
+            let mut pinned = HashSet::new();
+            let mut unparsed = Vec::new();
+
+            for key in pins.keys.keys() {
+                match key.parse::<Cid>() {
+                    Ok(cid) => {
+                        pinned.insert(cid);
+                    }
+                    Err(e) => unparsed.push((key.clone(), e)),
+                }
+            }
+
+            let diff = PinsetDiff::new(&desired, &pinned);
+            let report = PinsetReport {
+                unchanged: diff.unchanged,
+                unparsed,
+                ..Default::default()
+            };
+            let adds = diff.add.into_iter().map(|cid| (cid, true));
+            let removes = diff.remove.into_iter().map(|cid| (cid, false));
+
+            stream::iter_ok(adds.chain(removes))
+                .map(move |(cid, add)| {
+                    let res: AsyncResponse<()> = if add {
+                        Box::new(client.pin_add(cid.as_str(), true).map(|_| ()))
+                    } else {
+                        Box::new(client.pin_rm(cid.as_str(), true).map(|_| ()))
+                    };
+
+                    res.then(move |res| Ok((cid, add, res)))
+                })
+                .buffer_unordered(parallelism.max(1))
+                .fold(report, |mut report, (cid, add, res)| {
+                    match res {
+                        Ok(()) if add => report.added.push(cid),
+                        Ok(()) => report.removed.push(cid),
+                        Err(e) => report.failed.push((cid, e)),
+                    }
+
+                    Ok::<_, Error>(report)
+                })
+        });
+
+        Box::new(res)
     }
 
-    /// Output the raw bytes of an Ipfs object.
+    // TODO /pin/update
+
+    // TODO /pin/verify
+
+    /// Pings a peer.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1760,16 +5527,33 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_data("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
+    /// let req = client.ping("QmSoLV4Bbm51jM9C4gDYZQ9Cy3U6aXMJDAbzgu2fzaDs64", None);
+    /// let req = client.ping("QmSoLV4Bbm51jM9C4gDYZQ9Cy3U6aXMJDAbzgu2fzaDs64", Some(15));
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn object_data(&self, key: &str) -> AsyncStreamResponse<Bytes> {
-        self.request_stream_bytes(&request::ObjectData { key }, None)
+    pub fn ping<P>(
+        &self,
+        peer: P,
+        count: Option<i32>,
+    ) -> AsyncStreamResponse<response::PingResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.request_stream_json(
+                &request::Ping {
+                    peer: peer.as_str(),
+                    count,
+                },
+                None,
+            ),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
     }
 
-    /// Returns the diff of two Ipfs objects.
+    /// List subscribed pubsub topics.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1778,22 +5562,17 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_diff(
-    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
-    ///     "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA");
+    /// let req = client.pubsub_ls();
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn object_diff(
-        &self,
-        key0: &str,
-        key1: &str,
-    ) -> AsyncResponse<response::ObjectDiffResponse> {
-        self.request(&request::ObjectDiff { key0, key1 }, None)
+    pub fn pubsub_ls(&self) -> AsyncResponse<response::PubsubLsResponse> {
+        self.request(&request::PubsubLs, None)
     }
 
-    /// Returns the data in an object.
+    /// List peers that are being published to.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1802,16 +5581,21 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_get("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
+    /// let req = client.pubsub_peers(None);
+    /// let req = client.pubsub_peers(Some("feed"));
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn object_get(&self, key: &str) -> AsyncResponse<response::ObjectGetResponse> {
-        self.request(&request::ObjectGet { key }, None)
+    pub fn pubsub_peers(
+        &self,
+        topic: Option<&str>,
+    ) -> AsyncResponse<response::PubsubPeersResponse> {
+        self.request(&request::PubsubPeers { topic }, None)
     }
 
-    /// Returns the links that an object points to.
+    /// Publish a message to a topic.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1820,87 +5604,73 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_links("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
+    /// let req = client.pubsub_pub("feed", "Hello World!");
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn object_links(&self, key: &str) -> AsyncResponse<response::ObjectLinksResponse> {
-        self.request(&request::ObjectLinks { key }, None)
+    pub fn pubsub_pub(
+        &self,
+        topic: &str,
+        payload: &str,
+    ) -> AsyncResponse<response::PubsubPubResponse> {
+        self.request_empty(&request::PubsubPub { topic, payload }, None)
     }
 
-    /// Create a new object.
+    /// Returns a `Sink` that publishes each message sent to it to `topic`.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::{IpfsClient, ObjectTemplate};
+    /// use futures::{Future, Sink};
+    /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_new(None);
-    /// let req = client.object_new(Some(ObjectTemplate::UnixFsDir));
+    /// let req = client.pubsub_publisher("feed").send("Hello World!".to_string());
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn object_new(
-        &self,
-        template: Option<request::ObjectTemplate>,
-    ) -> AsyncResponse<response::ObjectNewResponse> {
-        self.request(&request::ObjectNew { template }, None)
+    pub fn pubsub_publisher(&self, topic: &str) -> PubsubPublisher<IpfsClient> {
+        PubsubPublisher::new(self.clone(), topic)
     }
 
-    // TODO /object/patch/add-link
-
-    // TODO /object/patch/append-data
-
-    // TODO /object/patch/rm-link
-
-    // TODO /object/patch/set-data
-
-    // TODO /object/put
-
-    /// Returns the stats for an object.
+    /// Returns a `PubsubRouter` to subscribe to many topics with, and the
+    /// stream that the messages published to all of them come from.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.object_stat("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY");
+    /// let (router, messages) = client.pubsub_router();
+    ///
+    /// router.subscribe("chat", false);
+    /// router.subscribe("presence", false);
+    ///
+    /// let req = messages.for_each(|(topic, msg)| {
+    ///     println!("{}: {:?}", topic, msg.data);
+    ///
+    ///     Ok(())
+    /// });
     /// # }
     /// ```
     ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn object_stat(&self, key: &str) -> AsyncResponse<response::ObjectStatResponse> {
-        self.request(&request::ObjectStat { key }, None)
+    pub fn pubsub_router(&self) -> (PubsubRouter<IpfsClient>, PubsubMessages) {
+        PubsubRouter::new(self.clone())
     }
 
-    // TODO /p2p/listener/close
-
-    // TODO /p2p/listener/ls
-
-    // TODO /p2p/listener/open
-
-    // TODO /p2p/stream/close
-
-    // TODO /p2p/stream/dial
-
-    // TODO /p2p/stream/ls
-
-    /// Pins a new object.
-    ///
-    /// The "recursive" option tells the server whether to
-    /// pin just the top-level object, or all sub-objects
-    /// it depends on.  For most cases you want it to be `true`.
-    ///
-    /// Does not yet implement the "progress" agument because
-    /// reading it is kinda squirrelly.
-    ///
-    /// # Examples
+    /// Subscribes to a pubsub topic.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1909,48 +5679,66 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pin_add("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ", true);
+    /// let req = client.pubsub_sub("feed", false);
+    /// let req = client.pubsub_sub("feed", true);
     /// # }
     /// ```
+    ///
+    #[cfg(feature = "pubsub")]
     #[inline]
-    pub fn pin_add(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinAddResponse> {
-        self.request(
-            &request::PinAdd {
-                key,
-                recursive: Some(recursive),
-                progress: false,
-            },
-            None,
-        )
+    pub fn pubsub_sub(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubSubResponse> {
+        self.request_stream_json(&request::PubsubSub { topic, discover }, None)
     }
 
-    /// Returns a list of pinned objects in local storage.
+    /// Subscribes to a pubsub topic, and subscribes again whenever the
+    /// subscription is lost, waiting longer after each failed attempt.
+    ///
+    /// A `Reconnected` event is returned each time, since messages published
+    /// while the client wasn't subscribed are missed. The stream never ends
+    /// on its own, but fails with errors that subscribing again wouldn't fix,
+    /// such as an error from the daemon. Requires a tokio runtime for the
+    /// delays between attempts.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
-    /// use ipfs_api::IpfsClient;
+    /// use futures::Stream;
+    /// use ipfs_api::{response::PubsubEvent, IpfsClient};
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pin_ls(None, None);
-    /// let req = client.pin_ls(
-    ///     Some("/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY"),
-    ///     None);
-    /// let req = client.pin_ls(None, Some("direct"));
+    /// let req = client
+    ///     .pubsub_sub_reconnecting("feed", false)
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             PubsubEvent::Message(msg) => println!("{:?}", msg.data),
+    ///             PubsubEvent::Reconnected => println!("reconnected"),
+    ///         }
+    ///         Ok(())
+    ///     });
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn pin_ls(
+    #[cfg(feature = "pubsub")]
+    pub fn pubsub_sub_reconnecting(
         &self,
-        key: Option<&str>,
-        typ: Option<&str>,
-    ) -> AsyncResponse<response::PinLsResponse> {
-        self.request(&request::PinLs { key, typ }, None)
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubEvent> {
+        let client = self.clone();
+        let topic = topic.to_string();
+        let subscribe = move || client.pubsub_sub(&topic, discover);
+
+        Box::new(pubsub::Reconnect::new(subscribe, pubsub::INITIAL_BACKOFF))
     }
 
-    /// Removes a pinned object from local storage.
+    /// Lists the references under a path, each once. Links are followed
+    /// `max_depth` levels deep, or to the bottom of the graph with `None`.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -1959,48 +5747,68 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pin_rm(
-    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
-    ///     false);
-    /// let req = client.pin_rm(
-    ///     "/ipfs/QmVrLsEDn27sScp3k23sgZNefVTjSAL3wpgW1iWPi4MgoY",
-    ///     true);
+    /// let req = client.refs("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", Some(1));
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn pin_rm(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinRmResponse> {
-        self.request(&request::PinRm { key, recursive }, None)
-    }
-
-    // TODO /pin/update
+    pub fn refs<P>(
+        &self,
+        path: P,
+        max_depth: Option<u64>,
+    ) -> AsyncStreamResponse<response::RefsResponse>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
 
-    // TODO /pin/verify
+        self.request_stream_json(
+            &request::Refs {
+                path: &path,
+                recursive: max_depth != Some(1),
+                unique: true,
+                max_depth,
+            },
+            None,
+        )
+    }
 
-    /// Pings a peer.
+    /// Lists the CIDs under a path one level of the graph at a time, with a
+    /// `refs` request for the direct links of each node, so that very large
+    /// graphs can be listed. Each CID is returned once, and links are not
+    /// followed past `max_depth`.
     ///
     /// ```no_run
+    /// # extern crate futures;
     /// # extern crate ipfs_api;
     /// #
+    /// use futures::Stream;
     /// use ipfs_api::IpfsClient;
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.ping("QmSoLV4Bbm51jM9C4gDYZQ9Cy3U6aXMJDAbzgu2fzaDs64", None);
-    /// let req = client.ping("QmSoLV4Bbm51jM9C4gDYZQ9Cy3U6aXMJDAbzgu2fzaDs64", Some(15));
+    /// let req = client
+    ///     .refs_walk("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn", None)
+    ///     .for_each(|cid| {
+    ///         println!("{}", cid);
+    ///         Ok(())
+    ///     });
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn ping(
-        &self,
-        peer: &str,
-        count: Option<i32>,
-    ) -> AsyncStreamResponse<response::PingResponse> {
-        self.request_stream_json(&request::Ping { peer, count }, None)
+    pub fn refs_walk<P>(&self, path: P, max_depth: Option<usize>) -> AsyncStreamResponse<Cid>
+    where
+        P: ToIpfsPath,
+    {
+        match path.to_ipfs_path() {
+            Ok(path) => Box::new(RefsWalk::new(self.clone(), path, max_depth)),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
     }
 
-    /// List subscribed pubsub topics.
+    /// Gets a list of local references.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -2009,16 +5817,19 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pubsub_ls();
+    /// let req = client.refs_local();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn pubsub_ls(&self) -> AsyncResponse<response::PubsubLsResponse> {
-        self.request(&request::PubsubLs, None)
+    pub fn refs_local(&self) -> AsyncStreamResponse<response::RefsLocalResponse> {
+        self.request_stream_json(&request::RefsLocal, None)
     }
 
-    /// List peers that are being published to.
+    // TODO /repo/fsck
+
+    /// Remove blocks that are not pinned from the repo, returning each
+    /// block as it is removed.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -2027,20 +5838,20 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pubsub_peers(None);
-    /// let req = client.pubsub_peers(Some("feed"));
+    /// let req = client.repo_gc();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn pubsub_peers(
-        &self,
-        topic: Option<&str>,
-    ) -> AsyncResponse<response::PubsubPeersResponse> {
-        self.request(&request::PubsubPeers { topic }, None)
+    pub fn repo_gc(&self) -> AsyncStreamResponse<response::RepoGcResponse> {
+        self.request_stream_json(&request::RepoGc, None)
     }
 
-    /// Publish a message to a topic.
+    /// Remove blocks that are not pinned from the repo, and return how many
+    /// were removed, how much space was reclaimed, and any errors.
+    ///
+    /// The space reclaimed is the difference in the size of the repo,
+    /// reported by `stats_repo`, before and after the collection.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -2049,20 +5860,38 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pubsub_pub("feed", "Hello World!");
+    /// let req = client.repo_gc_summary();
     /// # }
     /// ```
     ///
-    #[inline]
-    pub fn pubsub_pub(
-        &self,
-        topic: &str,
-        payload: &str,
-    ) -> AsyncResponse<response::PubsubPubResponse> {
-        self.request_empty(&request::PubsubPub { topic, payload }, None)
+    pub fn repo_gc_summary(&self) -> AsyncResponse<response::RepoGcSummary> {
+        let gc = self.clone();
+        let after = self.clone();
+
+        let res = self.stats_repo().and_then(move |before| {
+            gc.repo_gc()
+                .fold(response::RepoGcSummary::default(), |mut summary, res| {
+                    match res.error {
+                        Some(e) => summary.errors.push(e),
+                        None => summary.removed += 1,
+                    }
+
+                    Ok::<_, Error>(summary)
+                })
+                .and_then(move |summary| {
+                    after
+                        .stats_repo()
+                        .map(move |after| response::RepoGcSummary {
+                            reclaimed: before.repo_size.saturating_sub(after.repo_size),
+                            ..summary
+                        })
+                })
+        });
+
+        Box::new(res)
     }
 
-    /// Subscribes to a pubsub topic.
+    /// Returns repo stats.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -2071,21 +5900,16 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.pubsub_sub("feed", false);
-    /// let req = client.pubsub_sub("feed", true);
+    /// let req = client.repo_stat();
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn pubsub_sub(
-        &self,
-        topic: &str,
-        discover: bool,
-    ) -> AsyncStreamResponse<response::PubsubSubResponse> {
-        self.request_stream_json(&request::PubsubSub { topic, discover }, None)
+    pub fn repo_stat(&self) -> AsyncResponse<response::RepoStatResponse> {
+        self.repo_stat_with_options(&request::RepoStat::default())
     }
 
-    /// Gets a list of local references.
+    /// Returns repo stats, with options.
     ///
     /// ```no_run
     /// # extern crate ipfs_api;
@@ -2094,21 +5918,20 @@ impl IpfsClient {
     ///
     /// # fn main() {
     /// let client = IpfsClient::default();
-    /// let req = client.refs_local();
+    /// let mut stat = ipfs_api::request::RepoStat::default();
+    /// stat.size_only = Some(true);
+    /// let req = client.repo_stat_with_options(&stat);
     /// # }
     /// ```
     ///
     #[inline]
-    pub fn refs_local(&self) -> AsyncStreamResponse<response::RefsLocalResponse> {
-        self.request_stream_json(&request::RefsLocal, None)
+    pub fn repo_stat_with_options(
+        &self,
+        stat: &request::RepoStat,
+    ) -> AsyncResponse<response::RepoStatResponse> {
+        self.request(stat, None)
     }
 
-    // TODO /repo/fsck
-
-    // TODO /repo/gc
-
-    // TODO /repo/stat
-
     // TODO /repo/verify
 
     // TODO /repo/version
@@ -2168,6 +5991,38 @@ impl IpfsClient {
         self.request(&request::StatsBw, None)
     }
 
+    /// Sample `stats_bw` every `interval`, and return the bytes per second
+    /// received and sent since the previous sample, for graphing bandwidth.
+    ///
+    /// The first sample is returned after the second call. A failed call is
+    /// returned as an error, and sampling goes on if the stream is polled
+    /// again.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::IpfsClient;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client
+    ///     .bandwidth_samples(Duration::from_secs(1))
+    ///     .for_each(|sample| {
+    ///         println!("in: {:.0} B/s, out: {:.0} B/s", sample.rate_in, sample.rate_out);
+    ///
+    ///         Ok(())
+    ///     });
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn bandwidth_samples(&self, interval: Duration) -> AsyncStreamResponse<BandwidthSample> {
+        Box::new(BandwidthSamples::new(self.clone(), interval))
+    }
+
     /// Returns repo stats.
     ///
     /// ```no_run
@@ -2183,7 +6038,30 @@ impl IpfsClient {
     ///
     #[inline]
     pub fn stats_repo(&self) -> AsyncResponse<response::StatsRepoResponse> {
-        self.request(&request::StatsRepo, None)
+        self.stats_repo_with_options(&request::StatsRepo::default())
+    }
+
+    /// Returns repo stats, with options.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let mut stat = ipfs_api::request::StatsRepo::default();
+    /// stat.size_only = Some(true);
+    /// let req = client.stats_repo_with_options(&stat);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn stats_repo_with_options(
+        &self,
+        stat: &request::StatsRepo,
+    ) -> AsyncResponse<response::StatsRepoResponse> {
+        self.request(stat, None)
     }
 
     // TODO /swarm/addrs/listen
@@ -2201,6 +6079,7 @@ impl IpfsClient {
     /// # }
     /// ```
     ///
+    #[cfg(feature = "swarm")]
     #[inline]
     pub fn swarm_addrs_local(&self) -> AsyncResponse<response::SwarmAddrsLocalResponse> {
         self.request(&request::SwarmAddrsLocal, None)
@@ -2214,6 +6093,113 @@ impl IpfsClient {
 
     // TODO /swarm/filters/rm
 
+    /// Open a connection to a peer at `addr`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.swarm_connect(
+    ///     "/ip4/104.131.131.82/tcp/4001/ipfs/QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ",
+    /// );
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "swarm")]
+    #[inline]
+    pub fn swarm_connect(&self, addr: &str) -> AsyncResponse<response::SwarmConnectResponse> {
+        self.request(&request::SwarmConnect { addr }, None)
+    }
+
+    /// Try to connect to a peer at each of `addrs` in turn, and return the
+    /// first address that worked.
+    ///
+    /// If none of them work, all of them are tried again after a delay, up to
+    /// three times, and then the error from the last attempt is returned.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::{IpfsClient, Multiaddr};
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let addrs: Vec<Multiaddr> = vec![
+    ///     "/ip4/104.131.131.82/tcp/4001/ipfs/QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+    ///         .parse()
+    ///         .unwrap(),
+    /// ];
+    /// let req = client.swarm_connect_any(addrs);
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "swarm")]
+    pub fn swarm_connect_any<I>(&self, addrs: I) -> AsyncResponse<Multiaddr>
+    where
+        I: IntoIterator<Item = Multiaddr>,
+    {
+        const ATTEMPTS: usize = 3;
+        const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+        let addrs: Vec<Multiaddr> = addrs.into_iter().collect();
+        let client = self.clone();
+
+        if addrs.is_empty() {
+            return Box::new(future::err(Error::Uncategorized(
+                "no addresses to connect to".into(),
+            )));
+        }
+
+        let res = future::loop_fn(
+            (0, 0, INITIAL_BACKOFF, None),
+            move |(attempt, index, backoff, last_error): (
+                usize,
+                usize,
+                Duration,
+                Option<Error>,
+            )|
+                  -> AsyncResponse<future::Loop<Multiaddr, _>> {
+                if index == addrs.len() {
+                    let e = last_error.unwrap_or_else(|| {
+                        Error::Uncategorized("could not connect to any address".into())
+                    });
+
+                    if attempt + 1 == ATTEMPTS {
+                        return Box::new(future::err(e));
+                    }
+
+                    let retry = Delay::new(Instant::now() + backoff)
+                        .map_err(|e| Error::Uncategorized(e.to_string()))
+                        .map(move |_| {
+                            future::Loop::Continue((attempt + 1, 0, backoff * 2, Some(e)))
+                        });
+
+                    return Box::new(retry);
+                }
+
+                let addr = addrs[index].clone();
+                let res = client
+                    .swarm_connect(addr.as_str())
+                    .then(move |res| match res {
+                        Ok(_) => Ok(future::Loop::Break(addr)),
+                        Err(e) => Ok(future::Loop::Continue((
+                            attempt,
+                            index + 1,
+                            backoff,
+                            Some(e),
+                        ))),
+                    });
+
+                Box::new(res)
+            },
+        );
+
+        Box::new(res)
+    }
+
     /// Return a list of peers with open connections.
     ///
     /// ```no_run
@@ -2227,11 +6213,49 @@ impl IpfsClient {
     /// # }
     /// ```
     ///
+    #[cfg(feature = "swarm")]
     #[inline]
     pub fn swarm_peers(&self) -> AsyncResponse<response::SwarmPeersResponse> {
         self.request(&request::SwarmPeers, None)
     }
 
+    /// Watch the peers the daemon is connected to, by calling `swarm_peers`
+    /// every `interval`, and return a `PeerEvent` each time a peer connects
+    /// or disconnects.
+    ///
+    /// The peers that are connected when the stream starts are returned as
+    /// connected. A failed check is returned as an error, and checking goes
+    /// on if the stream is polled again.
+    ///
+    /// ```no_run
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use futures::Stream;
+    /// use ipfs_api::{IpfsClient, PeerEvent};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client
+    ///     .peer_events(Duration::from_secs(10))
+    ///     .for_each(|event| {
+    ///         match event {
+    ///             PeerEvent::Connected(peer) => println!("connected: {}", peer.peer),
+    ///             PeerEvent::Disconnected(peer) => println!("disconnected: {}", peer),
+    ///         }
+    ///
+    ///         Ok(())
+    ///     });
+    /// # }
+    /// ```
+    ///
+    #[cfg(feature = "swarm")]
+    #[inline]
+    pub fn peer_events(&self, interval: Duration) -> AsyncStreamResponse<PeerEvent> {
+        Box::new(PeerEvents::new(self.clone(), interval))
+    }
+
     /// Add a tar file to Ipfs.
     ///
     /// Note: `data` should already be a tar file. If it isn't the Api will return
@@ -2259,7 +6283,35 @@ impl IpfsClient {
 
         form.add_reader("file", data);
 
-        self.request(&request::TarAdd, Some(form))
+        self.request(&request::TarAdd, Some(form.into()))
+    }
+
+    /// Import a tar file, streamed from `data`, into IPFS.
+    ///
+    /// ```no_run
+    /// # extern crate bytes;
+    /// # extern crate futures;
+    /// # extern crate ipfs_api;
+    /// #
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    /// use ipfs_api::IpfsClient;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let data = stream::once(Ok(Bytes::from("Hello World!")));
+    /// let req = client.tar_add_stream(data);
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn tar_add_stream<S>(&self, data: S) -> AsyncResponse<response::TarAddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let part = StreamPart::new("file", data);
+
+        self.request(&request::TarAdd, Some(part.into()))
     }
 
     /// Export a tar file from Ipfs.
@@ -2297,4 +6349,268 @@ impl IpfsClient {
     pub fn version(&self) -> AsyncResponse<response::VersionResponse> {
         self.request(&request::Version, None)
     }
+
+    /// Waits until the daemon answers a version request, retrying with
+    /// increasing delays, and returns its version.
+    ///
+    /// If the daemon isn't ready within `timeout`, the error from the last
+    /// attempt is returned.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() {
+    /// let client = IpfsClient::default();
+    /// let req = client.wait_for_daemon(Duration::from_secs(30));
+    /// # }
+    /// ```
+    ///
+    pub fn wait_for_daemon(&self, timeout: Duration) -> AsyncResponse<response::VersionResponse> {
+        const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+        const MAX_BACKOFF: Duration = Duration::from_secs(2);
+
+        let client = self.clone();
+        let uri = self.inner.base.to_string();
+        let deadline = Instant::now() + timeout;
+
+        let res = future::loop_fn((INITIAL_BACKOFF, None), move |(backoff, last_error)| {
+            let uri = uri.clone();
+
+            Timeout::new_at(client.version(), deadline).then(
+                move |res| -> AsyncResponse<future::Loop<response::VersionResponse, _>> {
+                    let e = match res {
+                        Ok(version) => return Box::new(future::ok(future::Loop::Break(version))),
+                        Err(e) => match (e.into_inner(), last_error) {
+                            (Some(e), _) | (None, Some(e)) => e,
+                            (None, None) => Error::Timeout { uri },
+                        },
+                    };
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        return Box::new(future::err(e));
+                    }
+
+                    let retry = Delay::new(now + backoff.min(deadline - now))
+                        .map_err(|e| Error::Uncategorized(e.to_string()))
+                        .map(move |_| {
+                            future::Loop::Continue(((backoff * 2).min(MAX_BACKOFF), Some(e)))
+                        });
+
+                    Box::new(retry)
+                },
+            )
+        });
+
+        Box::new(res)
+    }
+
+    /// Check that the daemon is reachable every `interval`, by calling `id`,
+    /// so services can degrade gracefully while it restarts.
+    ///
+    /// The returned `DaemonMonitor` does the checking once it is spawned,
+    /// and `DaemonStatusWatch` returns the latest `DaemonStatus`.
+    ///
+    /// ```no_run
+    /// # extern crate ipfs_api;
+    /// # extern crate tokio;
+    /// #
+    /// use ipfs_api::IpfsClient;
+    /// use std::time::Duration;
+    /// use tokio::runtime::current_thread::Runtime;
+    ///
+    /// # fn main() {
+    /// let mut runtime = Runtime::new().unwrap();
+    /// let client = IpfsClient::default();
+    /// let (monitor, status) = client.monitor_daemon(Duration::from_secs(5));
+    ///
+    /// runtime.spawn(monitor);
+    ///
+    /// if !status.current().up {
+    ///     eprintln!("ipfs daemon is down");
+    /// }
+    /// # }
+    /// ```
+    ///
+    #[inline]
+    pub fn monitor_daemon(
+        &self,
+        interval: Duration,
+    ) -> (DaemonMonitor<IpfsClient>, DaemonStatusWatch) {
+        DaemonMonitor::new(self.clone(), interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IpfsClient;
+    use bytes::Bytes;
+    use http::StatusCode;
+    use response::{ApiErrorKind, Error};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_base_path() {
+        let path = |host| {
+            IpfsClient::build_base_path("http", host, 5001)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(path("localhost"), "http://localhost:5001/api/v0");
+        assert_eq!(path("127.0.0.1"), "http://127.0.0.1:5001/api/v0");
+        assert_eq!(path("::1"), "http://[::1]:5001/api/v0");
+        assert_eq!(path("[::1]"), "http://[::1]:5001/api/v0");
+    }
+
+    #[test]
+    fn test_from_ip() {
+        IpfsClient::from_ip(Ipv4Addr::LOCALHOST, 5001);
+        IpfsClient::from_ip(Ipv6Addr::LOCALHOST, 5001);
+    }
+
+    #[test]
+    fn test_error_from_json_body() {
+        let body = Bytes::from(r#"{"Message":"merkledag: not found","Code":0,"Type":"error"}"#);
+
+        match IpfsClient::build_error_from_body(StatusCode::INTERNAL_SERVER_ERROR, body) {
+            Error::Api(e) => assert_eq!(e.message, "merkledag: not found"),
+            e => panic!("expected an api error, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_error_from_deadline_exceeded() {
+        let body = Bytes::from(r#"{"Message":"context deadline exceeded","Code":0}"#);
+
+        match IpfsClient::build_error_from_body(StatusCode::INTERNAL_SERVER_ERROR, body) {
+            e @ Error::DeadlineExceeded(_) => {
+                assert_eq!(e.api_kind(), Some(ApiErrorKind::DeadlineExceeded))
+            }
+            e => panic!("expected DeadlineExceeded, got {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_redirect_drops_credentials_for_another_host() {
+        use http::{header, HeaderMap, Method, Response};
+
+        let uri = "http://localhost:5001/api/v0/version".parse().unwrap();
+        let redirect = |location| {
+            let res = Response::builder()
+                .status(StatusCode::FOUND)
+                .header(header::LOCATION, location)
+                .body(::hyper::Body::empty())
+                .unwrap();
+            let mut headers = HeaderMap::new();
+
+            headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+            headers.insert(header::COOKIE, "session=secret".parse().unwrap());
+
+            IpfsClient::redirect_request(&uri, &res, Method::GET, headers, false).unwrap()
+        };
+
+        let same_host = redirect("/api/v1/version");
+        let other_host = redirect("http://ipfs.test:5001/api/v0/version");
+
+        assert!(same_host.headers().contains_key(header::AUTHORIZATION));
+        assert!(same_host.headers().contains_key(header::COOKIE));
+        assert!(!other_host.headers().contains_key(header::AUTHORIZATION));
+        assert!(!other_host.headers().contains_key(header::COOKIE));
+        assert_eq!(other_host.uri(), "http://ipfs.test:5001/api/v0/version");
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_dns_error() {
+        let client = IpfsClient::new("does-not-exist.invalid", 5001).unwrap();
+        let mut runtime = ::tokio::runtime::current_thread::Runtime::new().unwrap();
+
+        match runtime.block_on(client.version()) {
+            Err(Error::Dns { uri, .. }) => {
+                assert_eq!(uri, "http://does-not-exist.invalid:5001/api/v0")
+            }
+            res => panic!("expected a dns error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_error_from_non_json_body() {
+        let error = |status, body: &'static str| {
+            IpfsClient::build_error_from_body(status, Bytes::from(body))
+        };
+
+        match error(StatusCode::NOT_FOUND, "404 page not found\n") {
+            Error::UnsupportedEndpoint { status, body } => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(body, "404 page not found");
+            }
+            e => panic!("expected UnsupportedEndpoint, got {:?}", e),
+        }
+        match error(StatusCode::METHOD_NOT_ALLOWED, "405 - Method Not Allowed") {
+            Error::UnsupportedEndpoint { .. } => (),
+            e => panic!("expected UnsupportedEndpoint, got {:?}", e),
+        }
+        match error(
+            StatusCode::BAD_GATEWAY,
+            "<html><h1>502 Bad Gateway</h1></html>",
+        ) {
+            Error::BadGateway { status, .. } => assert_eq!(status, StatusCode::BAD_GATEWAY),
+            e => panic!("expected BadGateway, got {:?}", e),
+        }
+        match error(StatusCode::INTERNAL_SERVER_ERROR, "oops") {
+            Error::UnexpectedStatus { status, body } => {
+                assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+                assert_eq!(body, "oops");
+            }
+            e => panic!("expected UnexpectedStatus, got {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_connect_error_names_address() {
+        let client = IpfsClient::new("127.0.0.1", 1).unwrap();
+        let mut runtime = ::tokio::runtime::current_thread::Runtime::new().unwrap();
+
+        match runtime.block_on(client.version()) {
+            Err(Error::ConnectionRefused { uri }) => assert_eq!(uri, "http://127.0.0.1:1/api/v0"),
+            res => panic!("expected a connect error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_wait_for_daemon_gives_up() {
+        let client = IpfsClient::new("127.0.0.1", 1).unwrap();
+        let mut runtime = ::tokio::runtime::current_thread::Runtime::new().unwrap();
+        let started = ::std::time::Instant::now();
+
+        match runtime.block_on(client.wait_for_daemon(::std::time::Duration::from_millis(300))) {
+            Err(Error::ConnectionRefused { .. }) => assert!(started.elapsed().as_secs() < 2),
+            res => panic!("expected a connect error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[cfg(feature = "hyper")]
+    fn assert_sync<T: Sync>(_: &T) {}
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_client_and_responses_are_send() {
+        let client = IpfsClient::default();
+
+        assert_send(&client);
+        assert_sync(&client);
+        assert_send(&client.version());
+        assert_send(&client.cat("/ipfs/QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"));
+        assert_send(&client.log_tail());
+    }
 }
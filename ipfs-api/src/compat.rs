@@ -0,0 +1,72 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! The compatibility layer of futures 0.3, for awaiting responses from
+//! `std::future` based code.
+//!
+//! Every method on `IpfsClient` returns a futures 0.1 `Future` or `Stream`,
+//! with or without this module. With the `futures03-compat` feature enabled,
+//! the extension traits re-exported here convert those into a
+//! `std::future::Future` (or a futures 0.3 `Stream`) that can be `.await`ed:
+//!
+//! ```edition2018,no_run
+//! use ipfs_api::compat::Future01CompatExt;
+//! use ipfs_api::IpfsClient;
+//!
+//! async fn version(client: &IpfsClient) -> Result<String, ipfs_api::response::Error> {
+//!     let res = client.version().compat().await?;
+//!
+//!     Ok(res.version)
+//! }
+//! ```
+//!
+//! Streams are converted with `Stream01CompatExt`:
+//!
+//! ```edition2018,no_run
+//! use futures03::TryStreamExt;
+//! use ipfs_api::compat::Stream01CompatExt;
+//! use ipfs_api::IpfsClient;
+//!
+//! async fn cat(client: &IpfsClient, path: &str) -> Result<Vec<u8>, ipfs_api::response::Error> {
+//!     let chunks: Vec<_> = client.cat(path).compat().try_collect().await?;
+//!
+//!     Ok(chunks.concat())
+//! }
+//! ```
+//!
+//! The underlying HTTP client still runs on tokio 0.1, so the converted
+//! futures need to be polled from within a tokio 0.1 runtime. A futures 0.3
+//! future can be handed back to that runtime with
+//! `futures03::compat::Compat`.
+//!
+
+pub use futures03::compat::{Compat01As03, Future01CompatExt, Stream01CompatExt};
+
+#[cfg(test)]
+mod tests {
+    use super::{Future01CompatExt, Stream01CompatExt};
+    use futures::{future, stream, Future, Stream};
+    use futures03::{executor::block_on, TryStreamExt};
+    use response::Error;
+
+    #[test]
+    fn test_future_compat() {
+        let res: Box<dyn Future<Item = u32, Error = Error> + Send> = Box::new(future::ok(7));
+
+        assert_eq!(block_on(res.compat()).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_stream_compat() {
+        let res: Box<dyn Stream<Item = u32, Error = Error> + Send> =
+            Box::new(stream::iter_ok(vec![1, 2, 3]));
+        let items: Vec<u32> = block_on(res.compat().try_collect()).unwrap();
+
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use cid::Cid;
+use multibase::{read_varint, write_varint};
+use std::str;
+
+/// Protobuf wire types of the fields in a dag-pb node.
+///
+const WIRE_VARINT: u64 = 0;
+const WIRE_LENGTH_DELIMITED: u64 = 2;
+
+/// A link from a dag-pb node to another block.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DagPbLink {
+    pub hash: Cid,
+
+    pub name: Option<String>,
+
+    /// The cumulative size of the linked block and its descendants.
+    ///
+    pub size: Option<u64>,
+}
+
+/// A dag-pb node, as returned by `object_get_node`.
+///
+/// Decoding and encoding again gives the same bytes for nodes in canonical
+/// form, which every node written by the daemon is.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DagPbNode {
+    pub data: Option<Vec<u8>>,
+
+    pub links: Vec<DagPbLink>,
+}
+
+impl DagPbNode {
+    /// Parses a protobuf encoded dag-pb node, returning `None` if it's
+    /// malformed.
+    ///
+    pub fn decode(mut bytes: &[u8]) -> Option<DagPbNode> {
+        let mut node = DagPbNode::default();
+
+        while !bytes.is_empty() {
+            match read_field(&mut bytes)? {
+                (1, Field::Bytes(data)) => node.data = Some(data.to_vec()),
+                (2, Field::Bytes(link)) => node.links.push(DagPbLink::decode(link)?),
+                _ => return None,
+            }
+        }
+
+        Some(node)
+    }
+
+    /// Encodes the node in canonical form: links first, then data.
+    ///
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        for link in &self.links {
+            write_bytes(2, &link.encode(), &mut bytes);
+        }
+
+        if let Some(ref data) = self.data {
+            write_bytes(1, data, &mut bytes);
+        }
+
+        bytes
+    }
+}
+
+impl DagPbLink {
+    fn decode(mut bytes: &[u8]) -> Option<DagPbLink> {
+        let mut hash = None;
+        let mut name = None;
+        let mut size = None;
+
+        while !bytes.is_empty() {
+            match read_field(&mut bytes)? {
+                (1, Field::Bytes(cid)) => hash = Some(Cid::from_bytes(cid).ok()?),
+                (2, Field::Bytes(s)) => name = Some(str::from_utf8(s).ok()?.to_string()),
+                (3, Field::Varint(n)) => size = Some(n),
+                _ => return None,
+            }
+        }
+
+        Some(DagPbLink {
+            hash: hash?,
+            name,
+            size,
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        write_bytes(1, &self.hash.to_bytes(), &mut bytes);
+
+        if let Some(ref name) = self.name {
+            write_bytes(2, name.as_bytes(), &mut bytes);
+        }
+
+        if let Some(size) = self.size {
+            write_varint(3 << 3 | WIRE_VARINT, &mut bytes);
+            write_varint(size, &mut bytes);
+        }
+
+        bytes
+    }
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Reads a field number and value from the front of `bytes`. dag-pb only
+/// uses varint and length delimited fields.
+///
+fn read_field<'a>(bytes: &mut &'a [u8]) -> Option<(u64, Field<'a>)> {
+    let key = read_varint(bytes)?;
+
+    match key & 0x7 {
+        WIRE_VARINT => Some((key >> 3, Field::Varint(read_varint(bytes)?))),
+        WIRE_LENGTH_DELIMITED => {
+            let len = read_varint(bytes)? as usize;
+
+            if len > bytes.len() {
+                return None;
+            }
+
+            let (value, rest) = bytes.split_at(len);
+
+            *bytes = rest;
+
+            Some((key >> 3, Field::Bytes(value)))
+        }
+        _ => None,
+    }
+}
+
+fn write_bytes(field: u64, value: &[u8], bytes: &mut Vec<u8>) {
+    write_varint(field << 3 | WIRE_LENGTH_DELIMITED, bytes);
+    write_varint(value.len() as u64, bytes);
+    bytes.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DagPbLink, DagPbNode};
+
+    #[test]
+    fn test_round_trip() {
+        let node = DagPbNode {
+            data: Some(b"\x08\x01".to_vec()),
+            links: vec![DagPbLink {
+                hash: "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+                    .parse()
+                    .unwrap(),
+                name: Some("readme.md".into()),
+                size: Some(1024),
+            }],
+        };
+        let bytes = node.encode();
+        let decoded = DagPbNode::decode(&bytes).unwrap();
+
+        assert_eq!(decoded, node);
+        assert_eq!(decoded.encode(), bytes);
+    }
+
+    #[test]
+    fn test_decode_empty_directory() {
+        // `ipfs object new unixfs-dir`
+        let node = DagPbNode::decode(b"\x0a\x02\x08\x01").unwrap();
+
+        assert_eq!(node.data.unwrap(), b"\x08\x01");
+        assert!(node.links.is_empty());
+    }
+
+    #[test]
+    fn test_decode_malformed_node() {
+        assert!(DagPbNode::decode(b"\x0a\x05\x08").is_none());
+        assert!(DagPbNode::decode(b"\x12\x02\x18\x01").is_none());
+    }
+}
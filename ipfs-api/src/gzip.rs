@@ -0,0 +1,167 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use bytes::Bytes;
+use flate2::write::GzDecoder;
+use futures::{try_ready, Async, Poll, Stream};
+use http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+use hyper::{self, Response};
+use std::{error, io::Write, mem};
+
+/// The value of the `Accept-Encoding` header sent with every request.
+///
+pub const ACCEPT_ENCODING: &str = "gzip";
+
+type BoxError = Box<dyn error::Error + Send + Sync>;
+
+/// Decompresses the body of a response if the daemon (or a proxy in front of
+/// it) compressed it with gzip.
+///
+/// The `Content-Encoding` and `Content-Length` headers are removed from a
+/// decompressed response, since they describe the compressed body.
+///
+pub fn decode_response(res: Response<hyper::Body>) -> Response<hyper::Body> {
+    let is_gzip = res
+        .headers()
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding == HeaderValue::from_static("gzip"));
+
+    if !is_gzip {
+        return res;
+    }
+
+    let (mut parts, body) = res.into_parts();
+
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    let body = GzipStream::new(body.map(|chunk| chunk.into_bytes()).from_err());
+
+    Response::from_parts(parts, hyper::Body::wrap_stream(body))
+}
+
+/// A stream of bytes decompressed from a stream of gzip compressed bytes, as
+/// each chunk is received.
+///
+pub struct GzipStream<S> {
+    stream: S,
+    decoder: Option<GzDecoder<Vec<u8>>>,
+}
+
+impl<S> GzipStream<S>
+where
+    S: Stream<Item = Bytes, Error = BoxError>,
+{
+    pub fn new(stream: S) -> GzipStream<S> {
+        GzipStream {
+            stream,
+            decoder: Some(GzDecoder::new(Vec::new())),
+        }
+    }
+}
+
+impl<S> Stream for GzipStream<S>
+where
+    S: Stream<Item = Bytes, Error = BoxError>,
+{
+    type Item = Bytes;
+
+    type Error = BoxError;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if self.decoder.is_none() {
+                return Ok(Async::Ready(None));
+            }
+
+            match try_ready!(self.stream.poll()) {
+                Some(chunk) => {
+                    let decoder = self.decoder.as_mut().unwrap();
+
+                    decoder.write_all(&chunk)?;
+
+                    // A chunk can end in the middle of a block, and decompress
+                    // to nothing.
+                    //
+                    let out = mem::take(decoder.get_mut());
+
+                    if !out.is_empty() {
+                        return Ok(Async::Ready(Some(Bytes::from(out))));
+                    }
+                }
+                None => {
+                    let out = self.decoder.take().unwrap().finish()?;
+
+                    return if out.is_empty() {
+                        Ok(Async::Ready(None))
+                    } else {
+                        Ok(Async::Ready(Some(Bytes::from(out))))
+                    };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_response, GzipStream};
+    use bytes::Bytes;
+    use flate2::{write::GzEncoder, Compression};
+    use futures::{stream, Future, Stream};
+    use http::header::CONTENT_ENCODING;
+    use hyper::{self, Response};
+    use std::io::Write;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_gzip_stream() {
+        let json: String = (0..1000)
+            .map(|i| format!("{{\"Hash\":\"{}\"}}\n", i))
+            .collect();
+        let chunks: Vec<Bytes> = compress(json.as_bytes())
+            .chunks(7)
+            .map(Bytes::from)
+            .collect();
+
+        let body = GzipStream::new(stream::iter_ok(chunks))
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, json.as_bytes());
+    }
+
+    #[test]
+    fn test_decode_response() {
+        let res = Response::builder()
+            .header(CONTENT_ENCODING, "gzip")
+            .body(hyper::Body::from(compress(b"Hello World!")))
+            .unwrap();
+        let res = decode_response(res);
+
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(
+            res.into_body().concat2().wait().unwrap().into_bytes(),
+            "Hello World!"
+        );
+
+        let res = decode_response(Response::new(hyper::Body::from("plain")));
+
+        assert_eq!(
+            res.into_body().concat2().wait().unwrap().into_bytes(),
+            "plain"
+        );
+    }
+}
@@ -6,6 +6,51 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use http::HeaderMap;
+
 pub use http::header::TRAILER;
 
+pub const X_CHUNKED_OUTPUT: &str = "x-chunked-output";
+
+pub const X_CONTENT_LENGTH: &str = "x-content-length";
+
+pub const X_IPFS_PATH: &str = "x-ipfs-path";
+
 pub const X_STREAM_ERROR: &str = "x-stream-error";
+
+pub const X_STREAM_OUTPUT: &str = "x-stream-output";
+
+/// The names of the trailers a response declares in its `Trailer` headers,
+/// which each hold a comma separated list.
+///
+pub fn declared_trailers(headers: &HeaderMap) -> Vec<&str> {
+    headers
+        .get_all(TRAILER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{declared_trailers, TRAILER};
+    use http::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_declared_trailers() {
+        let mut headers = HeaderMap::new();
+
+        assert!(declared_trailers(&headers).is_empty());
+
+        headers.append(TRAILER, HeaderValue::from_static("X-Stream-Error, X-Foo"));
+        headers.append(TRAILER, HeaderValue::from_static("X-Bar"));
+
+        assert_eq!(
+            declared_trailers(&headers),
+            vec!["X-Stream-Error", "X-Foo", "X-Bar"]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use multibase::read_varint;
+use std::{str, time::Duration};
+
+/// Protobuf wire types of the fields in an IPNS record.
+///
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LENGTH_DELIMITED: u64 = 2;
+const WIRE_FIXED32: u64 = 5;
+
+/// The `validityType` of a record whose validity is an expiration time.
+///
+const VALIDITY_EOL: u64 = 0;
+
+/// An IPNS record, as stored in the DHT under `/ipns/<peer id>`.
+///
+/// The signature is not checked.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpnsRecord {
+    /// The path the name points to (e.g. `/ipfs/Qm...`).
+    ///
+    pub value: String,
+
+    /// When the record expires, as an RFC 3339 timestamp.
+    ///
+    pub validity: Option<String>,
+
+    /// The version of the record, which is incremented on every publish.
+    ///
+    pub sequence: u64,
+
+    /// How long the record may be cached for.
+    ///
+    pub ttl: Option<Duration>,
+}
+
+impl IpnsRecord {
+    /// Parses a protobuf encoded IPNS record, returning `None` if it's
+    /// malformed.
+    ///
+    pub fn decode(mut bytes: &[u8]) -> Option<IpnsRecord> {
+        let mut value = None;
+        let mut validity_type = None;
+        let mut validity = None;
+        let mut sequence = 0;
+        let mut ttl = None;
+
+        while !bytes.is_empty() {
+            let key = read_varint(&mut bytes)?;
+
+            match (key >> 3, key & 0x7) {
+                (field, WIRE_VARINT) => {
+                    let n = read_varint(&mut bytes)?;
+
+                    match field {
+                        3 => validity_type = Some(n),
+                        5 => sequence = n,
+                        6 => ttl = Some(Duration::from_nanos(n)),
+                        _ => (),
+                    }
+                }
+                (field, WIRE_LENGTH_DELIMITED) => {
+                    let len = read_varint(&mut bytes)? as usize;
+
+                    if len > bytes.len() {
+                        return None;
+                    }
+
+                    let (data, rest) = bytes.split_at(len);
+
+                    match field {
+                        1 => value = Some(str::from_utf8(data).ok()?.to_string()),
+                        4 => validity = Some(str::from_utf8(data).ok()?.to_string()),
+                        _ => (),
+                    }
+
+                    bytes = rest;
+                }
+                (_, WIRE_FIXED64) if bytes.len() >= 8 => bytes = &bytes[8..],
+                (_, WIRE_FIXED32) if bytes.len() >= 4 => bytes = &bytes[4..],
+                _ => return None,
+            }
+        }
+
+        Some(IpnsRecord {
+            value: value?,
+            validity: validity.filter(|_| validity_type.unwrap_or(VALIDITY_EOL) == VALIDITY_EOL),
+            sequence,
+            ttl,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IpnsRecord;
+    use multibase::decode_base64;
+    use std::time::Duration;
+
+    #[test]
+    fn test_decode_record() {
+        let bytes = decode_base64(
+            b"CjQvaXBmcy9RbVhkTlNReDduYmRSdmtqR0NFUWdWalZ0VndzSHZWOE5tVjJhOHh6UVZ3dUZBEglzaWdu\
+              YXR1cmUYACIeMjAyNi0xMC0xNlQwMDowMDowMC4wMDAwMDAwMDBaKAMwgLCdwt8B",
+        )
+        .unwrap();
+        let record = IpnsRecord::decode(&bytes).unwrap();
+
+        assert_eq!(
+            record.value,
+            "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+        );
+        assert_eq!(
+            record.validity.as_ref().unwrap(),
+            "2026-10-16T00:00:00.000000000Z"
+        );
+        assert_eq!(record.sequence, 3);
+        assert_eq!(record.ttl, Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_decode_malformed_record() {
+        assert!(IpnsRecord::decode(b"\x0a\x20/ipfs/").is_none());
+        assert!(IpnsRecord::decode(b"\x28\x03").is_none());
+    }
+}
@@ -7,6 +7,8 @@
 //
 
 #![recursion_limit = "128"]
+// `io::Error::other` needs Rust 1.74, which is newer than we support.
+#![allow(unknown_lints, clippy::io_other_error)]
 
 //! Rust library for connecting to the IPFS HTTP API using tokio.
 //!
@@ -21,9 +23,80 @@
 //!
 //! ```toml
 //! [dependencies]
-//! ipfs-api = { version = "0.5.1", features = ["actix"], default-features = false }
+//! ipfs-api = { version = "0.5.1", features = ["actix", "all-commands"], default-features = false }
 //! ```
 //!
+//! Less common groups of api commands (`dag`, `dht`, `files`, `filestore`,
+//! `key`, `name`, `object`, `pin`, `pubsub` and `swarm`) each sit behind a
+//! feature of the same name, all enabled by default through `all-commands`.
+//! Consumers that only need a few commands can turn off the default features
+//! and pick the groups they use:
+//!
+//! ```toml
+//! [dependencies]
+//! ipfs-api = { version = "0.5.1", features = ["hyper", "hyper-multipart-rfc7578", "files"], default-features = false }
+//! ```
+//!
+//! Builds that already turned off the default features, like the `actix` one
+//! above, have to add `all-commands` to keep every command.
+//!
+//! The `futures03-compat` feature re-exports the compatibility layer of
+//! futures 0.3, whose `.compat()` turns a response into a
+//! `std::future::Future` or a futures 0.3 `Stream` that can be `.await`ed.
+//! The methods themselves still return futures 0.1 types. See the
+//! [`compat`](compat/index.html) module.
+//!
+//! ```toml
+//! [dependencies]
+//! ipfs-api = { version = "0.5.1", features = ["futures03-compat"] }
+//! ```
+//!
+//! The `test-util` feature provides `MockIpfsClient`, an implementation of
+//! the `IpfsApi` trait that returns canned responses, for testing code
+//! without a running daemon.
+//!
+//! The `test-support` feature provides a local http server that answers api
+//! requests with canned fixtures, including streamed responses that end in
+//! an error, for testing code that uses `IpfsClient` end to end. See the
+//! [`test_support`](test_support/index.html) module.
+//!
+//! The `conformance` feature builds a test that calls every endpoint that
+//! is safe to call against a running daemon, with a strict client, and
+//! reports which responses deserialize cleanly. It is run with
+//! `cargo test --features conformance --test conformance`.
+//!
+//! The `log` feature logs every request through the `log` crate: the api
+//! path and query at `debug` level (the full url at `trace`), followed by
+//! the response status and how long the daemon took to respond.
+//!
+//! Latency, error counts and bytes received can be collected for every
+//! request by passing a `MetricsSink` to `IpfsClientBuilder::metrics_sink`.
+//! See the [`metrics`](metrics/index.html) module.
+//!
+//! The `tracing` feature runs every request inside of a `tracing` span named
+//! `ipfs_api`, recording the api path (`endpoint`), the query arguments
+//! (`args`), and the response `status` or `error`.
+//!
+//! The `tls` feature lets the hyper client connect to the api over https,
+//! including authenticating with a client certificate
+//! (`IpfsClientBuilder::client_identity`) for daemons behind a proxy that
+//! requires mutual TLS.
+//!
+//! The `gzip` feature asks the daemon for gzip compressed responses with
+//! `Accept-Encoding: gzip`, and decompresses them as they are received. This
+//! cuts the transfer size of large json responses (e.g. `pin_ls` or
+//! `config_show`) when the api is reached through a proxy that compresses
+//! responses. It is only available with the hyper backend.
+//!
+//! The `dag-cbor` feature (which implies `dag`) adds `dag_get_cbor` and
+//! `dag_put_cbor`, which move IPLD data as dag-cbor instead of dag-json, so
+//! bytes and other values that json can't represent survive the round trip.
+//!
+//! The [`prelude`](prelude/index.html) module exports the client, the
+//! `IpfsApi` trait, common response types, and the `Future`, `Stream` and
+//! `Sink` traits needed to drive requests, so most code only needs
+//! `use ipfs_api::prelude::*`.
+//!
 //! ## Examples
 //!
 //! ### Writing a file to IPFS
@@ -101,7 +174,7 @@
 //! let client = IpfsClient::default();
 //!
 //! let req = client
-//!     .get("/test/file.json")
+//!     .get("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json")
 //!     .concat2()
 //!     .map(|res| {
 //!         let out = io::stdout();
@@ -130,7 +203,7 @@
 //! let client = IpfsClient::default();
 //!
 //! let req = client
-//!     .get("/test/file.json")
+//!     .get("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json")
 //!     .concat2()
 //!     .map(|res| {
 //!         let out = io::stdout();
@@ -182,29 +255,127 @@ extern crate actix_web;
 extern crate hyper;
 #[cfg(feature = "hyper")]
 extern crate hyper_multipart_rfc7578 as hyper_multipart;
+#[cfg(feature = "tls")]
+extern crate hyper_tls;
 
 extern crate bytes;
 #[macro_use]
 extern crate failure;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 extern crate futures;
+extern crate futures_cpupool;
+#[cfg(feature = "futures03-compat")]
+extern crate futures03;
 extern crate http;
+#[macro_use]
+extern crate lazy_static;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
 extern crate serde;
+#[cfg(feature = "dag-cbor")]
+extern crate serde_cbor;
 #[macro_use]
 extern crate serde_derive;
 extern crate dirs;
 extern crate multiaddr;
+#[cfg(feature = "tls")]
+extern crate native_tls;
+extern crate serde_ignored;
 extern crate serde_json;
 extern crate serde_urlencoded;
+extern crate tar;
 extern crate tokio;
 extern crate tokio_codec;
 extern crate tokio_io;
+extern crate tokio_sync;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "tracing")]
+extern crate tracing_futures;
 extern crate walkdir;
 
-pub use client::IpfsClient;
-pub use request::{KeyType, Logger, LoggingLevel, ObjectTemplate};
+pub use addr::{Multiaddr, Protocol};
+pub use api::IpfsApi;
+pub use archive::{TarDecodeStream, TarEntry, TarEntryBody};
+pub use bandwidth::{BandwidthSample, BandwidthSamples};
+pub use car::{CarBlock, CarHeader, CarReader, CarWriter};
+pub use cid::{Cid, CidBase, ToCid};
+pub use client::{AsyncResponse, AsyncStreamResponse, IpfsClient, IpfsClientBuilder};
+pub use dagpb::{DagPbLink, DagPbNode};
+pub use ipns::IpnsRecord;
+#[cfg(feature = "test-util")]
+pub use mock::MockIpfsClient;
+pub use monitor::{DaemonMonitor, DaemonStatus, DaemonStatusWatch};
+#[cfg(feature = "tls")]
+pub use native_tls::{Certificate, Identity};
+pub use path::{IpfsPath, MfsPath, ToIpfsPath, ToMfsPath};
+pub use peer::{PeerId, ToPeerId};
+#[cfg(feature = "swarm")]
+pub use peers::{PeerEvent, PeerEvents};
+#[cfg(feature = "pin")]
+pub use pinset::PinsetReport;
+pub use progress::Progress;
+#[cfg(feature = "pubsub")]
+pub use pubsub::{PubsubMessages, PubsubPublisher, PubsubRouter, PubsubSubscription};
+pub use read::{BlockingStreamReader, StreamReader};
+pub use refs::RefsWalk;
+#[cfg(feature = "key")]
+pub use request::KeyType;
+pub use request::{Logger, LoggingLevel};
+#[cfg(feature = "object")]
+pub use request::{ObjectEncoding, ObjectTemplate};
+pub use resolve::DnsResolver;
+pub use tree::{AddTreeOptions, SymlinkMode};
+pub use unpack::OverwritePolicy;
+#[cfg(feature = "object")]
+pub use walk::{Walk, WalkNode};
 
+mod addr;
+mod api;
+mod archive;
+mod bandwidth;
+mod car;
+mod cid;
 mod client;
+#[cfg(feature = "futures03-compat")]
+pub mod compat;
+mod dagpb;
+#[cfg(feature = "gzip")]
+mod gzip;
 mod header;
+mod ipns;
+mod limit;
+mod logging;
+pub mod metrics;
+#[cfg(feature = "files")]
+mod mfs;
+#[cfg(feature = "test-util")]
+pub mod mock;
+mod monitor;
+mod multibase;
+mod path;
+mod peer;
+#[cfg(feature = "swarm")]
+mod peers;
+#[cfg(feature = "pin")]
+mod pinset;
+mod prefetch;
+pub mod prelude;
+mod progress;
+#[cfg(feature = "pubsub")]
+mod pubsub;
 mod read;
+mod refs;
 pub mod request;
+mod resolve;
 pub mod response;
+mod resume;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod tree;
+mod unpack;
+mod upload;
+#[cfg(feature = "object")]
+mod walk;
@@ -0,0 +1,176 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use futures::{Async, Future, Poll, Stream};
+use response::Error;
+use std::sync::Arc;
+use tokio_sync::semaphore::{Permit, Semaphore};
+
+/// Caps the number of requests a client has in flight at once.
+///
+/// Clones share the same cap. Without a cap, permits are handed out
+/// immediately.
+///
+#[derive(Clone)]
+pub struct RequestLimit(Option<Arc<Semaphore>>);
+
+impl RequestLimit {
+    /// Creates a cap of `max` requests, or no cap at all. A cap of 0 is
+    /// raised to 1, so requests can still be made.
+    ///
+    #[inline]
+    pub fn new(max: Option<usize>) -> RequestLimit {
+        RequestLimit(max.map(|max| Arc::new(Semaphore::new(max.max(1)))))
+    }
+
+    /// Waits for a slot to make a request in.
+    ///
+    #[inline]
+    pub fn acquire(&self) -> Acquire {
+        Acquire(Some(RequestPermit {
+            semaphore: self.0.clone(),
+            permit: Permit::new(),
+        }))
+    }
+}
+
+/// Holds a slot until it is dropped.
+///
+pub struct RequestPermit {
+    semaphore: Option<Arc<Semaphore>>,
+    permit: Permit,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        if let Some(ref semaphore) = self.semaphore {
+            self.permit.release(semaphore);
+        }
+    }
+}
+
+/// Future resolving to a `RequestPermit` once a slot is free.
+///
+pub struct Acquire(Option<RequestPermit>);
+
+impl Future for Acquire {
+    type Item = RequestPermit;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        {
+            let permit = self
+                .0
+                .as_mut()
+                .expect("cannot poll Acquire after it has resolved");
+
+            if let Some(ref semaphore) = permit.semaphore {
+                match permit.permit.poll_acquire(semaphore) {
+                    Ok(Async::Ready(())) => (),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => return Err(Error::Uncategorized(e.to_string())),
+                }
+            }
+        }
+
+        Ok(Async::Ready(self.0.take().unwrap()))
+    }
+}
+
+/// Keeps a slot for as long as a response stream is being read.
+///
+pub struct WithPermit<S> {
+    stream: S,
+    permit: Option<RequestPermit>,
+}
+
+impl<S> WithPermit<S> {
+    #[inline]
+    pub fn new(stream: S, permit: RequestPermit) -> WithPermit<S> {
+        WithPermit {
+            stream,
+            permit: Some(permit),
+        }
+    }
+}
+
+impl<S> Stream for WithPermit<S>
+where
+    S: Stream,
+{
+    type Item = S::Item;
+
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        match self.stream.poll() {
+            Ok(Async::Ready(None)) => {
+                self.permit.take();
+
+                Ok(Async::Ready(None))
+            }
+            Err(e) => {
+                self.permit.take();
+
+                Err(e)
+            }
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestLimit;
+    use futures::{future, Async, Future};
+
+    #[test]
+    fn test_limit_waits_for_release() {
+        future::lazy(|| {
+            let limit = RequestLimit::new(Some(1));
+            let first = limit.acquire().wait().unwrap();
+            let mut second = limit.acquire();
+
+            assert!(second.poll().unwrap().is_not_ready());
+
+            drop(first);
+
+            match second.poll() {
+                Ok(Async::Ready(_)) => Ok::<(), ()>(()),
+                _ => panic!("expected a permit after the first was released"),
+            }
+        })
+        .wait()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_zero_limit_allows_one() {
+        future::lazy(|| {
+            let limit = RequestLimit::new(Some(0));
+            let first = limit.acquire().wait().unwrap();
+
+            assert!(limit.acquire().poll().unwrap().is_not_ready());
+
+            drop(first);
+
+            Ok::<(), ()>(())
+        })
+        .wait()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let limit = RequestLimit::new(None);
+        let permits: Vec<_> = (0..100).map(|_| limit.acquire().wait().unwrap()).collect();
+
+        assert_eq!(permits.len(), 100);
+    }
+}
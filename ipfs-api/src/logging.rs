@@ -0,0 +1,139 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use http::{StatusCode, Uri};
+use response::Error;
+#[cfg(feature = "log")]
+use std::time::Instant;
+#[cfg(feature = "tracing")]
+use tracing::{field, Span};
+#[cfg(feature = "tracing")]
+use tracing_futures::{Instrument, Instrumented};
+
+/// Logs the lifecycle of a single request to the `log` crate, and records
+/// its outcome on the current `tracing` span.
+///
+/// Without the `log` or `tracing` features, this does nothing.
+///
+#[derive(Clone, Copy)]
+pub struct RequestLog {
+    #[cfg(feature = "log")]
+    path: &'static str,
+
+    #[cfg(feature = "log")]
+    start: Instant,
+}
+
+impl RequestLog {
+    /// Logs the start of a request to an api path.
+    ///
+    #[cfg(feature = "log")]
+    pub fn start(path: &'static str, uri: &Uri) -> RequestLog {
+        trace!("{} request {}", path, uri);
+        debug!("{} request query '{}'", path, uri.query().unwrap_or(""));
+
+        RequestLog {
+            path,
+            start: Instant::now(),
+        }
+    }
+
+    #[cfg(not(feature = "log"))]
+    #[inline]
+    pub fn start(_path: &'static str, _uri: &Uri) -> RequestLog {
+        RequestLog {}
+    }
+
+    /// Logs the status the server responded with.
+    ///
+    #[cfg_attr(
+        not(any(feature = "log", feature = "tracing")),
+        allow(unused_variables)
+    )]
+    #[inline]
+    pub fn status(&self, status: StatusCode) {
+        #[cfg(feature = "log")]
+        debug!(
+            "{} responded {} after {:?}",
+            self.path,
+            status,
+            self.start.elapsed()
+        );
+
+        #[cfg(feature = "tracing")]
+        Span::current().record("status", status.as_u16());
+    }
+
+    /// Logs a request that failed without a response.
+    ///
+    #[cfg_attr(
+        not(any(feature = "log", feature = "tracing")),
+        allow(unused_variables)
+    )]
+    #[inline]
+    pub fn error(&self, err: &Error) {
+        #[cfg(feature = "log")]
+        debug!(
+            "{} failed after {:?}: {}",
+            self.path,
+            self.start.elapsed(),
+            err
+        );
+
+        #[cfg(feature = "tracing")]
+        Span::current().record("error", field::display(err));
+    }
+}
+
+/// A `tracing` span covering a single request, carrying the api path,
+/// the query arguments and, once known, the response status or error.
+///
+/// Without the `tracing` feature, this does nothing.
+///
+#[derive(Clone)]
+pub struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: Span,
+}
+
+impl RequestSpan {
+    /// Creates the span for a request to an api path.
+    ///
+    #[cfg(feature = "tracing")]
+    pub fn start(path: &'static str, uri: &Uri) -> RequestSpan {
+        RequestSpan {
+            span: ::tracing::info_span!(
+                "ipfs_api",
+                endpoint = path,
+                args = uri.query().unwrap_or(""),
+                status = field::Empty,
+                error = field::Empty
+            ),
+        }
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    #[inline]
+    pub fn start(_path: &'static str, _uri: &Uri) -> RequestSpan {
+        RequestSpan {}
+    }
+
+    /// Runs a future or stream inside of the span.
+    ///
+    #[cfg(feature = "tracing")]
+    #[inline]
+    pub fn instrument<T>(self, inner: T) -> Instrumented<T> {
+        inner.instrument(self.span)
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    #[inline]
+    pub fn instrument<T>(self, inner: T) -> T {
+        inner
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! Hooks for collecting metrics about the requests a client makes.
+//!
+//! ```no_run
+//! # extern crate ipfs_api;
+//! #
+//! use ipfs_api::metrics::RequestMetrics;
+//! use ipfs_api::IpfsClientBuilder;
+//!
+//! # fn main() {
+//! let client = IpfsClientBuilder::default()
+//!     .metrics_sink(|metrics: &RequestMetrics| {
+//!         println!(
+//!             "{} took {:?} ({} bytes)",
+//!             metrics.path, metrics.duration, metrics.bytes_received
+//!         );
+//!     })
+//!     .build()
+//!     .unwrap();
+//! # }
+//! ```
+//!
+
+use bytes::Bytes;
+use futures::{try_ready, Async, Poll, Stream};
+use http::StatusCode;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// Measurements for a single request made against the API.
+///
+#[derive(Clone, Debug)]
+pub struct RequestMetrics {
+    /// The api path that was called (e.g. `/add`).
+    ///
+    pub path: &'static str,
+
+    /// The status the daemon responded with, or `None` if no response
+    /// was received.
+    ///
+    pub status: Option<StatusCode>,
+
+    /// Time from sending the request until the response body was read, or
+    /// the request failed or was dropped.
+    ///
+    pub duration: Duration,
+
+    /// Number of response body bytes received.
+    ///
+    /// Streamed response bodies are only counted with the hyper backend.
+    ///
+    pub bytes_received: u64,
+
+    /// Number of request body bytes sent, for requests that upload files or
+    /// data.
+    ///
+    pub bytes_sent: u64,
+
+    /// Whether the request failed.
+    ///
+    pub error: bool,
+}
+
+/// Receives the metrics of every request made by a client.
+///
+/// Implemented for closures taking a `&RequestMetrics`.
+///
+pub trait MetricsSink: Send + Sync {
+    /// Called once per request, after it has completed.
+    ///
+    fn record(&self, metrics: &RequestMetrics);
+}
+
+impl<F> MetricsSink for F
+where
+    F: Fn(&RequestMetrics) + Send + Sync,
+{
+    #[inline]
+    fn record(&self, metrics: &RequestMetrics) {
+        self(metrics)
+    }
+}
+
+struct MeterState {
+    sink: Arc<dyn MetricsSink>,
+    path: &'static str,
+    start: Instant,
+    status: Mutex<Option<StatusCode>>,
+    bytes: AtomicUsize,
+    bytes_sent: AtomicUsize,
+    error: AtomicBool,
+    recorded: AtomicBool,
+}
+
+impl MeterState {
+    fn record(&self) {
+        if !self.recorded.swap(true, Ordering::SeqCst) {
+            self.sink.record(&RequestMetrics {
+                path: self.path,
+                status: *self.status.lock().unwrap(),
+                duration: self.start.elapsed(),
+                bytes_received: self.bytes.load(Ordering::SeqCst) as u64,
+                bytes_sent: self.bytes_sent.load(Ordering::SeqCst) as u64,
+                error: self.error.load(Ordering::SeqCst),
+            });
+        }
+    }
+}
+
+impl Drop for MeterState {
+    fn drop(&mut self) {
+        self.record();
+    }
+}
+
+/// Collects metrics for a single request.
+///
+/// Clones share the same measurements. They are reported to the sink when
+/// `finish` is called, or when the last clone is dropped. Without a sink,
+/// this does nothing.
+///
+#[derive(Clone)]
+pub(crate) struct RequestMeter(Option<Arc<MeterState>>);
+
+impl RequestMeter {
+    pub(crate) fn start(sink: Option<&Arc<dyn MetricsSink>>, path: &'static str) -> RequestMeter {
+        RequestMeter(sink.map(|sink| {
+            Arc::new(MeterState {
+                sink: sink.clone(),
+                path,
+                start: Instant::now(),
+                status: Mutex::new(None),
+                bytes: AtomicUsize::new(0),
+                bytes_sent: AtomicUsize::new(0),
+                error: AtomicBool::new(false),
+                recorded: AtomicBool::new(false),
+            })
+        }))
+    }
+
+    /// Returns true if the measurements will be reported anywhere.
+    ///
+    #[cfg_attr(not(feature = "hyper"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    pub(crate) fn status(&self, status: StatusCode) {
+        if let Some(ref state) = self.0 {
+            *state.status.lock().unwrap() = Some(status);
+        }
+    }
+
+    pub(crate) fn bytes(&self, len: usize) {
+        if let Some(ref state) = self.0 {
+            state.bytes.fetch_add(len, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn bytes_sent(&self, len: usize) {
+        if let Some(ref state) = self.0 {
+            state.bytes_sent.fetch_add(len, Ordering::SeqCst);
+        }
+    }
+
+    pub(crate) fn error(&self) {
+        if let Some(ref state) = self.0 {
+            state.error.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Reports the metrics collected so far.
+    ///
+    pub(crate) fn finish(&self) {
+        if let Some(ref state) = self.0 {
+            state.record();
+        }
+    }
+}
+
+/// Counts the bytes sent from a request body stream.
+///
+pub(crate) struct MeteredUpload<S> {
+    stream: S,
+    meter: RequestMeter,
+}
+
+impl<S> MeteredUpload<S> {
+    #[inline]
+    pub(crate) fn new(stream: S, meter: RequestMeter) -> MeteredUpload<S> {
+        MeteredUpload { stream, meter }
+    }
+}
+
+impl<S, T> Stream for MeteredUpload<S>
+where
+    S: Stream<Item = T>,
+    T: AsRef<[u8]>,
+{
+    type Item = T;
+
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let chunk = try_ready!(self.stream.poll());
+
+        if let Some(ref chunk) = chunk {
+            self.meter.bytes_sent(chunk.as_ref().len());
+        }
+
+        Ok(Async::Ready(chunk))
+    }
+}
+
+/// Records the size of a fully buffered response body.
+///
+#[inline]
+pub(crate) fn record_body(meter: &RequestMeter, status: StatusCode, body: &Bytes) {
+    meter.status(status);
+    meter.bytes(body.len());
+    meter.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MeteredUpload, MetricsSink, RequestMeter, RequestMetrics};
+    use futures::{stream, Future, Stream};
+    use http::StatusCode;
+    use std::sync::{Arc, Mutex};
+
+    fn sink() -> (Arc<dyn MetricsSink>, Arc<Mutex<Vec<RequestMetrics>>>) {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let inner = recorded.clone();
+        let sink: Arc<dyn MetricsSink> =
+            Arc::new(move |m: &RequestMetrics| inner.lock().unwrap().push(m.clone()));
+
+        (sink, recorded)
+    }
+
+    #[test]
+    fn test_metered_upload() {
+        let (sink, recorded) = sink();
+        let meter = RequestMeter::start(Some(&sink), "/add");
+        let chunks: Vec<Result<&'static [u8], ()>> = vec![Ok(b"abc"), Ok(b"de")];
+        let stream = MeteredUpload::new(stream::iter_result(chunks), meter.clone());
+
+        stream.collect().wait().unwrap();
+        meter.status(StatusCode::OK);
+        meter.bytes(2);
+
+        assert!(recorded.lock().unwrap().is_empty());
+
+        meter.finish();
+        drop(meter);
+
+        let recorded = recorded.lock().unwrap();
+
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].path, "/add");
+        assert_eq!(recorded[0].status, Some(StatusCode::OK));
+        assert_eq!(recorded[0].bytes_sent, 5);
+        assert_eq!(recorded[0].bytes_received, 2);
+        assert!(!recorded[0].error);
+    }
+
+    #[test]
+    fn test_dropped_meter_records_error() {
+        let (sink, recorded) = sink();
+        let meter = RequestMeter::start(Some(&sink), "/version");
+
+        meter.error();
+        drop(meter);
+
+        let recorded = recorded.lock().unwrap();
+
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].status, None);
+        assert!(recorded[0].error);
+    }
+}
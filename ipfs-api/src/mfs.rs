@@ -0,0 +1,117 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use response::Error;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The number of files transferred at the same time by `files_write_tree`
+/// and `files_read_tree`.
+///
+pub const PARALLELISM: usize = 8;
+
+/// The directories and files in a local tree, with the MFS paths they are
+/// written to.
+///
+#[derive(Debug, Default)]
+pub struct LocalTree {
+    /// MFS directories to create, parents before their children.
+    pub dirs: Vec<String>,
+
+    /// Local files, and the MFS path each is written to.
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Appends a relative local path to an MFS path, always separated by `/`.
+///
+pub fn join(mfs_path: &str, relative: &Path) -> String {
+    let mut joined = mfs_path.trim_end_matches('/').to_string();
+
+    for component in relative.components() {
+        if let Component::Normal(name) = component {
+            joined.push('/');
+            joined.push_str(&name.to_string_lossy());
+        }
+    }
+
+    if joined.is_empty() {
+        joined.push('/');
+    }
+
+    joined
+}
+
+/// Splits an MFS path into the directory it is in and its name.
+///
+pub fn split(mfs_path: &str) -> (&str, &str) {
+    let path = mfs_path.trim_end_matches('/');
+
+    match path.rfind('/') {
+        Some(0) => ("/", &path[1..]),
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => ("/", path),
+    }
+}
+
+/// Lists everything under `path`, which is mirrored to `mfs_path`. If `path`
+/// is a file, it is written to `mfs_path` itself.
+///
+pub fn local_tree(path: &Path, mfs_path: &str) -> Result<LocalTree, Error> {
+    let mut tree = LocalTree::default();
+
+    for entry in WalkDir::new(path).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        let target = join(mfs_path, entry.path().strip_prefix(path).unwrap());
+
+        if entry.file_type().is_dir() {
+            tree.dirs.push(target);
+        } else if entry.file_type().is_file() {
+            tree.files.push((entry.path().to_path_buf(), target));
+        }
+    }
+
+    Ok(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{join, local_tree, split};
+    use std::path::Path;
+
+    #[test]
+    fn test_join() {
+        assert_eq!(join("/docs/", Path::new("a/b.txt")), "/docs/a/b.txt");
+        assert_eq!(join("/docs", Path::new("")), "/docs");
+        assert_eq!(join("/", Path::new("a")), "/a");
+        assert_eq!(join("/", Path::new("")), "/");
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("/docs/a/b.txt"), ("/docs/a", "b.txt"));
+        assert_eq!(split("/docs/"), ("/", "docs"));
+        assert_eq!(split("/b.txt"), ("/", "b.txt"));
+    }
+
+    #[test]
+    fn test_local_tree() {
+        let tree = local_tree(Path::new("src/response"), "/mirror").unwrap();
+
+        assert_eq!(tree.dirs[0], "/mirror");
+        assert!(tree.dirs.contains(&"/mirror/tests".to_string()));
+        assert!(tree
+            .files
+            .iter()
+            .any(|(_, target)| target == "/mirror/tests/v0_add_0.json"));
+
+        let tree = local_tree(Path::new("src/lib.rs"), "/lib.rs").unwrap();
+
+        assert!(tree.dirs.is_empty());
+        assert_eq!(tree.files[0].1, "/lib.rs");
+    }
+}
@@ -0,0 +1,1872 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! A fake `IpfsApi` implementation for testing code that talks to IPFS
+//! without running a daemon.
+//!
+//! ```
+//! # extern crate futures;
+//! # extern crate ipfs_api;
+//! #
+//! use futures::Future;
+//! use ipfs_api::mock::MockIpfsClient;
+//! use ipfs_api::response::BlockRmResponse;
+//! use ipfs_api::IpfsApi;
+//!
+//! # fn main() {
+//! let mock = MockIpfsClient::new();
+//!
+//! mock.push_response(
+//!     "block_rm",
+//!     Ok(BlockRmResponse {
+//!         hash: "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA".parse().unwrap(),
+//!         error: None,
+//!     }),
+//! );
+//!
+//! let res = mock
+//!     .block_rm("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+//!     .wait()
+//!     .unwrap();
+//!
+//! assert!(res.error.is_none());
+//! assert_eq!(mock.calls()[0].endpoint, "block_rm");
+//! # }
+//! ```
+//!
+
+#[cfg(feature = "swarm")]
+use addr::Multiaddr;
+use api::IpfsApi;
+use bandwidth::{BandwidthSample, BandwidthSamples};
+use bytes::Bytes;
+use cid::{Cid, ToCid};
+use client::{AsyncResponse, AsyncStreamResponse};
+#[cfg(feature = "object")]
+use dagpb::DagPbNode;
+use futures::{future, stream, Future, IntoFuture, Stream};
+use http::{HeaderMap, StatusCode};
+use monitor::{DaemonMonitor, DaemonStatusWatch};
+use path::ToIpfsPath;
+#[cfg(feature = "files")]
+use path::ToMfsPath;
+#[cfg(feature = "key")]
+use peer::PeerId;
+use peer::ToPeerId;
+#[cfg(feature = "swarm")]
+use peers::{PeerEvent, PeerEvents};
+#[cfg(feature = "pin")]
+use pinset::PinsetReport;
+use prefetch::Prefetch;
+use progress::{Progress, ProgressStream};
+#[cfg(feature = "pubsub")]
+use pubsub::{PubsubMessages, PubsubPublisher, PubsubRouter};
+use read::{self, LimitedStream};
+use refs::RefsWalk;
+use request;
+use response::{self, Error, ResponseMetadata};
+use resume::{Resume, SkipBytes};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "dag-cbor")]
+use serde_cbor;
+use serde_json;
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    io::{self, Read},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tree::AddTreeOptions;
+use unpack::OverwritePolicy;
+#[cfg(feature = "object")]
+use walk::{Walk, WalkNode};
+
+/// Formats each argument of a call with `Debug`.
+///
+macro_rules! args {
+    ($($arg: expr),*) => {
+        vec![$(format!("{:?}", $arg)),*]
+    };
+}
+
+/// Queued responses, keyed by endpoint name.
+///
+type ResponseQueues = HashMap<&'static str, VecDeque<Box<dyn Any + Send>>>;
+
+/// A call made against a `MockIpfsClient`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    /// Name of the `IpfsApi` method that was called (e.g. `"files_write"`).
+    ///
+    pub endpoint: &'static str,
+
+    /// Arguments to the call, formatted with `Debug`.
+    ///
+    pub args: Vec<String>,
+
+    /// Data read from the reader passed to upload methods like `add`.
+    ///
+    pub data: Option<Vec<u8>>,
+}
+
+/// An `IpfsApi` implementation that returns canned responses.
+///
+/// Responses are queued per endpoint with `push_response` or
+/// `push_stream_response`, and handed out in order. Calling an endpoint that
+/// has no queued response returns an error. Every call is recorded, and can
+/// be inspected with `calls`.
+///
+/// Clones share the same responses and call log.
+///
+#[derive(Clone, Default)]
+pub struct MockIpfsClient {
+    responses: Arc<Mutex<ResponseQueues>>,
+    calls: Arc<Mutex<Vec<MockCall>>>,
+}
+
+impl MockIpfsClient {
+    /// Creates a `MockIpfsClient` with no queued responses.
+    ///
+    #[inline]
+    pub fn new() -> MockIpfsClient {
+        Default::default()
+    }
+
+    /// Queues the result of the next call to an endpoint that returns a
+    /// single response.
+    ///
+    /// The type of `res` has to match the response type of the endpoint.
+    ///
+    pub fn push_response<T>(&self, endpoint: &'static str, res: Result<T, Error>)
+    where
+        T: 'static + Send,
+    {
+        self.push(endpoint, Box::new(res));
+    }
+
+    /// Queues the items yielded by the next call to an endpoint that
+    /// returns a stream.
+    ///
+    pub fn push_stream_response<T>(&self, endpoint: &'static str, items: Vec<Result<T, Error>>)
+    where
+        T: 'static + Send,
+    {
+        self.push(endpoint, Box::new(items));
+    }
+
+    /// Returns every call made so far, oldest first.
+    ///
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn push(&self, endpoint: &'static str, res: Box<dyn Any + Send>) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(endpoint)
+            .or_default()
+            .push_back(res);
+    }
+
+    /// Removes the next queued response for an endpoint.
+    ///
+    fn pop<T>(&self, endpoint: &'static str) -> Result<T, Error>
+    where
+        T: 'static,
+    {
+        let res = self
+            .responses
+            .lock()
+            .unwrap()
+            .get_mut(endpoint)
+            .and_then(|queue| queue.pop_front());
+
+        match res {
+            Some(res) => res.downcast::<T>().map(|res| *res).map_err(|_| {
+                Error::Uncategorized(format!(
+                    "canned response for `{}` has the wrong type",
+                    endpoint
+                ))
+            }),
+            None => Err(Error::Uncategorized(format!(
+                "no canned response for `{}`",
+                endpoint
+            ))),
+        }
+    }
+
+    fn record(&self, endpoint: &'static str, args: Vec<String>, data: Option<Vec<u8>>) {
+        self.calls.lock().unwrap().push(MockCall {
+            endpoint,
+            args,
+            data,
+        });
+    }
+
+    fn call<T>(&self, endpoint: &'static str, args: Vec<String>) -> AsyncResponse<T>
+    where
+        T: 'static + Send,
+    {
+        self.record(endpoint, args, None);
+
+        Box::new(
+            self.pop::<Result<T, Error>>(endpoint)
+                .and_then(|res| res)
+                .into_future(),
+        )
+    }
+
+    fn call_with_data<R, T>(
+        &self,
+        endpoint: &'static str,
+        args: Vec<String>,
+        mut data: R,
+    ) -> AsyncResponse<T>
+    where
+        R: Read,
+        T: 'static + Send,
+    {
+        let mut buf = Vec::new();
+
+        if let Err(e) = data.read_to_end(&mut buf) {
+            return Box::new(future::err(e.into()));
+        }
+
+        self.record(endpoint, args, Some(buf));
+
+        Box::new(
+            self.pop::<Result<T, Error>>(endpoint)
+                .and_then(|res| res)
+                .into_future(),
+        )
+    }
+
+    fn call_with_stream<S, T>(
+        &self,
+        endpoint: &'static str,
+        args: Vec<String>,
+        data: S,
+    ) -> AsyncResponse<T>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+        T: 'static + Send,
+    {
+        let mock = self.clone();
+
+        Box::new(data.concat2().from_err().and_then(move |buf| {
+            mock.record(endpoint, args, Some(buf.to_vec()));
+            mock.pop::<Result<T, Error>>(endpoint).and_then(|res| res)
+        }))
+    }
+
+    fn call_stream<T>(&self, endpoint: &'static str, args: Vec<String>) -> AsyncStreamResponse<T>
+    where
+        T: 'static + Send,
+    {
+        self.record(endpoint, args, None);
+
+        match self.pop::<Vec<Result<T, Error>>>(endpoint) {
+            Ok(items) => Box::new(stream::iter_result(items)),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    #[cfg(feature = "dag")]
+    fn call_stream_with_data<R, T>(
+        &self,
+        endpoint: &'static str,
+        args: Vec<String>,
+        mut data: R,
+    ) -> AsyncStreamResponse<T>
+    where
+        R: Read,
+        T: 'static + Send,
+    {
+        let mut buf = Vec::new();
+
+        if let Err(e) = data.read_to_end(&mut buf) {
+            return Box::new(stream::once(Err(e.into())));
+        }
+
+        self.record(endpoint, args, Some(buf));
+
+        match self.pop::<Vec<Result<T, Error>>>(endpoint) {
+            Ok(items) => Box::new(stream::iter_result(items)),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+}
+
+impl IpfsApi for MockIpfsClient {
+    fn add<R>(&self, data: R) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("add", args![], data)
+    }
+
+    fn add_json<T>(&self, value: &T) -> AsyncResponse<Cid>
+    where
+        T: Serialize,
+    {
+        match serde_json::to_vec(value) {
+            Ok(data) => self.call_with_data("add_json", args![], io::Cursor::new(data)),
+            Err(e) => Box::new(future::err(e.into())),
+        }
+    }
+
+    fn add_stream<S>(&self, data: S) -> AsyncResponse<response::AddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        self.call_with_stream("add_stream", args![], data)
+    }
+
+    fn add_with_options<R>(
+        &self,
+        data: R,
+        add: &request::Add,
+    ) -> AsyncResponse<response::AddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("add_with_options", args![add], data)
+    }
+
+    fn add_many<I, N, R>(&self, files: I) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        self.add_many_with_options(files, &request::Add::default())
+    }
+
+    fn add_many_with_options<I, N, R>(
+        &self,
+        files: I,
+        add: &request::Add,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: Into<String>,
+        R: 'static + Read + Send,
+    {
+        let mut names = Vec::new();
+        let mut buf = Vec::new();
+
+        for (name, mut data) in files {
+            names.push(name.into());
+
+            if let Err(e) = data.read_to_end(&mut buf) {
+                return Box::new(future::err(e.into()));
+            }
+        }
+
+        self.call_with_data("add_many", args![names, add], io::Cursor::new(buf))
+    }
+
+    fn add_path<P>(&self, path: P) -> AsyncResponse<response::AddResponse>
+    where
+        P: AsRef<Path>,
+    {
+        self.call("add_path", args![path.as_ref()])
+    }
+
+    fn add_tree<P>(&self, path: P) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        self.call("add_tree", args![path.as_ref()])
+    }
+
+    fn add_tree_with_options<P>(
+        &self,
+        path: P,
+        options: &AddTreeOptions,
+    ) -> AsyncResponse<Vec<response::AddResponse>>
+    where
+        P: AsRef<Path>,
+    {
+        self.call("add_tree_with_options", args![path.as_ref(), options])
+    }
+
+    fn bitswap_ledger<P>(&self, peer: P) -> AsyncResponse<response::BitswapLedgerResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.call("bitswap_ledger", args![peer.as_str()]),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    fn bitswap_reprovide(&self) -> AsyncResponse<response::BitswapReprovideResponse> {
+        self.call("bitswap_reprovide", args![])
+    }
+
+    fn bitswap_stat(&self) -> AsyncResponse<response::BitswapStatResponse> {
+        self.call("bitswap_stat", args![])
+    }
+
+    fn bitswap_stat_with_options(
+        &self,
+        stat: &request::BitswapStat,
+    ) -> AsyncResponse<response::BitswapStatResponse> {
+        self.call("bitswap_stat_with_options", args![stat])
+    }
+
+    fn bitswap_unwant(&self, key: &str) -> AsyncResponse<response::BitswapUnwantResponse> {
+        self.call("bitswap_unwant", args![key])
+    }
+
+    fn bitswap_wantlist(
+        &self,
+        peer: Option<&str>,
+    ) -> AsyncResponse<response::BitswapWantlistResponse> {
+        self.call("bitswap_wantlist", args![peer])
+    }
+
+    fn block_get<C>(&self, cid: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.call_stream("block_get", args![cid.as_str()]),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    fn block_get_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let cids: Vec<Cid> = cids.into_iter().collect();
+        let mock = self.clone();
+        let res = stream::iter_ok(cids)
+            .map(move |cid| {
+                mock.block_get(&cid)
+                    .concat2()
+                    .then(move |res| Ok((cid, res)))
+            })
+            .buffer_unordered(parallelism.max(1));
+
+        Box::new(res)
+    }
+
+    fn block_get_with_progress<C, F>(&self, cid: C, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        match cid.to_cid() {
+            Ok(cid) => {
+                let stream = self.call_stream("block_get_with_progress", args![cid.as_str()]);
+
+                Box::new(ProgressStream::new(stream, None, callback))
+            }
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    fn block_put<R>(&self, data: R) -> AsyncResponse<response::BlockPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("block_put", args![], data)
+    }
+
+    fn block_put_stream<S>(&self, data: S) -> AsyncResponse<response::BlockPutResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        self.call_with_stream("block_put_stream", args![], data)
+    }
+
+    fn block_rm<C>(&self, cid: C) -> AsyncResponse<response::BlockRmResponse>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.call("block_rm", args![cid.as_str()]),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    fn block_stat<C>(&self, cid: C) -> AsyncResponse<response::BlockStatResponse>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.call("block_stat", args![cid.as_str()]),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    fn bootstrap_add_default(&self) -> AsyncResponse<response::BootstrapAddDefaultResponse> {
+        self.call("bootstrap_add_default", args![])
+    }
+
+    fn bootstrap_list(&self) -> AsyncResponse<response::BootstrapListResponse> {
+        self.call("bootstrap_list", args![])
+    }
+
+    fn bootstrap_rm_all(&self) -> AsyncResponse<response::BootstrapRmAllResponse> {
+        self.call("bootstrap_rm_all", args![])
+    }
+
+    fn cat<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("cat", args![path.as_str()])
+    }
+
+    fn cat_bytes<P>(&self, path: P, max_size: Option<usize>) -> AsyncResponse<Vec<u8>>
+    where
+        P: ToIpfsPath,
+    {
+        let res = read::concat_bytes(LimitedStream::new(self.cat(path), max_size))
+            .map(|body| body.to_vec());
+
+        Box::new(res)
+    }
+
+    fn cat_string<P>(&self, path: P) -> AsyncResponse<String>
+    where
+        P: ToIpfsPath,
+    {
+        let res = self
+            .cat_bytes(path, None)
+            .and_then(|body| String::from_utf8(body).map_err(Error::from));
+
+        Box::new(res)
+    }
+
+    fn cat_json<T, P>(&self, path: P) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("cat_json", args![path.as_str()])
+    }
+
+    fn cat_many<I>(
+        &self,
+        cids: I,
+        parallelism: usize,
+    ) -> AsyncStreamResponse<(Cid, Result<Bytes, Error>)>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let cids: Vec<Cid> = cids.into_iter().collect();
+        let mock = self.clone();
+        let res = stream::iter_ok(cids)
+            .map(move |cid| {
+                mock.cat(cid.as_str())
+                    .concat2()
+                    .then(move |res| Ok((cid, res)))
+            })
+            .buffer_unordered(parallelism.max(1));
+
+        Box::new(res)
+    }
+
+    fn cat_range<P>(&self, path: P, offset: u64, length: u64) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("cat_range", args![path.as_str(), offset, length])
+    }
+
+    fn cat_prefetch(
+        &self,
+        path: &str,
+        range_size: u64,
+        read_ahead: usize,
+    ) -> AsyncStreamResponse<Bytes> {
+        let mock = self.clone();
+        let path = path.to_string();
+        let range_size = range_size.max(1);
+        let ranges = stream::iter_ok(0..)
+            .map(move |i: u64| {
+                mock.cat_range(&path, i * range_size, range_size)
+                    .concat2()
+                    .then(Ok)
+            })
+            .buffered(read_ahead.max(1));
+
+        Box::new(Prefetch::new(ranges, range_size))
+    }
+
+    fn cat_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+        let mock = self.clone();
+
+        Box::new(Resume::new(move |offset| {
+            mock.call_stream("cat_resumable", args![path.as_str(), offset])
+        }))
+    }
+
+    fn cat_with_metadata<P>(
+        &self,
+        path: P,
+    ) -> AsyncResponse<(ResponseMetadata, AsyncStreamResponse<Bytes>)>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let metadata = ResponseMetadata::new(StatusCode::OK, HeaderMap::new());
+        let stream = self.call_stream("cat_with_metadata", args![path.as_str()]);
+
+        Box::new(future::ok((metadata, stream)))
+    }
+
+    fn cat_with_progress<P, F>(&self, path: P, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+        F: 'static + FnMut(Progress) + Send,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+        let stream = self.call_stream("cat_with_progress", args![path.as_str()]);
+
+        Box::new(ProgressStream::new(stream, None, callback))
+    }
+
+    fn commands(&self) -> AsyncResponse<response::CommandsResponse> {
+        self.call("commands", args![])
+    }
+
+    fn config_edit(&self) -> AsyncResponse<response::ConfigEditResponse> {
+        self.call("config_edit", args![])
+    }
+
+    fn config_replace<R>(&self, data: R) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("config_replace", args![], data)
+    }
+
+    fn config_replace_stream<S>(&self, data: S) -> AsyncResponse<response::ConfigReplaceResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        self.call_with_stream("config_replace_stream", args![], data)
+    }
+
+    fn config_show(&self) -> AsyncResponse<response::ConfigShowResponse> {
+        self.call("config_show", args![])
+    }
+
+    fn config_show_bytes(&self) -> AsyncResponse<Bytes> {
+        self.call("config_show_bytes", args![])
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_export<C>(&self, root: C) -> AsyncStreamResponse<Bytes>
+    where
+        C: ToCid,
+    {
+        match root.to_cid() {
+            Ok(root) => self.call_stream("dag_export", args![root.as_str()]),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_get(&self, path: &str) -> AsyncResponse<response::DagGetResponse> {
+        self.call("dag_get", args![path])
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_get_typed<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        self.call("dag_get_typed", args![path])
+    }
+
+    #[cfg(feature = "dag-cbor")]
+    fn dag_get_cbor<T>(&self, path: &str) -> AsyncResponse<T>
+    where
+        for<'de> T: 'static + Deserialize<'de> + Send,
+    {
+        self.call("dag_get_cbor", args![path])
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_import<R>(&self, data: R) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_stream_with_data("dag_import", args![], data)
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_import_with_options<R>(
+        &self,
+        data: R,
+        import: &request::DagImport,
+    ) -> AsyncStreamResponse<response::DagImportResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_stream_with_data("dag_import_with_options", args![import], data)
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_put<R>(&self, data: R) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("dag_put", args![], data)
+    }
+
+    #[cfg(feature = "dag")]
+    fn dag_put_with_options<R>(
+        &self,
+        data: R,
+        put: &request::DagPut,
+    ) -> AsyncResponse<response::DagPutResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("dag_put_with_options", args![put], data)
+    }
+
+    #[cfg(feature = "dag-cbor")]
+    fn dag_put_cbor<T>(&self, value: &T) -> AsyncResponse<response::DagPutResponse>
+    where
+        T: Serialize,
+    {
+        match serde_cbor::to_vec(value) {
+            Ok(data) => self.call_with_data("dag_put_cbor", args![], io::Cursor::new(data)),
+            Err(e) => Box::new(future::err(e.into())),
+        }
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_findpeer<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtFindPeerResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.call_stream("dht_findpeer", args![peer.as_str()]),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_findprovs(&self, key: &str) -> AsyncStreamResponse<response::DhtFindProvsResponse> {
+        self.call_stream("dht_findprovs", args![key])
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_get(&self, key: &str) -> AsyncStreamResponse<response::DhtGetResponse> {
+        self.call_stream("dht_get", args![key])
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_provide(&self, key: &str) -> AsyncStreamResponse<response::DhtProvideResponse> {
+        self.call_stream("dht_provide", args![key])
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_put(&self, key: &str, value: &str) -> AsyncStreamResponse<response::DhtPutResponse> {
+        self.call_stream("dht_put", args![key, value])
+    }
+
+    #[cfg(feature = "dht")]
+    fn dht_query<P>(&self, peer: P) -> AsyncStreamResponse<response::DhtQueryResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.call_stream("dht_query", args![peer.as_str()]),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    fn diag_cmds_clear(&self) -> AsyncResponse<response::DiagCmdsClearResponse> {
+        self.call("diag_cmds_clear", args![])
+    }
+
+    fn diag_cmds_set_time(&self, time: &str) -> AsyncResponse<response::DiagCmdsSetTimeResponse> {
+        self.call("diag_cmds_set_time", args![time])
+    }
+
+    fn diag_sys(&self) -> AsyncResponse<response::DiagSysResponse> {
+        self.call("diag_sys", args![])
+    }
+
+    fn diag_sys_bytes(&self) -> AsyncResponse<Bytes> {
+        self.call("diag_sys_bytes", args![])
+    }
+
+    fn dns(&self, link: &str, recursive: bool) -> AsyncResponse<response::DnsResponse> {
+        self.call("dns", args![link, recursive])
+    }
+
+    fn dns_stream(
+        &self,
+        link: &str,
+        recursive: bool,
+    ) -> AsyncStreamResponse<response::DnsResponse> {
+        self.call_stream("dns_stream", args![link, recursive])
+    }
+
+    fn file_ls(&self, path: &str) -> AsyncResponse<response::FileLsResponse> {
+        self.call("file_ls", args![path])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_cp<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesCpResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let dest = match dest.to_mfs_path() {
+            Ok(dest) => dest,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_cp", args![path.as_str(), dest.as_str()])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_flush(&self, path: Option<&str>) -> AsyncResponse<response::FilesFlushResponse> {
+        self.call("files_flush", args![path])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_ls(&self, path: Option<&str>) -> AsyncResponse<response::FilesLsResponse> {
+        self.call("files_ls", args![path])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_mkdir<P>(&self, path: P, parents: bool) -> AsyncResponse<response::FilesMkdirResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_mkdir", args![path.as_str(), parents])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_mv<P, D>(&self, path: P, dest: D) -> AsyncResponse<response::FilesMvResponse>
+    where
+        P: ToMfsPath,
+        D: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let dest = match dest.to_mfs_path() {
+            Ok(dest) => dest,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_mv", args![path.as_str(), dest.as_str()])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_read<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("files_read", args![path.as_str()])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_rm<P>(&self, path: P, recursive: bool) -> AsyncResponse<response::FilesRmResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_rm", args![path.as_str(), recursive])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_stat<P>(&self, path: P) -> AsyncResponse<response::FilesStatResponse>
+    where
+        P: ToMfsPath,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_stat", args![path.as_str()])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_write<P, R>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: R,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        R: 'static + Read + Send,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call_with_data("files_write", args![path.as_str(), create, truncate], data)
+    }
+
+    #[cfg(feature = "files")]
+    fn files_write_stream<P, S>(
+        &self,
+        path: P,
+        create: bool,
+        truncate: bool,
+        data: S,
+    ) -> AsyncResponse<response::FilesWriteResponse>
+    where
+        P: ToMfsPath,
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        let path = match path.to_mfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call_with_stream(
+            "files_write_stream",
+            args![path.as_str(), create, truncate],
+            data,
+        )
+    }
+
+    #[cfg(feature = "files")]
+    fn files_write_tree<M, P>(&self, local_path: P, mfs_path: M) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call(
+            "files_write_tree",
+            args![local_path.as_ref(), mfs_path.as_str()],
+        )
+    }
+
+    #[cfg(feature = "files")]
+    fn files_sync<P, M>(&self, local_path: P, mfs_path: M) -> AsyncResponse<Vec<String>>
+    where
+        P: AsRef<Path>,
+        M: ToMfsPath,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("files_sync", args![local_path.as_ref(), mfs_path.as_str()])
+    }
+
+    #[cfg(feature = "files")]
+    fn files_read_tree<M, P>(&self, mfs_path: M, local_path: P) -> AsyncResponse<()>
+    where
+        M: ToMfsPath,
+        P: AsRef<Path>,
+    {
+        let mfs_path = match mfs_path.to_mfs_path() {
+            Ok(mfs_path) => mfs_path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call(
+            "files_read_tree",
+            args![mfs_path.as_str(), local_path.as_ref()],
+        )
+    }
+
+    #[cfg(feature = "filestore")]
+    fn filestore_dups(&self) -> AsyncStreamResponse<response::FilestoreDupsResponse> {
+        self.call_stream("filestore_dups", args![])
+    }
+
+    #[cfg(feature = "filestore")]
+    fn filestore_ls(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreLsResponse> {
+        self.call_stream("filestore_ls", args![cid])
+    }
+
+    #[cfg(feature = "filestore")]
+    fn filestore_verify(
+        &self,
+        cid: Option<&str>,
+    ) -> AsyncStreamResponse<response::FilestoreVerifyResponse> {
+        self.call_stream("filestore_verify", args![cid])
+    }
+
+    fn get<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("get", args![path.as_str()])
+    }
+
+    fn get_resumable<P>(&self, path: P) -> AsyncStreamResponse<Bytes>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+        let mock = self.clone();
+
+        Box::new(Resume::new(move |offset| {
+            Box::new(SkipBytes::new(mock.get(&path), offset))
+        }))
+    }
+
+    fn get_to_path<I, P>(&self, path: I, dest: P, overwrite: OverwritePolicy) -> AsyncResponse<()>
+    where
+        I: ToIpfsPath,
+        P: AsRef<Path>,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call(
+            "get_to_path",
+            args![path.as_str(), dest.as_ref(), overwrite],
+        )
+    }
+
+    fn get_with_progress<F>(&self, path: &str, callback: F) -> AsyncStreamResponse<Bytes>
+    where
+        F: 'static + FnMut(Progress) + Send,
+    {
+        let stream = self.call_stream("get_with_progress", args![path]);
+
+        Box::new(ProgressStream::new(stream, None, callback))
+    }
+
+    fn id(&self, peer: Option<&str>) -> AsyncResponse<response::IdResponse> {
+        self.call("id", args![peer])
+    }
+
+    #[cfg(feature = "key")]
+    fn key_gen(
+        &self,
+        name: &str,
+        kind: request::KeyType,
+        size: i32,
+    ) -> AsyncResponse<response::KeyGenResponse> {
+        self.call("key_gen", args![name, kind, size])
+    }
+
+    #[cfg(feature = "key")]
+    fn key_list(&self) -> AsyncResponse<response::KeyListResponse> {
+        self.call("key_list", args![])
+    }
+
+    #[cfg(feature = "key")]
+    fn key_id(&self, name: &str) -> AsyncResponse<PeerId> {
+        self.call("key_id", args![name])
+    }
+
+    #[cfg(feature = "key")]
+    fn key_rename(
+        &self,
+        name: &str,
+        new: &str,
+        force: bool,
+    ) -> AsyncResponse<response::KeyRenameResponse> {
+        self.call("key_rename", args![name, new, force])
+    }
+
+    #[cfg(feature = "key")]
+    fn key_rm(&self, name: &str) -> AsyncResponse<response::KeyRmResponse> {
+        self.call("key_rm", args![name])
+    }
+
+    fn log_level(
+        &self,
+        logger: request::Logger,
+        level: request::LoggingLevel,
+    ) -> AsyncResponse<response::LogLevelResponse> {
+        self.call("log_level", args![logger, level])
+    }
+
+    fn log_ls(&self) -> AsyncResponse<response::LogLsResponse> {
+        self.call("log_ls", args![])
+    }
+
+    fn log_tail(&self) -> AsyncStreamResponse<String> {
+        self.call_stream("log_tail", args![])
+    }
+
+    fn ls(&self, path: Option<&str>) -> AsyncResponse<response::LsResponse> {
+        self.call("ls", args![path])
+    }
+
+    fn monitor_daemon(&self, interval: Duration) -> (DaemonMonitor<Self>, DaemonStatusWatch) {
+        DaemonMonitor::new(self.clone(), interval)
+    }
+
+    #[cfg(feature = "name")]
+    fn name_publish(
+        &self,
+        path: &str,
+        resolve: bool,
+        lifetime: Option<&str>,
+        ttl: Option<&str>,
+        key: Option<&str>,
+    ) -> AsyncResponse<response::NamePublishResponse> {
+        self.call("name_publish", args![path, resolve, lifetime, ttl, key])
+    }
+
+    #[cfg(all(feature = "key", feature = "name"))]
+    fn publish_name<C>(&self, cid: C, key: &str) -> AsyncResponse<String>
+    where
+        C: ToCid,
+    {
+        match cid.to_cid() {
+            Ok(cid) => self.call("publish_name", args![cid.as_str(), key]),
+            Err(e) => Box::new(future::err(e)),
+        }
+    }
+
+    #[cfg(feature = "name")]
+    fn name_resolve(
+        &self,
+        name: Option<&str>,
+        recursive: bool,
+        nocache: bool,
+    ) -> AsyncResponse<response::NameResolveResponse> {
+        self.call("name_resolve", args![name, recursive, nocache])
+    }
+
+    #[cfg(feature = "name")]
+    fn cat_ipns(&self, name: &str) -> AsyncStreamResponse<Bytes> {
+        self.call_stream("cat_ipns", args![name])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_data<K>(&self, key: K) -> AsyncStreamResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("object_data", args![key.as_str()])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_diff<K0, K1>(&self, key0: K0, key1: K1) -> AsyncResponse<response::ObjectDiffResponse>
+    where
+        K0: ToIpfsPath,
+        K1: ToIpfsPath,
+    {
+        let key0 = match key0.to_ipfs_path() {
+            Ok(key0) => key0,
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let key1 = match key1.to_ipfs_path() {
+            Ok(key1) => key1,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_diff", args![key0.as_str(), key1.as_str()])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_get<K>(&self, key: K) -> AsyncResponse<response::ObjectGetResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_get", args![key.as_str()])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_get_encoded<K>(
+        &self,
+        key: K,
+        encoding: request::ObjectEncoding,
+    ) -> AsyncResponse<Bytes>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_get_encoded", args![key.as_str(), encoding])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_get_node<K>(&self, key: K) -> AsyncResponse<DagPbNode>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_get_node", args![key.as_str()])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_links<K>(&self, key: K) -> AsyncResponse<response::ObjectLinksResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_links", args![key.as_str()])
+    }
+
+    /// Walks the canned `object_links` responses.
+    ///
+    #[cfg(feature = "object")]
+    fn walk(&self, hash: &str, max_depth: Option<usize>) -> AsyncStreamResponse<WalkNode> {
+        Box::new(Walk::new(self.clone(), hash, max_depth))
+    }
+
+    #[cfg(feature = "object")]
+    fn object_new(
+        &self,
+        template: Option<request::ObjectTemplate>,
+    ) -> AsyncResponse<response::ObjectNewResponse> {
+        self.call("object_new", args![template])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_patch_add_link(
+        &self,
+        key: &str,
+        name: &str,
+        link: &str,
+        create: bool,
+    ) -> AsyncResponse<response::ObjectPatchAddLinkResponse> {
+        self.call("object_patch_add_link", args![key, name, link, create])
+    }
+
+    #[cfg(feature = "object")]
+    fn object_stat<K>(&self, key: K) -> AsyncResponse<response::ObjectStatResponse>
+    where
+        K: ToIpfsPath,
+    {
+        let key = match key.to_ipfs_path() {
+            Ok(key) => key,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        self.call("object_stat", args![key.as_str()])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_add(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinAddResponse> {
+        self.call("pin_add", args![key, recursive])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_add_many(
+        &self,
+        keys: &[&str],
+        recursive: bool,
+    ) -> AsyncResponse<response::PinAddResponse> {
+        self.call("pin_add_many", args![keys, recursive])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_add_progress(&self, key: &str) -> AsyncStreamResponse<response::PinAddEvent> {
+        self.call_stream("pin_add_progress", args![key])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_ls(
+        &self,
+        key: Option<&str>,
+        typ: Option<&str>,
+    ) -> AsyncResponse<response::PinLsResponse> {
+        self.call("pin_ls", args![key, typ])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_rm(&self, key: &str, recursive: bool) -> AsyncResponse<response::PinRmResponse> {
+        self.call("pin_rm", args![key, recursive])
+    }
+
+    #[cfg(feature = "pin")]
+    fn pin_reconcile<I>(&self, desired: I, parallelism: usize) -> AsyncResponse<PinsetReport>
+    where
+        I: IntoIterator<Item = Cid>,
+    {
+        let desired: Vec<Cid> = desired.into_iter().collect();
+
+        self.call("pin_reconcile", args![desired, parallelism])
+    }
+
+    fn ping<P>(&self, peer: P, count: Option<i32>) -> AsyncStreamResponse<response::PingResponse>
+    where
+        P: ToPeerId,
+    {
+        match peer.to_peer_id() {
+            Ok(peer) => self.call_stream("ping", args![peer.as_str(), count]),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_ls(&self) -> AsyncResponse<response::PubsubLsResponse> {
+        self.call("pubsub_ls", args![])
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_peers(&self, topic: Option<&str>) -> AsyncResponse<response::PubsubPeersResponse> {
+        self.call("pubsub_peers", args![topic])
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_pub(&self, topic: &str, payload: &str) -> AsyncResponse<response::PubsubPubResponse> {
+        self.call("pubsub_pub", args![topic, payload])
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_publisher(&self, topic: &str) -> PubsubPublisher<Self> {
+        PubsubPublisher::new(self.clone(), topic)
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_router(&self) -> (PubsubRouter<Self>, PubsubMessages) {
+        PubsubRouter::new(self.clone())
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_sub(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubSubResponse> {
+        self.call_stream("pubsub_sub", args![topic, discover])
+    }
+
+    #[cfg(feature = "pubsub")]
+    fn pubsub_sub_reconnecting(
+        &self,
+        topic: &str,
+        discover: bool,
+    ) -> AsyncStreamResponse<response::PubsubEvent> {
+        self.call_stream("pubsub_sub_reconnecting", args![topic, discover])
+    }
+
+    fn refs<P>(
+        &self,
+        path: P,
+        max_depth: Option<u64>,
+    ) -> AsyncStreamResponse<response::RefsResponse>
+    where
+        P: ToIpfsPath,
+    {
+        let path = match path.to_ipfs_path() {
+            Ok(path) => path,
+            Err(e) => return Box::new(stream::once(Err(e))),
+        };
+
+        self.call_stream("refs", args![path.as_str(), max_depth])
+    }
+
+    /// Walks the canned `refs` responses.
+    ///
+    fn refs_walk<P>(&self, path: P, max_depth: Option<usize>) -> AsyncStreamResponse<Cid>
+    where
+        P: ToIpfsPath,
+    {
+        match path.to_ipfs_path() {
+            Ok(path) => Box::new(RefsWalk::new(self.clone(), path, max_depth)),
+            Err(e) => Box::new(stream::once(Err(e))),
+        }
+    }
+
+    fn refs_local(&self) -> AsyncStreamResponse<response::RefsLocalResponse> {
+        self.call_stream("refs_local", args![])
+    }
+
+    fn repo_gc(&self) -> AsyncStreamResponse<response::RepoGcResponse> {
+        self.call_stream("repo_gc", args![])
+    }
+
+    fn repo_gc_summary(&self) -> AsyncResponse<response::RepoGcSummary> {
+        self.call("repo_gc_summary", args![])
+    }
+
+    fn repo_stat(&self) -> AsyncResponse<response::RepoStatResponse> {
+        self.call("repo_stat", args![])
+    }
+
+    fn repo_stat_with_options(
+        &self,
+        stat: &request::RepoStat,
+    ) -> AsyncResponse<response::RepoStatResponse> {
+        self.call("repo_stat_with_options", args![stat])
+    }
+
+    fn shutdown(&self) -> AsyncResponse<response::ShutdownResponse> {
+        self.call("shutdown", args![])
+    }
+
+    fn stats_bitswap(&self) -> AsyncResponse<response::StatsBitswapResponse> {
+        self.call("stats_bitswap", args![])
+    }
+
+    fn stats_bw(&self) -> AsyncResponse<response::StatsBwResponse> {
+        self.call("stats_bw", args![])
+    }
+
+    fn bandwidth_samples(&self, interval: Duration) -> AsyncStreamResponse<BandwidthSample> {
+        Box::new(BandwidthSamples::new(self.clone(), interval))
+    }
+
+    fn stats_repo(&self) -> AsyncResponse<response::StatsRepoResponse> {
+        self.call("stats_repo", args![])
+    }
+
+    fn stats_repo_with_options(
+        &self,
+        stat: &request::StatsRepo,
+    ) -> AsyncResponse<response::StatsRepoResponse> {
+        self.call("stats_repo_with_options", args![stat])
+    }
+
+    #[cfg(feature = "swarm")]
+    fn swarm_addrs_local(&self) -> AsyncResponse<response::SwarmAddrsLocalResponse> {
+        self.call("swarm_addrs_local", args![])
+    }
+
+    #[cfg(feature = "swarm")]
+    fn swarm_connect(&self, addr: &str) -> AsyncResponse<response::SwarmConnectResponse> {
+        self.call("swarm_connect", args![addr])
+    }
+
+    #[cfg(feature = "swarm")]
+    fn swarm_connect_any<I>(&self, addrs: I) -> AsyncResponse<Multiaddr>
+    where
+        I: IntoIterator<Item = Multiaddr>,
+    {
+        let addrs: Vec<Multiaddr> = addrs.into_iter().collect();
+
+        self.call("swarm_connect_any", args![addrs])
+    }
+
+    #[cfg(feature = "swarm")]
+    fn swarm_peers(&self) -> AsyncResponse<response::SwarmPeersResponse> {
+        self.call("swarm_peers", args![])
+    }
+
+    #[cfg(feature = "swarm")]
+    fn peer_events(&self, interval: Duration) -> AsyncStreamResponse<PeerEvent> {
+        Box::new(PeerEvents::new(self.clone(), interval))
+    }
+
+    fn tar_add<R>(&self, data: R) -> AsyncResponse<response::TarAddResponse>
+    where
+        R: 'static + Read + Send,
+    {
+        self.call_with_data("tar_add", args![], data)
+    }
+
+    fn tar_add_stream<S>(&self, data: S) -> AsyncResponse<response::TarAddResponse>
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        self.call_with_stream("tar_add_stream", args![], data)
+    }
+
+    fn tar_cat(&self, path: &str) -> AsyncStreamResponse<Bytes> {
+        self.call_stream("tar_cat", args![path])
+    }
+
+    fn version(&self) -> AsyncResponse<response::VersionResponse> {
+        self.call("version", args![])
+    }
+
+    fn wait_for_daemon(&self, timeout: Duration) -> AsyncResponse<response::VersionResponse> {
+        self.call("wait_for_daemon", args![timeout])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockCall, MockIpfsClient};
+    use api::IpfsApi;
+    use bytes::Bytes;
+    use cid::Cid;
+    use futures::{stream, Future, Stream};
+    use response::{Error, VersionResponse};
+    use std::{
+        io::Cursor,
+        sync::{Arc, Mutex},
+    };
+    use tokio::runtime::current_thread::Runtime;
+
+    #[test]
+    fn test_canned_responses_in_order() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_response::<()>("config_edit", Ok(()));
+        mock.push_response::<()>("config_edit", Err(Error::Uncategorized("busy".into())));
+
+        assert!(mock.config_edit().wait().is_ok());
+        assert!(mock.config_edit().wait().is_err());
+        assert!(mock.config_edit().wait().is_err());
+    }
+
+    #[test]
+    fn test_wrong_response_type() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_response::<()>("version", Ok(()));
+
+        assert!(mock.version().wait().is_err());
+    }
+
+    #[test]
+    fn test_stream_response() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_stream_response("cat", vec![Ok(Bytes::from("a")), Ok(Bytes::from("b"))]);
+
+        let res = mock
+            .cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(&res[..], b"ab");
+    }
+
+    #[test]
+    fn test_cat_bytes_max_size() {
+        let mock = MockIpfsClient::new();
+        let path = "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+
+        mock.push_stream_response("cat", vec![Ok(Bytes::from("ab")), Ok(Bytes::from("c"))]);
+        mock.push_stream_response("cat", vec![Ok(Bytes::from("ab")), Ok(Bytes::from("c"))]);
+
+        let body = mock.cat_bytes(path, Some(3)).wait().unwrap();
+        let res = mock.cat_bytes(path, Some(2)).wait();
+
+        assert_eq!(body, b"abc");
+        match res {
+            Err(Error::ResponseTooLarge(2)) => (),
+            res => panic!("expected ResponseTooLarge, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_cat_string_invalid_utf8() {
+        let mock = MockIpfsClient::new();
+        let path = "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+
+        mock.push_stream_response(
+            "cat",
+            vec![Ok(Bytes::from("caf")), Ok(Bytes::from("\u{e9}"))],
+        );
+        mock.push_stream_response("cat", vec![Ok(Bytes::from(&b"caf\xe9"[..]))]);
+
+        assert_eq!(mock.cat_string(path).wait().unwrap(), "caf\u{e9}");
+        match mock.cat_string(path).wait() {
+            Err(Error::ParseUtf8(_)) => (),
+            res => panic!("expected ParseUtf8, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_cat_many_returns_errors_per_cid() {
+        let mock = MockIpfsClient::new();
+        let cids: Vec<Cid> = vec![
+            "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+                .parse()
+                .unwrap(),
+            "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+                .parse()
+                .unwrap(),
+        ];
+
+        mock.push_stream_response("cat", vec![Ok(Bytes::from("a"))]);
+        mock.push_stream_response::<Bytes>(
+            "cat",
+            vec![Err(Error::Uncategorized("not found".into()))],
+        );
+
+        let res = mock.cat_many(cids.clone(), 1).collect().wait().unwrap();
+
+        assert_eq!(res[0].0, cids[0]);
+        assert_eq!(&res[0].1.as_ref().unwrap()[..], b"a");
+        assert_eq!(res[1].0, cids[1]);
+        assert!(res[1].1.is_err());
+    }
+
+    #[test]
+    fn test_cat_prefetch_reads_ranges_in_order() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_stream_response("cat_range", vec![Ok(Bytes::from("abcd"))]);
+        mock.push_stream_response("cat_range", vec![Ok(Bytes::from("ef"))]);
+
+        let body = mock
+            .cat_prefetch("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA", 4, 2)
+            .concat2()
+            .wait()
+            .unwrap();
+        let calls = mock.calls();
+
+        assert_eq!(body, "abcdef");
+        assert_eq!(
+            calls[0].args,
+            vec![
+                "\"/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA\"",
+                "0",
+                "4"
+            ]
+        );
+        assert_eq!(
+            calls[1].args,
+            vec![
+                "\"/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA\"",
+                "4",
+                "4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_resumable_skips_received_bytes() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_stream_response(
+            "get",
+            vec![
+                Ok(Bytes::from("abc")),
+                Err(Error::Timeout {
+                    uri: "http://localhost:5001/api/v0".into(),
+                }),
+            ],
+        );
+        mock.push_stream_response("get", vec![Ok(Bytes::from("abcdef"))]);
+
+        let body = Runtime::new()
+            .unwrap()
+            .block_on(
+                mock.get_resumable("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+                    .concat2(),
+            )
+            .unwrap();
+
+        assert_eq!(body, "abcdef");
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_cat_with_progress() {
+        let mock = MockIpfsClient::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+
+        mock.push_stream_response(
+            "cat_with_progress",
+            vec![Ok(Bytes::from("ab")), Ok(Bytes::from("cde"))],
+        );
+
+        let body = mock
+            .cat_with_progress(
+                "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+                move |progress| sink.lock().unwrap().push(progress.received),
+            )
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "abcde");
+        assert_eq!(*received.lock().unwrap(), vec![2, 5]);
+    }
+
+    #[cfg(feature = "pubsub")]
+    #[test]
+    fn test_pubsub_publisher_publishes_through_mock() {
+        use futures::Sink;
+
+        let mock = MockIpfsClient::new();
+
+        mock.push_response::<()>("pubsub_pub", Ok(()));
+
+        mock.pubsub_publisher("feed")
+            .send("Hello".to_string())
+            .and_then(|publisher| publisher.flush())
+            .wait()
+            .unwrap();
+
+        assert_eq!(mock.calls()[0].endpoint, "pubsub_pub");
+        assert_eq!(mock.calls()[0].args, vec![r#""feed""#, r#""Hello""#]);
+    }
+
+    #[test]
+    fn test_calls_are_recorded() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_response(
+            "version",
+            Ok(VersionResponse {
+                version: "0.4.18".into(),
+                commit: "".into(),
+                repo: "7".into(),
+                system: "amd64/linux".into(),
+                golang: "go1.11".into(),
+            }),
+        );
+
+        let _ = mock.version().wait();
+        let _ = mock.dns("ipfs.io", true).wait();
+        let _ = mock.config_replace(Cursor::new("data")).wait();
+
+        assert_eq!(
+            mock.calls(),
+            vec![
+                MockCall {
+                    endpoint: "version",
+                    args: vec![],
+                    data: None,
+                },
+                MockCall {
+                    endpoint: "dns",
+                    args: vec!["\"ipfs.io\"".into(), "true".into()],
+                    data: None,
+                },
+                MockCall {
+                    endpoint: "config_replace",
+                    args: vec![],
+                    data: Some(b"data".to_vec()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_many_records_every_file() {
+        let mock = MockIpfsClient::new();
+        let files = vec![("a.txt", Cursor::new("da")), ("b.txt", Cursor::new("ta"))];
+
+        let _ = mock.add_many(files).wait();
+
+        assert_eq!(mock.calls()[0].endpoint, "add_many");
+        assert_eq!(mock.calls()[0].data, Some(b"data".to_vec()));
+    }
+
+    #[test]
+    fn test_stream_upload_is_recorded() {
+        let mock = MockIpfsClient::new();
+        let data = stream::iter_ok(vec![Bytes::from("da"), Bytes::from("ta")]);
+
+        mock.push_response::<()>("config_replace_stream", Ok(()));
+
+        assert!(mock.config_replace_stream(data).wait().is_ok());
+        assert_eq!(mock.calls()[0].data, Some(b"data".to_vec()));
+    }
+
+    #[cfg(feature = "dag-cbor")]
+    #[test]
+    fn test_dag_put_cbor_records_cbor() {
+        let mock = MockIpfsClient::new();
+
+        let _ = mock.dag_put_cbor(&vec![1u8, 2]).wait();
+
+        // An array of two small integers.
+        assert_eq!(mock.calls()[0].data, Some(vec![0x82, 0x01, 0x02]));
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use client::AsyncResponse;
+use futures::{Async, Future, Poll, Stream};
+use response::{Error, IdResponse};
+use std::time::{Duration, Instant};
+use tokio::timer::{Interval, Timeout};
+use tokio_sync::watch;
+
+/// Whether the daemon was reachable the last time `DaemonMonitor` checked.
+///
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DaemonStatus {
+    /// Whether the last check succeeded.
+    pub up: bool,
+
+    /// The agent version the daemon reported the last time it was up (e.g.
+    /// `go-ipfs/0.4.18/`).
+    pub version: Option<String>,
+
+    /// The error from the most recent failed check, if any check has failed.
+    pub last_error: Option<String>,
+}
+
+/// Checks that the daemon is reachable by calling `id` every `interval`,
+/// and publishes its `DaemonStatus` to a `DaemonStatusWatch`.
+///
+/// The monitor is a future that needs to be spawned on an executor. It
+/// finishes once every `DaemonStatusWatch` has been dropped.
+///
+pub struct DaemonMonitor<A> {
+    api: A,
+    interval: Duration,
+    ticks: Interval,
+    status: DaemonStatus,
+    sender: watch::Sender<DaemonStatus>,
+    current: Option<Timeout<AsyncResponse<IdResponse>>>,
+}
+
+impl<A> DaemonMonitor<A>
+where
+    A: IpfsApi,
+{
+    pub fn new(api: A, interval: Duration) -> (DaemonMonitor<A>, DaemonStatusWatch) {
+        let (sender, receiver) = watch::channel(DaemonStatus::default());
+        let monitor = DaemonMonitor {
+            api,
+            interval,
+            ticks: Interval::new(Instant::now(), interval),
+            status: DaemonStatus::default(),
+            sender,
+            current: None,
+        };
+
+        (monitor, DaemonStatusWatch { receiver })
+    }
+
+    /// The status after a check that returned `res`.
+    ///
+    fn next_status(&self, res: Result<IdResponse, Error>) -> DaemonStatus {
+        match res {
+            Ok(id) => DaemonStatus {
+                up: true,
+                version: Some(id.agent_version),
+                last_error: self.status.last_error.clone(),
+            },
+            Err(e) => DaemonStatus {
+                up: false,
+                version: self.status.version.clone(),
+                last_error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+impl<A> Future for DaemonMonitor<A>
+where
+    A: IpfsApi,
+{
+    type Item = ();
+
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        if let Ok(Async::Ready(())) = self.sender.poll_close() {
+            return Ok(Async::Ready(()));
+        }
+
+        loop {
+            if let Some(ref mut check) = self.current {
+                let res = match check.poll() {
+                    Ok(Async::Ready(id)) => Ok(id),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => Err(e
+                        .into_inner()
+                        .unwrap_or_else(|| Error::Uncategorized("daemon did not respond".into()))),
+                };
+
+                let status = self.next_status(res);
+
+                self.current = None;
+
+                if status != self.status {
+                    self.status = status.clone();
+
+                    if self.sender.broadcast(status).is_err() {
+                        return Ok(Async::Ready(()));
+                    }
+                }
+            }
+
+            match self.ticks.poll() {
+                Ok(Async::Ready(_)) => {
+                    self.current = Some(Timeout::new(self.api.id(None), self.interval))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(_) => return Err(()),
+            }
+        }
+    }
+}
+
+/// The latest `DaemonStatus` published by a `DaemonMonitor`.
+///
+/// As a `Stream`, it returns the current status, and then each time the
+/// status changes.
+///
+#[derive(Clone)]
+pub struct DaemonStatusWatch {
+    receiver: watch::Receiver<DaemonStatus>,
+}
+
+impl DaemonStatusWatch {
+    /// The status as of the most recent check.
+    ///
+    #[inline]
+    pub fn current(&self) -> DaemonStatus {
+        self.receiver.get_ref().clone()
+    }
+}
+
+impl Stream for DaemonStatusWatch {
+    type Item = DaemonStatus;
+
+    type Error = Error;
+
+    #[inline]
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        self.receiver
+            .poll()
+            .map_err(|e| Error::Uncategorized(e.to_string()))
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::{DaemonMonitor, DaemonStatus};
+    use futures::Stream;
+    use mock::MockIpfsClient;
+    use response::{Error, IdResponse};
+    use std::time::Duration;
+    use tokio::runtime::current_thread::Runtime;
+
+    #[test]
+    fn test_monitor_reports_changes() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_response(
+            "id",
+            Ok(IdResponse {
+                id: "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+                    .parse()
+                    .unwrap(),
+                public_key: String::new(),
+                addresses: Vec::new(),
+                agent_version: "go-ipfs/0.4.18/".into(),
+                protocol_version: "ipfs/0.1.0".into(),
+            }),
+        );
+        mock.push_response::<IdResponse>(
+            "id",
+            Err(Error::Uncategorized("connection refused".into())),
+        );
+
+        let (monitor, watch) = DaemonMonitor::new(mock, Duration::from_millis(1));
+        let mut runtime = Runtime::new().unwrap();
+
+        runtime.spawn(monitor);
+
+        let statuses = runtime.block_on(watch.take(3).collect()).unwrap();
+
+        assert_eq!(statuses[0], DaemonStatus::default());
+        assert!(statuses[1].up);
+        assert_eq!(statuses[2].version.as_ref().unwrap(), "go-ipfs/0.4.18/");
+        assert!(!statuses[2].up);
+        assert!(statuses[2]
+            .last_error
+            .as_ref()
+            .unwrap()
+            .contains("connection refused"));
+    }
+}
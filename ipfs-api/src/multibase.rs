@@ -0,0 +1,319 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! Decoding and encoding of the multibase strings CIDs and peer ids are
+//! written in.
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+const BASE36_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+const BASE64URL_ALPHABET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Decodes a multibase string, in base32, base36, base58btc, base16, base64
+/// or base64url.
+///
+pub fn decode(s: &str) -> Option<Vec<u8>> {
+    let mut chars = s.chars();
+
+    match chars.next()? {
+        'b' => decode_base32(chars.as_str().as_bytes()),
+        'B' => decode_base32(chars.as_str().to_ascii_lowercase().as_bytes()),
+        'k' => decode_base36(chars.as_str().as_bytes()),
+        'K' => decode_base36(chars.as_str().to_ascii_lowercase().as_bytes()),
+        'z' => decode_base58(chars.as_str().as_bytes()),
+        'f' | 'F' => decode_base16(chars.as_str().as_bytes()),
+        'm' => decode_base64(chars.as_str().as_bytes()),
+        'u' | 'U' => decode_base64url(chars.as_str().as_bytes()),
+        _ => None,
+    }
+}
+
+/// Reads an unsigned varint from the front of `bytes`.
+///
+pub fn read_varint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+/// Appends `value` to `bytes` as an unsigned varint.
+///
+pub fn write_varint(mut value: u64, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+/// Checks that `bytes` is exactly one multihash, returning its hash code.
+///
+pub fn check_multihash(bytes: &[u8]) -> Option<u64> {
+    let mut rest = bytes;
+    let code = read_varint(&mut rest)?;
+    let len = read_varint(&mut rest)?;
+
+    if rest.len() as u64 == len {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+pub fn decode_base58(input: &[u8]) -> Option<Vec<u8>> {
+    decode_radix(input, BASE58_ALPHABET)
+}
+
+pub fn decode_base36(input: &[u8]) -> Option<Vec<u8>> {
+    decode_radix(input, BASE36_ALPHABET)
+}
+
+/// Decodes a big-endian number written with `alphabet`, where each leading
+/// zero digit stands for a zero byte, as base58btc and base36 are.
+///
+fn decode_radix(input: &[u8], alphabet: &[u8]) -> Option<Vec<u8>> {
+    let radix = alphabet.len() as u32;
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+
+    for c in input {
+        let mut carry = alphabet.iter().position(|a| a == c)? as u32;
+
+        for byte in bytes.iter_mut().rev() {
+            carry += u32::from(*byte) * radix;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let zeros = input.iter().take_while(|c| **c == alphabet[0]).count();
+    let mut decoded = vec![0; zeros];
+
+    decoded.extend(bytes);
+
+    Some(decoded)
+}
+
+pub fn decode_base32(input: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input {
+        buffer = (buffer << 5) | BASE32_ALPHABET.iter().position(|a| a == c)? as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
+
+pub fn decode_base16(input: &[u8]) -> Option<Vec<u8>> {
+    input
+        .chunks(2)
+        .map(|pair| {
+            if pair.len() != 2 {
+                return None;
+            }
+
+            let hex = ::std::str::from_utf8(pair).ok()?;
+
+            u8::from_str_radix(hex, 16).ok()
+        })
+        .collect()
+}
+
+/// Decodes standard base64, with or without padding.
+///
+pub fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    decode_base64_with(input, BASE64_ALPHABET)
+}
+
+/// Decodes url-safe base64, with or without padding.
+///
+pub fn decode_base64url(input: &[u8]) -> Option<Vec<u8>> {
+    decode_base64_with(input, BASE64URL_ALPHABET)
+}
+
+fn decode_base64_with(input: &[u8], alphabet: &[u8]) -> Option<Vec<u8>> {
+    let input = match input.iter().position(|&c| c == b'=') {
+        Some(padding) if input[padding..].iter().all(|&c| c == b'=') => &input[..padding],
+        Some(_) => return None,
+        None => input,
+    };
+    let mut decoded = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input {
+        buffer = (buffer << 6) | alphabet.iter().position(|a| a == c)? as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
+
+pub fn encode_base58(input: &[u8]) -> String {
+    let mut digits: Vec<u8> = Vec::with_capacity(input.len() * 138 / 100 + 1);
+
+    for byte in input {
+        let mut carry = u32::from(*byte);
+
+        for digit in digits.iter_mut().rev() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.insert(0, (carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = input.iter().take_while(|b| **b == 0).count();
+    let mut encoded = "1".repeat(zeros);
+
+    encoded.extend(
+        digits
+            .into_iter()
+            .map(|d| char::from(BASE58_ALPHABET[d as usize])),
+    );
+
+    encoded
+}
+
+pub fn encode_base16(input: &[u8]) -> String {
+    input.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn encode_base32(input: &[u8]) -> String {
+    let mut encoded = String::with_capacity(input.len() * 8 / 5 + 1);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for byte in input {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(char::from(
+                BASE32_ALPHABET[(buffer >> bits) as usize & 0x1f],
+            ));
+        }
+
+        buffer &= (1 << bits) - 1;
+    }
+
+    if bits > 0 {
+        encoded.push(char::from(
+            BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 0x1f],
+        ));
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode, decode_base32, decode_base36, decode_base58, decode_base64, encode_base32,
+        encode_base58, read_varint, write_varint,
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let bytes = [0, 0, 1, 2, 3, 250, 251, 252, 253, 254, 255];
+
+        assert_eq!(
+            decode_base58(encode_base58(&bytes).as_bytes()).unwrap(),
+            bytes
+        );
+        assert_eq!(
+            decode_base32(encode_base32(&bytes).as_bytes()).unwrap(),
+            bytes
+        );
+        assert_eq!(encode_base58(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(encode_base32(b"foobar"), "mzxw6ytboi");
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64(b"Zm9vYmFy").unwrap(), b"foobar");
+        assert_eq!(decode_base64(b"Zm9vYg==").unwrap(), b"foob");
+        assert_eq!(decode_base64(b"Zm9vYg").unwrap(), b"foob");
+        assert_eq!(decode("mZm9vYmE").unwrap(), b"fooba");
+        assert!(decode_base64(b"Zm=9v").is_none());
+        assert!(decode_base64(b"Zm9v!").is_none());
+    }
+
+    #[test]
+    fn test_decode_base36_and_base64url() {
+        let key = "k51qzi5uqu5dlvj2baxnqndepeb86cbk3ng7n3i46uzyxzyqj2xjonzllnv0v8";
+        let bytes = decode(key).unwrap();
+
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(&bytes[..4], &[0x01, 0x72, 0x00, 0x24]);
+        assert_eq!(decode(&key.to_uppercase()).unwrap(), bytes);
+        assert_eq!(
+            decode("uAXIAJAgBEiDkaAsvjI0hCQ5qoyfxuzQquOfZI48eNYMaVNao9ckRJA").unwrap(),
+            bytes
+        );
+        assert_eq!(decode_base36(b"00a").unwrap(), [0, 0, 10]);
+        assert_eq!(decode("u-_8").unwrap(), [0xfb, 0xff]);
+        assert!(decode("k!").is_none());
+    }
+
+    #[test]
+    fn test_varint_round_trip() {
+        let mut bytes = Vec::new();
+
+        write_varint(0x70, &mut bytes);
+        write_varint(300, &mut bytes);
+        assert_eq!(bytes, [0x70, 0xac, 0x02]);
+
+        let mut rest = &bytes[..];
+
+        assert_eq!(read_varint(&mut rest), Some(0x70));
+        assert_eq!(read_varint(&mut rest), Some(300));
+        assert!(rest.is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use cid::Cid;
+use response::Error;
+use std::{fmt, ops::Deref, str::FromStr};
+
+/// An absolute path in the mutable file system, as taken by the `files_*`
+/// methods (e.g. `/photos/2019/cat.jpg`).
+///
+/// Paths have to start with `/`, and can't have empty, `.` or `..`
+/// segments. A trailing `/` is allowed. Passing a bare CID, a common mistake,
+/// is rejected instead of the daemon creating a file named after it.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::MfsPath;
+///
+/// # fn main() {
+/// let dir: MfsPath = "/photos".parse().unwrap();
+///
+/// assert_eq!(dir.join("cat.jpg").unwrap(), "/photos/cat.jpg");
+/// assert!("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+///     .parse::<MfsPath>()
+///     .is_err());
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MfsPath(String);
+
+impl MfsPath {
+    /// The root of the mutable file system (`/`).
+    ///
+    #[inline]
+    pub fn root() -> MfsPath {
+        MfsPath("/".into())
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The path of `name` inside of this directory. `name` has to be a single
+    /// segment, without any `/`.
+    ///
+    pub fn join(&self, name: &str) -> Result<MfsPath, Error> {
+        check_segment(name).map_err(|reason| Error::InvalidPath(name.into(), reason))?;
+
+        Ok(MfsPath(format!(
+            "{}/{}",
+            self.0.trim_end_matches('/'),
+            name
+        )))
+    }
+
+    /// The directory this path is in, or `None` for the root.
+    ///
+    pub fn parent(&self) -> Option<MfsPath> {
+        let path = self.0.trim_end_matches('/');
+
+        match path.rfind('/') {
+            Some(0) if path.len() > 1 => Some(MfsPath::root()),
+            Some(i) if i > 0 => Some(MfsPath(path[..i].into())),
+            _ => None,
+        }
+    }
+
+    /// The last segment of the path, or `None` for the root.
+    ///
+    pub fn file_name(&self) -> Option<&str> {
+        self.0
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+    }
+}
+
+impl FromStr for MfsPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<MfsPath, Error> {
+        let invalid = |reason| Error::InvalidPath(s.into(), reason);
+
+        if !s.starts_with('/') {
+            return Err(invalid("mfs paths must start with '/'"));
+        }
+
+        check_segments(&s[1..]).map_err(invalid)?;
+
+        Ok(MfsPath(s.into()))
+    }
+}
+
+/// A path to immutable content, as taken by `cat`, `get` and the `object_*`
+/// methods: `/ipfs/<cid>` or `/ipns/<name>`, optionally followed by a path
+/// inside of it, or a CID on its own (e.g.
+/// `QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/readme.md`).
+///
+/// The CID an `/ipfs/` path starts with is validated. An MFS path, which the
+/// daemon would try to read as a CID, is rejected.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::IpfsPath;
+///
+/// # fn main() {
+/// let path: IpfsPath = "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(
+///     path.join("readme.md").unwrap(),
+///     "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/readme.md"
+/// );
+/// assert!("/photos/cat.jpg".parse::<IpfsPath>().is_err());
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IpfsPath(String);
+
+impl IpfsPath {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The path of `name` inside of this path. `name` has to be a single
+    /// segment, without any `/`.
+    ///
+    pub fn join(&self, name: &str) -> Result<IpfsPath, Error> {
+        check_segment(name).map_err(|reason| Error::InvalidPath(name.into(), reason))?;
+
+        Ok(IpfsPath(format!(
+            "{}/{}",
+            self.0.trim_end_matches('/'),
+            name
+        )))
+    }
+
+    /// The CID the path starts at, or `None` for an `/ipns/` path.
+    ///
+    pub fn cid(&self) -> Option<Cid> {
+        if self.is_ipns() {
+            return None;
+        }
+
+        let path = self.0.trim_start_matches("/ipfs/");
+
+        path.split('/').next()?.parse().ok()
+    }
+
+    /// Whether the path starts with a name that has to be resolved first
+    /// (`/ipns/`).
+    ///
+    #[inline]
+    pub fn is_ipns(&self) -> bool {
+        self.0.starts_with("/ipns/")
+    }
+}
+
+impl FromStr for IpfsPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<IpfsPath, Error> {
+        let invalid = |reason| Error::InvalidPath(s.into(), reason);
+        let (root, rest) = if let Some(path) = s.strip_prefix("/ipfs/") {
+            split_root(path)
+        } else if let Some(path) = s.strip_prefix("/ipns/") {
+            let (name, rest) = split_root(path);
+
+            if name.is_empty() {
+                return Err(invalid("missing ipns name"));
+            }
+
+            check_segments(rest).map_err(invalid)?;
+
+            return Ok(IpfsPath(s.into()));
+        } else if s.starts_with('/') {
+            return Err(invalid("paths must start with '/ipfs/', '/ipns/' or a cid"));
+        } else {
+            split_root(s)
+        };
+
+        root.parse::<Cid>()
+            .map_err(|_| invalid("path does not start with a valid cid"))?;
+        check_segments(rest).map_err(invalid)?;
+
+        Ok(IpfsPath(s.into()))
+    }
+}
+
+impl From<Cid> for IpfsPath {
+    #[inline]
+    fn from(cid: Cid) -> IpfsPath {
+        IpfsPath(format!("/ipfs/{}", cid))
+    }
+}
+
+/// Splits the first segment off of a path.
+///
+fn split_root(path: &str) -> (&str, &str) {
+    match path.find('/') {
+        Some(i) => (&path[..i], &path[i + 1..]),
+        None => (path, ""),
+    }
+}
+
+/// Checks the segments of a path after the leading `/`. The last segment can
+/// be empty, for a path that ends in `/`.
+///
+fn check_segments(path: &str) -> Result<(), &'static str> {
+    if path.is_empty() {
+        return Ok(());
+    }
+
+    let path = path.trim_end_matches('/');
+
+    if path.is_empty() {
+        return Err("empty path segment");
+    }
+
+    path.split('/').try_for_each(check_segment)
+}
+
+fn check_segment(segment: &str) -> Result<(), &'static str> {
+    match segment {
+        "" => Err("empty path segment"),
+        "." | ".." => Err("relative path segment"),
+        s if s.contains('/') => Err("path segment contains '/'"),
+        _ => Ok(()),
+    }
+}
+
+macro_rules! impl_path_traits {
+    ($path:ident) => {
+        impl fmt::Display for $path {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl Deref for $path {
+            type Target = str;
+
+            #[inline]
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $path {
+            #[inline]
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<$path> for String {
+            #[inline]
+            fn from(path: $path) -> String {
+                path.0
+            }
+        }
+
+        impl PartialEq<str> for $path {
+            #[inline]
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl<'a> PartialEq<&'a str> for $path {
+            #[inline]
+            fn eq(&self, other: &&'a str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+impl_path_traits!(MfsPath);
+impl_path_traits!(IpfsPath);
+
+/// Types that can be converted to an `MfsPath`, validating them first if
+/// they are strings.
+///
+pub trait ToMfsPath {
+    fn to_mfs_path(&self) -> Result<MfsPath, Error>;
+}
+
+impl ToMfsPath for MfsPath {
+    #[inline]
+    fn to_mfs_path(&self) -> Result<MfsPath, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl ToMfsPath for str {
+    #[inline]
+    fn to_mfs_path(&self) -> Result<MfsPath, Error> {
+        self.parse()
+    }
+}
+
+impl ToMfsPath for String {
+    #[inline]
+    fn to_mfs_path(&self) -> Result<MfsPath, Error> {
+        self.parse()
+    }
+}
+
+impl<T> ToMfsPath for &T
+where
+    T: ToMfsPath + ?Sized,
+{
+    #[inline]
+    fn to_mfs_path(&self) -> Result<MfsPath, Error> {
+        (**self).to_mfs_path()
+    }
+}
+
+/// Types that can be converted to an `IpfsPath`, validating them first if
+/// they are strings.
+///
+pub trait ToIpfsPath {
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error>;
+}
+
+impl ToIpfsPath for IpfsPath {
+    #[inline]
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl ToIpfsPath for Cid {
+    #[inline]
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error> {
+        Ok(self.clone().into())
+    }
+}
+
+impl ToIpfsPath for str {
+    #[inline]
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error> {
+        self.parse()
+    }
+}
+
+impl ToIpfsPath for String {
+    #[inline]
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error> {
+        self.parse()
+    }
+}
+
+impl<T> ToIpfsPath for &T
+where
+    T: ToIpfsPath + ?Sized,
+{
+    #[inline]
+    fn to_ipfs_path(&self) -> Result<IpfsPath, Error> {
+        (**self).to_ipfs_path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IpfsPath, MfsPath};
+
+    const CID: &str = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+
+    #[test]
+    fn test_mfs_path() {
+        for path in &["/", "/foo", "/foo/bar baz.txt", "/foo/", "/ipfs/foo"] {
+            assert!(path.parse::<MfsPath>().is_ok(), "{}", path);
+        }
+
+        for path in &["", "foo", CID, "//", "/foo//bar", "/foo/../bar", "/."] {
+            assert!(path.parse::<MfsPath>().is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_mfs_path_segments() {
+        let path: MfsPath = "/foo/bar/".parse().unwrap();
+
+        assert_eq!(path.file_name(), Some("bar"));
+        assert_eq!(path.parent().unwrap(), "/foo");
+        assert_eq!(path.parent().unwrap().parent().unwrap(), "/");
+        assert_eq!(MfsPath::root().parent(), None);
+        assert_eq!(MfsPath::root().file_name(), None);
+        assert_eq!(MfsPath::root().join("foo").unwrap(), "/foo");
+        assert_eq!(path.join("baz").unwrap(), "/foo/bar/baz");
+        assert!(path.join("a/b").is_err());
+        assert!(path.join("..").is_err());
+    }
+
+    #[test]
+    fn test_ipfs_path() {
+        let valid = [
+            CID.to_string(),
+            format!("{}/readme.md", CID),
+            format!("/ipfs/{}", CID),
+            format!("/ipfs/{}/a/b/", CID),
+            "/ipfs/bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+            "/ipns/docs.ipfs.io/index.html".to_string(),
+        ];
+
+        for path in &valid {
+            assert!(path.parse::<IpfsPath>().is_ok(), "{}", path);
+        }
+
+        let invalid = [
+            "/foo/bar".to_string(),
+            "/ipfs/QmFoo".to_string(),
+            "/ipfs/".to_string(),
+            "/ipns/".to_string(),
+            format!("/ipfs/{}//a", CID),
+            format!("/ipfs/{}/../a", CID),
+        ];
+
+        for path in &invalid {
+            assert!(path.parse::<IpfsPath>().is_err(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_ipfs_path_cid() {
+        let path: IpfsPath = format!("/ipfs/{}/a", CID).parse().unwrap();
+
+        assert_eq!(path.cid().unwrap(), CID);
+        assert_eq!(CID.parse::<IpfsPath>().unwrap().cid().unwrap(), CID);
+        assert!(!path.is_ipns());
+
+        let path: IpfsPath = "/ipns/docs.ipfs.io".parse().unwrap();
+
+        assert!(path.is_ipns());
+        assert_eq!(path.cid(), None);
+        assert_eq!(
+            IpfsPath::from(CID.parse::<::cid::Cid>().unwrap()),
+            format!("/ipfs/{}", CID).as_str()
+        );
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use multibase::{self, check_multihash, decode_base58, encode_base32, encode_base58, read_varint};
+use response::Error;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::{fmt, ops::Deref, str::FromStr};
+
+/// Multihash codes a peer id can be hashed with: identity (for small keys
+/// like ed25519) and sha2-256.
+///
+const PEER_ID_HASHES: &[u64] = &[0x00, 0x12];
+
+/// Multicodec of a CIDv1 that holds a peer id.
+///
+const LIBP2P_KEY: u64 = 0x72;
+
+/// A validated libp2p peer id.
+///
+/// Peer ids are accepted in their base58 form (`Qm...` or `12D3KooW...`), or
+/// as a CIDv1 with the `libp2p-key` codec. CIDs of any other content are
+/// rejected. A `PeerId` always displays in base58, which is what the api
+/// returns.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::PeerId;
+///
+/// # fn main() {
+/// let peer: PeerId = "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ".parse().unwrap();
+/// let base32 = peer.to_base32();
+///
+/// assert_eq!(base32.parse::<PeerId>().unwrap(), peer);
+/// assert!("bafybeihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku"
+///     .parse::<PeerId>()
+///     .is_err());
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PeerId {
+    peer: String,
+    multihash: Vec<u8>,
+}
+
+impl PeerId {
+    /// The peer id in base58.
+    ///
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.peer
+    }
+
+    /// The peer id as a base32 CIDv1 (`bafz...`).
+    ///
+    pub fn to_base32(&self) -> String {
+        let mut cid = vec![0x01, LIBP2P_KEY as u8];
+
+        cid.extend_from_slice(&self.multihash);
+
+        format!("b{}", encode_base32(&cid))
+    }
+
+    fn invalid(peer: &str, reason: &'static str) -> Error {
+        Error::InvalidPeerId(peer.into(), reason)
+    }
+}
+
+impl FromStr for PeerId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<PeerId, Error> {
+        let multihash = if s.starts_with("Qm") || s.starts_with('1') {
+            decode_base58(s.as_bytes()).ok_or_else(|| PeerId::invalid(s, "bad base58"))?
+        } else {
+            let bytes =
+                multibase::decode(s).ok_or_else(|| PeerId::invalid(s, "bad multibase encoding"))?;
+            let mut rest = &bytes[..];
+
+            if read_varint(&mut rest) != Some(1) || read_varint(&mut rest) != Some(LIBP2P_KEY) {
+                return Err(PeerId::invalid(s, "not a libp2p-key cid"));
+            }
+
+            rest.to_vec()
+        };
+
+        match check_multihash(&multihash) {
+            Some(code) if PEER_ID_HASHES.contains(&code) => Ok(PeerId {
+                peer: encode_base58(&multihash),
+                multihash,
+            }),
+            _ => Err(PeerId::invalid(s, "bad multihash")),
+        }
+    }
+}
+
+impl fmt::Display for PeerId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.peer)
+    }
+}
+
+impl Deref for PeerId {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        &self.peer
+    }
+}
+
+impl AsRef<str> for PeerId {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        &self.peer
+    }
+}
+
+impl From<PeerId> for String {
+    #[inline]
+    fn from(peer: PeerId) -> String {
+        peer.peer
+    }
+}
+
+impl PartialEq<str> for PeerId {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.peer == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for PeerId {
+    #[inline]
+    fn eq(&self, other: &&'a str) -> bool {
+        self.peer == *other
+    }
+}
+
+impl PartialEq<String> for PeerId {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        &self.peer == other
+    }
+}
+
+impl Serialize for PeerId {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.peer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PeerId {
+    fn deserialize<D>(deserializer: D) -> Result<PeerId, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Values that can be used where the api expects a peer id.
+///
+/// Implemented for `PeerId`, and for strings, which are validated before any
+/// request is made.
+///
+pub trait ToPeerId {
+    fn to_peer_id(&self) -> Result<PeerId, Error>;
+}
+
+impl ToPeerId for PeerId {
+    #[inline]
+    fn to_peer_id(&self) -> Result<PeerId, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl ToPeerId for str {
+    #[inline]
+    fn to_peer_id(&self) -> Result<PeerId, Error> {
+        self.parse()
+    }
+}
+
+impl ToPeerId for String {
+    #[inline]
+    fn to_peer_id(&self) -> Result<PeerId, Error> {
+        self.parse()
+    }
+}
+
+impl<T> ToPeerId for &T
+where
+    T: ToPeerId + ?Sized,
+{
+    #[inline]
+    fn to_peer_id(&self) -> Result<PeerId, Error> {
+        (**self).to_peer_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PeerId, ToPeerId};
+
+    #[test]
+    fn test_parse() {
+        let rsa: PeerId = "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+            .parse()
+            .unwrap();
+        let ed25519: PeerId = "12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"
+            .parse()
+            .unwrap();
+
+        assert_eq!(rsa, "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ");
+        assert_eq!(
+            ed25519,
+            "12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"
+        );
+        assert!(ed25519.to_base32().starts_with("bafzaa"));
+        assert_eq!(ed25519.to_base32().to_peer_id().unwrap(), ed25519);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".to_peer_id().is_err());
+        assert!("QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvu"
+            .to_peer_id()
+            .is_err());
+        assert!("12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXT0"
+            .to_peer_id()
+            .is_err());
+        assert!(
+            "bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae"
+                .to_peer_id()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use client::AsyncResponse;
+use futures::{Async, Future, Poll, Stream};
+use peer::PeerId;
+use response::{Error, SwarmPeer, SwarmPeersResponse};
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+use tokio::timer::{Interval, Timeout};
+
+/// A change in the peers the daemon has open connections to.
+///
+#[derive(Debug)]
+pub enum PeerEvent {
+    /// A peer that was not connected at the previous check is now.
+    Connected(SwarmPeer),
+
+    /// A peer that was connected at the previous check no longer is.
+    Disconnected(PeerId),
+}
+
+impl PeerEvent {
+    /// The peer the event is about.
+    ///
+    #[inline]
+    pub fn peer(&self) -> &PeerId {
+        match *self {
+            PeerEvent::Connected(ref peer) => &peer.peer,
+            PeerEvent::Disconnected(ref peer) => peer,
+        }
+    }
+}
+
+/// Calls `swarm_peers` every `interval`, and returns a `PeerEvent` for each
+/// peer that connected or disconnected since the previous call.
+///
+/// Every peer connected at the first call is returned as connected. Peers
+/// that connect and disconnect again between two calls are not seen.
+///
+/// A call that fails or takes longer than `interval` is returned as an
+/// error, and the set of connected peers is kept as it was. The stream
+/// keeps checking on later ticks if it is polled again.
+///
+pub struct PeerEvents<A> {
+    api: A,
+    interval: Duration,
+    ticks: Interval,
+    connected: HashSet<PeerId>,
+    pending: VecDeque<PeerEvent>,
+    current: Option<Timeout<AsyncResponse<SwarmPeersResponse>>>,
+}
+
+impl<A> PeerEvents<A>
+where
+    A: IpfsApi,
+{
+    pub fn new(api: A, interval: Duration) -> PeerEvents<A> {
+        PeerEvents {
+            api,
+            interval,
+            ticks: Interval::new(Instant::now(), interval),
+            connected: HashSet::new(),
+            pending: VecDeque::new(),
+            current: None,
+        }
+    }
+
+    /// Queues the events that take the connected peers to `peers`.
+    ///
+    fn update(&mut self, peers: Vec<SwarmPeer>) {
+        let now: HashSet<PeerId> = peers.iter().map(|peer| peer.peer.clone()).collect();
+        let mut gone: Vec<PeerId> = self.connected.difference(&now).cloned().collect();
+
+        gone.sort();
+
+        self.pending
+            .extend(gone.into_iter().map(PeerEvent::Disconnected));
+
+        for peer in peers {
+            if !self.connected.contains(&peer.peer) {
+                self.pending.push_back(PeerEvent::Connected(peer));
+            }
+        }
+
+        self.connected = now;
+    }
+}
+
+impl<A> Stream for PeerEvents<A>
+where
+    A: IpfsApi,
+{
+    type Item = PeerEvent;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Async::Ready(Some(event)));
+            }
+
+            if let Some(ref mut check) = self.current {
+                let res = match check.poll() {
+                    Ok(Async::Ready(res)) => Ok(res),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => Err(e
+                        .into_inner()
+                        .unwrap_or_else(|| Error::Uncategorized("daemon did not respond".into()))),
+                };
+
+                self.current = None;
+                self.update(res?.peers);
+
+                continue;
+            }
+
+            match self.ticks.poll() {
+                Ok(Async::Ready(_)) => {
+                    self.current = Some(Timeout::new(self.api.swarm_peers(), self.interval))
+                }
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Err(e) => return Err(Error::Uncategorized(e.to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::{PeerEvent, PeerEvents};
+    use futures::Stream;
+    use mock::MockIpfsClient;
+    use response::{Error, SwarmPeer, SwarmPeersResponse};
+    use std::time::Duration;
+    use tokio::runtime::current_thread::Runtime;
+
+    const PEERS: [&str; 3] = [
+        "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+        "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn",
+        "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ",
+    ];
+
+    fn peers(mock: &MockIpfsClient, ns: &[usize]) {
+        let peers = ns
+            .iter()
+            .map(|&n| SwarmPeer {
+                addr: "/ip4/127.0.0.1/tcp/4001".parse().unwrap(),
+                peer: PEERS[n].parse().unwrap(),
+                latency: String::new(),
+                muxer: String::new(),
+                streams: Vec::new(),
+            })
+            .collect();
+
+        mock.push_response("swarm_peers", Ok(SwarmPeersResponse { peers }));
+    }
+
+    fn describe(event: &PeerEvent) -> String {
+        match *event {
+            PeerEvent::Connected(ref peer) => format!("+{}", peer.peer),
+            PeerEvent::Disconnected(ref peer) => format!("-{}", peer),
+        }
+    }
+
+    #[test]
+    fn test_peer_events() {
+        let mock = MockIpfsClient::new();
+
+        peers(&mock, &[0, 1]);
+        peers(&mock, &[1, 2]);
+        mock.push_response::<SwarmPeersResponse>(
+            "swarm_peers",
+            Err(Error::Uncategorized("connection refused".into())),
+        );
+        peers(&mock, &[]);
+
+        let events = PeerEvents::new(mock, Duration::from_millis(1))
+            .then(|res| Ok::<_, ()>(res.map(|event| describe(&event))))
+            .take(7)
+            .collect();
+        let events = Runtime::new().unwrap().block_on(events).unwrap();
+
+        assert_eq!(events[0].as_ref().unwrap(), &format!("+{}", PEERS[0]));
+        assert_eq!(events[1].as_ref().unwrap(), &format!("+{}", PEERS[1]));
+        assert_eq!(events[2].as_ref().unwrap(), &format!("-{}", PEERS[0]));
+        assert_eq!(events[3].as_ref().unwrap(), &format!("+{}", PEERS[2]));
+        assert!(events[4].is_err());
+        assert_eq!(events[5].as_ref().unwrap(), &format!("-{}", PEERS[1]));
+        assert_eq!(events[6].as_ref().unwrap(), &format!("-{}", PEERS[2]));
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use cid::Cid;
+use response::Error;
+use std::collections::{HashMap, HashSet};
+
+/// The changes `pin_reconcile` made to bring the recursive pins in line with
+/// the desired set.
+///
+#[derive(Debug, Default)]
+pub struct PinsetReport {
+    /// The CIDs that were pinned, in the order the pins finished.
+    pub added: Vec<Cid>,
+
+    /// The CIDs that were unpinned, in the order the pins were removed.
+    pub removed: Vec<Cid>,
+
+    /// The number of CIDs in the desired set that were already pinned.
+    pub unchanged: usize,
+
+    /// The CIDs that could not be pinned or unpinned, with the error the
+    /// daemon returned.
+    pub failed: Vec<(Cid, Error)>,
+
+    /// The pinned keys the daemon listed that could not be parsed as CIDs,
+    /// with the error. They are left pinned.
+    pub unparsed: Vec<(String, Error)>,
+}
+
+impl PinsetReport {
+    /// Whether every pin that had to be added or removed was.
+    ///
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// The pins to add and to remove to go from `pinned` to `desired`.
+///
+/// CIDs are matched by multihash, so the same content written as a CIDv0 on
+/// one side and a CIDv1 (in any multibase) on the other counts as pinned,
+/// and never shows up in both `add` and `remove`.
+///
+#[derive(Debug, Default, PartialEq)]
+pub struct PinsetDiff {
+    pub add: Vec<Cid>,
+    pub remove: Vec<Cid>,
+    pub unchanged: usize,
+}
+
+impl PinsetDiff {
+    pub fn new(desired: &HashSet<Cid>, pinned: &HashSet<Cid>) -> PinsetDiff {
+        let mut diff = PinsetDiff::default();
+        let desired = by_multihash(desired);
+        let pinned = by_multihash(pinned);
+
+        for (multihash, cid) in &desired {
+            if pinned.contains_key(multihash) {
+                diff.unchanged += 1;
+            } else {
+                diff.add.push((*cid).clone());
+            }
+        }
+
+        diff.remove = pinned
+            .iter()
+            .filter(|(multihash, _)| !desired.contains_key(*multihash))
+            .map(|(_, cid)| (*cid).clone())
+            .collect();
+
+        diff
+    }
+}
+
+/// Indexes `cids` by multihash, keeping one CID per block.
+///
+fn by_multihash(cids: &HashSet<Cid>) -> HashMap<Vec<u8>, &Cid> {
+    let mut map = HashMap::with_capacity(cids.len());
+
+    for cid in cids {
+        let kept = map.entry(cid.multihash()).or_insert(cid);
+
+        // Keep the smallest, so the choice doesn't depend on hash order.
+        //
+        if cid < *kept {
+            *kept = cid;
+        }
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PinsetDiff;
+    use cid::{Cid, CidBase};
+    use std::collections::HashSet;
+
+    /// A CIDv0 whose digest is `n` repeated.
+    ///
+    fn cid(n: u8) -> Cid {
+        let mut bytes = vec![0x12, 0x20];
+
+        bytes.extend_from_slice(&[n; 32]);
+
+        Cid::from_bytes(&bytes).unwrap()
+    }
+
+    fn set(ns: &[u8]) -> HashSet<Cid> {
+        ns.iter().map(|&n| cid(n)).collect()
+    }
+
+    #[test]
+    fn test_pinset_diff() {
+        let diff = PinsetDiff::new(&set(&[1, 2, 3]), &set(&[2, 3, 4, 5]));
+        let remove: HashSet<_> = diff.remove.iter().cloned().collect();
+
+        assert_eq!(diff.add, vec![cid(1)]);
+        assert_eq!(remove, set(&[4, 5]));
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn test_pinset_diff_unchanged() {
+        let diff = PinsetDiff::new(&set(&[1, 2]), &set(&[1, 2]));
+
+        assert!(diff.add.is_empty());
+        assert!(diff.remove.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn test_pinset_diff_mixed_versions() {
+        let v1 = |n| cid(n).to_base(CidBase::Base32);
+        let desired: HashSet<_> = vec![v1(1), cid(2), v1(3)].into_iter().collect();
+        let pinned: HashSet<_> = vec![cid(1), v1(2), cid(3).to_base(CidBase::Base16), cid(4)]
+            .into_iter()
+            .collect();
+        let diff = PinsetDiff::new(&desired, &pinned);
+
+        assert!(diff.add.is_empty());
+        assert_eq!(diff.remove, vec![cid(4)]);
+        assert_eq!(diff.unchanged, 3);
+    }
+
+    #[test]
+    fn test_pinset_diff_never_adds_and_removes_same_content() {
+        let desired = set(&[1, 2]);
+        let pinned: HashSet<_> = vec![cid(1).to_base(CidBase::Base32), cid(3)]
+            .into_iter()
+            .collect();
+        let diff = PinsetDiff::new(&desired, &pinned);
+        let added: HashSet<_> = diff.add.iter().map(Cid::multihash).collect();
+
+        assert_eq!(diff.add, vec![cid(2)]);
+        assert_eq!(diff.remove, vec![cid(3)]);
+        assert!(diff
+            .remove
+            .iter()
+            .all(|cid| !added.contains(&cid.multihash())));
+    }
+}
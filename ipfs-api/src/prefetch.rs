@@ -0,0 +1,118 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use bytes::Bytes;
+use futures::{try_ready, Async, Poll, Stream};
+use response::Error;
+
+/// A stream of consecutive ranges of a file, each `range_size` bytes long,
+/// that ends after the first range that comes back short.
+///
+/// The ranges are fetched by the wrapped stream (usually a `Buffered`
+/// stream of requests, so that the next few ranges are being downloaded
+/// while the current one is processed). Requests that are still in flight
+/// once the end of the file has been found are dropped.
+///
+/// Each range is a `Result`, because `Buffered` returns the first error
+/// from any request in flight, even one past the end of the file that
+/// would never be read. Errors are returned in order here instead.
+///
+pub struct Prefetch<S> {
+    ranges: S,
+    range_size: u64,
+    done: bool,
+}
+
+impl<S> Prefetch<S>
+where
+    S: Stream<Item = Result<Bytes, Error>, Error = Error>,
+{
+    pub fn new(ranges: S, range_size: u64) -> Prefetch<S> {
+        Prefetch {
+            ranges,
+            range_size,
+            done: false,
+        }
+    }
+}
+
+impl<S> Stream for Prefetch<S>
+where
+    S: Stream<Item = Result<Bytes, Error>, Error = Error>,
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        if self.done {
+            return Ok(Async::Ready(None));
+        }
+
+        match try_ready!(self.ranges.poll()) {
+            Some(Err(e)) => {
+                self.done = true;
+
+                Err(e)
+            }
+            Some(Ok(ref range)) if range.is_empty() => {
+                self.done = true;
+
+                Ok(Async::Ready(None))
+            }
+            Some(Ok(range)) => {
+                if (range.len() as u64) < self.range_size {
+                    self.done = true;
+                }
+
+                Ok(Async::Ready(Some(range)))
+            }
+            None => {
+                self.done = true;
+
+                Ok(Async::Ready(None))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Prefetch;
+    use bytes::Bytes;
+    use futures::{stream, Future, Stream};
+    use response::Error;
+
+    #[test]
+    fn test_prefetch_stops_after_short_range() {
+        let ranges = stream::iter_ok::<_, Error>(vec![
+            Ok(Bytes::from("abcd")),
+            Ok(Bytes::from("ef")),
+            Err(Error::Uncategorized(
+                "offset was past the end of file".into(),
+            )),
+        ]);
+        let body = Prefetch::new(ranges, 4).concat2().wait().unwrap();
+
+        assert_eq!(body, "abcdef");
+    }
+
+    #[test]
+    fn test_prefetch_stops_after_empty_range() {
+        let ranges = stream::iter_ok::<_, Error>(vec![
+            Ok(Bytes::from("abcd")),
+            Ok(Bytes::from("")),
+            Err(Error::Uncategorized(
+                "offset was past the end of file".into(),
+            )),
+        ]);
+        let body = Prefetch::new(ranges, 4).concat2().wait().unwrap();
+
+        assert_eq!(body, "abcd");
+    }
+}
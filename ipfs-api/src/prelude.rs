@@ -0,0 +1,29 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! The types and traits most code using the client needs, for importing
+//! with a single glob.
+//!
+//! ```no_run
+//! # extern crate ipfs_api;
+//! #
+//! use ipfs_api::prelude::*;
+//!
+//! # fn main() {
+//! let client = IpfsClient::default();
+//! let req = client.cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA").concat2();
+//! # }
+//! ```
+
+pub use addr::Multiaddr;
+pub use api::IpfsApi;
+pub use cid::{Cid, ToCid};
+pub use client::{AsyncResponse, AsyncStreamResponse, IpfsClient, IpfsClientBuilder};
+pub use futures::{Future, Sink, Stream};
+pub use peer::{PeerId, ToPeerId};
+pub use response::{AddResponse, Error, IpfsHeader, ResponseMetadata, VersionResponse};
@@ -0,0 +1,126 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use bytes::Bytes;
+use futures::{try_ready, Async, Poll, Stream};
+use response::Error;
+
+/// How much of a download has been received so far.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of bytes received.
+    pub received: u64,
+
+    /// The size of the download, if the daemon sent `X-Content-Length`.
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// The percentage of the download received, if its size is known.
+    ///
+    pub fn percent(&self) -> Option<f64> {
+        self.total.map(|total| {
+            if total == 0 {
+                100.0
+            } else {
+                self.received as f64 * 100.0 / total as f64
+            }
+        })
+    }
+}
+
+/// A stream of bytes that calls a function with the progress of the download
+/// each time a chunk is received.
+///
+pub struct ProgressStream<S, F> {
+    stream: S,
+    callback: F,
+    progress: Progress,
+}
+
+impl<S, F> ProgressStream<S, F>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+    F: FnMut(Progress),
+{
+    pub fn new(stream: S, total: Option<u64>, callback: F) -> ProgressStream<S, F> {
+        ProgressStream {
+            stream,
+            callback,
+            progress: Progress { received: 0, total },
+        }
+    }
+}
+
+impl<S, F> Stream for ProgressStream<S, F>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+    F: FnMut(Progress),
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let chunk = try_ready!(self.stream.poll());
+
+        if let Some(ref chunk) = chunk {
+            self.progress.received += chunk.len() as u64;
+
+            (self.callback)(self.progress);
+        }
+
+        Ok(Async::Ready(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Progress, ProgressStream};
+    use bytes::Bytes;
+    use futures::{stream, Future, Stream};
+    use response::Error;
+
+    #[test]
+    fn test_progress_stream() {
+        let chunks = stream::iter_ok::<_, Error>(vec![Bytes::from("abc"), Bytes::from("de")]);
+        let mut seen = Vec::new();
+
+        let body = ProgressStream::new(chunks, Some(10), |progress| seen.push(progress))
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "abcde");
+        assert_eq!(
+            seen,
+            vec![
+                Progress {
+                    received: 3,
+                    total: Some(10)
+                },
+                Progress {
+                    received: 5,
+                    total: Some(10)
+                },
+            ]
+        );
+        assert_eq!(seen[1].percent(), Some(50.0));
+    }
+
+    #[test]
+    fn test_percent_unknown_total() {
+        let progress = Progress {
+            received: 5,
+            total: None,
+        };
+
+        assert_eq!(progress.percent(), None);
+    }
+}
@@ -0,0 +1,593 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use client::{AsyncResponse, AsyncStreamResponse};
+use futures::{
+    stream::FuturesUnordered,
+    task::{self, Task},
+    try_ready, Async, AsyncSink, Future, Poll, Sink, StartSend, Stream,
+};
+use response::{Error, PubsubEvent, PubsubPubResponse, PubsubSubResponse};
+use resume;
+use std::{
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, Instant},
+};
+use tokio::timer::Delay;
+
+/// How long to wait before subscribing again the first time a subscription
+/// is lost.
+///
+pub const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The longest wait between attempts to subscribe again.
+///
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The default number of messages a `PubsubPublisher` publishes at once.
+///
+const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+enum State {
+    Subscribed(AsyncStreamResponse<PubsubSubResponse>),
+    Waiting(Delay),
+}
+
+/// A pubsub subscription that subscribes again, with exponential backoff,
+/// whenever its stream ends or fails with a transient error. Any other error
+/// (e.g. the daemon has pubsub disabled) is returned, since subscribing again
+/// would fail the same way.
+///
+pub struct Reconnect<F> {
+    subscribe: F,
+    state: State,
+    initial_backoff: Duration,
+    backoff: Duration,
+}
+
+impl<F> Reconnect<F>
+where
+    F: FnMut() -> AsyncStreamResponse<PubsubSubResponse>,
+{
+    pub fn new(mut subscribe: F, initial_backoff: Duration) -> Reconnect<F> {
+        let state = State::Subscribed(subscribe());
+
+        Reconnect {
+            subscribe,
+            state,
+            initial_backoff,
+            backoff: initial_backoff,
+        }
+    }
+}
+
+impl<F> Stream for Reconnect<F>
+where
+    F: FnMut() -> AsyncStreamResponse<PubsubSubResponse>,
+{
+    type Item = PubsubEvent;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let next = match self.state {
+                State::Subscribed(ref mut stream) => match stream.poll() {
+                    Ok(Async::Ready(Some(msg))) => {
+                        self.backoff = self.initial_backoff;
+
+                        return Ok(Async::Ready(Some(PubsubEvent::Message(msg))));
+                    }
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) if !resume::is_transient(&e) => return Err(e),
+                    Ok(Async::Ready(None)) | Err(_) => {
+                        let delay = Delay::new(Instant::now() + self.backoff);
+
+                        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+
+                        State::Waiting(delay)
+                    }
+                },
+                State::Waiting(ref mut delay) => match delay.poll() {
+                    Ok(Async::Ready(())) => {
+                        self.state = State::Subscribed((self.subscribe)());
+
+                        return Ok(Async::Ready(Some(PubsubEvent::Reconnected)));
+                    }
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => return Err(Error::Uncategorized(e.to_string())),
+                },
+            };
+
+            self.state = next;
+        }
+    }
+}
+
+/// A `Sink` that publishes each message sent to it to a pubsub topic.
+///
+/// Up to `max_in_flight` messages are published at the same time. Once that
+/// many are waiting on the daemon, the sink stops accepting messages until
+/// one of them is published. Messages published at the same time may arrive
+/// in any order, so use a limit of 1 to keep them in order.
+///
+/// ```no_run
+/// # extern crate futures;
+/// # extern crate ipfs_api;
+/// #
+/// use futures::{stream, Future, Sink};
+/// use ipfs_api::{response::Error, IpfsClient, PubsubPublisher};
+///
+/// # fn main() {
+/// let client = IpfsClient::default();
+/// let messages = stream::iter_ok::<_, Error>(vec!["a".to_string(), "b".to_string()]);
+/// let req = PubsubPublisher::new(client, "feed").send_all(messages);
+/// # }
+/// ```
+///
+pub struct PubsubPublisher<A> {
+    api: A,
+    topic: String,
+    max_in_flight: usize,
+    in_flight: FuturesUnordered<AsyncResponse<PubsubPubResponse>>,
+}
+
+impl<A> PubsubPublisher<A>
+where
+    A: IpfsApi,
+{
+    #[inline]
+    pub fn new(api: A, topic: &str) -> PubsubPublisher<A> {
+        PubsubPublisher::with_max_in_flight(api, topic, DEFAULT_MAX_IN_FLIGHT)
+    }
+
+    /// Creates a publisher that publishes at most `max_in_flight` messages at
+    /// the same time.
+    ///
+    pub fn with_max_in_flight(api: A, topic: &str, max_in_flight: usize) -> PubsubPublisher<A> {
+        PubsubPublisher {
+            api,
+            topic: topic.to_string(),
+            max_in_flight: max_in_flight.max(1),
+            in_flight: FuturesUnordered::new(),
+        }
+    }
+
+    /// Waits for messages that have been published, until there are none
+    /// left or the daemon has yet to respond to one.
+    ///
+    fn poll_in_flight(&mut self) -> Poll<(), Error> {
+        while try_ready!(self.in_flight.poll()).is_some() {}
+
+        Ok(Async::Ready(()))
+    }
+}
+
+impl<A> Sink for PubsubPublisher<A>
+where
+    A: IpfsApi,
+{
+    type SinkItem = String;
+
+    type SinkError = Error;
+
+    fn start_send(&mut self, item: Self::SinkItem) -> StartSend<Self::SinkItem, Self::SinkError> {
+        if self.in_flight.len() >= self.max_in_flight {
+            self.poll_in_flight()?;
+
+            if self.in_flight.len() >= self.max_in_flight {
+                return Ok(AsyncSink::NotReady(item));
+            }
+        }
+
+        self.in_flight.push(self.api.pubsub_pub(&self.topic, &item));
+
+        Ok(AsyncSink::Ready)
+    }
+
+    #[inline]
+    fn poll_complete(&mut self) -> Poll<(), Self::SinkError> {
+        self.poll_in_flight()
+    }
+}
+
+/// The subscriptions of a `PubsubRouter`, shared with its `PubsubMessages`
+/// and each `PubsubSubscription`.
+///
+#[derive(Default)]
+struct Subscriptions {
+    streams: Vec<(String, AsyncStreamResponse<PubsubSubResponse>)>,
+    next: usize,
+    routers: usize,
+    task: Option<Task>,
+}
+
+impl Subscriptions {
+    /// Wakes up `PubsubMessages`, so that it polls a new subscription, or
+    /// finishes once it has nothing left to wait for.
+    ///
+    fn notify(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.notify();
+        }
+    }
+}
+
+/// Subscribes to any number of pubsub topics, and returns the messages
+/// published to all of them from a single `PubsubMessages` stream.
+///
+/// The router can be cloned, and used to subscribe to more topics while the
+/// messages are being read. Each topic is subscribed to once, however many
+/// times `subscribe` is called with it.
+///
+/// ```no_run
+/// # extern crate futures;
+/// # extern crate ipfs_api;
+/// #
+/// use futures::Stream;
+/// use ipfs_api::{IpfsClient, PubsubRouter};
+///
+/// # fn main() {
+/// let client = IpfsClient::default();
+/// let (router, messages) = PubsubRouter::new(client);
+///
+/// router.subscribe("chat", false);
+///
+/// let presence = router.subscribe("presence", false);
+///
+/// presence.unsubscribe();
+///
+/// let req = messages.for_each(|(topic, msg)| {
+///     println!("{}: {:?}", topic, msg.data);
+///
+///     Ok(())
+/// });
+/// # }
+/// ```
+///
+pub struct PubsubRouter<A> {
+    api: A,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+}
+
+impl<A> PubsubRouter<A>
+where
+    A: IpfsApi,
+{
+    // Responses from the actix client are not `Send`, so with it, neither
+    // are the subscriptions.
+    #[cfg_attr(feature = "actix", allow(clippy::arc_with_non_send_sync))]
+    pub fn new(api: A) -> (PubsubRouter<A>, PubsubMessages) {
+        let subscriptions = Arc::new(Mutex::new(Subscriptions {
+            routers: 1,
+            ..Subscriptions::default()
+        }));
+        let router = PubsubRouter {
+            api,
+            subscriptions: subscriptions.clone(),
+        };
+
+        (router, PubsubMessages { subscriptions })
+    }
+
+    /// Subscribes to `topic`, unless the router is already subscribed to it,
+    /// and returns a handle to unsubscribe with.
+    ///
+    pub fn subscribe(&self, topic: &str, discover: bool) -> PubsubSubscription {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+
+        if !subscriptions.streams.iter().any(|(t, _)| t == topic) {
+            let stream = self.api.pubsub_sub(topic, discover);
+
+            subscriptions.streams.push((topic.to_string(), stream));
+            subscriptions.notify();
+        }
+
+        PubsubSubscription {
+            topic: topic.to_string(),
+            subscriptions: Arc::downgrade(&self.subscriptions),
+        }
+    }
+
+    /// The topics the router is subscribed to.
+    ///
+    pub fn topics(&self) -> Vec<String> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+
+        subscriptions
+            .streams
+            .iter()
+            .map(|(topic, _)| topic.clone())
+            .collect()
+    }
+}
+
+impl<A> Clone for PubsubRouter<A>
+where
+    A: Clone,
+{
+    fn clone(&self) -> PubsubRouter<A> {
+        self.subscriptions.lock().unwrap().routers += 1;
+
+        PubsubRouter {
+            api: self.api.clone(),
+            subscriptions: self.subscriptions.clone(),
+        }
+    }
+}
+
+impl<A> Drop for PubsubRouter<A> {
+    fn drop(&mut self) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+
+        subscriptions.routers -= 1;
+        subscriptions.notify();
+    }
+}
+
+/// A handle to one of the topics a `PubsubRouter` is subscribed to.
+///
+/// Dropping the handle does not unsubscribe from the topic.
+///
+#[derive(Clone)]
+pub struct PubsubSubscription {
+    topic: String,
+    subscriptions: Weak<Mutex<Subscriptions>>,
+}
+
+impl PubsubSubscription {
+    /// The topic subscribed to.
+    ///
+    #[inline]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Unsubscribes from the topic, for every handle to it. Does nothing if
+    /// the router is no longer subscribed to the topic.
+    ///
+    pub fn unsubscribe(self) {
+        if let Some(subscriptions) = self.subscriptions.upgrade() {
+            let mut subscriptions = subscriptions.lock().unwrap();
+
+            subscriptions
+                .streams
+                .retain(|(topic, _)| *topic != self.topic);
+            subscriptions.notify();
+        }
+    }
+}
+
+/// The messages published to every topic a `PubsubRouter` is subscribed to,
+/// with the topic each was published to.
+///
+/// Subscriptions are polled in turn, so a busy topic does not hold up the
+/// others. A subscription that fails is dropped, and its error returned; the
+/// other topics can still be read by polling the stream again. The stream
+/// finishes once every `PubsubRouter` has been dropped and no subscriptions
+/// are left.
+///
+pub struct PubsubMessages {
+    subscriptions: Arc<Mutex<Subscriptions>>,
+}
+
+impl Stream for PubsubMessages {
+    type Item = (String, PubsubSubResponse);
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let mut pending = 0;
+
+        while pending < subscriptions.streams.len() {
+            let i = subscriptions.next % subscriptions.streams.len();
+
+            match subscriptions.streams[i].1.poll() {
+                Ok(Async::Ready(Some(msg))) => {
+                    subscriptions.next = i + 1;
+
+                    let topic = subscriptions.streams[i].0.clone();
+
+                    return Ok(Async::Ready(Some((topic, msg))));
+                }
+                Ok(Async::NotReady) => {
+                    subscriptions.next = i + 1;
+                    pending += 1;
+                }
+                Ok(Async::Ready(None)) => {
+                    drop(subscriptions.streams.remove(i));
+                    subscriptions.next = i;
+                }
+                Err(e) => {
+                    drop(subscriptions.streams.remove(i));
+                    subscriptions.next = i;
+
+                    return Err(e);
+                }
+            }
+        }
+
+        if subscriptions.streams.is_empty() && subscriptions.routers == 0 {
+            return Ok(Async::Ready(None));
+        }
+
+        subscriptions.task = Some(task::current());
+
+        Ok(Async::NotReady)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reconnect;
+    use client::AsyncStreamResponse;
+    use futures::{stream, Stream};
+    use response::{Error, PubsubEvent, PubsubSubResponse};
+    use std::time::Duration;
+    use tokio::runtime::current_thread::Runtime;
+
+    fn message(data: &str) -> PubsubSubResponse {
+        PubsubSubResponse {
+            from: None,
+            data: Some(data.into()),
+            seqno: None,
+            topic_ids: None,
+            unrecognized: None,
+        }
+    }
+
+    #[test]
+    fn test_reconnect() {
+        let mut attempt = 0;
+        let subscribe = move || -> AsyncStreamResponse<PubsubSubResponse> {
+            attempt += 1;
+
+            if attempt == 1 {
+                Box::new(stream::iter_result(vec![
+                    Ok(message("a")),
+                    Err(Error::Timeout {
+                        uri: "http://localhost:5001/api/v0".into(),
+                    }),
+                ]))
+            } else {
+                Box::new(stream::iter_ok(vec![message("b")]))
+            }
+        };
+
+        let events = Reconnect::new(subscribe, Duration::from_millis(1))
+            .take(3)
+            .collect();
+        let events = Runtime::new().unwrap().block_on(events).unwrap();
+
+        match events.as_slice() {
+            [PubsubEvent::Message(a), PubsubEvent::Reconnected, PubsubEvent::Message(b)] => {
+                assert_eq!(a.data.as_ref().unwrap(), "a");
+                assert_eq!(b.data.as_ref().unwrap(), "b");
+            }
+            _ => panic!("unexpected events: {:?}", events),
+        }
+    }
+
+    #[test]
+    fn test_reconnect_returns_permanent_errors() {
+        let subscribe = || -> AsyncStreamResponse<PubsubSubResponse> {
+            Box::new(stream::iter_result(vec![
+                Ok(message("a")),
+                Err(Error::Uncategorized("pubsub not enabled".into())),
+            ]))
+        };
+
+        let events = Reconnect::new(subscribe, Duration::from_millis(1)).collect();
+
+        match Runtime::new().unwrap().block_on(events) {
+            Err(Error::Uncategorized(msg)) => assert_eq!(msg, "pubsub not enabled"),
+            res => panic!("expected the subscription to fail, got {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_publisher() {
+        use super::PubsubPublisher;
+        use futures::{Future, Sink};
+        use mock::MockIpfsClient;
+
+        let mock = MockIpfsClient::new();
+        let messages = stream::iter_ok::<_, Error>(vec!["a".to_string(), "b".to_string()]);
+
+        mock.push_response("pubsub_pub", Ok(()));
+        mock.push_response("pubsub_pub", Ok(()));
+
+        let _ = PubsubPublisher::with_max_in_flight(mock.clone(), "feed", 1)
+            .send_all(messages)
+            .wait()
+            .unwrap();
+
+        let calls = mock.calls();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].args, vec!["\"feed\"", "\"b\""]);
+    }
+
+    #[cfg(feature = "test-util")]
+    fn subscription(mock: &::mock::MockIpfsClient, data: &[&str]) {
+        let items = data.iter().map(|data| Ok(message(data))).collect();
+
+        mock.push_stream_response("pubsub_sub", items);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_router() {
+        use super::PubsubRouter;
+        use futures::Future;
+        use mock::MockIpfsClient;
+
+        let mock = MockIpfsClient::new();
+
+        subscription(&mock, &["a1", "a2"]);
+        subscription(&mock, &["b1"]);
+
+        let (router, messages) = PubsubRouter::new(mock.clone());
+
+        router.subscribe("a", false);
+        router.subscribe("b", true);
+        router.subscribe("a", false);
+
+        assert_eq!(router.topics(), vec!["a", "b"]);
+
+        drop(router);
+
+        let messages: Vec<_> = messages
+            .collect()
+            .wait()
+            .unwrap()
+            .into_iter()
+            .map(|(topic, msg)| (topic, msg.data.unwrap()))
+            .collect();
+        let expected = [("a", "a1"), ("b", "b1"), ("a", "a2")];
+
+        assert_eq!(
+            messages,
+            expected
+                .iter()
+                .map(|&(topic, data)| (topic.to_string(), data.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_router_unsubscribe() {
+        use super::PubsubRouter;
+        use futures::Future;
+        use mock::MockIpfsClient;
+
+        let mock = MockIpfsClient::new();
+
+        subscription(&mock, &["a1"]);
+        subscription(&mock, &["b1"]);
+
+        let (router, messages) = PubsubRouter::new(mock);
+
+        router.subscribe("a", false);
+        router.subscribe("b", false).unsubscribe();
+
+        assert_eq!(router.topics(), vec!["a"]);
+
+        drop(router);
+
+        let messages = messages.collect().wait().unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, "a");
+    }
+}
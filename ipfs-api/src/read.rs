@@ -7,10 +7,22 @@
 //
 
 use bytes::{Bytes, BytesMut};
-use futures::{Async, Stream};
+use futures::{
+    executor::{self, Spawn},
+    try_ready, Async, Future, Poll, Stream,
+};
 use header::X_STREAM_ERROR;
+#[cfg(feature = "hyper")]
+use http::HeaderMap;
+#[cfg(feature = "hyper")]
+use hyper::{self, body::Payload};
+#[cfg(feature = "hyper")]
+use metrics::RequestMeter;
 use response::Error;
+#[cfg(feature = "hyper")]
+use response::ReceivedTrailers;
 use serde::Deserialize;
+use serde_ignored;
 use serde_json;
 use std::{
     cmp,
@@ -20,6 +32,312 @@ use std::{
 use tokio_codec::Decoder;
 use tokio_io::AsyncRead;
 
+/// Deserializes a json response. In strict mode, fields in the response that
+/// `T` doesn't have are an error instead of being ignored.
+///
+pub fn from_json_slice<T>(slice: &[u8], strict: bool) -> Result<T, Error>
+where
+    for<'de> T: Deserialize<'de>,
+{
+    if !strict {
+        return serde_json::from_slice(slice).map_err(From::from);
+    }
+
+    let mut unknown = Vec::new();
+    let mut de = serde_json::Deserializer::from_slice(slice);
+    let value = serde_ignored::deserialize(&mut de, |path| unknown.push(path.to_string()))?;
+
+    de.end()?;
+
+    if unknown.is_empty() {
+        Ok(value)
+    } else {
+        Err(Error::UnknownFields(unknown.join(", ")))
+    }
+}
+
+/// Concatenates a stream of chunks into a single buffer. A body that
+/// arrives in a single chunk is returned without being copied.
+///
+pub fn concat_bytes<S, E>(stream: S) -> impl Future<Item = Bytes, Error = E>
+where
+    S: Stream<Item = Bytes, Error = E>,
+{
+    stream.fold(Bytes::new(), |body, chunk| {
+        if body.is_empty() {
+            return Ok(chunk);
+        }
+
+        let mut body = BytesMut::from(body);
+
+        body.extend_from_slice(&chunk);
+
+        Ok(body.freeze())
+    })
+}
+
+/// A stream of chunks that fails with `Error::ResponseTooLarge` once more
+/// than `limit` bytes have been received. Without a limit, chunks are passed
+/// through as they are.
+///
+pub struct LimitedStream<S> {
+    stream: S,
+    limit: Option<usize>,
+    received: usize,
+}
+
+impl<S> LimitedStream<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    #[inline]
+    pub fn new(stream: S, limit: Option<usize>) -> LimitedStream<S> {
+        LimitedStream {
+            stream,
+            limit,
+            received: 0,
+        }
+    }
+}
+
+impl<S> Stream for LimitedStream<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let chunk = try_ready!(self.stream.poll());
+
+        if let (Some(limit), Some(chunk)) = (self.limit, chunk.as_ref()) {
+            self.received += chunk.len();
+
+            if self.received > limit {
+                return Err(Error::ResponseTooLarge(limit));
+            }
+        }
+
+        Ok(Async::Ready(chunk))
+    }
+}
+
+/// The body of a hyper response, as a stream of bytes that fails with
+/// `Error::StreamError` if the response ends with an `X-Stream-Error`
+/// trailer.
+///
+/// Every trailer received is saved to the `ReceivedTrailers` in the
+/// extensions of the response, if it has one, so other trailers reach the
+/// `ResponseMetadata` of the response. If the extensions hold a
+/// `RequestMeter`, the bytes of the body are counted towards it.
+///
+#[cfg(feature = "hyper")]
+pub struct TrailerBody {
+    body: hyper::Body,
+    received: Option<ReceivedTrailers>,
+    meter: Option<RequestMeter>,
+    done: bool,
+}
+
+#[cfg(feature = "hyper")]
+impl TrailerBody {
+    #[inline]
+    pub fn new(body: hyper::Body) -> TrailerBody {
+        TrailerBody {
+            body,
+            received: None,
+            meter: None,
+            done: false,
+        }
+    }
+
+    #[inline]
+    pub fn from_response(res: hyper::Response<hyper::Body>) -> TrailerBody {
+        let received = res.extensions().get::<ReceivedTrailers>().cloned();
+        let meter = res.extensions().get::<RequestMeter>().cloned();
+
+        TrailerBody {
+            received,
+            meter,
+            ..TrailerBody::new(res.into_body())
+        }
+    }
+
+    fn poll_body(&mut self) -> Poll<Option<Bytes>, Error> {
+        if !self.done {
+            match try_ready!(self.body.poll_data()) {
+                Some(chunk) => return Ok(Async::Ready(Some(chunk.into_bytes()))),
+                None => self.done = true,
+            }
+        }
+
+        let trailers = try_ready!(self.body.poll_trailers());
+
+        self.finish(trailers).map(|_| Async::Ready(None))
+    }
+
+    /// Saves the trailers that ended the body, returning the error in the
+    /// `X-Stream-Error` trailer if there is one.
+    ///
+    fn finish(&self, trailers: Option<HeaderMap>) -> Result<(), Error> {
+        let trailers = match trailers {
+            Some(trailers) => trailers,
+            None => return Ok(()),
+        };
+
+        if let Some(ref received) = self.received {
+            received.set(trailers.clone());
+        }
+
+        match trailers.get(X_STREAM_ERROR) {
+            Some(err) => Err(Error::StreamError(
+                String::from_utf8_lossy(err.as_bytes()).into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl Stream for TrailerBody {
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let res = self.poll_body();
+
+        if let Some(ref meter) = self.meter {
+            match res {
+                Ok(Async::Ready(Some(ref chunk))) => meter.bytes(chunk.len()),
+                Ok(Async::Ready(None)) => meter.finish(),
+                Ok(Async::NotReady) => (),
+                Err(_) => {
+                    meter.error();
+                    meter.finish();
+                }
+            }
+        }
+
+        res
+    }
+}
+
+/// A stream of bytes that fails with `Error::StreamError` if it ends before
+/// `expected` bytes were received.
+///
+/// The daemon reports an error that happens after it started sending a body
+/// in the `X-Stream-Error` trailer, which HTTP/1.1 clients usually drop, so
+/// a body that ends early is the only sign of the error that remains.
+///
+pub struct ExpectedLength<S> {
+    stream: S,
+    expected: Option<u64>,
+    received: u64,
+}
+
+impl<S> ExpectedLength<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    #[inline]
+    pub fn new(stream: S, expected: Option<u64>) -> ExpectedLength<S> {
+        ExpectedLength {
+            stream,
+            expected,
+            received: 0,
+        }
+    }
+}
+
+impl<S> Stream for ExpectedLength<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        match try_ready!(self.stream.poll()) {
+            Some(chunk) => {
+                self.received += chunk.len() as u64;
+
+                Ok(Async::Ready(Some(chunk)))
+            }
+            None => match self.expected {
+                Some(expected) if self.received < expected => Err(Error::StreamError(format!(
+                    "response ended after {} of {} bytes",
+                    self.received, expected
+                ))),
+                _ => Ok(Async::Ready(None)),
+            },
+        }
+    }
+}
+
+/// Decodes a stream of chunks with a `Decoder`.
+///
+/// Unlike a `FramedRead` over a `StreamReader`, which copies every chunk
+/// into the decoder's buffer, each chunk is handed to the decoder as it is,
+/// and only a frame split between chunks is copied.
+///
+pub struct ChunkDecoder<S, D> {
+    stream: S,
+    decoder: D,
+    buf: BytesMut,
+    eof: bool,
+}
+
+impl<S, D> ChunkDecoder<S, D>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+    D: Decoder<Error = Error>,
+{
+    #[inline]
+    pub fn new(stream: S, decoder: D) -> ChunkDecoder<S, D> {
+        ChunkDecoder {
+            stream,
+            decoder,
+            buf: BytesMut::new(),
+            eof: false,
+        }
+    }
+}
+
+impl<S, D> Stream for ChunkDecoder<S, D>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+    D: Decoder<Error = Error>,
+{
+    type Item = D::Item;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if self.eof {
+                return Ok(Async::Ready(self.decoder.decode_eof(&mut self.buf)?));
+            }
+
+            if let Some(item) = self.decoder.decode(&mut self.buf)? {
+                return Ok(Async::Ready(Some(item)));
+            }
+
+            match try_ready!(self.stream.poll()) {
+                Some(chunk) if self.buf.is_empty() => self.buf = BytesMut::from(chunk),
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => self.eof = true,
+            }
+        }
+    }
+}
+
+/// The longest line a `JsonLineDecoder` buffers by default.
+///
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 64 * 1024 * 1024;
+
 /// A decoder for a response where each line is a full json object.
 ///
 pub struct JsonLineDecoder<T> {
@@ -28,17 +346,48 @@ pub struct JsonLineDecoder<T> {
     ///
     parse_stream_error: bool,
 
+    /// Set to true if fields that `T` doesn't have should be an error.
+    ///
+    strict: bool,
+
+    /// The longest line that is buffered before it is rejected.
+    ///
+    max_line_length: usize,
+
+    /// How far into the buffer has already been searched for a new line, so
+    /// a line that arrives over many chunks is only searched once.
+    ///
+    next_index: usize,
+
+    /// Set after a line was too long, until the end of that line has been
+    /// skipped.
+    ///
+    discarding: bool,
+
     ty: PhantomData<T>,
 }
 
 impl<T> JsonLineDecoder<T> {
     #[inline]
-    pub fn new(parse_stream_error: bool) -> JsonLineDecoder<T> {
+    pub fn new(parse_stream_error: bool, strict: bool) -> JsonLineDecoder<T> {
         JsonLineDecoder {
             parse_stream_error,
+            strict,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            next_index: 0,
+            discarding: false,
             ty: PhantomData,
         }
     }
+
+    /// Sets the longest line that is buffered. A longer line is an error
+    /// (`Error::LineTooLong`), and is skipped.
+    ///
+    #[inline]
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> JsonLineDecoder<T> {
+        self.max_line_length = max_line_length;
+        self
+    }
 }
 
 impl<T> Decoder for JsonLineDecoder<T>
@@ -53,38 +402,78 @@ where
     /// and parse the first slice.
     ///
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        let nl_index = src.iter().position(|b| *b == b'\n');
+        loop {
+            let nl_index = src[self.next_index..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|offset| self.next_index + offset);
 
-        if let Some(pos) = nl_index {
-            let slice = src.split_to(pos + 1);
-            let slice = &slice[..slice.len() - 1];
-
-            match serde_json::from_slice(slice) {
-                Ok(json) => Ok(json),
-                // If a JSON object couldn't be parsed from the response, it is possible
-                // that a stream error trailing header was returned. If the JSON decoder
-                // was configured to parse these kinds of error, it should try. If a header
-                // couldn't be parsed, it will return the original error.
-                //
-                Err(e) => {
-                    if self.parse_stream_error {
-                        match slice.iter().position(|&x| x == b':') {
-                            Some(colon) if &slice[..colon] == X_STREAM_ERROR.as_bytes() => {
-                                let e = Error::StreamError(
-                                    String::from_utf8_lossy(&slice[colon + 2..]).into(),
-                                );
-
-                                Err(e)
-                            }
-                            _ => Err(e.into()),
+            match nl_index {
+                Some(pos) if self.discarding => {
+                    src.split_to(pos + 1);
+                    self.next_index = 0;
+                    self.discarding = false;
+                }
+                Some(pos) => {
+                    self.next_index = 0;
+
+                    return self.decode_line(&src.split_to(pos + 1));
+                }
+                None if self.discarding => {
+                    src.clear();
+                    self.next_index = 0;
+
+                    return Ok(None);
+                }
+                None if src.len() > self.max_line_length => {
+                    src.clear();
+                    self.next_index = 0;
+                    self.discarding = true;
+
+                    return Err(Error::LineTooLong(self.max_line_length));
+                }
+                None => {
+                    self.next_index = src.len();
+
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+impl<T> JsonLineDecoder<T>
+where
+    for<'de> T: Deserialize<'de>,
+{
+    /// Parses a line, including its new line character.
+    ///
+    fn decode_line(&self, line: &[u8]) -> Result<Option<T>, Error> {
+        let slice = &line[..line.len() - 1];
+
+        match from_json_slice(slice, self.strict) {
+            Ok(json) => Ok(json),
+            // If a JSON object couldn't be parsed from the response, it is possible
+            // that a stream error trailing header was returned. If the JSON decoder
+            // was configured to parse these kinds of error, it should try. If a header
+            // couldn't be parsed, it will return the original error.
+            //
+            Err(e) => {
+                if self.parse_stream_error {
+                    match slice.iter().position(|&x| x == b':') {
+                        Some(colon) if &slice[..colon] == X_STREAM_ERROR.as_bytes() => {
+                            let e = Error::StreamError(
+                                String::from_utf8_lossy(&slice[colon + 2..]).into(),
+                            );
+
+                            Err(e)
                         }
-                    } else {
-                        Err(e.into())
+                        _ => Err(e),
                     }
+                } else {
+                    Err(e)
                 }
             }
-        } else {
-            Ok(None)
         }
     }
 }
@@ -130,6 +519,26 @@ enum ReadState {
 
 /// Reads from a stream of chunks asynchronously.
 ///
+/// Wraps the byte streams returned by `cat`, `get`, `block_get` and
+/// `files_read` as an `AsyncRead`, so they can be used with `tokio_io`
+/// utilities and decoders. Reading returns `WouldBlock` when the next chunk
+/// hasn't arrived yet, so it has to be done from inside of a task.
+///
+/// ```no_run
+/// # extern crate futures;
+/// # extern crate ipfs_api;
+/// # extern crate tokio;
+/// #
+/// use futures::Future;
+/// use ipfs_api::{IpfsClient, StreamReader};
+///
+/// # fn main() {
+/// let client = IpfsClient::default();
+/// let reader = StreamReader::new(client.cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json"));
+/// let req = tokio::io::read_to_end(reader, Vec::new()).map(|(_, data)| data.len());
+/// # }
+/// ```
+///
 pub struct StreamReader<S> {
     stream: S,
     state: ReadState,
@@ -203,3 +612,283 @@ where
 }
 
 impl<S> AsyncRead for StreamReader<S> where S: Stream<Item = Bytes, Error = Error> {}
+
+/// Reads from a stream of chunks, blocking the current thread until each
+/// chunk arrives.
+///
+/// This lets the output of `cat`, `get`, `block_get` and `files_read` be
+/// passed to code that expects a `std::io::Read`. The client's requests
+/// still need a runtime to make progress, so this must not be used from one
+/// of the runtime's own threads.
+///
+/// ```no_run
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::{BlockingStreamReader, IpfsClient};
+/// use std::io::{self, Read};
+///
+/// # fn main() {
+/// let client = IpfsClient::default();
+/// let mut reader = BlockingStreamReader::new(client.cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/file.json"));
+/// let mut data = String::new();
+/// reader.read_to_string(&mut data).unwrap();
+/// # }
+/// ```
+///
+pub struct BlockingStreamReader<S> {
+    stream: Spawn<S>,
+    chunk: Bytes,
+}
+
+impl<S> BlockingStreamReader<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    #[inline]
+    pub fn new(stream: S) -> BlockingStreamReader<S> {
+        BlockingStreamReader {
+            stream: executor::spawn(stream),
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl<S> Read for BlockingStreamReader<S>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.stream.wait_stream() {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(Error::Io(e))) => return Err(e),
+                Some(Err(e)) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+                None => return Ok(0),
+            }
+        }
+
+        let len = cmp::min(buf.len(), self.chunk.len());
+
+        buf[..len].copy_from_slice(&self.chunk.split_to(len));
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        from_json_slice, BlockingStreamReader, ChunkDecoder, ExpectedLength, JsonLineDecoder,
+        LineDecoder,
+    };
+    use bytes::{Bytes, BytesMut};
+    use futures::{stream, Future, Stream};
+    use response::{Error, VersionResponse};
+    use std::io::Read;
+    use tokio_codec::Decoder;
+
+    #[test]
+    fn test_strict_json() {
+        let raw = br#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11","Extra":1}"#;
+
+        assert!(from_json_slice::<VersionResponse>(raw, false).is_ok());
+
+        match from_json_slice::<VersionResponse>(raw, true) {
+            Err(Error::UnknownFields(fields)) => assert_eq!(fields, "Extra"),
+            _ => panic!("expected unknown fields"),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_concat_bytes() {
+        use super::concat_bytes;
+
+        let chunk = Bytes::from(vec![b'a'; 1024]);
+        let ptr = chunk.as_ptr();
+        let body = concat_bytes(stream::iter_ok::<_, Error>(vec![chunk]))
+            .wait()
+            .unwrap();
+
+        assert_eq!(body.as_ptr(), ptr);
+
+        let chunks = vec![Bytes::from("Hello "), Bytes::from("World!")];
+        let body = concat_bytes(stream::iter_ok::<_, Error>(chunks))
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "Hello World!");
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_limited_stream() {
+        use super::{concat_bytes, LimitedStream};
+
+        let chunks = vec![Bytes::from("abc"), Bytes::from("def")];
+
+        let body = concat_bytes(LimitedStream::new(stream::iter_ok(chunks.clone()), Some(6)))
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "abcdef");
+
+        match concat_bytes(LimitedStream::new(stream::iter_ok(chunks), Some(5))).wait() {
+            Err(Error::ResponseTooLarge(5)) => (),
+            res => panic!("expected ResponseTooLarge, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_expected_length() {
+        let chunks = vec![Bytes::from("abc"), Bytes::from("def")];
+
+        let body = ExpectedLength::new(stream::iter_ok(chunks.clone()), Some(6))
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "abcdef");
+
+        match ExpectedLength::new(stream::iter_ok(chunks), Some(10))
+            .concat2()
+            .wait()
+        {
+            Err(Error::StreamError(msg)) => assert_eq!(msg, "response ended after 6 of 10 bytes"),
+            res => panic!("expected StreamError, got {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_trailer_body() {
+        use super::TrailerBody;
+        use hyper;
+
+        let body = TrailerBody::new(hyper::Body::from("abc"))
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, "abc");
+    }
+
+    #[cfg(feature = "hyper")]
+    #[test]
+    fn test_trailer_body_trailers() {
+        use super::TrailerBody;
+        use http::{HeaderMap, HeaderValue};
+        use hyper;
+        use response::ReceivedTrailers;
+
+        let received = ReceivedTrailers::default();
+        let mut res = hyper::Response::new(hyper::Body::from("abc"));
+
+        res.extensions_mut().insert(received.clone());
+
+        let body = TrailerBody::from_response(res);
+        let mut trailers = HeaderMap::new();
+
+        assert!(body.finish(None).is_ok());
+        assert!(received.get().is_none());
+
+        trailers.insert("X-Foo", HeaderValue::from_static("bar"));
+        assert!(body.finish(Some(trailers.clone())).is_ok());
+        assert_eq!(received.get().unwrap()["X-Foo"], "bar");
+
+        trailers.insert(
+            "X-Stream-Error",
+            HeaderValue::from_static("context canceled"),
+        );
+        match body.finish(Some(trailers)) {
+            Err(Error::StreamError(ref err)) if err == "context canceled" => (),
+            res => panic!("expected StreamError, got {:?}", res),
+        }
+        assert!(received.get().unwrap().contains_key("X-Stream-Error"));
+    }
+
+    #[test]
+    fn test_chunk_decoder() {
+        let chunks = vec![
+            Bytes::from("a\nb"),
+            Bytes::from("c\nd\n"),
+            Bytes::from("e\n"),
+        ];
+        let lines = ChunkDecoder::new(stream::iter_ok(chunks), LineDecoder)
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(lines, vec!["a", "bc", "d", "e"]);
+
+        let chunks = vec![Bytes::from("a\nb")];
+        let res = ChunkDecoder::new(stream::iter_ok(chunks), LineDecoder)
+            .collect()
+            .wait();
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_json_line_decoder_long_line() {
+        let version = "a".repeat(3 * 1024 * 1024);
+        let line = format!(
+            "{{\"Version\":\"{}\",\"Commit\":\"\",\"Repo\":\"7\",\"System\":\"amd64/linux\",\"Golang\":\"go1.11\"}}\n",
+            version
+        );
+        let body = Bytes::from(format!("{}{}", line, line));
+        let chunks: Vec<Bytes> = body.chunks(8 * 1024).map(Bytes::from).collect();
+
+        let decoder = JsonLineDecoder::<VersionResponse>::new(false, false);
+        let versions = ChunkDecoder::new(stream::iter_ok(chunks), decoder)
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].version, version);
+    }
+
+    #[test]
+    fn test_json_line_decoder_max_line_length() {
+        let mut decoder =
+            JsonLineDecoder::<VersionResponse>::new(false, false).with_max_line_length(128);
+        let mut buf = BytesMut::new();
+
+        buf.extend_from_slice(&[b'a'; 100]);
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(&[b'a'; 100]);
+        match decoder.decode(&mut buf) {
+            Err(Error::LineTooLong(128)) => (),
+            res => panic!("expected LineTooLong, got {:?}", res.map(|_| ())),
+        }
+
+        buf.extend_from_slice(b"aaaa\n");
+        buf.extend_from_slice(
+            br#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11"}"#,
+        );
+        assert!(decoder.decode(&mut buf).unwrap().is_none());
+
+        buf.extend_from_slice(b"\n");
+        assert_eq!(decoder.decode(&mut buf).unwrap().unwrap().version, "0.4.18");
+    }
+
+    #[test]
+    fn test_blocking_stream_reader() {
+        let chunks = vec![Ok(Bytes::from("Hello ")), Ok(Bytes::from("World!"))];
+        let mut reader = BlockingStreamReader::new(stream::iter_result(chunks));
+        let mut data = String::new();
+
+        reader.read_to_string(&mut data).unwrap();
+        assert_eq!(data, "Hello World!");
+
+        let chunks: Vec<Result<Bytes, Error>> = vec![
+            Ok(Bytes::from("Hello")),
+            Err(Error::Uncategorized("broken".into())),
+        ];
+        let mut reader = BlockingStreamReader::new(stream::iter_result(chunks));
+
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    }
+}
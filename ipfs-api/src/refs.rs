@@ -0,0 +1,183 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use cid::Cid;
+use client::AsyncStreamResponse;
+use futures::{try_ready, Async, Poll, Stream};
+use path::IpfsPath;
+use response::{Error, RefsResponse};
+use std::{
+    collections::{HashSet, VecDeque},
+    mem,
+};
+
+/// Lists the graph under a path one level at a time, with a `refs` request
+/// limited to a depth of 1 for each node of the level.
+///
+/// Each CID is returned once, in order of its depth, however many nodes link
+/// to it. No single request lists more than the direct links of one node, so
+/// graphs too large for one recursive `refs` request can be listed.
+///
+pub struct RefsWalk<A> {
+    api: A,
+    max_depth: Option<usize>,
+    depth: usize,
+    level: VecDeque<IpfsPath>,
+    next: Vec<IpfsPath>,
+    seen: HashSet<Cid>,
+    current: Option<AsyncStreamResponse<RefsResponse>>,
+}
+
+impl<A> RefsWalk<A>
+where
+    A: IpfsApi,
+{
+    pub fn new(api: A, path: IpfsPath, max_depth: Option<usize>) -> RefsWalk<A> {
+        let mut level = VecDeque::new();
+
+        if max_depth != Some(0) {
+            level.push_back(path);
+        }
+
+        RefsWalk {
+            api,
+            max_depth,
+            depth: 0,
+            level,
+            next: Vec::new(),
+            seen: HashSet::new(),
+            current: None,
+        }
+    }
+}
+
+impl<A> Stream for RefsWalk<A>
+where
+    A: IpfsApi,
+{
+    type Item = Cid;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some(ref mut refs) = self.current {
+                match try_ready!(refs.poll()) {
+                    Some(res) => {
+                        if !res.err.is_empty() {
+                            return Err(Error::Uncategorized(res.err));
+                        }
+
+                        let cid: Cid = res.reference.parse()?;
+
+                        if self.seen.insert(cid.clone()) {
+                            self.next.push(cid.clone().into());
+
+                            return Ok(Async::Ready(Some(cid)));
+                        }
+
+                        continue;
+                    }
+                    None => self.current = None,
+                }
+            }
+
+            match self.level.pop_front() {
+                Some(path) => self.current = Some(self.api.refs(path, Some(1))),
+                None => {
+                    self.depth += 1;
+
+                    if self.next.is_empty()
+                        || self
+                            .max_depth
+                            .is_some_and(|max_depth| self.depth >= max_depth)
+                    {
+                        return Ok(Async::Ready(None));
+                    }
+
+                    self.level = mem::take(&mut self.next).into();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::RefsWalk;
+    use cid::Cid;
+    use futures::{Future, Stream};
+    use mock::MockIpfsClient;
+    use response::RefsResponse;
+
+    /// A CIDv0 whose digest is `n` repeated.
+    ///
+    fn cid(n: u8) -> Cid {
+        let mut bytes = vec![0x12, 0x20];
+
+        bytes.extend_from_slice(&[n; 32]);
+
+        Cid::from_bytes(&bytes).unwrap()
+    }
+
+    fn refs(mock: &MockIpfsClient, children: &[u8]) {
+        let items = children
+            .iter()
+            .map(|&child| {
+                Ok(RefsResponse {
+                    reference: cid(child).to_string(),
+                    err: String::new(),
+                })
+            })
+            .collect();
+
+        mock.push_stream_response("refs", items);
+    }
+
+    #[test]
+    fn test_refs_walk_by_level() {
+        let mock = MockIpfsClient::new();
+
+        // 1 -> 2, 3; 2 -> 3, 4; 3 -> 4; 4 -> 5
+        refs(&mock, &[2, 3]);
+        refs(&mock, &[3, 4]);
+        refs(&mock, &[4]);
+        refs(&mock, &[5]);
+        refs(&mock, &[]);
+
+        let cids = RefsWalk::new(mock.clone(), cid(1).into(), None)
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(cids, vec![cid(2), cid(3), cid(4), cid(5)]);
+
+        let calls = mock.calls();
+
+        assert_eq!(calls.len(), 5);
+        assert!(calls.iter().all(|call| call.args[1] == "Some(1)"));
+        assert_eq!(calls[1].args[0], format!("\"/ipfs/{}\"", cid(2)));
+    }
+
+    #[test]
+    fn test_refs_walk_max_depth() {
+        let mock = MockIpfsClient::new();
+
+        refs(&mock, &[2]);
+        refs(&mock, &[3]);
+
+        let cids = RefsWalk::new(mock.clone(), cid(1).into(), Some(2))
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(cids, vec![cid(2), cid(3)]);
+        assert_eq!(mock.calls().len(), 2);
+    }
+}
@@ -9,7 +9,7 @@
 use http::Method;
 use request::ApiRequest;
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Add<'a, 'b> {
     pub recursive: Option<bool>,
@@ -26,25 +26,6 @@ pub struct Add<'a, 'b> {
     pub inline_limit: Option<isize>
 }
 
-impl<'a,'b> Default for Add<'a,'b> {
-    fn default() -> Self {
-        Self {
-            recursive: None,
-            progress: None,
-            trickle: None,
-            only_hash: None,
-            chunker: None,
-            pin: None,
-            raw_leaves: None,
-            fscache: None,
-            cid_version: None,
-            hash: None,
-            inline: None,
-            inline_limit: None,
-        }
-    }
-}
-
 impl<'a, 'b> ApiRequest for Add<'a,'b> {
     const PATH: &'static str = "/add";
 
@@ -26,9 +26,15 @@ impl ApiRequest for BitswapReprovide {
     const PATH: &'static str = "/bitswap/reprovide";
 }
 
-pub struct BitswapStat;
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct BitswapStat {
+    /// List each peer's ledger, not just its id.
+    pub verbose: Option<bool>,
 
-impl_skip_serialize!(BitswapStat);
+    /// Print sizes in human readable form.
+    pub human: Option<bool>,
+}
 
 impl ApiRequest for BitswapStat {
     const PATH: &'static str = "/bitswap/stat";
@@ -12,6 +12,10 @@ use request::ApiRequest;
 pub struct Cat<'a> {
     #[serde(rename = "arg")]
     pub path: &'a str,
+
+    pub offset: Option<u64>,
+
+    pub length: Option<u64>,
 }
 
 impl<'a> ApiRequest for Cat<'a> {
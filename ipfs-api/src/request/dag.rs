@@ -9,22 +9,60 @@
 use http::Method;
 use request::ApiRequest;
 
+#[derive(Serialize)]
+pub struct DagExport<'a> {
+    #[serde(rename = "arg")]
+    pub root: &'a str,
+}
+
+impl<'a> ApiRequest for DagExport<'a> {
+    const PATH: &'static str = "/dag/export";
+}
+
 #[derive(Serialize)]
 pub struct DagGet<'a> {
     #[serde(rename = "arg")]
     pub path: &'a str,
+
+    /// The codec the node is returned in (`dag-json` when not set).
+    #[serde(rename = "output-codec")]
+    pub output_codec: Option<&'a str>,
 }
 
 impl<'a> ApiRequest for DagGet<'a> {
     const PATH: &'static str = "/dag/get";
 }
 
-pub struct DagPut;
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DagPut<'a> {
+    /// The codec the uploaded data is encoded in (`dag-json` when not set).
+    pub input_codec: Option<&'a str>,
+
+    /// The codec the node is stored as (`dag-cbor` when not set).
+    pub store_codec: Option<&'a str>,
 
-impl_skip_serialize!(DagPut);
+    pub pin: Option<bool>,
+}
 
-impl ApiRequest for DagPut {
+impl<'a> ApiRequest for DagPut<'a> {
     const PATH: &'static str = "/dag/put";
 
     const METHOD: &'static Method = &Method::POST;
 }
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct DagImport {
+    /// Whether the roots of the archive are pinned (`true` when not set).
+    pub pin_roots: Option<bool>,
+
+    /// Whether the daemon reports the number of blocks and bytes imported.
+    pub stats: Option<bool>,
+}
+
+impl ApiRequest for DagImport {
+    const PATH: &'static str = "/dag/import";
+
+    const METHOD: &'static Method = &Method::POST;
+}
@@ -14,6 +14,9 @@ pub struct Dns<'a> {
     pub link: &'a str,
 
     pub recursive: bool,
+
+    /// Stream each intermediate answer while resolving.
+    pub stream: Option<bool>,
 }
 
 impl<'a> ApiRequest for Dns<'a> {
@@ -36,6 +36,9 @@ impl<'a> ApiRequest for FilesFlush<'a> {
 pub struct FilesLs<'a> {
     #[serde(rename = "arg")]
     pub path: Option<&'a str>,
+
+    /// Include the type, size and hash of each entry.
+    pub long: bool,
 }
 
 impl<'a> ApiRequest for FilesLs<'a> {
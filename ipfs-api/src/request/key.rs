@@ -9,7 +9,7 @@
 use request::ApiRequest;
 use serde::ser::{Serialize, Serializer};
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum KeyType {
     Rsa,
     Ed25519,
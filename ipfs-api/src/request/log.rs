@@ -10,7 +10,7 @@ use request::ApiRequest;
 use serde::ser::{Serialize, Serializer};
 use std::borrow::Cow;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum LoggingLevel {
     Debug,
     Info,
@@ -36,6 +36,7 @@ impl Serialize for LoggingLevel {
     }
 }
 
+#[derive(Debug)]
 pub enum Logger<'a> {
     All,
     Specific(Cow<'a, str>),
@@ -13,26 +13,37 @@ pub use self::bootstrap::*;
 pub use self::cat::*;
 pub use self::commands::*;
 pub use self::config::*;
+#[cfg(feature = "dag")]
 pub use self::dag::*;
+#[cfg(feature = "dht")]
 pub use self::dht::*;
 pub use self::diag::*;
 pub use self::dns::*;
 pub use self::file::*;
+#[cfg(feature = "files")]
 pub use self::files::*;
+#[cfg(feature = "filestore")]
 pub use self::filestore::*;
 pub use self::get::*;
 pub use self::id::*;
+#[cfg(feature = "key")]
 pub use self::key::*;
 pub use self::log::*;
 pub use self::ls::*;
+#[cfg(feature = "name")]
 pub use self::name::*;
+#[cfg(feature = "object")]
 pub use self::object::*;
+#[cfg(feature = "pin")]
 pub use self::pin::*;
 pub use self::ping::*;
+#[cfg(feature = "pubsub")]
 pub use self::pubsub::*;
 pub use self::refs::*;
+pub use self::repo::*;
 pub use self::shutdown::*;
 pub use self::stats::*;
+#[cfg(feature = "swarm")]
 pub use self::swarm::*;
 pub use self::tar::*;
 pub use self::version::*;
@@ -74,26 +85,37 @@ mod bootstrap;
 mod cat;
 mod commands;
 mod config;
+#[cfg(feature = "dag")]
 mod dag;
+#[cfg(feature = "dht")]
 mod dht;
 mod diag;
 mod dns;
 mod file;
+#[cfg(feature = "files")]
 mod files;
+#[cfg(feature = "filestore")]
 mod filestore;
 mod get;
 mod id;
+#[cfg(feature = "key")]
 mod key;
 mod log;
 mod ls;
+#[cfg(feature = "name")]
 mod name;
+#[cfg(feature = "object")]
 mod object;
+#[cfg(feature = "pin")]
 mod pin;
 mod ping;
+#[cfg(feature = "pubsub")]
 mod pubsub;
 mod refs;
+mod repo;
 mod shutdown;
 mod stats;
+#[cfg(feature = "swarm")]
 mod swarm;
 mod tar;
 mod version;
@@ -111,3 +133,299 @@ pub trait ApiRequest {
     ///
     const METHOD: &'static ::http::Method = &::http::Method::GET;
 }
+
+/// Any number of positional arguments, serialized as repeated `arg`
+/// parameters in order.
+///
+/// Requests with a fixed number of arguments can rename each field to `arg`.
+/// A variable number of arguments is added to a request as a field marked
+/// `#[serde(flatten)]` (see `PinAddMany`).
+///
+pub struct Args<'a>(pub &'a [&'a str]);
+
+impl<'a> ::serde::Serialize for Args<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+
+        for arg in self.0 {
+            map.serialize_entry("arg", arg)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Serializes a request into a query string.
+///
+/// Values are percent-encoded, with spaces encoded as `%20` rather than the
+/// `+` used by html forms, which some proxies in front of the api pass on as
+/// a literal `+`. The daemon decodes both the same way.
+///
+pub(crate) fn encode_query<Q>(query: &Q) -> Result<String, ::serde_urlencoded::ser::Error>
+where
+    Q: ::serde::Serialize,
+{
+    // A `+` in a value is encoded as `%2B`, so every `+` left in the string
+    // is an encoded space.
+    //
+    ::serde_urlencoded::to_string(query).map(|query| query.replace('+', "%20"))
+}
+
+/// Options the daemon accepts on every command, which an `IpfsClient` adds
+/// to each request it makes.
+///
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct GlobalOptions {
+    /// Run the command without using the network.
+    pub offline: Option<bool>,
+
+    /// How long the daemon lets the command run before cancelling it, as a
+    /// Go duration (e.g. `1500ms`).
+    pub timeout: Option<String>,
+
+    /// The multibase CIDv1s in the response are written in.
+    pub cid_base: Option<::cid::CidBase>,
+}
+
+/// Formats a duration the way Go's `time.ParseDuration` reads it.
+///
+pub(crate) fn format_duration(duration: ::std::time::Duration) -> String {
+    if duration.subsec_nanos() == 0 {
+        format!("{}s", duration.as_secs())
+    } else if duration.subsec_nanos() == duration.subsec_millis() * 1_000_000 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{}ns", duration.as_nanos())
+    }
+}
+
+impl GlobalOptions {
+    /// Serializes a request into a query string, followed by the options
+    /// that are set.
+    ///
+    pub fn encode_query<Q>(&self, query: &Q) -> Result<String, ::serde_urlencoded::ser::Error>
+    where
+        Q: ::serde::Serialize,
+    {
+        let mut encoded = encode_query(query)?;
+        let options = encode_query(self)?;
+
+        if !options.is_empty() {
+            if !encoded.is_empty() {
+                encoded.push('&');
+            }
+
+            encoded.push_str(&options);
+        }
+
+        Ok(encoded)
+    }
+}
+
+/// Options set on a client for every request to an api path, which are
+/// added to a request unless it sets the option itself.
+///
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DefaultOptions {
+    options: ::std::collections::HashMap<&'static str, Vec<String>>,
+}
+
+impl DefaultOptions {
+    /// Uses the options set in `req` as the defaults for its path, replacing
+    /// any set before. Positional arguments are ignored.
+    ///
+    pub fn set<Req>(&mut self, req: &Req) -> Result<(), ::serde_urlencoded::ser::Error>
+    where
+        Req: ApiRequest + ::serde::Serialize,
+    {
+        let options = encode_query(req)?
+            .split('&')
+            .filter(|option| !option.is_empty() && option_name(option) != "arg")
+            .map(Into::into)
+            .collect();
+
+        self.options.insert(Req::PATH, options);
+
+        Ok(())
+    }
+
+    /// Appends the defaults for `path` to an encoded query, skipping the
+    /// ones it already sets.
+    ///
+    pub fn apply(&self, path: &str, query: &mut String) {
+        let options = match self.options.get(path) {
+            Some(options) => options,
+            None => return,
+        };
+        let missing: Vec<&String> = options
+            .iter()
+            .filter(|option| {
+                !query
+                    .split('&')
+                    .any(|set| option_name(set) == option_name(option))
+            })
+            .collect();
+
+        for option in missing {
+            if !query.is_empty() {
+                query.push('&');
+            }
+
+            query.push_str(option);
+        }
+    }
+}
+
+/// The name of an encoded `name=value` pair.
+///
+fn option_name(option: &str) -> &str {
+    match option.find('=') {
+        Some(i) => &option[..i],
+        None => option,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_query, format_duration, Add, Cat, DefaultOptions, GlobalOptions, Version};
+    use http::Uri;
+    use std::time::Duration;
+
+    const AWKWARD_PATH: &str =
+        "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA/a b&c+d#e=f%g?h/Ünïcødé 文件.txt";
+
+    #[test]
+    fn test_encode_awkward_path() {
+        let query = encode_query(&Cat {
+            path: AWKWARD_PATH,
+            offset: None,
+            length: None,
+        })
+        .unwrap();
+
+        assert_eq!(
+            query,
+            "arg=%2Fipfs%2FQmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA%2F\
+             a%20b%26c%2Bd%23e%3Df%25g%3Fh%2F\
+             %C3%9Cn%C3%AFc%C3%B8d%C3%A9%20%E6%96%87%E4%BB%B6.txt"
+        );
+    }
+
+    #[test]
+    fn test_awkward_path_round_trip() {
+        let query = encode_query(&Cat {
+            path: AWKWARD_PATH,
+            offset: Some(1),
+            length: None,
+        })
+        .unwrap();
+        let uri: Uri = format!("http://localhost:5001/api/v0/cat?{}", query)
+            .parse()
+            .unwrap();
+        let args: Vec<(String, String)> =
+            ::serde_urlencoded::from_str(uri.query().unwrap()).unwrap();
+
+        assert_eq!(
+            args,
+            vec![
+                ("arg".to_string(), AWKWARD_PATH.to_string()),
+                ("offset".to_string(), "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_global_options() {
+        let cat = Cat {
+            path: "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+            offset: None,
+            length: None,
+        };
+        let mut options = GlobalOptions::default();
+
+        assert_eq!(
+            options.encode_query(&cat).unwrap(),
+            encode_query(&cat).unwrap()
+        );
+        assert_eq!(options.encode_query(&Version).unwrap(), "");
+
+        options.offline = Some(true);
+
+        assert!(options
+            .encode_query(&cat)
+            .unwrap()
+            .ends_with("&offline=true"));
+        assert_eq!(options.encode_query(&Version).unwrap(), "offline=true");
+
+        options.timeout = Some(format_duration(Duration::from_secs(30)));
+
+        assert_eq!(
+            options.encode_query(&Version).unwrap(),
+            "offline=true&timeout=30s"
+        );
+
+        options.cid_base = Some(::cid::CidBase::Base32);
+
+        assert!(options
+            .encode_query(&Version)
+            .unwrap()
+            .ends_with("&cid-base=base32"));
+    }
+
+    #[test]
+    fn test_default_options() {
+        let mut defaults = DefaultOptions::default();
+
+        defaults
+            .set(&Add {
+                cid_version: Some(1),
+                raw_leaves: Some(true),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mut query = encode_query(&Add {
+            raw_leaves: Some(false),
+            pin: Some(false),
+            ..Default::default()
+        })
+        .unwrap();
+
+        defaults.apply("/add", &mut query);
+        assert_eq!(query, "pin=false&raw-leaves=false&cid-version=1");
+
+        let mut query = String::new();
+
+        defaults.apply("/cat", &mut query);
+        assert_eq!(query, "");
+
+        defaults.apply("/add", &mut query);
+        assert_eq!(query, "raw-leaves=true&cid-version=1");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(format_duration(Duration::new(1, 5)), "1000000005ns");
+    }
+
+    #[cfg(feature = "files")]
+    #[test]
+    fn test_mfs_path_round_trip() {
+        use super::FilesRead;
+
+        let path = "/my files/a+b & c #1.txt";
+        let query = encode_query(&FilesRead { path }).unwrap();
+        let args: Vec<(String, String)> = ::serde_urlencoded::from_str(&query).unwrap();
+
+        assert!(!query.contains(' ') && !query.contains('+'));
+        assert_eq!(args, vec![("arg".to_string(), path.to_string())]);
+    }
+}
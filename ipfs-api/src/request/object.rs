@@ -36,6 +36,35 @@ impl<'a> ApiRequest for ObjectDiff<'a> {
 pub struct ObjectGet<'a> {
     #[serde(rename = "arg")]
     pub key: &'a str,
+
+    pub encoding: Option<ObjectEncoding>,
+}
+
+/// The encodings `object_get` can return a node in.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ObjectEncoding {
+    Json,
+
+    /// The node's raw dag-pb bytes.
+    Protobuf,
+
+    Xml,
+}
+
+impl Serialize for ObjectEncoding {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = match self {
+            ObjectEncoding::Json => "json",
+            ObjectEncoding::Protobuf => "protobuf",
+            ObjectEncoding::Xml => "xml",
+        };
+
+        serializer.serialize_str(s)
+    }
 }
 
 impl<'a> ApiRequest for ObjectGet<'a> {
@@ -52,7 +81,7 @@ impl<'a> ApiRequest for ObjectLinks<'a> {
     const PATH: &'static str = "/object/links";
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum ObjectTemplate {
     UnixFsDir,
 }
@@ -80,6 +109,24 @@ impl ApiRequest for ObjectNew {
     const PATH: &'static str = "/object/new";
 }
 
+#[derive(Serialize)]
+pub struct ObjectPatchAddLink<'a> {
+    #[serde(rename = "arg")]
+    pub key: &'a str,
+
+    #[serde(rename = "arg")]
+    pub name: &'a str,
+
+    #[serde(rename = "arg")]
+    pub link: &'a str,
+
+    pub create: bool,
+}
+
+impl<'a> ApiRequest for ObjectPatchAddLink<'a> {
+    const PATH: &'static str = "/object/patch/add-link";
+}
+
 #[derive(Serialize)]
 pub struct ObjectStat<'a> {
     #[serde(rename = "arg")]
@@ -92,7 +139,7 @@ impl<'a> ApiRequest for ObjectStat<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::ObjectDiff;
+    use super::{ObjectDiff, ObjectPatchAddLink};
 
     serialize_url_test!(
         test_serializes_0,
@@ -102,4 +149,15 @@ mod tests {
         },
         "arg=test&arg=test2"
     );
+
+    serialize_url_test!(
+        test_serializes_1,
+        &ObjectPatchAddLink {
+            key: "QmRoot",
+            name: "a b",
+            link: "QmChild",
+            create: true,
+        },
+        "arg=QmRoot&arg=a+b&arg=QmChild&create=true"
+    );
 }
@@ -6,7 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use request::ApiRequest;
+use request::{ApiRequest, Args};
 
 #[derive(Serialize)]
 pub struct PinAdd<'a> {
@@ -21,6 +21,19 @@ impl<'a> ApiRequest for PinAdd<'a> {
     const PATH: &'static str = "/pin/add";
 }
 
+#[derive(Serialize)]
+pub struct PinAddMany<'a> {
+    #[serde(flatten)]
+    pub keys: Args<'a>,
+
+    pub recursive: Option<bool>,
+    pub progress: bool,
+}
+
+impl<'a> ApiRequest for PinAddMany<'a> {
+    const PATH: &'static str = "/pin/add";
+}
+
 #[derive(Serialize)]
 pub struct PinLs<'a> {
     #[serde(rename = "arg")]
@@ -45,3 +58,28 @@ pub struct PinRm<'a> {
 impl<'a> ApiRequest for PinRm<'a> {
     const PATH: &'static str = "/pin/rm";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, PinAddMany};
+
+    serialize_url_test!(
+        test_serializes_many_keys_in_order,
+        &PinAddMany {
+            keys: Args(&["QmB", "QmA", "QmC"]),
+            recursive: Some(true),
+            progress: false,
+        },
+        "arg=QmB&arg=QmA&arg=QmC&recursive=true&progress=false"
+    );
+
+    serialize_url_test!(
+        test_serializes_no_keys,
+        &PinAddMany {
+            keys: Args(&[]),
+            recursive: None,
+            progress: false,
+        },
+        "progress=false"
+    );
+}
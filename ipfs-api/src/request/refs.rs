@@ -8,6 +8,23 @@
 
 use request::ApiRequest;
 
+#[derive(Serialize)]
+pub struct Refs<'a> {
+    #[serde(rename = "arg")]
+    pub path: &'a str,
+
+    pub recursive: bool,
+
+    pub unique: bool,
+
+    #[serde(rename = "max-depth")]
+    pub max_depth: Option<u64>,
+}
+
+impl<'a> ApiRequest for Refs<'a> {
+    const PATH: &'static str = "/refs";
+}
+
 pub struct RefsLocal;
 
 impl_skip_serialize!(RefsLocal);
@@ -15,3 +32,29 @@ impl_skip_serialize!(RefsLocal);
 impl ApiRequest for RefsLocal {
     const PATH: &'static str = "/refs/local";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Refs;
+
+    serialize_url_test!(
+        test_serializes_0,
+        Refs {
+            path: "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+            recursive: true,
+            unique: true,
+            max_depth: Some(1),
+        },
+        "arg=QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA&recursive=true&unique=true&max-depth=1"
+    );
+    serialize_url_test!(
+        test_serializes_1,
+        Refs {
+            path: "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA",
+            recursive: false,
+            unique: false,
+            max_depth: None,
+        },
+        "arg=QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA&recursive=false&unique=false"
+    );
+}
@@ -0,0 +1,32 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use request::ApiRequest;
+
+pub struct RepoGc;
+
+impl_skip_serialize!(RepoGc);
+
+impl ApiRequest for RepoGc {
+    const PATH: &'static str = "/repo/gc";
+}
+
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct RepoStat {
+    /// Print sizes in human readable form.
+    pub human: Option<bool>,
+
+    /// Only report the repo size and storage limit, which is much cheaper
+    /// than counting objects.
+    pub size_only: Option<bool>,
+}
+
+impl ApiRequest for RepoStat {
+    const PATH: &'static str = "/repo/stat";
+}
@@ -24,9 +24,16 @@ impl ApiRequest for StatsBw {
     const PATH: &'static str = "/stats/bw";
 }
 
-pub struct StatsRepo;
-
-impl_skip_serialize!(StatsRepo);
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct StatsRepo {
+    /// Print sizes in human readable form.
+    pub human: Option<bool>,
+
+    /// Only report the repo size and storage limit, which is much cheaper
+    /// than counting objects.
+    pub size_only: Option<bool>,
+}
 
 impl ApiRequest for StatsRepo {
     const PATH: &'static str = "/stats/repo";
@@ -16,6 +16,16 @@ impl ApiRequest for SwarmAddrsLocal {
     const PATH: &'static str = "/swarm/addrs/local";
 }
 
+#[derive(Serialize)]
+pub struct SwarmConnect<'a> {
+    #[serde(rename = "arg")]
+    pub addr: &'a str,
+}
+
+impl<'a> ApiRequest for SwarmConnect<'a> {
+    const PATH: &'static str = "/swarm/connect";
+}
+
 pub struct SwarmPeers;
 
 impl_skip_serialize!(SwarmPeers);
@@ -0,0 +1,136 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use futures::{future, Future};
+#[cfg(feature = "hyper")]
+use hyper::client::connect::dns::{GaiResolver, Name, Resolve};
+#[cfg(feature = "hyper")]
+use std::{collections::HashMap, error, fmt, sync::Arc, vec};
+use std::{io, net::IpAddr};
+
+/// Resolves the host name of the api to the addresses the client connects
+/// to, in place of the system resolver.
+///
+/// Implemented for closures taking the host name and returning its
+/// addresses.
+///
+pub trait DnsResolver: Send + Sync {
+    /// Resolves `host`. The client tries each of the addresses in order.
+    ///
+    fn resolve(&self, host: &str) -> Box<dyn Future<Item = Vec<IpAddr>, Error = io::Error> + Send>;
+}
+
+impl<F> DnsResolver for F
+where
+    F: Fn(&str) -> Result<Vec<IpAddr>, io::Error> + Send + Sync,
+{
+    #[inline]
+    fn resolve(&self, host: &str) -> Box<dyn Future<Item = Vec<IpAddr>, Error = io::Error> + Send> {
+        Box::new(future::result(self(host)))
+    }
+}
+
+/// A failure to look up a host name, carried inside of the `io::Error` the
+/// connector reports, so the client can tell it apart from a failure to
+/// connect.
+///
+#[cfg(feature = "hyper")]
+#[derive(Debug)]
+pub(crate) struct ResolveError {
+    host: String,
+    source: io::Error,
+}
+
+#[cfg(feature = "hyper")]
+impl ResolveError {
+    /// Wraps an error looking up `host`, keeping its kind.
+    ///
+    pub(crate) fn wrap(host: &str, source: io::Error) -> io::Error {
+        io::Error::new(
+            source.kind(),
+            ResolveError {
+                host: host.into(),
+                source,
+            },
+        )
+    }
+
+    /// Returns whether `err` is a failure to look up a host name.
+    ///
+    pub(crate) fn is(err: &io::Error) -> bool {
+        match err.get_ref() {
+            Some(e) => e.is::<ResolveError>(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to resolve '{}': {}", self.host, self.source)
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The resolver the hyper connector looks up host names with: the static
+/// addresses given to the builder first, then the custom resolver if there
+/// is one, otherwise the system resolver.
+///
+#[cfg(feature = "hyper")]
+#[derive(Clone)]
+pub(crate) struct Resolver {
+    hosts: Arc<HashMap<String, Vec<IpAddr>>>,
+    custom: Option<Arc<dyn DnsResolver>>,
+    system: GaiResolver,
+}
+
+#[cfg(feature = "hyper")]
+impl Resolver {
+    pub(crate) fn new(
+        hosts: HashMap<String, Vec<IpAddr>>,
+        custom: Option<Arc<dyn DnsResolver>>,
+    ) -> Resolver {
+        Resolver {
+            hosts: Arc::new(hosts),
+            custom,
+            system: GaiResolver::new(4),
+        }
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl Resolve for Resolver {
+    type Addrs = vec::IntoIter<IpAddr>;
+
+    type Future = Box<dyn Future<Item = Self::Addrs, Error = io::Error> + Send>;
+
+    fn resolve(&self, name: Name) -> Self::Future {
+        if let Some(addrs) = self.hosts.get(name.as_str()) {
+            return Box::new(future::ok(addrs.clone().into_iter()));
+        }
+
+        let host = name.as_str().to_string();
+        let addrs: Self::Future = match self.custom {
+            Some(ref custom) => Box::new(custom.resolve(name.as_str()).map(Vec::into_iter)),
+            None => Box::new(
+                self.system
+                    .resolve(name)
+                    .map(|addrs| addrs.collect::<Vec<_>>().into_iter()),
+            ),
+        };
+
+        Box::new(addrs.map_err(move |e| ResolveError::wrap(&host, e)))
+    }
+}
@@ -6,10 +6,37 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use cid::Cid;
+use response::serde;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct AddResponse {
     pub name: String,
-    pub hash: String,
-    pub size: String,
+    pub hash: Cid,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
+    pub size: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AddResponse;
+    use serde_json;
+
+    deserialize_test!(v0_add_0, AddResponse);
+
+    #[test]
+    fn test_size_as_string_or_number() {
+        let raw = include_str!("tests/v0_add_0.json");
+        let res: AddResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(res.size, 20);
+
+        let raw = raw.replace("\"20\"", "20");
+        let res: AddResponse = serde_json::from_str(&raw).unwrap();
+
+        assert_eq!(res.size, 20);
+        assert!(serde_json::from_str::<AddResponse>(&raw.replace("20", "-1")).is_err());
+    }
 }
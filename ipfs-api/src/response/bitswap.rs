@@ -6,15 +6,23 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use cid::Cid;
+use peer::PeerId;
 use response::serde;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BitswapLedgerResponse {
-    pub peer: String,
+    pub peer: PeerId,
     pub value: f64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub sent: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub recv: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub exchanged: u64,
 }
 
@@ -25,17 +33,28 @@ pub type BitswapReprovideResponse = ();
 pub struct BitswapStatResponse {
     pub provide_buf_len: i32,
 
-    #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub wantlist: Vec<String>,
+    /// The blocks the node is looking for. Older daemons list these as plain
+    /// strings, newer ones as IPLD links.
+    ///
+    #[serde(deserialize_with = "serde::deserialize_links")]
+    pub wantlist: Vec<Cid>,
 
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub peers: Vec<String>,
+    pub peers: Vec<PeerId>,
 
     pub blocks_received: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub data_received: u64,
+
     pub blocks_sent: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub data_sent: u64,
+
     pub dup_blks_received: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub dup_data_received: u64,
 }
 
@@ -50,5 +69,32 @@ pub struct BitswapWantlistResponse {
 
 #[cfg(test)]
 mod tests {
+    use super::BitswapStatResponse;
+
     deserialize_test!(v0_bitswap_stat_0, BitswapStatResponse);
+    deserialize_test!(v0_bitswap_stat_1, BitswapStatResponse);
+
+    #[test]
+    fn test_wantlist_links_are_cids() {
+        let raw = include_str!("tests/v0_bitswap_stat_1.json");
+        let stat: BitswapStatResponse = ::serde_json::from_str(raw).unwrap();
+
+        assert_eq!(
+            stat.wantlist[0].to_string(),
+            "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+        );
+        assert_eq!(
+            stat.peers[0].to_string(),
+            "QmNMVHJTSZHTWMWBbmBrQgkA1hZPWYuVJx2DpSGESWW6Kn"
+        );
+    }
+
+    #[test]
+    fn test_wantlist_plain_strings() {
+        let raw = r#"{"ProvideBufLen":0,"Wantlist":["QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"],"Peers":null,"BlocksReceived":0,"DataReceived":0,"BlocksSent":0,"DataSent":0,"DupBlksReceived":0,"DupDataReceived":0}"#;
+        let stat: BitswapStatResponse = ::serde_json::from_str(raw).unwrap();
+
+        assert_eq!(stat.wantlist.len(), 1);
+        assert!(stat.peers.is_empty());
+    }
 }
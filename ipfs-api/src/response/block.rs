@@ -6,24 +6,31 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use cid::Cid;
+use response::serde;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BlockPutResponse {
-    pub key: String,
+    pub key: Cid,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BlockRmResponse {
-    pub hash: String,
+    pub hash: Cid,
     pub error: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BlockStatResponse {
-    pub key: String,
+    pub key: Cid,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 }
 
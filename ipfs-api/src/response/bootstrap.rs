@@ -6,27 +6,28 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use addr::Multiaddr;
 use response::serde;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BootstrapAddDefaultResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub peers: Vec<String>,
+    pub peers: Vec<Multiaddr>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BootstrapListResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub peers: Vec<String>,
+    pub peers: Vec<Multiaddr>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BootstrapRmAllResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub peers: Vec<String>,
+    pub peers: Vec<Multiaddr>,
 }
 
 #[cfg(test)]
@@ -6,8 +6,242 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use addr::Multiaddr;
+use peer::PeerId;
+use response::serde;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
 pub type ConfigEditResponse = ();
 
 pub type ConfigReplaceResponse = ();
 
-pub type ConfigShowResponse = String;
+/// The daemon's configuration, as returned by `config_show`.
+///
+/// Only the well-known sections are modelled. Keys that are not are kept in
+/// `extra` of the section they appear in, so nothing in the config is lost.
+///
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigShowResponse {
+    pub identity: ConfigIdentity,
+    pub datastore: ConfigDatastore,
+    pub addresses: ConfigAddresses,
+    pub mounts: ConfigMounts,
+    pub discovery: ConfigDiscovery,
+    pub routing: ConfigRouting,
+    pub ipns: ConfigIpns,
+
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub bootstrap: Vec<Multiaddr>,
+
+    pub gateway: ConfigGateway,
+
+    #[serde(rename = "API")]
+    pub api: ConfigApi,
+
+    pub swarm: ConfigSwarm,
+    pub reprovider: ConfigReprovider,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigIdentity {
+    #[serde(rename = "PeerID")]
+    pub peer_id: Option<PeerId>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigDatastore {
+    pub storage_max: Option<String>,
+
+    #[serde(rename = "StorageGCWatermark")]
+    pub storage_gc_watermark: Option<i64>,
+
+    #[serde(rename = "GCPeriod")]
+    pub gc_period: Option<String>,
+
+    pub spec: Option<Value>,
+    pub hash_on_read: Option<bool>,
+    pub bloom_filter_size: Option<i64>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigAddresses {
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub swarm: Vec<Multiaddr>,
+
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub announce: Vec<Multiaddr>,
+
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub no_announce: Vec<String>,
+
+    /// The api may listen on one address or several.
+    #[serde(rename = "API", deserialize_with = "serde::deserialize_string_or_vec")]
+    pub api: Vec<String>,
+
+    /// The gateway may listen on one address or several.
+    #[serde(deserialize_with = "serde::deserialize_string_or_vec")]
+    pub gateway: Vec<String>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigMounts {
+    #[serde(rename = "IPFS")]
+    pub ipfs: Option<String>,
+
+    #[serde(rename = "IPNS")]
+    pub ipns: Option<String>,
+
+    pub fuse_allow_other: Option<bool>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigDiscovery {
+    #[serde(rename = "MDNS")]
+    pub mdns: ConfigMdns,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigMdns {
+    pub enabled: Option<bool>,
+    pub interval: Option<i64>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigRouting {
+    #[serde(rename = "Type")]
+    pub typ: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigIpns {
+    pub republish_period: Option<String>,
+    pub record_lifetime: Option<String>,
+    pub resolve_cache_size: Option<i64>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigGateway {
+    #[serde(
+        rename = "HTTPHeaders",
+        deserialize_with = "serde::deserialize_hashmap"
+    )]
+    pub http_headers: HashMap<String, Vec<String>>,
+
+    pub root_redirect: Option<String>,
+    pub writable: Option<bool>,
+
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub path_prefixes: Vec<String>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigApi {
+    #[serde(
+        rename = "HTTPHeaders",
+        deserialize_with = "serde::deserialize_hashmap"
+    )]
+    pub http_headers: HashMap<String, Vec<String>>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigSwarm {
+    #[serde(deserialize_with = "serde::deserialize_vec")]
+    pub addr_filters: Vec<String>,
+
+    pub disable_bandwidth_metrics: Option<bool>,
+    pub disable_nat_port_map: Option<bool>,
+    pub disable_relay: Option<bool>,
+    pub enable_relay_hop: Option<bool>,
+
+    #[serde(rename = "ConnMgr")]
+    pub conn_mgr: ConfigConnMgr,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigConnMgr {
+    #[serde(rename = "Type")]
+    pub typ: Option<String>,
+
+    pub low_water: Option<i64>,
+    pub high_water: Option<i64>,
+    pub grace_period: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct ConfigReprovider {
+    pub interval: Option<String>,
+    pub strategy: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    deserialize_test!(v0_config_show_0, ConfigShowResponse);
+
+    #[test]
+    fn test_unknown_keys_are_kept() {
+        let config: super::ConfigShowResponse =
+            ::serde_json::from_str(include_str!("tests/v0_config_show_0.json")).unwrap();
+
+        assert_eq!(config.addresses.api, vec!["/ip4/127.0.0.1/tcp/5001"]);
+        assert_eq!(config.bootstrap.len(), 2);
+        assert!(config.extra.contains_key("Experimental"));
+        assert!(config.swarm.extra.contains_key("EnableAutoRelay"));
+    }
+}
@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use cid::Cid;
 use response::serde;
 use std::collections::HashMap;
 
@@ -13,6 +14,8 @@ use std::collections::HashMap;
 #[serde(rename_all = "PascalCase")]
 pub struct DagIpfsHeader {
     pub name: String,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 
     #[serde(deserialize_with = "serde::deserialize_hashmap")]
@@ -27,13 +30,45 @@ pub struct DagGetResponse {
     pub links: Vec<DagIpfsHeader>,
 }
 
+/// One line of the response to `dag_import`: either a root of the archive,
+/// or, when stats were asked for, the totals for the import.
+///
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DagImportResponse {
+    pub root: Option<DagImportRoot>,
+    pub stats: Option<DagImportStats>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DagImportRoot {
+    #[serde(deserialize_with = "serde::deserialize_link")]
+    pub cid: Cid,
+
+    /// Why the root could not be pinned, or empty if it was.
+    #[serde(default)]
+    pub pin_error_msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DagImportStats {
+    pub block_count: u64,
+    pub block_bytes_count: u64,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DagPutResponse {
-    pub cid: String,
+    #[serde(deserialize_with = "serde::deserialize_link")]
+    pub cid: Cid,
 }
 
 #[cfg(test)]
 mod tests {
     deserialize_test!(v0_dag_get_0, DagGetResponse);
+    deserialize_test!(v0_dag_import_0, DagImportResponse);
+    deserialize_test!(v0_dag_import_1, DagImportResponse);
+    deserialize_test!(v0_dag_put_0, DagPutResponse);
 }
@@ -6,13 +6,17 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use addr::Multiaddr;
+use ipns::IpnsRecord;
+use multibase;
+use peer::PeerId;
 use response::serde;
 use serde::de::{Deserialize, Deserializer, Error};
 
 /// See
 /// [libp2p](https://github.com/libp2p/go-libp2p-routing/blob/master/notifications/query.go#L16).
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum DhtType {
     SendingQuery,
     PeerResponse,
@@ -48,10 +52,10 @@ impl<'de> Deserialize<'de> for DhtType {
 #[serde(rename_all = "PascalCase")]
 pub struct DhtPeerResponse {
     #[serde(rename = "ID")]
-    pub id: String,
+    pub id: PeerId,
 
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub addrs: Vec<String>,
+    pub addrs: Vec<Multiaddr>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -69,6 +73,27 @@ pub struct DhtMessage {
     pub extra: String,
 }
 
+impl DhtMessage {
+    /// The value of a record found by `dht_get`, decoded from the base64 in
+    /// `extra`. `None` for messages other than `DhtType::Value`.
+    ///
+    pub fn value(&self) -> Option<Vec<u8>> {
+        if self.typ != DhtType::Value {
+            return None;
+        }
+
+        multibase::decode_base64(self.extra.trim().as_bytes())
+    }
+
+    /// The value of a record found by `dht_get` for an `/ipns/` key, parsed
+    /// as an IPNS record.
+    ///
+    #[inline]
+    pub fn ipns_record(&self) -> Option<IpnsRecord> {
+        self.value().and_then(|value| IpnsRecord::decode(&value))
+    }
+}
+
 pub type DhtFindPeerResponse = DhtMessage;
 
 pub type DhtFindProvsResponse = DhtMessage;
@@ -80,3 +105,25 @@ pub type DhtProvideResponse = DhtMessage;
 pub type DhtPutResponse = DhtMessage;
 
 pub type DhtQueryResponse = DhtMessage;
+
+#[cfg(test)]
+mod tests {
+    use super::DhtType;
+
+    deserialize_test!(v0_dht_get_0, DhtGetResponse);
+
+    #[test]
+    fn test_dht_get_value() {
+        let res: super::DhtGetResponse =
+            ::serde_json::from_str(include_str!("tests/v0_dht_get_0.json")).unwrap();
+        let record = res.ipns_record().unwrap();
+
+        assert_eq!(res.typ, DhtType::Value);
+        assert_eq!(res.value().unwrap().len(), 108);
+        assert_eq!(
+            record.value,
+            "/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+        );
+        assert_eq!(record.sequence, 3);
+    }
+}
@@ -5,11 +5,20 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 //
+
+// `failure_derive` expands to trait impls nested inside an anonymous const.
+//
+#![allow(non_local_definitions)]
+
 #[cfg(feature = "actix")]
 use actix_web;
 use http;
 #[cfg(feature = "hyper")]
 use hyper;
+#[cfg(feature = "tls")]
+use native_tls;
+#[cfg(feature = "dag-cbor")]
+use serde_cbor;
 use serde_json;
 use serde_urlencoded;
 use std;
@@ -21,6 +30,58 @@ use std::string::FromUtf8Error;
 pub struct ApiError {
     pub message: String,
     pub code: u8,
+
+    #[serde(rename = "Type", default)]
+    pub error_type: String,
+}
+
+impl ApiError {
+    /// The category of the error, so callers can branch on it without
+    /// matching on the message.
+    ///
+    pub fn kind(&self) -> ApiErrorKind {
+        let message = self.message.as_str();
+
+        if message.starts_with("merkledag: not found") {
+            ApiErrorKind::MerkledagNotFound
+        } else if message.contains("not pinned") {
+            ApiErrorKind::NotPinned
+        } else if message.contains("context deadline exceeded") {
+            ApiErrorKind::DeadlineExceeded
+        } else if self.code == API_CODE_NOT_FOUND
+            || message.ends_with("not found")
+            || message.contains("does not exist")
+        {
+            ApiErrorKind::NotFound
+        } else {
+            ApiErrorKind::Other
+        }
+    }
+}
+
+/// `Code` the daemon sets on errors for things that don't exist.
+///
+const API_CODE_NOT_FOUND: u8 = 3;
+
+/// Common categories of errors returned by the Ipfs api.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiErrorKind {
+    /// A block, file, path, key or peer does not exist.
+    NotFound,
+
+    /// A block could not be found in the local repo, or retrieved from the
+    /// network (while offline, for instance).
+    MerkledagNotFound,
+
+    /// An object being unpinned is not pinned.
+    NotPinned,
+
+    /// The request took longer than its timeout.
+    DeadlineExceeded,
+
+    /// Any other error.
+    Other,
 }
 
 #[derive(Fail, Debug)]
@@ -42,12 +103,68 @@ pub enum Error {
     #[fail(display = "actix client send request error '{}'", _0)]
     ClientSend(actix_web::client::SendRequestError),
 
+    /// The api could not be reached.
+    #[fail(
+        display = "could not connect to the Ipfs api at '{}' (is the daemon running?): {}",
+        uri, source
+    )]
+    Connect { uri: String, source: Box<Error> },
+
+    /// Nothing is listening at the address of the api.
+    #[fail(
+        display = "connection to the Ipfs api at '{}' was refused (is the daemon running?)",
+        uri
+    )]
+    ConnectionRefused { uri: String },
+
+    /// The host name in the address of the api could not be resolved.
+    #[fail(
+        display = "could not resolve the host of the Ipfs api at '{}': {}",
+        uri, source
+    )]
+    Dns { uri: String, source: Box<Error> },
+
+    /// The api did not respond in time.
+    #[fail(display = "request to the Ipfs api at '{}' timed out", uri)]
+    Timeout { uri: String },
+
+    /// The api redirected a request more times than allowed by
+    /// `IpfsClientBuilder::max_redirects`.
+    #[fail(
+        display = "request to '{}' was redirected more than {} times",
+        uri, max
+    )]
+    TooManyRedirects { uri: String, max: usize },
+
+    #[cfg(feature = "tls")]
+    #[fail(display = "tls error '{}'", _0)]
+    Tls(native_tls::Error),
+
     #[fail(display = "http error '{}'", _0)]
     Http(http::Error),
 
     #[fail(display = "json parse error '{}'", _0)]
     Parse(serde_json::Error),
 
+    #[cfg(feature = "dag-cbor")]
+    #[fail(display = "cbor error '{}'", _0)]
+    Cbor(serde_cbor::Error),
+
+    /// A response had fields the client doesn't know about, while the
+    /// client was in strict mode.
+    #[fail(display = "response has unknown fields: {}", _0)]
+    UnknownFields(String),
+
+    /// A streamed response had a line longer than the limit set with
+    /// `IpfsClientBuilder::max_line_length`. The line is skipped.
+    #[fail(display = "response has a line longer than {} bytes", _0)]
+    LineTooLong(usize),
+
+    /// A response was larger than the limit set with
+    /// `IpfsClientBuilder::max_response_size`, and was not read any further.
+    #[fail(display = "response is larger than {} bytes", _0)]
+    ResponseTooLarge(usize),
+
     #[fail(display = "utf8 decoding error '{}'", _0)]
     ParseUtf8(FromUtf8Error),
 
@@ -64,16 +181,158 @@ pub enum Error {
     #[fail(display = "api returned error '{}'", _0)]
     Api(ApiError),
 
+    /// The daemon gave up on a request that took longer than its deadline
+    /// (`context deadline exceeded`).
+    #[fail(display = "api request exceeded its deadline '{}'", _0)]
+    DeadlineExceeded(ApiError),
+
     /// A stream error indicated in the Trailer header.
     #[fail(display = "api returned an error while streaming: '{}'", _0)]
     StreamError(String),
 
     /// API returned a trailer header with unrecognized value.
+    ///
+    /// No longer returned: trailers other than `X-Stream-Error` are passed
+    /// on through `ResponseMetadata::trailers`.
     #[fail(display = "api returned a trailer header with unknown value: '{}'", _0)]
     UnrecognizedTrailerHeader(String),
 
+    /// The api responded with `404 Not Found` or `405 Method Not Allowed`
+    /// and a body that isn't an api error, which is how daemons that are
+    /// too old to have an endpoint respond.
+    #[fail(
+        display = "api does not support this endpoint ({}): '{}'",
+        status, body
+    )]
+    UnsupportedEndpoint {
+        status: http::StatusCode,
+        body: String,
+    },
+
+    /// A proxy in front of the api responded with `502 Bad Gateway`,
+    /// `503 Service Unavailable` or `504 Gateway Timeout`, usually because
+    /// the daemon behind it is down.
+    #[fail(display = "bad gateway ({}): '{}'", status, body)]
+    BadGateway {
+        status: http::StatusCode,
+        body: String,
+    },
+
+    /// The api responded with an error status and a body that isn't an api
+    /// error (e.g. plain text or html).
+    #[fail(display = "api returned status {}: '{}'", status, body)]
+    UnexpectedStatus {
+        status: http::StatusCode,
+        body: String,
+    },
+
     #[fail(display = "api returned unknwon error '{}'", _0)]
     Uncategorized(String),
+
+    /// A string that is not a valid CID was given where one was expected.
+    #[fail(display = "invalid cid '{}': {}", _0, _1)]
+    InvalidCid(String, &'static str),
+
+    /// A string that is not a valid peer id was given where one was expected.
+    #[fail(display = "invalid peer id '{}': {}", _0, _1)]
+    InvalidPeerId(String, &'static str),
+
+    /// A path that is not valid where it was given (e.g. a CID where an
+    /// MFS path was expected).
+    #[fail(display = "invalid path '{}': {}", _0, _1)]
+    InvalidPath(String, &'static str),
+
+    /// A multiaddr returned by the api could not be parsed.
+    #[fail(display = "invalid multiaddr '{}': {}", _0, _1)]
+    InvalidMultiaddr(String, String),
+
+    /// There is no key with the name given.
+    #[fail(display = "no key named '{}'", _0)]
+    KeyNotFound(String),
+
+    /// A name could not be resolved to a path, so its content was never
+    /// requested.
+    #[fail(display = "could not resolve '{}': {}", _0, _1)]
+    Resolve(String, Box<Error>),
+
+    /// An error from a request, with the api path and arguments the request
+    /// was made with (e.g. `/files/write arg=/foo create=true`).
+    ///
+    /// Only returned by clients built with `error_context` enabled.
+    #[fail(display = "{}: {}", request, source)]
+    Request { request: String, source: Box<Error> },
+}
+
+impl Error {
+    /// The category of an error returned by the api, or `None` if the error
+    /// did not come from the api.
+    ///
+    pub fn api_kind(&self) -> Option<ApiErrorKind> {
+        match self.without_context() {
+            Error::Api(e) | Error::DeadlineExceeded(e) => Some(e.kind()),
+            _ => None,
+        }
+    }
+
+    /// The error without the request it came from, for matching on errors
+    /// from clients built with `error_context` enabled.
+    ///
+    pub fn without_context(&self) -> &Error {
+        match self {
+            Error::Request { source, .. } => source.without_context(),
+            e => e,
+        }
+    }
+}
+
+/// The longest argument value included in the context of an error, in
+/// characters.
+///
+const MAX_CONTEXT_VALUE_LENGTH: usize = 64;
+
+/// The api path and arguments of a request, which are added to its errors.
+///
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorContext {
+    request: String,
+}
+
+impl ErrorContext {
+    /// Describes a request to `path` with an encoded `query`. Long argument
+    /// values are shortened.
+    ///
+    pub fn new(path: &str, query: &str) -> ErrorContext {
+        let args: Vec<(String, String)> = serde_urlencoded::from_str(query).unwrap_or_default();
+        let mut request = path.to_string();
+
+        for (name, value) in args {
+            request.push(' ');
+            request.push_str(&name);
+            request.push('=');
+
+            if value.chars().count() > MAX_CONTEXT_VALUE_LENGTH {
+                request.extend(value.chars().take(MAX_CONTEXT_VALUE_LENGTH));
+                request.push_str("...");
+            } else {
+                request.push_str(&value);
+            }
+        }
+
+        ErrorContext { request }
+    }
+
+    /// Adds the request to an error, unless the error already names the
+    /// request it came from.
+    ///
+    pub fn wrap(&self, source: Error) -> Error {
+        match source {
+            e @ Error::Request { .. } => e,
+            source => Error::Request {
+                request: self.request.clone(),
+                source: Box::new(source),
+            },
+        }
+    }
 }
 
 #[cfg(feature = "hyper")]
@@ -104,6 +363,13 @@ impl From<actix_web::error::PayloadError> for Error {
     }
 }
 
+#[cfg(feature = "tls")]
+impl From<native_tls::Error> for Error {
+    fn from(err: native_tls::Error) -> Error {
+        Error::Tls(err)
+    }
+}
+
 impl From<http::Error> for Error {
     fn from(err: http::Error) -> Error {
         Error::Http(err)
@@ -116,6 +382,13 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "dag-cbor")]
+impl From<serde_cbor::Error> for Error {
+    fn from(err: serde_cbor::Error) -> Error {
+        Error::Cbor(err)
+    }
+}
+
 impl From<FromUtf8Error> for Error {
     fn from(err: FromUtf8Error) -> Error {
         Error::ParseUtf8(err)
@@ -139,3 +412,71 @@ impl From<serde_urlencoded::ser::Error> for Error {
         Error::EncodeUrl(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiError, ApiErrorKind, Error, ErrorContext};
+    use serde_json;
+
+    fn api_kind(body: &str) -> Option<ApiErrorKind> {
+        Error::Api(serde_json::from_str::<ApiError>(body).unwrap()).api_kind()
+    }
+
+    #[test]
+    fn test_api_error_kind() {
+        assert_eq!(
+            api_kind(r#"{"Message":"merkledag: not found","Code":0,"Type":"error"}"#),
+            Some(ApiErrorKind::MerkledagNotFound)
+        );
+        assert_eq!(
+            api_kind(r#"{"Message":"not pinned or pinned indirectly","Code":0,"Type":"error"}"#),
+            Some(ApiErrorKind::NotPinned)
+        );
+        assert_eq!(
+            api_kind(r#"{"Message":"context deadline exceeded","Code":0,"Type":"error"}"#),
+            Some(ApiErrorKind::DeadlineExceeded)
+        );
+        assert_eq!(
+            api_kind(r#"{"Message":"file does not exist","Code":0,"Type":"error"}"#),
+            Some(ApiErrorKind::NotFound)
+        );
+        assert_eq!(
+            api_kind(r#"{"Message":"no such key","Code":3,"Type":"error"}"#),
+            Some(ApiErrorKind::NotFound)
+        );
+        assert_eq!(
+            api_kind(r#"{"Message":"invalid path","Code":1}"#),
+            Some(ApiErrorKind::Other)
+        );
+        assert_eq!(Error::Uncategorized("".into()).api_kind(), None);
+    }
+
+    #[test]
+    fn test_error_context() {
+        let context = ErrorContext::new("/files/write", "arg=%2Ffoo%20bar&create=true");
+        let api_error = r#"{"Message":"file does not exist","Code":0,"Type":"error"}"#;
+        let err = context.wrap(Error::Api(serde_json::from_str(api_error).unwrap()));
+
+        assert_eq!(
+            err.to_string(),
+            "/files/write arg=/foo bar create=true: api returned error 'file does not exist'"
+        );
+        assert_eq!(err.api_kind(), Some(ApiErrorKind::NotFound));
+
+        let err = ErrorContext::new("/add", "").wrap(err);
+
+        assert!(err.to_string().starts_with("/files/write "));
+        match err.without_context() {
+            Error::Api(_) => (),
+            e => panic!("expected an api error, got {:?}", e),
+        }
+
+        let long = "a".repeat(100);
+        let context = ErrorContext::new("/cat", &format!("arg={}", long));
+
+        assert_eq!(
+            context.wrap(Error::Uncategorized("".into())).to_string(),
+            format!("/cat arg={}...: api returned unknwon error ''", &long[..64])
+        );
+    }
+}
@@ -13,6 +13,8 @@ use std::collections::HashMap;
 #[serde(rename_all = "PascalCase")]
 pub struct IpfsDetailedFile {
     pub hash: String,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 
     #[serde(rename = "Type")]
@@ -6,24 +6,51 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use response::serde;
+use cid::Cid;
+use response::{serde, EntryKind};
+use std::time::{Duration, SystemTime};
 
 pub type FilesCpResponse = ();
 
-pub type FilesFlushResponse = ();
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FilesFlushResponse {
+    /// The CID of the flushed path. Older daemons return an empty body, in
+    /// which case this is `None`.
+    ///
+    pub cid: Option<Cid>,
+}
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct FilesEntry {
     pub name: String,
 
-    // This is a protocol buffer enum type defined in
-    // https://github.com/ipfs/go-ipfs/blob/master/unixfs/pb/unixfs.proto ...
-    // So it might be some other type than u64, but certainly shouldn't be *bigger* than u64.
-    #[serde(rename = "Type")]
-    pub typ: u64,
+    #[serde(
+        rename = "Type",
+        deserialize_with = "serde::deserialize_mfs_entry_kind"
+    )]
+    pub typ: EntryKind,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
+
     pub hash: String,
+
+    /// The modification time, to the second, if one is set on the entry.
+    #[serde(default, deserialize_with = "serde::deserialize_unix_time")]
+    pub mtime: Option<SystemTime>,
+
+    #[serde(default)]
+    pub mtime_nsecs: u32,
+}
+
+impl FilesEntry {
+    /// The full modification time, if one is set on the entry.
+    ///
+    pub fn modified(&self) -> Option<SystemTime> {
+        modified(self.mtime, self.mtime_nsecs)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,18 +70,105 @@ pub type FilesRmResponse = ();
 #[serde(rename_all = "PascalCase")]
 pub struct FilesStatResponse {
     pub hash: String,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub cumulative_size: u64,
+
     pub blocks: u64,
 
     #[serde(rename = "Type")]
     pub typ: String,
+
+    /// The modification time, to the second, if one is set on the file.
+    #[serde(default, deserialize_with = "serde::deserialize_unix_time")]
+    pub mtime: Option<SystemTime>,
+
+    #[serde(default)]
+    pub mtime_nsecs: u32,
+}
+
+impl FilesStatResponse {
+    /// The full modification time, if one is set on the file.
+    ///
+    pub fn modified(&self) -> Option<SystemTime> {
+        modified(self.mtime, self.mtime_nsecs)
+    }
+}
+
+#[inline]
+fn modified(mtime: Option<SystemTime>, nsecs: u32) -> Option<SystemTime> {
+    mtime.map(|mtime| mtime + Duration::from_nanos(nsecs.into()))
 }
 
 pub type FilesWriteResponse = ();
 
 #[cfg(test)]
 mod tests {
+    use super::{FilesFlushResponse, FilesLsResponse, FilesStatResponse};
+    use response::EntryKind;
+    use serde_json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    deserialize_test!(v0_files_flush_0, FilesFlushResponse);
     deserialize_test!(v0_files_ls_0, FilesLsResponse);
+    deserialize_test!(v0_files_ls_1, FilesLsResponse);
+    deserialize_test!(v0_files_ls_2, FilesLsResponse);
     deserialize_test!(v0_files_stat_0, FilesStatResponse);
+    deserialize_test!(v0_files_stat_1, FilesStatResponse);
+
+    #[test]
+    fn test_files_stat_mtime() {
+        let raw = include_str!("tests/v0_files_stat_0.json");
+        let stat: FilesStatResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(stat.modified(), None);
+
+        let raw = include_str!("tests/v0_files_stat_1.json");
+        let stat: FilesStatResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(
+            stat.modified(),
+            Some(UNIX_EPOCH + Duration::new(1_600_000_000, 500))
+        );
+    }
+
+    #[test]
+    fn test_files_ls_mtime() {
+        let raw = include_str!("tests/v0_files_ls_1.json");
+        let ls: FilesLsResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(ls.entries[1].modified(), None);
+
+        let raw = include_str!("tests/v0_files_ls_2.json");
+        let ls: FilesLsResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(ls.entries[0].modified(), None);
+        assert_eq!(
+            ls.entries[1].modified(),
+            Some(UNIX_EPOCH + Duration::new(1_600_000_000, 500))
+        );
+    }
+
+    #[test]
+    fn test_files_flush_cid() {
+        let raw = include_str!("tests/v0_files_flush_0.json");
+        let flush: FilesFlushResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(
+            flush.cid.unwrap().to_string(),
+            "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"
+        );
+    }
+
+    #[test]
+    fn test_files_ls_entry_kinds() {
+        let raw = include_str!("tests/v0_files_ls_1.json");
+        let ls: FilesLsResponse = serde_json::from_str(raw).unwrap();
+
+        assert_eq!(ls.entries[0].typ, EntryKind::Directory);
+        assert_eq!(ls.entries[1].typ, EntryKind::File);
+    }
 }
@@ -6,6 +6,8 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use response::serde;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct FilestoreDupsResponse {
@@ -22,7 +24,11 @@ pub struct FilestoreObject {
     pub error_msg: String,
     pub key: String,
     pub file_path: String,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub offset: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 }
 
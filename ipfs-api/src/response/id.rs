@@ -6,13 +6,14 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use peer::PeerId;
 use response::serde;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct IdResponse {
     #[serde(rename = "ID")]
-    pub id: String,
+    pub id: PeerId,
 
     pub public_key: String,
 
@@ -7,6 +7,68 @@
 //
 
 use response::serde;
+use serde::de::{Deserialize, Deserializer};
+use std::fmt;
+
+/// The kind of an entry in a directory listing.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+
+    /// A type this client doesn't know about, with the number the daemon
+    /// reported.
+    ///
+    Unknown(i32),
+}
+
+impl EntryKind {
+    /// Converts the unixfs data type used by `ls`.
+    ///
+    pub fn from_unixfs(typ: i64) -> EntryKind {
+        match typ {
+            0 | 2 => EntryKind::File,
+            1 | 5 => EntryKind::Directory,
+            4 => EntryKind::Symlink,
+            i => EntryKind::Unknown(i as i32),
+        }
+    }
+
+    /// Converts the node type used by `files/ls`.
+    ///
+    pub fn from_mfs(typ: i64) -> EntryKind {
+        match typ {
+            0 => EntryKind::File,
+            1 => EntryKind::Directory,
+            i => EntryKind::Unknown(i as i32),
+        }
+    }
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EntryKind::File => f.write_str("file"),
+            EntryKind::Directory => f.write_str("directory"),
+            EntryKind::Symlink => f.write_str("symlink"),
+            EntryKind::Unknown(i) => write!(f, "unknown ({})", i),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EntryKind {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_i64(serde::IntegerVisitor)
+            .map(EntryKind::from_unixfs)
+    }
+}
 
 #[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -22,10 +84,12 @@ pub struct IpfsFile {
 pub struct IpfsFileHeader {
     pub name: String,
     pub hash: String,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 
     #[serde(rename = "Type")]
-    pub typ: u32,
+    pub typ: EntryKind,
 }
 
 #[derive(Deserialize)]
@@ -37,6 +101,25 @@ pub struct LsResponse {
 
 #[cfg(test)]
 mod tests {
+    use super::{EntryKind, LsResponse};
+
     deserialize_test!(v0_ls_0, LsResponse);
     deserialize_test!(v0_ls_1, LsResponse);
+
+    #[test]
+    fn test_ls_entry_kinds() {
+        let raw = include_str!("tests/v0_ls_0.json");
+        let ls: LsResponse = ::serde_json::from_str(raw).unwrap();
+
+        assert_eq!(ls.objects[0].links[0].typ, EntryKind::Directory);
+    }
+
+    #[test]
+    fn test_unixfs_entry_kinds() {
+        assert_eq!(EntryKind::from_unixfs(2), EntryKind::File);
+        assert_eq!(EntryKind::from_unixfs(4), EntryKind::Symlink);
+        assert_eq!(EntryKind::from_unixfs(9), EntryKind::Unknown(9));
+        assert_eq!(EntryKind::from_mfs(0), EntryKind::File);
+        assert_eq!(EntryKind::Unknown(9).to_string(), "unknown (9)");
+    }
 }
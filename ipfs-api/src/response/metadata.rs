@@ -0,0 +1,167 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use header::{self, X_CHUNKED_OUTPUT, X_CONTENT_LENGTH, X_IPFS_PATH, X_STREAM_OUTPUT};
+use http::{HeaderMap, StatusCode};
+use std::sync::{Arc, Mutex};
+
+/// The status and headers the daemon responded with, returned alongside the
+/// body by the `*_with_metadata` methods.
+///
+#[derive(Clone, Debug)]
+pub struct ResponseMetadata {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    trailers: ReceivedTrailers,
+}
+
+/// The trailers of a response, which are only known once its body has been
+/// read to the end.
+///
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ReceivedTrailers(Arc<Mutex<Option<HeaderMap>>>);
+
+impl ReceivedTrailers {
+    #[cfg(any(feature = "hyper", test))]
+    #[inline]
+    pub fn set(&self, trailers: HeaderMap) {
+        *self.0.lock().unwrap() = Some(trailers);
+    }
+
+    #[inline]
+    pub fn get(&self) -> Option<HeaderMap> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl ResponseMetadata {
+    #[inline]
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap) -> ResponseMetadata {
+        ResponseMetadata {
+            status,
+            headers,
+            trailers: ReceivedTrailers::default(),
+        }
+    }
+
+    /// Fills in `trailers` from `received` once the body has been read.
+    ///
+    #[cfg(any(feature = "hyper", test))]
+    #[inline]
+    pub(crate) fn with_trailers(mut self, received: ReceivedTrailers) -> ResponseMetadata {
+        self.trailers = received;
+        self
+    }
+
+    /// Returns the value of a header, if it is set and valid utf-8.
+    ///
+    #[inline]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// The Ipfs path of the returned content (`X-Ipfs-Path`).
+    ///
+    #[inline]
+    pub fn ipfs_path(&self) -> Option<&str> {
+        self.header(X_IPFS_PATH)
+    }
+
+    /// The size of the content being streamed (`X-Content-Length`), which
+    /// the daemon sends instead of `Content-Length` for streamed output.
+    ///
+    #[inline]
+    pub fn content_length(&self) -> Option<u64> {
+        self.header(X_CONTENT_LENGTH)
+            .and_then(|len| len.parse().ok())
+    }
+
+    /// Whether the response body is a sequence of separately encoded
+    /// values (`X-Chunked-Output`).
+    ///
+    #[inline]
+    pub fn chunked_output(&self) -> bool {
+        self.header(X_CHUNKED_OUTPUT) == Some("1")
+    }
+
+    /// Whether the response body is streamed (`X-Stream-Output`).
+    ///
+    #[inline]
+    pub fn stream_output(&self) -> bool {
+        self.header(X_STREAM_OUTPUT) == Some("1")
+    }
+
+    /// The names of the trailers the daemon declared it may send after the
+    /// body (`Trailer`).
+    ///
+    #[inline]
+    pub fn trailer_names(&self) -> Vec<&str> {
+        header::declared_trailers(&self.headers)
+    }
+
+    /// The trailers the daemon sent after the body of a streamed response.
+    ///
+    /// `None` until the body has been read to the end, and for connections
+    /// that drop trailers, which HTTP/1.1 clients usually do. Only set by the
+    /// hyper backend.
+    ///
+    #[inline]
+    pub fn trailers(&self) -> Option<HeaderMap> {
+        self.trailers.get()
+    }
+
+    /// Returns the value of a trailer, if it was received and is valid
+    /// utf-8.
+    ///
+    pub fn trailer(&self, name: &str) -> Option<String> {
+        self.trailers()?
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReceivedTrailers, ResponseMetadata};
+    use http::{HeaderMap, HeaderValue, StatusCode};
+
+    #[test]
+    fn test_metadata_headers() {
+        let mut headers = HeaderMap::new();
+
+        headers.insert("X-Content-Length", HeaderValue::from_static("1024"));
+        headers.insert("X-Chunked-Output", HeaderValue::from_static("1"));
+        headers.insert("X-Ipfs-Path", HeaderValue::from_static("/ipfs/QmFoo"));
+
+        let metadata = ResponseMetadata::new(StatusCode::OK, headers);
+
+        assert_eq!(metadata.content_length(), Some(1024));
+        assert!(metadata.chunked_output());
+        assert!(!metadata.stream_output());
+        assert_eq!(metadata.ipfs_path(), Some("/ipfs/QmFoo"));
+        assert!(metadata.trailer_names().is_empty());
+    }
+
+    #[test]
+    fn test_metadata_trailers() {
+        let received = ReceivedTrailers::default();
+        let metadata =
+            ResponseMetadata::new(StatusCode::OK, HeaderMap::new()).with_trailers(received.clone());
+
+        assert!(metadata.trailers().is_none());
+
+        let mut trailers = HeaderMap::new();
+
+        trailers.insert("X-Foo", HeaderValue::from_static("bar"));
+        received.set(trailers);
+
+        assert_eq!(metadata.trailer("X-Foo"), Some("bar".into()));
+        assert_eq!(metadata.trailer("X-Stream-Error"), None);
+    }
+}
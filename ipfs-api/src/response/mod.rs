@@ -14,33 +14,46 @@ pub use self::block::*;
 pub use self::bootstrap::*;
 pub use self::commands::*;
 pub use self::config::*;
+#[cfg(feature = "dag")]
 pub use self::dag::*;
+#[cfg(feature = "dht")]
 pub use self::dht::*;
 pub use self::diag::*;
 pub use self::dns::*;
 pub use self::error::*;
 pub use self::file::*;
+#[cfg(feature = "files")]
 pub use self::files::*;
+#[cfg(feature = "filestore")]
 pub use self::filestore::*;
 pub use self::id::*;
+#[cfg(feature = "key")]
 pub use self::key::*;
 pub use self::log::*;
 pub use self::ls::*;
+pub use self::metadata::*;
 pub use self::mount::*;
+#[cfg(feature = "name")]
 pub use self::name::*;
+#[cfg(feature = "object")]
 pub use self::object::*;
+#[cfg(feature = "pin")]
 pub use self::pin::*;
 pub use self::ping::*;
+#[cfg(feature = "pubsub")]
 pub use self::pubsub::*;
 pub use self::refs::*;
 pub use self::repo::*;
 pub use self::resolve::*;
 pub use self::shutdown::*;
 pub use self::stats::*;
+#[cfg(feature = "swarm")]
 pub use self::swarm::*;
 pub use self::tar::*;
 pub use self::version::*;
 
+use cid::Cid;
+
 /// Create a test to deserialize a file to the given instance.
 ///
 #[cfg(test)]
@@ -51,8 +64,8 @@ macro_rules! deserialize_test {
             let raw = include_str!(concat!("tests/", stringify!($f), ".json"));
 
             match ::serde_json::from_str::<super::$ty>(raw) {
-                Ok(_) => assert!(true),
-                Err(e) => assert!(false, format!("failed with error: {}", e)),
+                Ok(_) => (),
+                Err(e) => panic!("failed with error: {}", e),
             };
         }
     };
@@ -64,23 +77,33 @@ mod block;
 mod bootstrap;
 mod commands;
 mod config;
+#[cfg(feature = "dag")]
 mod dag;
+#[cfg(feature = "dht")]
 mod dht;
 mod diag;
 mod dns;
 mod error;
 mod file;
+#[cfg(feature = "files")]
 mod files;
+#[cfg(feature = "filestore")]
 mod filestore;
 mod id;
+#[cfg(feature = "key")]
 mod key;
 mod log;
 mod ls;
+mod metadata;
 mod mount;
+#[cfg(feature = "name")]
 mod name;
+#[cfg(feature = "object")]
 mod object;
+#[cfg(feature = "pin")]
 mod pin;
 mod ping;
+#[cfg(feature = "pubsub")]
 mod pubsub;
 mod refs;
 mod repo;
@@ -88,6 +111,7 @@ mod resolve;
 mod serde;
 mod shutdown;
 mod stats;
+#[cfg(feature = "swarm")]
 mod swarm;
 mod tar;
 mod version;
@@ -96,7 +120,9 @@ mod version;
 #[serde(rename_all = "PascalCase")]
 pub struct IpfsHeader {
     pub name: String,
-    pub hash: String,
+    pub hash: Cid,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub size: u64,
 
     #[serde(rename = "Type")]
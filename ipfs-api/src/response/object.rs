@@ -6,29 +6,98 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use cid::Cid;
 use response::{serde, IpfsHeader};
-use std::collections::HashMap;
+use serde::de::{Deserialize, Deserializer, Error};
+
+/// A difference between two objects, found by `object_diff`. `path` is the
+/// path of the link that changed, relative to the objects compared, and is
+/// empty if the objects themselves differ.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectChange {
+    /// A link only the second object has.
+    Added { path: String, after: Cid },
+
+    /// A link only the first object has.
+    Removed { path: String, before: Cid },
+
+    /// A link that points to different content in each object.
+    Modified {
+        path: String,
+        before: Cid,
+        after: Cid,
+    },
+}
 
-#[derive(Debug, Deserialize)]
+impl ObjectChange {
+    /// The path of the link that changed.
+    ///
+    #[inline]
+    pub fn path(&self) -> &str {
+        match self {
+            ObjectChange::Added { path, .. }
+            | ObjectChange::Removed { path, .. }
+            | ObjectChange::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// A change as the api returns it, with a `Type` of 0 (added), 1 (removed)
+/// or 2 (modified), and `null` for a missing side.
+///
+#[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct ObjectDiff {
+struct RawObjectChange {
     #[serde(rename = "Type")]
-    pub typ: u64,
+    typ: u64,
+
+    path: String,
 
-    pub path: String,
+    #[serde(default)]
+    before: Option<Link>,
 
-    #[serde(deserialize_with = "serde::deserialize_hashmap")]
-    pub before: HashMap<String, String>,
+    #[serde(default)]
+    after: Option<Link>,
+}
 
-    #[serde(deserialize_with = "serde::deserialize_hashmap")]
-    pub after: HashMap<String, String>,
+#[derive(Deserialize)]
+struct Link(#[serde(deserialize_with = "serde::deserialize_link")] Cid);
+
+impl<'de> Deserialize<'de> for ObjectChange {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let RawObjectChange {
+            typ,
+            path,
+            before,
+            after,
+        } = RawObjectChange::deserialize(deserializer)?;
+
+        match (typ, before, after) {
+            (0, _, Some(Link(after))) => Ok(ObjectChange::Added { path, after }),
+            (1, Some(Link(before)), _) => Ok(ObjectChange::Removed { path, before }),
+            (2, Some(Link(before)), Some(Link(after))) => Ok(ObjectChange::Modified {
+                path,
+                before,
+                after,
+            }),
+            (typ, _, _) if typ > 2 => Err(D::Error::custom(format!(
+                "unknown object change type '{}'",
+                typ
+            ))),
+            _ => Err(D::Error::custom("object change is missing a link")),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ObjectDiffResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub changes: Vec<ObjectDiff>,
+    pub changes: Vec<ObjectChange>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +112,7 @@ pub struct ObjectGetResponse {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ObjectLinksResponse {
-    pub hash: String,
+    pub hash: Cid,
 
     #[serde(deserialize_with = "serde::deserialize_vec")]
     pub links: Vec<IpfsHeader>,
@@ -108,15 +177,79 @@ pub struct ObjectPutResponse {
 pub struct ObjectStatResponse {
     pub hash: String,
     pub num_links: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub block_size: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub links_size: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub data_size: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub cumulative_size: u64,
 }
 
 #[cfg(test)]
 mod tests {
     deserialize_test!(v0_object_diff_0, ObjectDiffResponse);
+
+    #[test]
+    fn test_object_changes() {
+        let res: super::ObjectDiffResponse = ::serde_json::from_str(
+            r#"{"Changes":[
+                {"Type":0,"Path":"a","Before":null,"After":{"/":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"}},
+                {"Type":1,"Path":"b","Before":{"/":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"},"After":null},
+                {"Type":2,"Path":"","Before":{"/":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"},"After":{"/":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"}}
+            ]}"#,
+        )
+        .unwrap();
+        let a: ::cid::Cid = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+            .parse()
+            .unwrap();
+        let b: ::cid::Cid = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            res.changes,
+            vec![
+                super::ObjectChange::Added {
+                    path: "a".into(),
+                    after: a.clone(),
+                },
+                super::ObjectChange::Removed {
+                    path: "b".into(),
+                    before: b.clone(),
+                },
+                super::ObjectChange::Modified {
+                    path: "".into(),
+                    before: b,
+                    after: a,
+                },
+            ]
+        );
+        assert_eq!(res.changes[1].path(), "b");
+        assert!(::serde_json::from_str::<super::ObjectDiffResponse>(
+            r#"{"Changes":[{"Type":3,"Path":"","Before":null,"After":null}]}"#
+        )
+        .is_err());
+    }
     deserialize_test!(v0_object_links_0, ObjectLinksResponse);
     deserialize_test!(v0_object_stat_0, ObjectStatResponse);
+
+    #[test]
+    fn test_object_links_are_cids() {
+        let res: super::ObjectLinksResponse =
+            ::serde_json::from_str(include_str!("tests/v0_object_links_0.json")).unwrap();
+
+        assert_eq!(res.hash.version(), 0);
+        assert_eq!(res.links[0].hash.codec(), 0x70);
+        assert_eq!(res.links[0].size, 1688);
+        assert!(::serde_json::from_str::<super::ObjectLinksResponse>(
+            r#"{"Hash":"QmNotACid","Links":[]}"#
+        )
+        .is_err());
+    }
 }
@@ -12,12 +12,32 @@ use std::collections::HashMap;
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct PinAddResponse {
-    #[serde(deserialize_with = "serde::deserialize_vec")]
+    #[serde(default, deserialize_with = "serde::deserialize_vec")]
     pub pins: Vec<String>,
 
     pub progress: Option<i32>,
 }
 
+/// An update from `pin_add_progress`.
+///
+#[derive(Debug, PartialEq)]
+pub enum PinAddEvent {
+    /// The number of blocks fetched so far.
+    Progress(u64),
+
+    /// The objects were pinned.
+    Pinned(Vec<String>),
+}
+
+impl From<PinAddResponse> for PinAddEvent {
+    fn from(res: PinAddResponse) -> PinAddEvent {
+        match res.progress {
+            Some(progress) if res.pins.is_empty() => PinAddEvent::Progress(progress.max(0) as u64),
+            _ => PinAddEvent::Pinned(res.pins),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct PinType {
@@ -43,4 +63,25 @@ pub struct PinRmResponse {
 mod tests {
     deserialize_test!(v0_pin_ls_0, PinLsResponse);
     deserialize_test!(v0_pin_add_0, PinAddResponse);
+    deserialize_test!(v0_pin_add_1, PinAddResponse);
+
+    #[test]
+    fn test_pin_add_event() {
+        use super::{PinAddEvent, PinAddResponse};
+
+        let progress = PinAddResponse {
+            pins: vec![],
+            progress: Some(12),
+        };
+        let pinned = PinAddResponse {
+            pins: vec!["QmFoo".into()],
+            progress: None,
+        };
+
+        assert_eq!(PinAddEvent::from(progress), PinAddEvent::Progress(12));
+        assert_eq!(
+            PinAddEvent::from(pinned),
+            PinAddEvent::Pinned(vec!["QmFoo".into()])
+        );
+    }
 }
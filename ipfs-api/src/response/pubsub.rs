@@ -37,6 +37,18 @@ pub struct PubsubSubResponse {
     pub unrecognized: Option<Vec<u8>>,
 }
 
+/// An item from `pubsub_sub_reconnecting`.
+///
+#[derive(Debug)]
+pub enum PubsubEvent {
+    /// A message published to the topic.
+    Message(PubsubSubResponse),
+
+    /// The subscription was lost, and the client subscribed again. Messages
+    /// published in between were missed.
+    Reconnected,
+}
+
 #[cfg(test)]
 mod tests {
     deserialize_test!(v0_pubsub_ls_0, PubsubLsResponse);
@@ -6,6 +6,8 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+pub type RefsResponse = RefsLocalResponse;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct RefsLocalResponse {
@@ -18,17 +18,47 @@ pub struct RepoFsckResponse {
 #[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct RepoGcResponse {
-    #[serde(deserialize_with = "serde::deserialize_hashmap")]
+    #[serde(default, deserialize_with = "serde::deserialize_hashmap")]
     pub key: HashMap<String, String>,
     pub error: Option<String>,
 }
 
+/// The outcome of `repo_gc_summary`.
+///
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RepoGcSummary {
+    /// The number of blocks removed.
+    pub removed: u64,
+
+    /// How much smaller the repo is after the collection, in bytes.
+    pub reclaimed: u64,
+
+    /// The errors reported for blocks that could not be removed.
+    pub errors: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct RepoStatResponse {
+    /// The number of objects in the repo, or 0 for a size-only request.
+    ///
+    #[serde(default)]
     pub num_objects: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_size")]
     pub repo_size: u64,
+
+    #[serde(default, deserialize_with = "serde::deserialize_size")]
+    pub storage_max: u64,
+
+    /// The repo's location, or empty for a size-only request.
+    ///
+    #[serde(default)]
     pub repo_path: String,
+
+    /// The repo's version, or empty for a size-only request.
+    ///
+    #[serde(default)]
     pub version: String,
 }
 
@@ -50,7 +80,32 @@ pub struct RepoVersionResponse {
 #[cfg(test)]
 mod tests {
     deserialize_test!(v0_repo_gc_0, RepoGcResponse);
+    deserialize_test!(v0_repo_gc_1, RepoGcResponse);
+    use super::RepoStatResponse;
+
     deserialize_test!(v0_repo_stat_0, RepoStatResponse);
+    deserialize_test!(v0_repo_stat_1, RepoStatResponse);
+    deserialize_test!(v0_repo_stat_2, RepoStatResponse);
+
+    #[test]
+    fn test_repo_stat_size_only() {
+        let raw = include_str!("tests/v0_repo_stat_1.json");
+        let stat: RepoStatResponse = ::serde_json::from_str(raw).unwrap();
+
+        assert_eq!(stat.repo_size, 27387827);
+        assert_eq!(stat.storage_max, 10000000000);
+        assert_eq!(stat.num_objects, 0);
+        assert!(stat.repo_path.is_empty());
+    }
+
+    #[test]
+    fn test_repo_stat_human() {
+        let raw = include_str!("tests/v0_repo_stat_2.json");
+        let stat: RepoStatResponse = ::serde_json::from_str(raw).unwrap();
+
+        assert_eq!(stat.repo_size, 27_000_000);
+        assert_eq!(stat.storage_max, 10 * 1024 * 1024 * 1024);
+    }
     deserialize_test!(v0_repo_verify_0, RepoVerifyResponse);
     deserialize_test!(v0_repo_verify_1, RepoVerifyResponse);
     deserialize_test!(v0_repo_version_0, RepoVersionResponse);
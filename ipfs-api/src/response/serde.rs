@@ -6,11 +6,16 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use cid::Cid;
+#[cfg(feature = "files")]
+use response::EntryKind;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::marker::PhantomData;
+#[cfg(feature = "files")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub struct IntegerVisitor;
 
@@ -169,3 +174,326 @@ where
 
     deserializer.deserialize_option(MapVisitor(PhantomData))
 }
+
+/// Deserializes a string, a sequence of strings, or null as a vec.
+///
+pub fn deserialize_string_or_vec<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Visits a string, sequence or null type, returning a vector with the
+    // one string, the sequence, or an empty vector.
+    //
+    struct StringOrVecVisitor;
+
+    impl<'de> Visitor<'de> for StringOrVecVisitor {
+        type Value = Vec<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("string, sequence or unit")
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(vec![s.into()])
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut vec = Vec::new();
+
+            while let Some(item) = seq.next_element()? {
+                vec.push(item);
+            }
+
+            Ok(vec)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(Default::default())
+        }
+    }
+
+    deserializer.deserialize_any(StringOrVecVisitor)
+}
+
+// Visits a string, or a map with a single "/" key holding a string.
+//
+struct LinkVisitor;
+
+impl<'de> Visitor<'de> for LinkVisitor {
+    type Value = Cid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("cid or ipld link")
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        match map.next_entry::<String, String>()? {
+            Some((ref key, ref cid)) if key == "/" => self.visit_str(cid),
+            _ => Err(de::Error::custom("expected an ipld link")),
+        }
+    }
+}
+
+/// Deserializes an IPLD link (`{"/": "<cid>"}`), or a plain string, as a CID.
+///
+pub fn deserialize_link<'de, D>(deserializer: D) -> Result<Cid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(LinkVisitor)
+}
+
+/// Deserializes a sequence of IPLD links or plain strings, or null, as a vec
+/// of CIDs.
+///
+pub fn deserialize_links<'de, D>(deserializer: D) -> Result<Vec<Cid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Link(Cid);
+
+    impl<'de> Deserialize<'de> for Link {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_link(deserializer).map(Link)
+        }
+    }
+
+    deserialize_vec(deserializer).map(|links: Vec<Link>| links.into_iter().map(|l| l.0).collect())
+}
+
+/// Deserializes a byte size encoded as either a number or a string.
+///
+pub fn deserialize_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Visits an unsigned integer, or a string holding one.
+    //
+    struct U64Visitor;
+
+    impl<'de> Visitor<'de> for U64Visitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("unsigned integer or string")
+        }
+
+        fn visit_u64<E>(self, num: u64) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(num)
+        }
+
+        fn visit_i64<E>(self, num: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if num < 0 {
+                Err(E::invalid_value(de::Unexpected::Signed(num), &self))
+            } else {
+                Ok(num as u64)
+            }
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            s.parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(s), &self))
+        }
+    }
+
+    deserializer.deserialize_any(U64Visitor)
+}
+
+/// Deserializes a byte size encoded as a number, a string holding one, or a
+/// human readable string such as `27 MB` or `9.3 GiB`.
+///
+pub fn deserialize_size<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Visits an unsigned integer, or a string holding a number optionally
+    // followed by a unit.
+    //
+    struct SizeVisitor;
+
+    impl<'de> Visitor<'de> for SizeVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("byte size")
+        }
+
+        fn visit_u64<E>(self, num: u64) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(num)
+        }
+
+        fn visit_i64<E>(self, num: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if num < 0 {
+                Err(E::invalid_value(de::Unexpected::Signed(num), &self))
+            } else {
+                Ok(num as u64)
+            }
+        }
+
+        fn visit_f64<E>(self, num: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if num < 0.0 {
+                Err(E::invalid_value(de::Unexpected::Float(num), &self))
+            } else {
+                Ok(num as u64)
+            }
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            parse_size(s).ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &self))
+        }
+    }
+
+    deserializer.deserialize_any(SizeVisitor)
+}
+
+/// Parses a size as printed by go-ipfs with `--human`, e.g. `27 MB`.
+///
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+
+    if let Ok(num) = num.parse::<u64>() {
+        if unit.trim().is_empty() {
+            return Some(num);
+        }
+    }
+
+    let num: f64 = num.parse().ok()?;
+    let multiplier: u64 = match unit.trim() {
+        "" | "B" => 1,
+        "kB" | "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "PB" => 1_000_000_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        "PiB" => 1 << 50,
+        _ => return None,
+    };
+
+    Some((num * multiplier as f64).round() as u64)
+}
+
+/// Deserializes the `Type` of an entry listed by `files/ls`, which uses
+/// different numbers than `ls`.
+///
+#[cfg(feature = "files")]
+pub fn deserialize_mfs_entry_kind<'de, D>(deserializer: D) -> Result<EntryKind, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer
+        .deserialize_i64(IntegerVisitor)
+        .map(EntryKind::from_mfs)
+}
+
+/// Deserializes a unix timestamp in seconds, or null, as a time.
+///
+#[cfg(feature = "files")]
+pub fn deserialize_unix_time<'de, D>(deserializer: D) -> Result<Option<SystemTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    // Visits an integer or null type, returning the time that many seconds
+    // from the unix epoch.
+    //
+    struct UnixTimeVisitor;
+
+    impl<'de> Visitor<'de> for UnixTimeVisitor {
+        type Value = Option<SystemTime>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("unix timestamp or unit")
+        }
+
+        fn visit_u64<E>(self, secs: u64) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(Some(UNIX_EPOCH + Duration::from_secs(secs)))
+        }
+
+        fn visit_i64<E>(self, secs: i64) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if secs < 0 {
+                Ok(Some(UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())))
+            } else {
+                Ok(Some(UNIX_EPOCH + Duration::from_secs(secs as u64)))
+            }
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    deserializer.deserialize_option(UnixTimeVisitor)
+}
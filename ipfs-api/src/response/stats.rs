@@ -6,15 +6,19 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use response::{BitswapStatResponse, RepoStatResponse};
+use response::{serde, BitswapStatResponse, RepoStatResponse};
 
 pub type StatsBitswapResponse = BitswapStatResponse;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct StatsBwResponse {
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub total_in: u64,
+
+    #[serde(deserialize_with = "serde::deserialize_u64")]
     pub total_out: u64,
+
     pub rate_in: f64,
     pub rate_out: f64,
 }
@@ -6,22 +6,26 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+use addr::Multiaddr;
+use peer::PeerId;
 use response::serde;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SwarmAddrsLocalResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
-    pub strings: Vec<String>,
+    pub strings: Vec<Multiaddr>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SwarmAddrsConnectResponse {
     #[serde(deserialize_with = "serde::deserialize_vec")]
     pub strings: Vec<String>,
 }
 
+pub type SwarmConnectResponse = SwarmAddrsConnectResponse;
+
 #[derive(Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SwarmAddrsDisconnectResponse {
@@ -52,8 +56,8 @@ pub struct SwarmPeerStream {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SwarmPeer {
-    pub addr: String,
-    pub peer: String,
+    pub addr: Multiaddr,
+    pub peer: PeerId,
     pub latency: String,
     pub muxer: String,
 
@@ -71,6 +75,7 @@ pub struct SwarmPeersResponse {
 #[cfg(test)]
 mod tests {
     deserialize_test!(v0_swarm_addrs_local_0, SwarmAddrsLocalResponse);
+    deserialize_test!(v0_swarm_connect_0, SwarmConnectResponse);
     deserialize_test!(v0_swarm_peers_0, SwarmPeersResponse);
     deserialize_test!(v0_swarm_peers_1, SwarmPeersResponse);
     deserialize_test!(v0_swarm_peers_2, SwarmPeersResponse);
@@ -0,0 +1,312 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use bytes::Bytes;
+use client::AsyncStreamResponse;
+use futures::{try_ready, Async, Future, Poll, Stream};
+use response::Error;
+use std::time::{Duration, Instant};
+use tokio::timer::Delay;
+
+/// The number of times a download is re-requested in a row, without
+/// receiving any bytes in between, before its error is returned.
+///
+pub const RESUME_ATTEMPTS: usize = 3;
+
+/// How long to wait before re-requesting a download the first time it
+/// fails. The wait doubles with each attempt in a row.
+///
+pub const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Returns true if a request that failed with `err` might succeed if it is
+/// sent again (the connection dropped, or a proxy timed out), and false if
+/// the daemon rejected it or failed while handling it.
+///
+pub fn is_transient(err: &Error) -> bool {
+    match err.without_context() {
+        #[cfg(feature = "hyper")]
+        Error::Client(_) => true,
+        #[cfg(feature = "actix")]
+        Error::Client(_) | Error::ClientPayload(_) | Error::ClientSend(_) => true,
+        Error::Connect { .. }
+        | Error::ConnectionRefused { .. }
+        | Error::Timeout { .. }
+        | Error::Io(_)
+        | Error::BadGateway { .. } => true,
+        _ => false,
+    }
+}
+
+enum State {
+    Receiving(AsyncStreamResponse<Bytes>),
+    Waiting(Delay),
+}
+
+/// A download that is re-requested from where it left off when it fails
+/// with a transient error, so the consumer sees a single stream.
+///
+/// `request` is called with the number of bytes received so far, and must
+/// return the rest of the download starting at that offset.
+///
+pub struct Resume<F> {
+    request: F,
+    state: State,
+    received: u64,
+    attempts: usize,
+    initial_backoff: Duration,
+}
+
+impl<F> Resume<F>
+where
+    F: FnMut(u64) -> AsyncStreamResponse<Bytes>,
+{
+    #[inline]
+    pub fn new(request: F) -> Resume<F> {
+        Resume::with_backoff(request, INITIAL_BACKOFF)
+    }
+
+    /// Creates a download that waits `initial_backoff` before it is first
+    /// re-requested, instead of `INITIAL_BACKOFF`.
+    ///
+    pub fn with_backoff(mut request: F, initial_backoff: Duration) -> Resume<F> {
+        let state = State::Receiving(request(0));
+
+        Resume {
+            request,
+            state,
+            received: 0,
+            attempts: 0,
+            initial_backoff,
+        }
+    }
+}
+
+impl<F> Stream for Resume<F>
+where
+    F: FnMut(u64) -> AsyncStreamResponse<Bytes>,
+{
+    type Item = Bytes;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            let next = match self.state {
+                State::Receiving(ref mut current) => match current.poll() {
+                    Ok(Async::Ready(Some(chunk))) => {
+                        if !chunk.is_empty() {
+                            self.received += chunk.len() as u64;
+                            self.attempts = 0;
+                        }
+
+                        return Ok(Async::Ready(Some(chunk)));
+                    }
+                    Ok(poll) => return Ok(poll),
+                    Err(ref e) if is_transient(e) && self.attempts + 1 < RESUME_ATTEMPTS => {
+                        let backoff = self.initial_backoff * (1 << self.attempts);
+
+                        self.attempts += 1;
+
+                        State::Waiting(Delay::new(Instant::now() + backoff))
+                    }
+                    Err(e) => return Err(e),
+                },
+                State::Waiting(ref mut delay) => match delay.poll() {
+                    Ok(Async::Ready(())) => State::Receiving((self.request)(self.received)),
+                    Ok(Async::NotReady) => return Ok(Async::NotReady),
+                    Err(e) => return Err(Error::Uncategorized(e.to_string())),
+                },
+            };
+
+            self.state = next;
+        }
+    }
+}
+
+/// A stream of bytes with the first `skip` bytes left out, for downloads
+/// that can't be requested from an offset.
+///
+pub struct SkipBytes<S> {
+    stream: S,
+    skip: u64,
+}
+
+impl<S> SkipBytes<S>
+where
+    S: Stream<Item = Bytes>,
+{
+    pub fn new(stream: S, skip: u64) -> SkipBytes<S> {
+        SkipBytes { stream, skip }
+    }
+}
+
+impl<S> Stream for SkipBytes<S>
+where
+    S: Stream<Item = Bytes>,
+{
+    type Item = Bytes;
+
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            match try_ready!(self.stream.poll()) {
+                Some(mut chunk) => {
+                    if self.skip == 0 {
+                        return Ok(Async::Ready(Some(chunk)));
+                    }
+
+                    if (chunk.len() as u64) <= self.skip {
+                        self.skip -= chunk.len() as u64;
+                    } else {
+                        let rest = chunk.split_off(self.skip as usize);
+
+                        self.skip = 0;
+
+                        return Ok(Async::Ready(Some(rest)));
+                    }
+                }
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Resume, SkipBytes};
+    use bytes::Bytes;
+    use client::AsyncStreamResponse;
+    use futures::{stream, Future, Stream};
+    use response::{Error, ErrorContext};
+    use std::time::{Duration, Instant};
+    use tokio::runtime::current_thread::Runtime;
+
+    fn response(chunks: Vec<Result<&'static str, Error>>) -> AsyncStreamResponse<Bytes> {
+        Box::new(stream::iter_result(
+            chunks.into_iter().map(|chunk| chunk.map(Bytes::from)),
+        ))
+    }
+
+    fn timeout() -> Error {
+        Error::Timeout {
+            uri: "http://localhost:5001/api/v0".into(),
+        }
+    }
+
+    #[test]
+    fn test_resume_from_offset() {
+        let mut offsets = Vec::new();
+        let body = Resume::with_backoff(
+            |offset| {
+                offsets.push(offset);
+
+                match offset {
+                    0 => response(vec![Ok("abc"), Err(timeout())]),
+                    _ => response(vec![Ok("def")]),
+                }
+            },
+            Duration::from_millis(1),
+        )
+        .concat2();
+        let body = Runtime::new().unwrap().block_on(body).unwrap();
+
+        assert_eq!(body, "abcdef");
+        assert_eq!(offsets, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_resume_with_error_context() {
+        let context = ErrorContext::new("/cat", "arg=QmA");
+        let mut requests = 0;
+        let body = Resume::with_backoff(
+            |_| {
+                requests += 1;
+
+                match requests {
+                    1 => response(vec![Err(context.wrap(timeout()))]),
+                    _ => response(vec![Ok("abc")]),
+                }
+            },
+            Duration::from_millis(1),
+        )
+        .concat2();
+        let body = Runtime::new().unwrap().block_on(body).unwrap();
+
+        assert_eq!(body, "abc");
+        assert_eq!(requests, 2);
+    }
+
+    #[test]
+    fn test_resume_waits_between_attempts() {
+        let backoff = Duration::from_millis(20);
+        let started = Instant::now();
+        let res = Resume::with_backoff(|_| response(vec![Err(timeout())]), backoff).concat2();
+        let res = Runtime::new().unwrap().block_on(res);
+
+        assert!(res.is_err());
+        assert!(started.elapsed() >= backoff * 3);
+    }
+
+    #[test]
+    fn test_resume_gives_up() {
+        let mut requests = 0;
+        let res = Resume::with_backoff(
+            |_| {
+                requests += 1;
+
+                response(vec![Err(timeout())])
+            },
+            Duration::from_millis(1),
+        )
+        .concat2();
+        let res = Runtime::new().unwrap().block_on(res);
+
+        assert!(res.is_err());
+        assert_eq!(requests, super::RESUME_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_resume_does_not_retry_daemon_errors() {
+        let requests_until = |error: Error| {
+            let mut error = Some(error);
+            let mut requests = 0;
+            let res = Resume::new(|_| {
+                requests += 1;
+
+                response(vec![Err(error.take().unwrap())])
+            })
+            .concat2()
+            .wait();
+
+            assert!(res.is_err());
+            requests
+        };
+
+        assert_eq!(
+            requests_until(Error::Uncategorized("no link named".into())),
+            1
+        );
+        assert_eq!(
+            requests_until(Error::StreamError("context canceled".into())),
+            1
+        );
+    }
+
+    #[test]
+    fn test_skip_bytes() {
+        let chunks = stream::iter_ok::<_, Error>(vec![
+            Bytes::from("ab"),
+            Bytes::from("cdef"),
+            Bytes::from("g"),
+        ]);
+        let body = SkipBytes::new(chunks, 3).concat2().wait().unwrap();
+
+        assert_eq!(body, "defg");
+    }
+}
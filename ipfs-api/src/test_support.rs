@@ -0,0 +1,739 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! A local http server that answers `/api/v0/*` requests with canned
+//! fixtures, for testing code that uses `IpfsClient` end to end without
+//! running a daemon.
+//!
+//! Unlike `MockIpfsClient`, the requests go through the real client, so the
+//! query arguments it sends and the way it decodes (streamed) responses are
+//! tested as well.
+//!
+//! ```
+//! # extern crate futures;
+//! # extern crate ipfs_api;
+//! # extern crate tokio;
+//! #
+//! use ipfs_api::test_support::{Fixture, MockServer};
+//! use tokio::runtime::current_thread::Runtime;
+//!
+//! # fn main() {
+//! let server = MockServer::start();
+//!
+//! server.push(
+//!     "version",
+//!     Fixture::json(r#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11"}"#),
+//! );
+//!
+//! let mut runtime = Runtime::new().unwrap();
+//! let version = runtime.block_on(server.client().version()).unwrap();
+//!
+//! assert_eq!(version.version, "0.4.18");
+//! assert_eq!(server.requests()[0].path, "/api/v0/version");
+//! # }
+//! ```
+//!
+//! Fixtures can also be recorded from a real daemon with
+//! `MockServer::record`, which forwards every request to the daemon and
+//! writes the responses to a directory, and replayed later with
+//! `MockServer::replay`. Each response is saved as a `.json` file with its
+//! status and headers, next to a `.body` file with the raw body.
+//!
+
+use bytes::Bytes;
+use client::IpfsClient;
+use futures::{future, stream, sync::oneshot, Future, Stream};
+use header::{TRAILER, X_CONTENT_LENGTH, X_STREAM_ERROR};
+use http::{
+    header::{HeaderName, CONTENT_TYPE},
+    HeaderMap, Method, Request, Response, StatusCode, Uri,
+};
+use hyper::{self, client::HttpConnector, service::service_fn, Client, Server};
+use serde_json;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+use tokio::runtime::current_thread::Runtime;
+
+/// The prefix of the path of every api endpoint.
+///
+const API_PREFIX: &str = "/api/v0/";
+
+/// A canned response, returned for one request to an endpoint.
+///
+#[derive(Clone, Debug)]
+pub struct Fixture {
+    status: StatusCode,
+    headers: Vec<(String, String)>,
+    chunks: Vec<Bytes>,
+}
+
+impl Fixture {
+    /// A `200 OK` response with a json body.
+    ///
+    pub fn json<B>(body: B) -> Fixture
+    where
+        B: Into<Bytes>,
+    {
+        Fixture::chunks(vec![body.into()]).with_header(CONTENT_TYPE.as_str(), "application/json")
+    }
+
+    /// A `200 OK` response with a body of raw bytes.
+    ///
+    #[inline]
+    pub fn bytes<B>(body: B) -> Fixture
+    where
+        B: Into<Bytes>,
+    {
+        Fixture::chunks(vec![body.into()])
+    }
+
+    /// A `200 OK` response sent with chunked transfer encoding, one chunk at
+    /// a time, the way the daemon streams responses.
+    ///
+    pub fn chunks<I, B>(chunks: I) -> Fixture
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Bytes>,
+    {
+        Fixture {
+            status: StatusCode::OK,
+            headers: Vec::new(),
+            chunks: chunks.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// A response with the json error body the daemon sends when it rejects
+    /// a request.
+    ///
+    pub fn api_error(status: StatusCode, message: &str) -> Fixture {
+        let body = format!(
+            r#"{{"Message":{},"Code":0,"Type":"error"}}"#,
+            serde_json::to_string(message).unwrap()
+        );
+
+        Fixture::json(body).with_status(status)
+    }
+
+    /// Sets the status of the response.
+    ///
+    #[inline]
+    pub fn with_status(mut self, status: StatusCode) -> Fixture {
+        self.status = status;
+        self
+    }
+
+    /// Adds a header to the response.
+    ///
+    #[inline]
+    pub fn with_header(mut self, name: &str, value: &str) -> Fixture {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Ends a stream of json objects with an error, after the chunks that
+    /// were already sent, the way the daemon reports an error that happens
+    /// after it started responding.
+    ///
+    pub fn with_stream_error(mut self, message: &str) -> Fixture {
+        self.chunks
+            .push(format!("{}: {}\n", X_STREAM_ERROR, message).into());
+        self.with_header(TRAILER.as_str(), "X-Stream-Error")
+    }
+
+    /// Declares the length of a byte stream with the `X-Content-Length`
+    /// header. A body shorter than `length` ends with an error, which is how
+    /// errors in byte streams are reported.
+    ///
+    pub fn with_content_length(self, length: u64) -> Fixture {
+        self.with_header(TRAILER.as_str(), "X-Stream-Error")
+            .with_header(X_CONTENT_LENGTH, &length.to_string())
+    }
+
+    fn into_response(self) -> Response<hyper::Body> {
+        let mut builder = Response::builder();
+
+        builder.status(self.status);
+
+        for (name, value) in &self.headers {
+            builder.header(name.as_str(), value.as_str());
+        }
+
+        let body = hyper::Body::wrap_stream(stream::iter_ok::<_, io::Error>(self.chunks));
+
+        builder.body(body).unwrap()
+    }
+}
+
+/// A request received by a `MockServer`.
+///
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: Method,
+
+    /// The path of the request, including the `/api/v0/` prefix.
+    ///
+    pub path: String,
+
+    /// The query string, without the leading `?`.
+    ///
+    pub query: Option<String>,
+
+    pub body: Bytes,
+}
+
+/// Headers of a recorded response that describe the connection it was sent
+/// over, rather than the response itself, and aren't saved.
+///
+const UNRECORDED_HEADERS: &[&str] = &[
+    "access-control-allow-headers",
+    "access-control-expose-headers",
+    "connection",
+    "content-length",
+    "date",
+    "transfer-encoding",
+    "vary",
+];
+
+/// A response recorded from a daemon, as it is saved to disk.
+///
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    path: String,
+    query: Option<String>,
+    status: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl Interaction {
+    fn into_fixture(self, body: Bytes) -> Result<Fixture, io::Error> {
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        Ok(Fixture {
+            status,
+            headers: self.headers,
+            chunks: vec![body],
+        })
+    }
+}
+
+/// Forwards requests to a daemon, and saves its responses.
+///
+struct Recording {
+    client: Client<HttpConnector>,
+    upstream: SocketAddr,
+    dir: PathBuf,
+    next: usize,
+}
+
+#[derive(Default)]
+struct State {
+    fixtures: HashMap<String, VecDeque<Fixture>>,
+    requests: Vec<RecordedRequest>,
+    recording: Option<Recording>,
+    connections: usize,
+}
+
+/// A local http server, which returns the fixtures pushed for an endpoint
+/// in order, and `404 Not Found` for endpoints without any left.
+///
+/// The server is shut down when it is dropped.
+///
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on a free port of the loopback interface.
+    ///
+    #[inline]
+    pub fn start() -> MockServer {
+        MockServer::serve(State::default())
+    }
+
+    /// Starts a server that forwards every request to the daemon's api at
+    /// `upstream`, and saves each response to `dir` (which is created if it
+    /// doesn't exist), to be replayed with `MockServer::replay`.
+    ///
+    pub fn record<P>(upstream: SocketAddr, dir: P) -> io::Result<MockServer>
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref().to_path_buf();
+
+        fs::create_dir_all(&dir)?;
+
+        Ok(MockServer::serve(State {
+            recording: Some(Recording {
+                client: Client::new(),
+                upstream,
+                dir,
+                next: 0,
+            }),
+            ..State::default()
+        }))
+    }
+
+    /// Starts a server that returns the responses saved to `dir` by
+    /// `MockServer::record`. The responses to each endpoint are returned in
+    /// the order they were recorded.
+    ///
+    pub fn replay<P>(dir: P) -> io::Result<MockServer>
+    where
+        P: AsRef<Path>,
+    {
+        let mut names: Vec<PathBuf> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?;
+        let mut state = State::default();
+
+        names.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+        names.sort();
+
+        for path in names {
+            let interaction: Interaction = serde_json::from_slice(&fs::read(&path)?)?;
+            let body = fs::read(path.with_extension("body"))?;
+
+            state
+                .fixtures
+                .entry(interaction.path.clone())
+                .or_default()
+                .push_back(interaction.into_fixture(body.into())?);
+        }
+
+        Ok(MockServer::serve(state))
+    }
+
+    fn serve(state: State) -> MockServer {
+        let state = Arc::new(Mutex::new(state));
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let (addr_tx, addr_rx) = mpsc::channel();
+        let server_state = state.clone();
+
+        let thread = thread::spawn(move || {
+            let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(move || {
+                let state = server_state.clone();
+
+                state.lock().unwrap().connections += 1;
+
+                service_fn(move |req| MockServer::respond(&state, req))
+            });
+
+            addr_tx.send(server.local_addr()).unwrap();
+
+            let server = server
+                .map_err(|_| ())
+                .select(shutdown_rx.map_err(|_| ()))
+                .then(|_| Ok::<_, ()>(()));
+
+            Runtime::new().unwrap().block_on(server).unwrap();
+        });
+
+        MockServer {
+            addr: addr_rx.recv().expect("mock server failed to start"),
+            state,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        }
+    }
+
+    /// The address the server is listening on.
+    ///
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// A client connected to the server.
+    ///
+    #[inline]
+    pub fn client(&self) -> IpfsClient {
+        IpfsClient::from_ip(self.addr.ip(), self.addr.port())
+    }
+
+    /// Queues a fixture to be returned for the next request to `endpoint`
+    /// (e.g. `"version"` or `"files/ls"`).
+    ///
+    pub fn push(&self, endpoint: &str, fixture: Fixture) {
+        let path = format!("{}{}", API_PREFIX, endpoint.trim_start_matches('/'));
+
+        self.state
+            .lock()
+            .unwrap()
+            .fixtures
+            .entry(path)
+            .or_default()
+            .push_back(fixture);
+    }
+
+    /// The requests received so far, in the order they arrived.
+    ///
+    #[inline]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The number of connections accepted so far.
+    ///
+    #[inline]
+    pub fn connections(&self) -> usize {
+        self.state.lock().unwrap().connections
+    }
+
+    fn respond(
+        state: &Arc<Mutex<State>>,
+        req: Request<hyper::Body>,
+    ) -> Box<dyn Future<Item = Response<hyper::Body>, Error = hyper::Error> + Send> {
+        let state = state.clone();
+        let (parts, body) = req.into_parts();
+
+        let res =
+            body.concat2()
+                .and_then(move |body| -> Box<dyn Future<Item = _, Error = _> + Send> {
+                    let body = body.into_bytes();
+                    let path = parts.uri.path().to_string();
+                    let mut state = state.lock().unwrap();
+
+                    state.requests.push(RecordedRequest {
+                        method: parts.method.clone(),
+                        path: path.clone(),
+                        query: parts.uri.query().map(Into::into),
+                        body: body.clone(),
+                    });
+
+                    if let Some(ref mut recording) = state.recording {
+                        let forward = MockServer::forward(
+                            recording,
+                            parts.method,
+                            parts.uri,
+                            &parts.headers,
+                            body,
+                        );
+
+                        recording.next += 1;
+
+                        return Box::new(forward);
+                    }
+
+                    let res = match state.fixtures.get_mut(&path).and_then(VecDeque::pop_front) {
+                        Some(fixture) => fixture.into_response(),
+                        // The daemon responds to unknown endpoints like this.
+                        //
+                        None => Fixture::bytes("404 page not found\n")
+                            .with_status(StatusCode::NOT_FOUND)
+                            .into_response(),
+                    };
+
+                    Box::new(future::ok(res))
+                });
+
+        Box::new(res)
+    }
+
+    /// Sends a request to the daemon being recorded, and saves its response
+    /// before returning it.
+    ///
+    fn forward(
+        recording: &Recording,
+        method: Method,
+        uri: Uri,
+        headers: &HeaderMap,
+        body: Bytes,
+    ) -> impl Future<Item = Response<hyper::Body>, Error = hyper::Error> {
+        let index = recording.next;
+        let dir = recording.dir.clone();
+        let path_and_query = uri.path_and_query().map_or("/", |p| p.as_str());
+        let mut builder = Request::builder();
+
+        builder
+            .method(method.clone())
+            .uri(format!("http://{}{}", recording.upstream, path_and_query));
+
+        // Uploads are multipart forms, whose boundary is in the content type.
+        //
+        if let Some(content_type) = headers.get(CONTENT_TYPE) {
+            builder.header(CONTENT_TYPE, content_type.clone());
+        }
+
+        let req = builder.body(hyper::Body::from(body)).unwrap();
+
+        recording.client.request(req).and_then(move |res| {
+            let (parts, body) = res.into_parts();
+
+            body.concat2().map(move |body| {
+                let body = body.into_bytes();
+                let interaction = Interaction {
+                    method: method.to_string(),
+                    path: uri.path().into(),
+                    query: uri.query().map(Into::into),
+                    status: parts.status.as_u16(),
+                    headers: parts
+                        .headers
+                        .iter()
+                        .filter(|(name, _)| !UNRECORDED_HEADERS.contains(&name.as_str()))
+                        .map(|(name, value)| {
+                            (
+                                header_name(name),
+                                String::from_utf8_lossy(value.as_bytes()).into(),
+                            )
+                        })
+                        .collect(),
+                };
+
+                match save_interaction(&dir, index, interaction, body) {
+                    Ok(fixture) => fixture.into_response(),
+                    Err(e) => Fixture::bytes(format!("failed to record response: {}", e))
+                        .with_status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .into_response(),
+                }
+            })
+        })
+    }
+}
+
+/// The name of a recorded header, capitalized the way the daemon sends it
+/// (hyper lowercases header names).
+///
+fn header_name(name: &HeaderName) -> String {
+    name.as_str()
+        .split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Writes a recorded response to `dir`, as `<index>-<endpoint>.json` and
+/// `<index>-<endpoint>.body`, and returns it as a fixture.
+///
+fn save_interaction(
+    dir: &Path,
+    index: usize,
+    interaction: Interaction,
+    body: Bytes,
+) -> io::Result<Fixture> {
+    let endpoint = interaction
+        .path
+        .trim_start_matches(API_PREFIX)
+        .replace('/', "_");
+    let name = dir.join(format!("{:04}-{}", index, endpoint));
+
+    fs::write(
+        name.with_extension("json"),
+        serde_json::to_vec_pretty(&interaction)?,
+    )?;
+    fs::write(name.with_extension("body"), &body)?;
+
+    interaction.into_fixture(body)
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fixture, MockServer};
+    use futures::Stream;
+    use http::StatusCode;
+    use response::Error;
+    use std::{env, fs, process};
+    use tokio::runtime::current_thread::Runtime;
+
+    #[test]
+    fn test_json_response() {
+        let server = MockServer::start();
+
+        server.push(
+            "block/stat",
+            Fixture::json(r#"{"Key":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":12}"#),
+        );
+
+        let res = Runtime::new()
+            .unwrap()
+            .block_on(
+                server
+                    .client()
+                    .block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"),
+            )
+            .unwrap();
+        let requests = server.requests();
+
+        assert_eq!(res.size, 12);
+        assert_eq!(requests[0].path, "/api/v0/block/stat");
+        assert_eq!(
+            requests[0].query.as_ref().unwrap(),
+            "arg=QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"
+        );
+    }
+
+    #[test]
+    fn test_streamed_json_with_stream_error() {
+        let server = MockServer::start();
+
+        server.push(
+            "refs/local",
+            Fixture::chunks(vec![
+                "{\"Ref\":\"QmA\",\"Err\":\"\"}\n",
+                "{\"Ref\":\"QmB\",\"Err\":\"\"}\n",
+            ])
+            .with_stream_error("context canceled"),
+        );
+
+        let res = Runtime::new()
+            .unwrap()
+            .block_on(server.client().refs_local().then(Ok::<_, ()>).collect())
+            .unwrap();
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[1].as_ref().unwrap().reference, "QmB");
+
+        match res[2] {
+            Err(Error::StreamError(ref e)) => assert_eq!(e, "context canceled"),
+            ref e => panic!("expected a stream error, got {:?}", e.as_ref().map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_truncated_byte_stream() {
+        let server = MockServer::start();
+
+        server.push(
+            "cat",
+            Fixture::chunks(vec!["Hello ", "Wor"]).with_content_length(12),
+        );
+
+        let res = Runtime::new().unwrap().block_on(
+            server
+                .client()
+                .cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+                .concat2(),
+        );
+
+        match res {
+            Err(Error::StreamError(_)) => (),
+            res => panic!("expected a stream error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_unknown_endpoint() {
+        let server = MockServer::start();
+        let res = Runtime::new().unwrap().block_on(server.client().version());
+
+        match res {
+            Err(Error::UnsupportedEndpoint { status, .. }) => {
+                assert_eq!(status, StatusCode::NOT_FOUND)
+            }
+            res => panic!(
+                "expected an unsupported endpoint, got {:?}",
+                res.map(|_| ())
+            ),
+        }
+    }
+
+    #[test]
+    fn test_api_error() {
+        let server = MockServer::start();
+
+        server.push(
+            "block/stat",
+            Fixture::api_error(StatusCode::INTERNAL_SERVER_ERROR, "merkledag: not found"),
+        );
+
+        let res = Runtime::new().unwrap().block_on(
+            server
+                .client()
+                .block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"),
+        );
+
+        match res {
+            Err(Error::Api(ref e)) => assert_eq!(e.message, "merkledag: not found"),
+            res => panic!("expected an api error, got {:?}", res.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = env::temp_dir().join(format!("ipfs-api-record-{}", process::id()));
+        let daemon = MockServer::start();
+
+        daemon.push(
+            "block/stat",
+            Fixture::json(r#"{"Key":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":12}"#),
+        );
+        daemon.push("cat", Fixture::chunks(vec!["Hello ", "World!"]));
+
+        let mut runtime = Runtime::new().unwrap();
+
+        {
+            let recorder = MockServer::record(daemon.addr(), &dir).unwrap();
+            let client = recorder.client();
+
+            runtime
+                .block_on(client.block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"))
+                .unwrap();
+            runtime
+                .block_on(
+                    client
+                        .cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+                        .concat2(),
+                )
+                .unwrap();
+        }
+
+        assert!(dir.join("0000-block_stat.json").exists());
+        assert_eq!(
+            fs::read(dir.join("0001-cat.body")).unwrap(),
+            b"Hello World!"
+        );
+
+        let replay = MockServer::replay(&dir).unwrap();
+        let client = replay.client();
+        let stat = runtime
+            .block_on(client.block_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"))
+            .unwrap();
+        let body = runtime
+            .block_on(
+                client
+                    .cat("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+                    .concat2(),
+            )
+            .unwrap();
+
+        assert_eq!(stat.size, 12);
+        assert_eq!(body, "Hello World!");
+        assert_eq!(daemon.requests().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
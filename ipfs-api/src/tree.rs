@@ -0,0 +1,384 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+#[cfg(feature = "actix")]
+use actix_multipart::client::multipart;
+#[cfg(feature = "hyper")]
+use hyper_multipart::client::multipart;
+use response::Error;
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+use walkdir::{DirEntry, WalkDir};
+
+/// A hard limit on the number of files kept open while a tree is uploaded.
+/// The smaller files past the limit are read into memory.
+///
+const FILE_DESCRIPTOR_LIMIT: usize = 127;
+
+/// How symlinks are added by `add_tree`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymlinkMode {
+    /// Symlinks are added as symlinks, pointing to their original target.
+    Preserve,
+
+    /// Symlinks are followed, and what they point to is added in their place.
+    Dereference,
+}
+
+impl Default for SymlinkMode {
+    #[inline]
+    fn default() -> SymlinkMode {
+        SymlinkMode::Preserve
+    }
+}
+
+/// Options for adding a directory with `add_tree_with_options`.
+///
+/// The defaults match `ipfs add -r`: hidden files are skipped, and symlinks
+/// are preserved.
+///
+/// ```
+/// # extern crate ipfs_api;
+/// #
+/// use ipfs_api::{AddTreeOptions, SymlinkMode};
+///
+/// # fn main() {
+/// let mut options = AddTreeOptions::default();
+/// options.hidden = true;
+/// options.symlinks = SymlinkMode::Dereference;
+/// # }
+/// ```
+///
+#[derive(Clone, Debug, Default)]
+pub struct AddTreeOptions {
+    /// Include files and directories whose names start with a `.`.
+    pub hidden: bool,
+
+    pub symlinks: SymlinkMode,
+}
+
+/// What an entry in the tree is added as.
+///
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Dir,
+    File(u64),
+    Symlink(PathBuf),
+}
+
+#[inline]
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// Lists every file, directory and symlink under `path` that is added,
+/// directories before their contents.
+///
+fn entries(path: &Path, options: &AddTreeOptions) -> Result<Vec<(PathBuf, Kind)>, Error> {
+    let walk = WalkDir::new(path)
+        .follow_links(options.symlinks == SymlinkMode::Dereference)
+        .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+        .into_iter()
+        .filter_entry(|entry| options.hidden || !is_hidden(entry));
+    let mut entries: Vec<(PathBuf, Kind)> = vec![];
+
+    for entry in walk {
+        let entry = entry.map_err(|e| Error::Io(e.into()))?;
+        let file_type = entry.file_type();
+
+        let kind = if file_type.is_dir() {
+            Kind::Dir
+        } else if file_type.is_file() {
+            Kind::File(entry.metadata().map(|metadata| metadata.len()).unwrap_or(0))
+        } else if file_type.is_symlink() {
+            Kind::Symlink(fs::read_link(entry.path())?)
+        } else {
+            continue;
+        };
+
+        entries.push((entry.path().to_path_buf(), kind));
+    }
+
+    Ok(entries)
+}
+
+/// Builds a form with a part for every file, directory and symlink under
+/// `path`, named by their path relative to the parent of `path`. Directories
+/// come before their contents. Files that are kept open are read through a
+/// buffer of `read_buffer_size` bytes.
+///
+pub fn build_form(
+    path: &Path,
+    options: &AddTreeOptions,
+    read_buffer_size: usize,
+) -> Result<multipart::Form<'static>, Error> {
+    let prefix = path.parent();
+    let entries = entries(path, options)?;
+
+    // Keep the largest files open, and read the rest into memory.
+    //
+    let size = |i: usize| match entries[i].1 {
+        Kind::File(size) => size,
+        _ => 0,
+    };
+    let mut by_size: Vec<usize> = (0..entries.len())
+        .filter(|&i| matches!(entries[i].1, Kind::File(_)))
+        .collect();
+
+    by_size.sort_unstable_by(|&a, &b| size(a).cmp(&size(b)).reverse());
+
+    let mut keep_open = vec![false; entries.len()];
+
+    for &i in by_size.iter().take(FILE_DESCRIPTOR_LIMIT) {
+        keep_open[i] = true;
+    }
+
+    let mut form = multipart::Form::default();
+
+    for (i, (path, kind)) in entries.into_iter().enumerate() {
+        let file_name = match prefix {
+            Some(prefix) => path.strip_prefix(prefix).unwrap(),
+            None => path.as_path(),
+        }
+        .to_string_lossy()
+        .into_owned();
+
+        match kind {
+            Kind::Dir => form.add_reader_file_with_mime(
+                "path",
+                io::empty(),
+                file_name,
+                "application/x-directory".parse().unwrap(),
+            ),
+            Kind::Symlink(target) => form.add_reader_file_with_mime(
+                "path",
+                io::Cursor::new(target.to_string_lossy().into_owned()),
+                file_name,
+                "application/symlink".parse().unwrap(),
+            ),
+            Kind::File(_) if keep_open[i] => {
+                let file = io::BufReader::with_capacity(read_buffer_size, fs::File::open(&path)?);
+
+                form.add_reader_file("path", file, file_name)
+            }
+            Kind::File(size) => {
+                let mut buf = Vec::with_capacity(size as usize);
+
+                fs::File::open(&path)?.read_to_end(&mut buf)?;
+                form.add_reader_file("path", io::Cursor::new(buf), file_name);
+            }
+        }
+    }
+
+    Ok(form)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_form, entries, AddTreeOptions, Kind, SymlinkMode};
+    #[cfg(feature = "actix")]
+    use actix_multipart::client::multipart;
+    use futures::{Future, Stream};
+    #[cfg(feature = "hyper")]
+    use hyper_multipart::client::multipart;
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+        process,
+    };
+    use upload::FormBody;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ipfs-api-{}-{}", name, process::id()));
+
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// The entries under `root`, by their path relative to it.
+    ///
+    fn relative_entries(root: &Path, options: &AddTreeOptions) -> Vec<(String, Kind)> {
+        entries(root, options)
+            .unwrap()
+            .into_iter()
+            .map(|(path, kind)| {
+                let path = path.strip_prefix(root).unwrap().to_string_lossy();
+
+                (path.into_owned(), kind)
+            })
+            .collect()
+    }
+
+    /// Renders `form`, returning the file name, content type and contents of
+    /// each of its parts.
+    ///
+    fn parts(form: multipart::Form<'static>) -> Vec<(String, String, String)> {
+        let content_type = form.content_type();
+        let boundary = content_type
+            .split("boundary=")
+            .nth(1)
+            .unwrap()
+            .trim_matches('"');
+
+        #[cfg(feature = "hyper")]
+        let body = form
+            .set_body_convert::<FormBody, FormBody>(&mut ::http::Request::builder())
+            .unwrap()
+            .into_body();
+        #[cfg(feature = "actix")]
+        let body = FormBody::from(multipart::Body::from(form));
+
+        let body = body.into_stream(1024).concat2().wait().unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let end = format!("\r\n--{}--", boundary);
+
+        body.trim_end_matches(end.as_str())
+            .split(&format!("\r\n--{}\r\n", boundary))
+            .skip(1)
+            .map(|part| {
+                let mut split = part.splitn(2, "\r\n\r\n");
+                let headers = split.next().unwrap().to_lowercase();
+                let header = |prefix: &str| {
+                    let start = headers.find(prefix).unwrap() + prefix.len();
+
+                    headers[start..]
+                        .split(&['"', '\r'][..])
+                        .next()
+                        .unwrap()
+                        .to_string()
+                };
+
+                (
+                    header("filename=\""),
+                    header("content-type: "),
+                    split.next().unwrap().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_entries() {
+        let root = temp_dir("tree-entries");
+        let mut options = AddTreeOptions::default();
+
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("dir/b.txt"), "b").unwrap();
+        fs::write(root.join(".hidden/c.txt"), "c").unwrap();
+        fs::write(root.join(".secret"), "s").unwrap();
+
+        assert_eq!(
+            relative_entries(&root, &options),
+            vec![
+                ("".into(), Kind::Dir),
+                ("a.txt".into(), Kind::File(1)),
+                ("dir".into(), Kind::Dir),
+                ("dir/b.txt".into(), Kind::File(1)),
+            ]
+        );
+
+        options.hidden = true;
+
+        assert_eq!(
+            relative_entries(&root, &options),
+            vec![
+                ("".into(), Kind::Dir),
+                (".hidden".into(), Kind::Dir),
+                (".hidden/c.txt".into(), Kind::File(1)),
+                (".secret".into(), Kind::File(1)),
+                ("a.txt".into(), Kind::File(1)),
+                ("dir".into(), Kind::Dir),
+                ("dir/b.txt".into(), Kind::File(1)),
+            ]
+        );
+        assert!(entries(&root.join("does-not-exist"), &options).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_build_form() {
+        let root = temp_dir("tree-form");
+        let name = root.file_name().unwrap().to_string_lossy().into_owned();
+
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("dir/b.txt"), "b").unwrap();
+        fs::write(root.join(".secret"), "s").unwrap();
+
+        // Directories are parts without a body, which the multipart writer
+        // leaves out of the rendered form.
+        //
+        assert_eq!(
+            parts(build_form(&root, &AddTreeOptions::default(), 1024).unwrap()),
+            vec![
+                (
+                    format!("{}/a.txt", name),
+                    "application/octet-stream".into(),
+                    "a".into()
+                ),
+                (
+                    format!("{}/dir/b.txt", name),
+                    "application/octet-stream".into(),
+                    "b".into()
+                ),
+            ]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_form_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = temp_dir("tree-symlink");
+        let name = root.file_name().unwrap().to_string_lossy().into_owned();
+        let mut options = AddTreeOptions::default();
+
+        fs::write(root.join("a.txt"), "a").unwrap();
+        symlink("a.txt", root.join("link")).unwrap();
+
+        assert_eq!(
+            relative_entries(&root, &options).pop().unwrap(),
+            ("link".into(), Kind::Symlink("a.txt".into()))
+        );
+        assert_eq!(
+            parts(build_form(&root, &options, 1024).unwrap())
+                .pop()
+                .unwrap(),
+            (
+                format!("{}/link", name),
+                "application/symlink".into(),
+                "a.txt".into()
+            )
+        );
+
+        options.symlinks = SymlinkMode::Dereference;
+
+        assert_eq!(
+            parts(build_form(&root, &options, 1024).unwrap())
+                .pop()
+                .unwrap(),
+            (
+                format!("{}/link", name),
+                "application/octet-stream".into(),
+                "a".into()
+            )
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use archive::{TarDecodeStream, TarEntry};
+use bytes::Bytes;
+use futures::{
+    future::{self, Either},
+    Future, Stream,
+};
+use response::Error;
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Component, Path, PathBuf},
+};
+use tar::EntryType;
+
+/// What `get_to_path` does when a file it unpacks already exists.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Existing files are replaced, like `ipfs get`.
+    Replace,
+
+    /// Existing files are left alone, and the archived file is skipped.
+    Skip,
+
+    /// Unpacking stops with an error.
+    Fail,
+}
+
+impl Default for OverwritePolicy {
+    #[inline]
+    fn default() -> OverwritePolicy {
+        OverwritePolicy::Replace
+    }
+}
+
+/// Unpacks a tar archive returned by `get` into `dest` as it is downloaded.
+/// The root of the archive is written to `dest` itself, like `ipfs get -o`.
+///
+/// Only one chunk of the archive is held in memory at a time. Files are
+/// written with blocking I/O from inside the future, one chunk at a time.
+///
+/// Entries are never written through a symlink, so a symlink in the
+/// archive can't be used to write outside of `dest`.
+///
+pub fn unpack<S>(
+    archive: S,
+    dest: PathBuf,
+    overwrite: OverwritePolicy,
+) -> impl Future<Item = (), Error = Error>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    TarDecodeStream::new(archive).for_each(move |entry| unpack_entry(entry, &dest, overwrite))
+}
+
+/// Writes a single entry of the archive to its place under `dest`.
+///
+fn unpack_entry<S>(
+    entry: TarEntry<S>,
+    dest: &Path,
+    overwrite: OverwritePolicy,
+) -> impl Future<Item = (), Error = Error>
+where
+    S: Stream<Item = Bytes, Error = Error>,
+{
+    let target = match target(&entry, dest, overwrite) {
+        Ok(Some(target)) => target,
+        Ok(None) => return Either::A(future::ok(())),
+        Err(e) => return Either::A(future::err(e)),
+    };
+
+    let res = match entry.entry_type() {
+        EntryType::Directory => fs::create_dir_all(&target),
+        EntryType::Symlink => match entry.link_name() {
+            Some(link_name) => symlink(link_name, &target),
+            None => Err(invalid_entry(&entry, "symlink without a target")),
+        },
+        EntryType::Regular | EntryType::Continuous => {
+            let mode = entry.header().mode().ok();
+            let file = match File::create(&target) {
+                Ok(file) => file,
+                Err(e) => return Either::A(future::err(e.into())),
+            };
+            let res = entry
+                .into_body()
+                .fold(file, |mut file, chunk| {
+                    file.write_all(&chunk).map(|_| file).map_err(Error::from)
+                })
+                .and_then(move |_| set_mode(&target, mode).map_err(Error::from));
+
+            return Either::B(res);
+        }
+        _ => Err(invalid_entry(&entry, "unsupported entry type")),
+    };
+
+    Either::A(future::result(res.map_err(Error::from)))
+}
+
+/// Where `entry` is written, or `None` if it is skipped because of
+/// `overwrite`.
+///
+fn target<S>(
+    entry: &TarEntry<S>,
+    dest: &Path,
+    overwrite: OverwritePolicy,
+) -> Result<Option<PathBuf>, Error> {
+    // Replace the first component, the name of the root, with `dest`.
+    //
+    let mut components = entry.path().components();
+    components.next();
+
+    let relative = components.as_path();
+
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(invalid_entry(entry, "invalid path").into());
+    }
+
+    // Refuse to follow a symlink created by an earlier entry, or one that
+    // was already in `dest`.
+    //
+    let mut parent = dest.to_path_buf();
+
+    for component in relative.parent().into_iter().flat_map(Path::components) {
+        parent.push(component);
+
+        match fs::symlink_metadata(&parent) {
+            Ok(ref metadata) if metadata.file_type().is_symlink() => {
+                return Err(invalid_entry(entry, "path goes through a symlink").into());
+            }
+            Ok(_) => (),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let target = dest.join(relative);
+
+    if entry.entry_type().is_dir() {
+        return match fs::symlink_metadata(&target) {
+            Ok(ref metadata) if metadata.is_dir() => Ok(Some(target)),
+            Ok(_) if overwrite == OverwritePolicy::Replace => {
+                fs::remove_file(&target)?;
+                Ok(Some(target))
+            }
+            Ok(_) if overwrite == OverwritePolicy::Skip => Ok(None),
+            Ok(_) => Err(already_exists(&target).into()),
+            Err(_) => Ok(Some(target)),
+        };
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if let Ok(metadata) = fs::symlink_metadata(&target) {
+        match overwrite {
+            OverwritePolicy::Replace if metadata.is_dir() => fs::remove_dir_all(&target)?,
+            OverwritePolicy::Replace => fs::remove_file(&target)?,
+            OverwritePolicy::Skip => return Ok(None),
+            OverwritePolicy::Fail => return Err(already_exists(&target).into()),
+        }
+    }
+
+    Ok(Some(target))
+}
+
+fn invalid_entry<S>(entry: &TarEntry<S>, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} in archive: {}", reason, entry.path().display()),
+    )
+}
+
+fn already_exists(target: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", target.display()),
+    )
+}
+
+#[cfg(unix)]
+fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    ::std::os::unix::fs::symlink(src, dst)
+}
+
+#[cfg(windows)]
+fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    ::std::os::windows::fs::symlink_file(src, dst)
+}
+
+#[cfg(unix)]
+fn set_mode(target: &Path, mode: Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    match mode {
+        Some(mode) => fs::set_permissions(target, fs::Permissions::from_mode(mode & 0o777)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_mode(_target: &Path, _mode: Option<u32>) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unpack, OverwritePolicy};
+    use bytes::Bytes;
+    use futures::{stream, Future};
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+        process,
+    };
+    use tar::{Builder, EntryType, Header};
+
+    fn archive() -> Bytes {
+        let mut builder = Builder::new(Vec::new());
+
+        for (path, data) in &[("QmRoot/a.txt", "a"), ("QmRoot/dir/b.txt", "b")] {
+            let mut header = Header::new_gnu();
+
+            header.set_path(path).unwrap();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, data.as_bytes()).unwrap();
+        }
+
+        builder.into_inner().unwrap().into()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("ipfs-api-{}-{}", name, process::id()))
+    }
+
+    fn unpack_bytes(
+        archive: &Bytes,
+        dest: &Path,
+        overwrite: OverwritePolicy,
+    ) -> Result<(), ::response::Error> {
+        unpack(
+            stream::once(Ok(archive.clone())),
+            dest.to_path_buf(),
+            overwrite,
+        )
+        .wait()
+    }
+
+    #[test]
+    fn test_unpack() {
+        let dest = temp_dir("unpack");
+        let archive = archive();
+
+        unpack_bytes(&archive, &dest, OverwritePolicy::default()).unwrap();
+
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(dest.join("dir/b.txt")).unwrap(), "b");
+
+        fs::write(dest.join("a.txt"), "changed").unwrap();
+
+        unpack_bytes(&archive, &dest, OverwritePolicy::Skip).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "changed");
+        assert!(unpack_bytes(&archive, &dest, OverwritePolicy::Fail).is_err());
+
+        fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unpack_does_not_follow_symlinks() {
+        let dest = temp_dir("unpack-symlink");
+        let outside = temp_dir("unpack-symlink-outside");
+        let mut builder = Builder::new(Vec::new());
+        let mut link = Header::new_gnu();
+
+        fs::create_dir_all(&outside).unwrap();
+
+        link.set_entry_type(EntryType::Symlink);
+        link.set_size(0);
+        link.set_mode(0o777);
+        builder
+            .append_link(&mut link, "QmRoot/link", &outside)
+            .unwrap();
+
+        let mut file = Header::new_gnu();
+
+        file.set_size(4);
+        file.set_mode(0o644);
+        builder
+            .append_data(&mut file, "QmRoot/link/evil", &b"evil"[..])
+            .unwrap();
+
+        let archive = builder.into_inner().unwrap().into();
+
+        assert!(unpack_bytes(&archive, &dest, OverwritePolicy::default()).is_err());
+        assert!(fs::symlink_metadata(dest.join("link"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(!outside.join("evil").exists());
+
+        fs::remove_dir_all(&dest).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+}
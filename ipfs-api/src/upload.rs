@@ -0,0 +1,348 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+#[cfg(feature = "actix")]
+use actix_multipart::client::multipart;
+use bytes::{Bytes, BytesMut};
+use futures::{
+    stream,
+    sync::mpsc::{self, Receiver, SendError, Sender},
+    try_ready, Async, Future, Poll, Sink, Stream,
+};
+use futures_cpupool::{Builder, CpuPool};
+#[cfg(feature = "hyper")]
+use hyper_multipart::client::multipart;
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    io,
+};
+
+/// The number of chunks of a form that are read ahead of the request
+/// sending them.
+///
+const FORM_BUFFER_CHUNKS: usize = 8;
+
+/// The number of threads forms are read on, shared by every client.
+///
+const FORM_READER_THREADS: usize = 4;
+
+lazy_static! {
+    static ref FORM_READERS: CpuPool = Builder::new()
+        .pool_size(FORM_READER_THREADS)
+        .name_prefix("ipfs-api-form-")
+        .create();
+}
+
+/// The size of the chunks an upload is sent in, unless set with
+/// `IpfsClientBuilder::upload_chunk_size`.
+///
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The size of the buffer local files are read through, unless set with
+/// `IpfsClientBuilder::read_buffer_size`.
+///
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A stream of bytes uploaded as the body of a request.
+///
+pub type ByteStream = Box<dyn Stream<Item = Bytes, Error = io::Error> + Send>;
+
+/// The body of a request that uploads data.
+///
+pub enum Upload {
+    /// A multipart form, with each part read from a `Read` on a pool of
+    /// threads.
+    Form(multipart::Form<'static>),
+
+    /// A multipart form with a single part, streamed without blocking.
+    Stream(StreamPart),
+}
+
+impl From<multipart::Form<'static>> for Upload {
+    #[inline]
+    fn from(form: multipart::Form<'static>) -> Upload {
+        Upload::Form(form)
+    }
+}
+
+impl From<StreamPart> for Upload {
+    #[inline]
+    fn from(part: StreamPart) -> Upload {
+        Upload::Stream(part)
+    }
+}
+
+/// The body of a multipart form, whose parts are read from their `Read`s
+/// on a pool of threads shared by every client.
+///
+/// A form pulls its data from blocking readers, so reading it on the event
+/// loop would stall every other request while a large file is read. At most
+/// `FORM_BUFFER_CHUNKS` chunks are read ahead of the request.
+///
+pub struct FormBody(ByteStream);
+
+impl FormBody {
+    /// Returns the contents of the form in chunks of `chunk_size` bytes.
+    ///
+    /// Nothing is read until the stream is first polled, so a request that
+    /// is waiting to be sent (e.g. for `max_in_flight`) doesn't hold on to
+    /// any of its data.
+    ///
+    pub fn into_stream(self, chunk_size: usize) -> ByteStream {
+        let (sender, receiver) = mpsc::channel(FORM_BUFFER_CHUNKS);
+
+        Box::new(FormStream {
+            unread: Some((self.0, chunk_size, sender)),
+            receiver,
+        })
+    }
+}
+
+/// A chunk of a form, read on `FORM_READERS`.
+///
+type FormChunk = Result<Bytes, io::Error>;
+
+/// The contents of a form, which start being read on `FORM_READERS` once
+/// they are first polled.
+///
+struct FormStream {
+    unread: Option<(ByteStream, usize, Sender<FormChunk>)>,
+    receiver: Receiver<FormChunk>,
+}
+
+impl Stream for FormStream {
+    type Item = Bytes;
+
+    type Error = io::Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, io::Error> {
+        if let Some((body, chunk_size, sender)) = self.unread.take() {
+            let chunks = Rechunk::new(body, chunk_size).then(Ok::<_, SendError<_>>);
+
+            // Sending only fails once the request has been dropped, and
+            // there is no one left to read the form.
+            //
+            FORM_READERS
+                .spawn(sender.send_all(chunks).then(|_| Ok::<_, ()>(())))
+                .forget();
+        }
+
+        match self.receiver.poll() {
+            Ok(Async::Ready(Some(chunk))) => chunk.map(|chunk| Async::Ready(Some(chunk))),
+            Ok(Async::Ready(None)) => Ok(Async::Ready(None)),
+            Ok(Async::NotReady) => Ok(Async::NotReady),
+            Err(()) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "form stopped being read",
+            )),
+        }
+    }
+}
+
+impl<S> From<S> for FormBody
+where
+    S: 'static + Stream<Item = Bytes> + Send,
+    S::Error: fmt::Display,
+{
+    #[inline]
+    fn from(body: S) -> FormBody {
+        FormBody(Box::new(body.map_err(|e| {
+            io::Error::new(io::ErrorKind::Other, e.to_string())
+        })))
+    }
+}
+
+/// A file part of a multipart form, whose contents come from a stream.
+///
+pub struct StreamPart {
+    name: &'static str,
+    stream: ByteStream,
+}
+
+impl StreamPart {
+    #[inline]
+    pub fn new<S>(name: &'static str, stream: S) -> StreamPart
+    where
+        S: 'static + Stream<Item = Bytes, Error = io::Error> + Send,
+    {
+        StreamPart {
+            name,
+            stream: Box::new(stream),
+        }
+    }
+
+    /// Returns the content type of the form, and a stream of the form with
+    /// the part's contents, in chunks of `chunk_size` bytes, in between its
+    /// header and the closing boundary.
+    ///
+    pub fn into_body(self, chunk_size: usize) -> (String, ByteStream) {
+        let boundary = boundary();
+        let header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n",
+            boundary, self.name, self.name
+        );
+        let footer = format!("\r\n--{}--\r\n", boundary);
+
+        let body = stream::once(Ok(Bytes::from(header)))
+            .chain(Rechunk::new(self.stream, chunk_size))
+            .chain(stream::once(Ok(Bytes::from(footer))));
+
+        (
+            format!("multipart/form-data; boundary={}", boundary),
+            Box::new(body),
+        )
+    }
+}
+
+/// A stream of bytes regrouped into chunks of `chunk_size` bytes, except for
+/// the last one, which can be shorter.
+///
+pub struct Rechunk<S> {
+    stream: S,
+    chunk_size: usize,
+    buf: BytesMut,
+    done: bool,
+}
+
+impl<S> Rechunk<S>
+where
+    S: Stream<Item = Bytes>,
+{
+    pub fn new(stream: S, chunk_size: usize) -> Rechunk<S> {
+        Rechunk {
+            stream,
+            chunk_size: chunk_size.max(1),
+            buf: BytesMut::new(),
+            done: false,
+        }
+    }
+}
+
+impl<S> Stream for Rechunk<S>
+where
+    S: Stream<Item = Bytes>,
+{
+    type Item = Bytes;
+
+    type Error = S::Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if self.buf.len() >= self.chunk_size {
+                return Ok(Async::Ready(Some(
+                    self.buf.split_to(self.chunk_size).freeze(),
+                )));
+            }
+
+            if self.done {
+                return if self.buf.is_empty() {
+                    Ok(Async::Ready(None))
+                } else {
+                    Ok(Async::Ready(Some(self.buf.take().freeze())))
+                };
+            }
+
+            match try_ready!(self.stream.poll()) {
+                // A chunk of exactly the right size can be passed on without
+                // copying it.
+                //
+                Some(chunk) if self.buf.is_empty() && chunk.len() == self.chunk_size => {
+                    return Ok(Async::Ready(Some(chunk)));
+                }
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+/// Generates a random boundary for a multipart form.
+///
+fn boundary() -> String {
+    let state = RandomState::new();
+
+    (0..2)
+        .map(|i| {
+            let mut hasher = state.build_hasher();
+
+            hasher.write_u8(i);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormBody, Rechunk, StreamPart};
+    use bytes::Bytes;
+    use futures::{stream, Future, Stream};
+    use std::io;
+
+    #[test]
+    fn test_stream_part_body() {
+        let data = stream::iter_ok(vec![Bytes::from("Hello "), Bytes::from("World!")]);
+        let (content_type, body) = StreamPart::new("file", data).into_body(4);
+        let boundary = content_type.split("boundary=").nth(1).unwrap().to_string();
+        let body = body.concat2().wait().unwrap();
+
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            format!(
+                "--{0}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"file\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\nHello World!\r\n--{0}--\r\n",
+                boundary
+            )
+        );
+    }
+
+    #[test]
+    fn test_form_body() {
+        let chunks = (0..100).map(|i| Bytes::from(format!("{},", i)));
+        let expected: String = (0..100).map(|i| format!("{},", i)).collect();
+        let body = FormBody::from(stream::iter_ok::<_, io::Error>(chunks))
+            .into_stream(16)
+            .concat2()
+            .wait()
+            .unwrap();
+
+        assert_eq!(body, expected.as_bytes());
+    }
+
+    #[test]
+    fn test_form_body_error() {
+        let chunks = vec![
+            Ok(Bytes::from("a")),
+            Err(io::Error::new(io::ErrorKind::Other, "disk on fire")),
+        ];
+        let res = FormBody::from(stream::iter_result(chunks))
+            .into_stream(16)
+            .concat2()
+            .wait();
+
+        assert_eq!(res.unwrap_err().to_string(), "disk on fire");
+    }
+
+    #[test]
+    fn test_rechunk() {
+        let chunks = vec![
+            Bytes::from("abc"),
+            Bytes::from("defgh"),
+            Bytes::from("ijkl"),
+            Bytes::from("m"),
+        ];
+        let rechunked: Vec<Bytes> = Rechunk::new(stream::iter_ok::<_, io::Error>(chunks), 4)
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(rechunked, vec!["abcd", "efgh", "ijkl", "m"]);
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use api::IpfsApi;
+use cid::Cid;
+use client::AsyncResponse;
+use futures::{try_ready, Async, Poll, Stream};
+use response::{Error, IpfsHeader, ObjectLinksResponse};
+use std::collections::{HashSet, VecDeque};
+
+/// A node reached by `walk`.
+///
+#[derive(Debug)]
+pub struct WalkNode {
+    /// The hash of the node.
+    pub hash: Cid,
+
+    /// The number of links between the root and this node. The root is at
+    /// depth 0.
+    pub depth: usize,
+
+    /// The links from this node to its children.
+    pub links: Vec<IpfsHeader>,
+}
+
+/// Walks the graph under a node breadth first, fetching the links of one
+/// node at a time with `object_links`.
+///
+/// A node reachable by more than one path is only returned, and its links
+/// only followed, once.
+///
+pub struct Walk<A> {
+    api: A,
+    max_depth: Option<usize>,
+    queue: VecDeque<(String, usize)>,
+    seen: HashSet<String>,
+    current: Option<(usize, AsyncResponse<ObjectLinksResponse>)>,
+}
+
+impl<A> Walk<A>
+where
+    A: IpfsApi,
+{
+    pub fn new(api: A, hash: &str, max_depth: Option<usize>) -> Walk<A> {
+        let mut queue = VecDeque::new();
+        let mut seen = HashSet::new();
+
+        queue.push_back((hash.to_string(), 0));
+        seen.insert(hash.to_string());
+
+        Walk {
+            api,
+            max_depth,
+            queue,
+            seen,
+            current: None,
+        }
+    }
+}
+
+impl<A> Stream for Walk<A>
+where
+    A: IpfsApi,
+{
+    type Item = WalkNode;
+
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        loop {
+            if let Some((depth, ref mut req)) = self.current {
+                let res = try_ready!(req.poll());
+
+                self.current = None;
+
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    for link in &res.links {
+                        if self.seen.insert(link.hash.to_string()) {
+                            self.queue.push_back((link.hash.to_string(), depth + 1));
+                        }
+                    }
+                }
+
+                return Ok(Async::Ready(Some(WalkNode {
+                    hash: res.hash,
+                    depth,
+                    links: res.links,
+                })));
+            }
+
+            match self.queue.pop_front() {
+                Some((hash, depth)) => self.current = Some((depth, self.api.object_links(&hash))),
+                None => return Ok(Async::Ready(None)),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::Walk;
+    use cid::Cid;
+    use futures::{Future, Stream};
+    use mock::MockIpfsClient;
+    use response::{IpfsHeader, ObjectLinksResponse};
+
+    /// A CIDv0 whose digest is `n` repeated.
+    ///
+    fn cid(n: u8) -> Cid {
+        let mut bytes = vec![0x12, 0x20];
+
+        bytes.extend_from_slice(&[n; 32]);
+
+        Cid::from_bytes(&bytes).unwrap()
+    }
+
+    fn links(hash: u8, children: &[u8]) -> ObjectLinksResponse {
+        ObjectLinksResponse {
+            hash: cid(hash),
+            links: children
+                .iter()
+                .map(|&child| IpfsHeader {
+                    name: String::new(),
+                    hash: cid(child),
+                    size: 0,
+                    typ: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_walk_skips_seen_nodes() {
+        let mock = MockIpfsClient::new();
+
+        // 1 -> 2, 3; 2 -> 3, 1; 3 -> 4
+        mock.push_response("object_links", Ok(links(1, &[2, 3])));
+        mock.push_response("object_links", Ok(links(2, &[3, 1])));
+        mock.push_response("object_links", Ok(links(3, &[4])));
+        mock.push_response("object_links", Ok(links(4, &[])));
+
+        let nodes = Walk::new(mock.clone(), cid(1).as_str(), None)
+            .collect()
+            .wait()
+            .unwrap();
+        let visited: Vec<_> = nodes
+            .iter()
+            .map(|node| (node.hash.clone(), node.depth))
+            .collect();
+
+        assert_eq!(
+            visited,
+            vec![(cid(1), 0), (cid(2), 1), (cid(3), 1), (cid(4), 2)]
+        );
+    }
+
+    #[test]
+    fn test_walk_max_depth() {
+        let mock = MockIpfsClient::new();
+
+        mock.push_response("object_links", Ok(links(1, &[2])));
+        mock.push_response("object_links", Ok(links(2, &[3])));
+
+        let nodes = Walk::new(mock.clone(), cid(1).as_str(), Some(1))
+            .collect()
+            .wait()
+            .unwrap();
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(mock.calls().len(), 2);
+    }
+}
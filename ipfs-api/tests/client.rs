@@ -0,0 +1,921 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+// Tests of client behaviour against the test-support mock server.
+//
+//     cargo test -p ipfs-api --features test-support --test client
+//
+// Tests of endpoints in a command group only run when that group is
+// enabled.
+//
+
+extern crate futures;
+extern crate http;
+extern crate ipfs_api;
+#[macro_use]
+extern crate serde_derive;
+extern crate tokio;
+
+use futures::{future::Either, Future, Stream};
+use http::{Method, StatusCode};
+use ipfs_api::metrics::RequestMetrics;
+use ipfs_api::response::{Error, VersionResponse};
+use ipfs_api::test_support::{Fixture, MockServer};
+use ipfs_api::{request, Cid, IpfsClient, IpfsClientBuilder};
+use std::{
+    io::{self, Cursor, Read},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::runtime::current_thread::Runtime;
+use tokio::timer::Delay;
+
+#[cfg(feature = "files")]
+#[test]
+fn test_files_flush_with_and_without_cid() {
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+
+    server.push(
+        "files/flush",
+        Fixture::json(r#"{"Cid":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}"#),
+    );
+    server.push("files/flush", Fixture::bytes(""));
+
+    let flushed = runtime.block_on(server.client().files_flush(None)).unwrap();
+    let empty = runtime.block_on(server.client().files_flush(None)).unwrap();
+
+    assert_eq!(
+        flushed.cid.unwrap().to_string(),
+        "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"
+    );
+    assert!(empty.cid.is_none());
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_files_sync() {
+    use std::{env, fs, process};
+
+    let dir = env::temp_dir().join(format!("ipfs-api-sync-{}", process::id()));
+    let server = MockServer::start();
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.txt"), "Hello").unwrap();
+    fs::write(dir.join("b.txt"), "World!").unwrap();
+    fs::write(dir.join("c.txt"), "New").unwrap();
+
+    server.push(
+        "files/ls",
+        Fixture::json(
+            r#"{"Entries":[
+                {"Name":"a.txt","Type":0,"Size":5,"Hash":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"},
+                {"Name":"b.txt","Type":0,"Size":5,"Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"}
+            ]}"#,
+        ),
+    );
+    server.push(
+        "add",
+        Fixture::json(
+            r#"{"Name":"a.txt","Hash":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":"13"}"#,
+        ),
+    );
+    server.push("files/write", Fixture::bytes(""));
+    server.push("files/write", Fixture::bytes(""));
+
+    let res = Runtime::new()
+        .unwrap()
+        .block_on(server.client().files_sync(&dir, "/site"));
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    let requests = server.requests();
+    let paths: Vec<_> = requests.iter().map(|req| req.path.as_str()).collect();
+
+    assert_eq!(res.unwrap(), vec!["/site/b.txt", "/site/c.txt"]);
+    assert_eq!(
+        paths,
+        vec![
+            "/api/v0/files/ls",
+            "/api/v0/add",
+            "/api/v0/files/write",
+            "/api/v0/files/write",
+        ]
+    );
+    assert!(requests[1]
+        .query
+        .as_ref()
+        .unwrap()
+        .contains("only-hash=true"));
+}
+
+#[cfg(feature = "pin")]
+#[test]
+fn test_pin_reconcile() {
+    let server = MockServer::start();
+    let kept = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    let added = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+    let removed = "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ";
+
+    server.push(
+        "pin/ls",
+        Fixture::json(format!(
+            r#"{{"Keys":{{"{}":{{"Type":"recursive"}},"{}":{{"Type":"recursive"}}}}}}"#,
+            kept, removed
+        )),
+    );
+    server.push(
+        "pin/add",
+        Fixture::json(format!(r#"{{"Pins":["{}"]}}"#, added)),
+    );
+    server.push(
+        "pin/rm",
+        Fixture::api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "not pinned or pinned indirectly",
+        ),
+    );
+
+    let desired = vec![kept.parse::<Cid>().unwrap(), added.parse().unwrap()];
+    let report = Runtime::new()
+        .unwrap()
+        .block_on(server.client().pin_reconcile(desired, 2))
+        .unwrap();
+
+    assert_eq!(report.added, vec![added.parse::<Cid>().unwrap()]);
+    assert!(report.removed.is_empty());
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0, removed.parse::<Cid>().unwrap());
+    assert!(!report.is_complete());
+    assert_eq!(
+        server.requests()[0].query.as_ref().unwrap(),
+        "type=recursive"
+    );
+}
+
+#[cfg(feature = "pin")]
+#[test]
+fn test_pin_reconcile_skips_malformed_keys() {
+    let server = MockServer::start();
+    let kept = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    let removed = "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ";
+
+    server.push(
+        "pin/ls",
+        Fixture::json(format!(
+            r#"{{"Keys":{{"{}":{{"Type":"recursive"}},"not-a-cid":{{"Type":"recursive"}},"{}":{{"Type":"recursive"}}}}}}"#,
+            kept, removed
+        )),
+    );
+    server.push(
+        "pin/rm",
+        Fixture::json(format!(r#"{{"Pins":["{}"]}}"#, removed)),
+    );
+
+    let desired = vec![kept.parse::<Cid>().unwrap()];
+    let report = Runtime::new()
+        .unwrap()
+        .block_on(server.client().pin_reconcile(desired, 2))
+        .unwrap();
+
+    assert_eq!(report.removed, vec![removed.parse::<Cid>().unwrap()]);
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.unparsed.len(), 1);
+    assert_eq!(report.unparsed[0].0, "not-a-cid");
+    assert!(report.is_complete());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[cfg(feature = "key")]
+#[test]
+fn test_key_id() {
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+    let keys = r#"{"Keys":[
+        {"Name":"self","Id":"QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"}
+    ]}"#;
+
+    server.push("key/list", Fixture::json(keys));
+    server.push("key/list", Fixture::json(keys));
+
+    let id = runtime.block_on(server.client().key_id("self")).unwrap();
+    let missing = runtime.block_on(server.client().key_id("backup"));
+
+    assert_eq!(
+        id.as_str(),
+        "QmaCpDMGvV2BGHeYERUEnRQAwe3N8SzbUtfsmvsqQLuvuJ"
+    );
+    match missing {
+        Err(Error::KeyNotFound(ref name)) if name == "backup" => (),
+        res => panic!("expected a missing key, got {:?}", res),
+    }
+}
+
+#[test]
+fn test_add_and_cat_json() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Post {
+        title: String,
+    }
+
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+    let post = Post {
+        title: "Hello".into(),
+    };
+
+    server.push(
+        "add",
+        Fixture::json(
+            r#"{"Name":"","Hash":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":"25"}"#,
+        ),
+    );
+    server.push("cat", Fixture::chunks(vec![r#"{"title":"#, r#""Hello"}"#]));
+
+    let cid = runtime.block_on(server.client().add_json(&post)).unwrap();
+    let read: Post = runtime
+        .block_on(server.client().cat_json(cid.as_str()))
+        .unwrap();
+    let body = String::from_utf8_lossy(&server.requests()[0].body).into_owned();
+
+    assert_eq!(read, post);
+    assert!(body.contains(r#"{"title":"Hello"}"#));
+}
+
+#[test]
+fn test_offline_flag() {
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+    let stat = r#"{"Key":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":12}"#;
+    let hash = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+
+    server.push("block/stat", Fixture::json(stat));
+    server.push("block/stat", Fixture::json(stat));
+
+    let client = server.client();
+
+    runtime
+        .block_on(client.with_offline(true).block_stat(hash))
+        .unwrap();
+    runtime.block_on(client.block_stat(hash)).unwrap();
+
+    let requests = server.requests();
+
+    assert_eq!(
+        requests[0].query.as_ref().unwrap(),
+        &format!("arg={}&offline=true", hash)
+    );
+    assert_eq!(
+        requests[1].query.as_ref().unwrap(),
+        &format!("arg={}", hash)
+    );
+}
+
+#[test]
+fn test_refs_walk() {
+    let server = MockServer::start();
+    let root = "QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA";
+    let child = "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn";
+
+    server.push(
+        "refs",
+        Fixture::chunks(vec![format!("{{\"Ref\":\"{}\",\"Err\":\"\"}}\n", child)]),
+    );
+    server.push("refs", Fixture::chunks(Vec::<String>::new()));
+
+    let cids = Runtime::new()
+        .unwrap()
+        .block_on(server.client().refs_walk(root, None).collect())
+        .unwrap();
+    let requests = server.requests();
+
+    assert_eq!(cids, vec![child.parse::<Cid>().unwrap()]);
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[1].query.as_ref().unwrap(),
+        &format!(
+            "arg=%2Fipfs%2F{}&recursive=false&unique=true&max-depth=1",
+            child
+        )
+    );
+}
+
+#[test]
+fn test_server_timeout() {
+    let server = MockServer::start();
+
+    server.push(
+        "version",
+        Fixture::api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "context deadline exceeded",
+        ),
+    );
+
+    let res = Runtime::new().unwrap().block_on(
+        server
+            .client()
+            .with_timeout(Duration::from_millis(250))
+            .version(),
+    );
+
+    match res {
+        Err(Error::DeadlineExceeded(_)) => (),
+        _ => panic!("expected the deadline to be exceeded"),
+    }
+    assert_eq!(
+        server.requests()[0].query.as_ref().unwrap(),
+        "timeout=250ms"
+    );
+}
+
+#[cfg(feature = "object")]
+#[test]
+fn test_object_get_node() {
+    let server = MockServer::start();
+
+    server.push("object/get", Fixture::bytes(&b"\x0a\x02\x08\x01"[..]));
+
+    let node = Runtime::new()
+        .unwrap()
+        .block_on(
+            server
+                .client()
+                .object_get_node("QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn"),
+        )
+        .unwrap();
+
+    assert_eq!(node.data.unwrap(), b"\x08\x01");
+    assert_eq!(
+        server.requests()[0].query.as_ref().unwrap(),
+        "arg=QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn&encoding=protobuf"
+    );
+}
+
+#[test]
+fn test_follow_redirects() {
+    let proxy = MockServer::start();
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+    let redirect = || {
+        Fixture::bytes("")
+            .with_status(StatusCode::TEMPORARY_REDIRECT)
+            .with_header(
+                "Location",
+                &format!("http://{}/api/v0/version", server.addr()),
+            )
+    };
+    let version =
+        r#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11.2"}"#;
+
+    proxy.push("version", redirect());
+    proxy.push("version", redirect());
+    server.push("version", Fixture::json(version));
+
+    let client = IpfsClientBuilder::from(proxy.addr())
+        .max_redirects(1)
+        .build()
+        .unwrap();
+
+    match runtime.block_on(proxy.client().version()) {
+        Err(Error::UnexpectedStatus { status, .. }) => {
+            assert_eq!(status, StatusCode::TEMPORARY_REDIRECT)
+        }
+        _ => panic!("expected the redirect to not be followed"),
+    }
+
+    let res = runtime.block_on(client.version()).unwrap();
+
+    assert_eq!(res.version, "0.4.18");
+    assert_eq!(server.requests()[0].method, Method::GET);
+}
+
+#[test]
+fn test_too_many_redirects() {
+    let server = MockServer::start();
+    let redirect = Fixture::bytes("")
+        .with_status(StatusCode::FOUND)
+        .with_header("Location", "/api/v0/version");
+
+    server.push("version", redirect.clone());
+    server.push("version", redirect.clone());
+    server.push("version", redirect);
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .max_redirects(2)
+        .build()
+        .unwrap();
+
+    match Runtime::new().unwrap().block_on(client.version()) {
+        Err(Error::TooManyRedirects { max, .. }) => assert_eq!(max, 2),
+        _ => panic!("expected too many redirects"),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn test_see_other_after_upload() {
+    let server = MockServer::start();
+    let added = r#"{"Name":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","Hash":"QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn","Size":"6"}"#;
+
+    server.push(
+        "add",
+        Fixture::bytes("")
+            .with_status(StatusCode::SEE_OTHER)
+            .with_header("Location", "/api/v0/add"),
+    );
+    server.push("add", Fixture::json(added));
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .max_redirects(1)
+        .build()
+        .unwrap();
+    let res = Runtime::new()
+        .unwrap()
+        .block_on(client.add(Cursor::new("hello\n")))
+        .unwrap();
+    let requests = server.requests();
+
+    assert_eq!(res.hash, "QmUNLLsPACCz1vLxQVkXqqLX5R1X345qqfHbsf67hvA3Nn");
+    assert_eq!(requests[0].method, Method::POST);
+    assert!(!requests[0].body.is_empty());
+    assert_eq!(requests[1].method, Method::GET);
+    assert!(requests[1].body.is_empty());
+}
+
+#[test]
+fn test_temporary_redirect_after_upload_is_not_followed() {
+    let server = MockServer::start();
+
+    server.push(
+        "add",
+        Fixture::bytes("")
+            .with_status(StatusCode::TEMPORARY_REDIRECT)
+            .with_header("Location", "/api/v0/add"),
+    );
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .max_redirects(1)
+        .build()
+        .unwrap();
+
+    match Runtime::new()
+        .unwrap()
+        .block_on(client.add(Cursor::new("hello\n")))
+    {
+        Err(Error::UnexpectedStatus { status, .. }) => {
+            assert_eq!(status, StatusCode::TEMPORARY_REDIRECT)
+        }
+        res => panic!(
+            "expected the redirect to not be followed, got {:?}",
+            res.map(|_| ())
+        ),
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn test_keep_alive() {
+    let version =
+        r#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11.2"}"#;
+    let connections = |client: IpfsClient, server: MockServer, pause: Duration| {
+        let mut runtime = Runtime::new().unwrap();
+
+        server.push("version", Fixture::json(version));
+        server.push("version", Fixture::json(version));
+
+        runtime.block_on(client.version()).unwrap();
+        thread::sleep(pause);
+        runtime.block_on(client.version()).unwrap();
+
+        server.connections()
+    };
+
+    let server = MockServer::start();
+    assert_eq!(
+        connections(server.client(), server, Duration::from_millis(0)),
+        2
+    );
+
+    let server = MockServer::start();
+    let client = IpfsClientBuilder::from(server.addr())
+        .keep_alive(true)
+        .build()
+        .unwrap();
+    assert_eq!(connections(client, server, Duration::from_millis(0)), 1);
+
+    let server = MockServer::start();
+    let client = IpfsClientBuilder::from(server.addr())
+        .keep_alive(true)
+        .idle_timeout(Duration::from_millis(50))
+        .build()
+        .unwrap();
+    assert_eq!(connections(client, server, Duration::from_millis(200)), 2);
+}
+
+#[test]
+fn test_default_options() {
+    let server = MockServer::start();
+    let added = r#"{"Name":"data","Hash":"bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e","Size":"12"}"#;
+
+    server.push("add", Fixture::json(added));
+    server.push("add", Fixture::json(added));
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .default_options(&request::Add {
+            cid_version: Some(1),
+            raw_leaves: Some(true),
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    runtime
+        .block_on(client.add(Cursor::new("Hello World!")))
+        .unwrap();
+    runtime
+        .block_on(client.add_with_options(
+            Cursor::new("Hello World!"),
+            &request::Add {
+                cid_version: Some(0),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+
+    let requests = server.requests();
+
+    assert_eq!(
+        requests[0].query.as_ref().unwrap(),
+        "raw-leaves=true&cid-version=1"
+    );
+    assert_eq!(
+        requests[1].query.as_ref().unwrap(),
+        "cid-version=0&raw-leaves=true"
+    );
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_error_context() {
+    let server = MockServer::start();
+
+    server.push(
+        "files/stat",
+        Fixture::api_error(StatusCode::INTERNAL_SERVER_ERROR, "file does not exist"),
+    );
+    server.push(
+        "refs/local",
+        Fixture::chunks(vec!["{\"Ref\":\"QmA\",\"Err\":\"\"}\n"])
+            .with_stream_error("context canceled"),
+    );
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .error_context(true)
+        .build()
+        .unwrap();
+    let mut runtime = Runtime::new().unwrap();
+    let err = runtime.block_on(client.files_stat("/foo")).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "/files/stat arg=/foo: api returned error 'file does not exist'"
+    );
+    assert!(err.api_kind().is_some());
+
+    let res = runtime
+        .block_on(client.refs_local().then(Ok::<_, ()>).collect())
+        .unwrap();
+
+    match res[1] {
+        Err(Error::Request {
+            ref request,
+            ref source,
+        }) => {
+            assert_eq!(request, "/refs/local");
+            match **source {
+                Error::StreamError(_) => (),
+                ref e => panic!("expected a stream error, got {:?}", e),
+            }
+        }
+        ref e => panic!("expected a request error, got {:?}", e.as_ref().map(|_| ())),
+    }
+}
+
+#[test]
+fn test_resolve() {
+    let version =
+        r#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11.2"}"#;
+    let server = MockServer::start();
+    let mut runtime = Runtime::new().unwrap();
+
+    server.push("version", Fixture::json(version));
+    server.push("version", Fixture::json(version));
+
+    let client = IpfsClientBuilder::new("ipfs.test", server.addr().port())
+        .resolve("ipfs.test", server.addr().ip())
+        .dns_resolver(|_: &str| Ok(vec![]))
+        .build()
+        .unwrap();
+
+    runtime.block_on(client.version()).unwrap();
+
+    let ip = server.addr().ip();
+    let client = IpfsClientBuilder::new("ipfs.test", server.addr().port())
+        .dns_resolver(move |host: &str| {
+            assert_eq!(host, "ipfs.test");
+            Ok(vec![ip])
+        })
+        .build()
+        .unwrap();
+
+    runtime.block_on(client.version()).unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn test_resolver_error_is_dns_error() {
+    let client = IpfsClientBuilder::new("ipfs.test", 5001)
+        .dns_resolver(|_: &str| Err(io::Error::new(io::ErrorKind::NotFound, "no such host")))
+        .build()
+        .unwrap();
+
+    match Runtime::new().unwrap().block_on(client.version()) {
+        Err(Error::Dns { uri, .. }) => assert_eq!(uri, "http://ipfs.test:5001/api/v0"),
+        res => panic!("expected a dns error, got {:?}", res.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_dns_stream() {
+    let server = MockServer::start();
+
+    server.push(
+        "dns",
+        Fixture::chunks(vec![
+            "{\"Path\":\"/ipns/docs.ipfs.io\"}\n",
+            "{\"Path\":\"/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA\"}\n",
+        ]),
+    );
+
+    let res = Runtime::new()
+        .unwrap()
+        .block_on(server.client().dns_stream("ipfs.io", true).collect())
+        .unwrap();
+    let requests = server.requests();
+
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].path, "/ipns/docs.ipfs.io");
+    assert_eq!(
+        requests[0].query.as_ref().unwrap(),
+        "arg=ipfs.io&recursive=true&stream=true"
+    );
+}
+
+#[test]
+fn test_unknown_trailers() {
+    let server = MockServer::start();
+
+    server.push(
+        "refs/local",
+        Fixture::chunks(vec!["{\"Ref\":\"QmA\",\"Err\":\"\"}\n"])
+            .with_header("Trailer", "X-Stream-Error, X-Future-Trailer"),
+    );
+    server.push(
+        "cat",
+        Fixture::bytes("abc").with_header("Trailer", "X-Future-Trailer"),
+    );
+
+    let mut runtime = Runtime::new().unwrap();
+    let refs = runtime
+        .block_on(server.client().refs_local().collect())
+        .unwrap();
+
+    assert_eq!(refs.len(), 1);
+
+    let (metadata, body) = runtime
+        .block_on(
+            server
+                .client()
+                .cat_with_metadata("/ipfs/QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"),
+        )
+        .unwrap();
+
+    assert_eq!(metadata.trailer_names(), vec!["X-Future-Trailer"]);
+    assert_eq!(runtime.block_on(body.concat2()).unwrap(), "abc");
+    assert!(metadata.trailers().is_none());
+}
+
+#[test]
+fn test_request_with_metadata() {
+    let server = MockServer::start();
+
+    server.push(
+        "version",
+        Fixture::json(r#"{"Version":"0.4.18","Commit":"","Repo":"7","System":"amd64/linux","Golang":"go1.11"}"#)
+            .with_header("Server", "go-ipfs/0.4.18"),
+    );
+    server.push(
+        "version",
+        Fixture::api_error(StatusCode::INTERNAL_SERVER_ERROR, "repo locked"),
+    );
+
+    let mut runtime = Runtime::new().unwrap();
+    let (metadata, version) = runtime
+        .block_on(
+            server
+                .client()
+                .request_with_metadata::<_, VersionResponse>(&request::Version),
+        )
+        .unwrap();
+
+    assert_eq!(metadata.status, StatusCode::OK);
+    assert_eq!(metadata.headers["Server"], "go-ipfs/0.4.18");
+    assert_eq!(version.version, "0.4.18");
+
+    let res = runtime.block_on(
+        server
+            .client()
+            .request_with_metadata::<_, VersionResponse>(&request::Version),
+    );
+
+    match res {
+        Err(Error::Api(ref e)) => assert_eq!(e.message, "repo locked"),
+        res => panic!("expected an api error, got {:?}", res.map(|_| ())),
+    }
+}
+
+#[cfg(feature = "files")]
+#[test]
+fn test_invalid_path() {
+    let server = MockServer::start();
+    let res = Runtime::new().unwrap().block_on(
+        server
+            .client()
+            .files_stat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA"),
+    );
+
+    match res {
+        Err(Error::InvalidPath(..)) => (),
+        res => panic!("expected an invalid path, got {:?}", res.map(|_| ())),
+    }
+
+    assert!(server.requests().is_empty());
+}
+
+#[test]
+fn test_metrics_keep_stream_errors() {
+    let server = MockServer::start();
+    let recorded = Arc::new(Mutex::new(Vec::<RequestMetrics>::new()));
+    let sink = recorded.clone();
+
+    server.push(
+        "add",
+        Fixture::json(
+            r#"{"Name":"","Hash":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":"20"}"#,
+        ),
+    );
+    server.push(
+        "refs/local",
+        Fixture::chunks(vec!["{\"Ref\":\"QmA\",\"Err\":\"\"}\n"])
+            .with_stream_error("context canceled"),
+    );
+
+    let client = IpfsClientBuilder::from(server.addr())
+        .metrics_sink(move |metrics: &RequestMetrics| sink.lock().unwrap().push(metrics.clone()))
+        .build()
+        .unwrap();
+    let mut runtime = Runtime::new().unwrap();
+
+    runtime
+        .block_on(client.add(Cursor::new("Hello World!")))
+        .unwrap();
+
+    let res = runtime
+        .block_on(client.refs_local().then(Ok::<_, ()>).collect())
+        .unwrap();
+
+    match res[1] {
+        Err(Error::StreamError(ref e)) => assert_eq!(e, "context canceled"),
+        ref e => panic!("expected a stream error, got {:?}", e.as_ref().map(|_| ())),
+    }
+
+    let recorded = recorded.lock().unwrap();
+    let add = recorded.iter().find(|m| m.path == "/add").unwrap();
+    let refs = recorded.iter().find(|m| m.path == "/refs/local").unwrap();
+
+    assert!(add.bytes_sent >= "Hello World!".len() as u64);
+    assert!(add.bytes_received > 0);
+    assert!(!add.error);
+    assert!(refs.bytes_received > 0);
+}
+
+#[test]
+fn test_queued_upload_is_not_read() {
+    struct Tracked(Cursor<&'static str>, Arc<AtomicBool>);
+
+    impl Read for Tracked {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.1.store(true, Ordering::SeqCst);
+            self.0.read(buf)
+        }
+    }
+
+    let server = MockServer::start();
+    let client = IpfsClientBuilder::from(server.addr())
+        .max_in_flight(1)
+        .build()
+        .unwrap();
+    let mut runtime = Runtime::new().unwrap();
+    let read = Arc::new(AtomicBool::new(false));
+
+    server.push("cat", Fixture::bytes("Hello"));
+    server.push(
+        "add",
+        Fixture::json(
+            r#"{"Name":"","Hash":"QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA","Size":"20"}"#,
+        ),
+    );
+
+    // The unfinished `cat` holds the only permit.
+    //
+    let (_, cat) = runtime
+        .block_on(
+            client
+                .cat("QmXdNSQx7nbdRvkjGCEQgVjVtVwsHvV8NmV2a8xzQVwuFA")
+                .into_future(),
+        )
+        .map_err(|(e, _)| e)
+        .unwrap();
+    let add = client.add(Tracked(Cursor::new("Hello World!"), read.clone()));
+    let wait = Delay::new(Instant::now() + Duration::from_millis(200));
+    let add = match runtime.block_on(add.select2(wait)) {
+        Ok(Either::B((_, add))) => add,
+        _ => panic!("expected the add to wait for the cat"),
+    };
+
+    assert!(!read.load(Ordering::SeqCst));
+
+    drop(cat);
+
+    runtime.block_on(add).unwrap();
+
+    assert!(read.load(Ordering::SeqCst));
+}
+
+#[cfg(feature = "dag")]
+#[test]
+fn test_dag_export_and_import() {
+    let server = MockServer::start();
+    let root = "bafyreidykglsfhoixmivffc5uwhcgshx4j465xwqntbmu43nb2dzqwfvae";
+    let mut runtime = Runtime::new().unwrap();
+
+    server.push("dag/export", Fixture::chunks(vec!["car ", "bytes"]));
+    server.push(
+        "dag/import",
+        Fixture::chunks(vec![
+            format!(
+                "{{\"Root\":{{\"Cid\":{{\"/\":\"{}\"}},\"PinErrorMsg\":\"\"}}}}\n",
+                root
+            ),
+            "{\"Stats\":{\"BlockCount\":1,\"BlockBytesCount\":9}}\n".into(),
+        ]),
+    );
+
+    let car = runtime
+        .block_on(server.client().dag_export(root).concat2())
+        .unwrap();
+    let import = ipfs_api::request::DagImport {
+        stats: Some(true),
+        ..Default::default()
+    };
+    let res = runtime
+        .block_on(
+            server
+                .client()
+                .dag_import_with_options(Cursor::new(car.to_vec()), &import)
+                .collect(),
+        )
+        .unwrap();
+    let requests = server.requests();
+
+    assert_eq!(car, "car bytes");
+    assert_eq!(res[0].root.as_ref().unwrap().cid, root);
+    assert_eq!(res[1].stats.as_ref().unwrap().block_count, 1);
+    assert_eq!(
+        requests[0].query.as_ref().unwrap(),
+        &format!("arg={}", root)
+    );
+    assert_eq!(requests[1].query.as_ref().unwrap(), "stats=true");
+}
@@ -0,0 +1,236 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+// Calls every endpoint that can be called without side effects (or whose
+// side effects are undone) against a running daemon, with a strict client,
+// and reports which responses deserialize cleanly.
+//
+//     cargo test -p ipfs-api --features conformance --test conformance
+//
+// The daemon is found the same way `IpfsClient::default` finds it. Objects
+// created along the way are removed again, and MFS changes are confined to
+// `/ipfs-api-conformance`.
+//
+
+extern crate futures;
+extern crate ipfs_api;
+extern crate tokio;
+
+use futures::Stream;
+use ipfs_api::response::Error;
+use ipfs_api::{AsyncResponse, AsyncStreamResponse, IpfsClient, IpfsClientBuilder, KeyType};
+use std::{io::Cursor, process};
+use tokio::runtime::current_thread::Runtime;
+
+/// The number of items read from streamed responses, some of which (e.g.
+/// `refs_local`) can be very long.
+///
+const STREAM_ITEMS: u64 = 16;
+
+/// The MFS directory files are written to.
+///
+const MFS_DIR: &str = "/ipfs-api-conformance";
+
+/// The name of the key generated and removed again.
+///
+const KEY_NAME: &str = "ipfs-api-conformance";
+
+enum Outcome {
+    /// The response deserialized without any unknown fields.
+    ///
+    Ok,
+
+    /// The response could not be deserialized, or had fields the client
+    /// doesn't know about.
+    ///
+    Mismatch(String),
+
+    /// The daemon doesn't have the endpoint.
+    ///
+    Unsupported,
+
+    /// The daemon returned an error, so the response couldn't be checked.
+    ///
+    Skipped(String),
+}
+
+struct Report {
+    runtime: Runtime,
+    results: Vec<(&'static str, Outcome)>,
+}
+
+impl Report {
+    fn new() -> Report {
+        Report {
+            runtime: Runtime::new().expect("failed to start a runtime"),
+            results: Vec::new(),
+        }
+    }
+
+    /// Waits for a response, records how it deserialized, and returns it.
+    ///
+    fn check<T>(&mut self, endpoint: &'static str, req: AsyncResponse<T>) -> Option<T> {
+        let res = self.runtime.block_on(req);
+        let outcome = match res {
+            Ok(_) => Outcome::Ok,
+            Err(Error::UnknownFields(ref fields)) => {
+                Outcome::Mismatch(format!("unknown fields: {}", fields))
+            }
+            Err(Error::Parse(ref e)) => Outcome::Mismatch(e.to_string()),
+            Err(Error::UnsupportedEndpoint { .. }) => Outcome::Unsupported,
+            Err(ref e) => Outcome::Skipped(e.to_string()),
+        };
+
+        self.results.push((endpoint, outcome));
+
+        res.ok()
+    }
+
+    fn check_stream<T>(
+        &mut self,
+        endpoint: &'static str,
+        req: AsyncStreamResponse<T>,
+    ) -> Option<Vec<T>>
+    where
+        T: 'static + Send,
+    {
+        self.check(endpoint, Box::new(req.take(STREAM_ITEMS).collect()))
+    }
+
+    /// Prints the results, and returns true if every checked response
+    /// deserialized cleanly.
+    ///
+    fn print(&self) -> bool {
+        let mut passed = true;
+
+        for (endpoint, outcome) in &self.results {
+            match outcome {
+                Outcome::Ok => println!("ok           {}", endpoint),
+                Outcome::Mismatch(e) => {
+                    passed = false;
+                    println!("MISMATCH     {}: {}", endpoint, e);
+                }
+                Outcome::Unsupported => println!("unsupported  {}", endpoint),
+                Outcome::Skipped(e) => println!("skipped      {}: {}", endpoint, e),
+            }
+        }
+
+        let count = |f: fn(&Outcome) -> bool| self.results.iter().filter(|(_, o)| f(o)).count();
+
+        println!();
+        println!(
+            "{} ok, {} mismatched, {} unsupported, {} skipped",
+            count(|o| matches!(o, Outcome::Ok)),
+            count(|o| matches!(o, Outcome::Mismatch(_))),
+            count(|o| matches!(o, Outcome::Unsupported)),
+            count(|o| matches!(o, Outcome::Skipped(_))),
+        );
+
+        passed
+    }
+}
+
+fn main() {
+    let client = IpfsClientBuilder::default()
+        .strict(true)
+        .build()
+        .expect("failed to build a client");
+    let mut report = Report::new();
+
+    match report.check("version", client.version()) {
+        Some(version) => println!("daemon version {}\n", version.version),
+        None => {
+            eprintln!("could not reach the daemon");
+            process::exit(2);
+        }
+    }
+
+    check_node(&mut report, &client);
+    check_content(&mut report, &client);
+    check_files(&mut report, &client);
+    check_keys(&mut report, &client);
+
+    println!();
+
+    if !report.print() {
+        process::exit(1);
+    }
+}
+
+fn check_node(report: &mut Report, client: &IpfsClient) {
+    report.check("id", client.id(None));
+    report.check("commands", client.commands());
+    report.check("config/show", client.config_show());
+    report.check("diag/sys", client.diag_sys());
+    report.check("bootstrap/list", client.bootstrap_list());
+    report.check("bitswap/stat", client.bitswap_stat());
+    report.check("bitswap/wantlist", client.bitswap_wantlist(None));
+    report.check("stats/bitswap", client.stats_bitswap());
+    report.check("stats/bw", client.stats_bw());
+    report.check("stats/repo", client.stats_repo());
+    report.check("repo/stat", client.repo_stat());
+    report.check("swarm/peers", client.swarm_peers());
+    report.check("swarm/addrs/local", client.swarm_addrs_local());
+    report.check("log/ls", client.log_ls());
+    report.check("pubsub/ls", client.pubsub_ls());
+    report.check("pubsub/peers", client.pubsub_peers(None));
+    report.check_stream("refs/local", client.refs_local());
+    report.check_stream("filestore/ls", client.filestore_ls(None));
+}
+
+fn check_content(report: &mut Report, client: &IpfsClient) {
+    let added = report.check("add", client.add(Cursor::new("ipfs-api conformance")));
+    let hash = match added {
+        Some(added) => added.hash.to_string(),
+        None => return,
+    };
+    let path = format!("/ipfs/{}", hash);
+
+    report.check("cat", Box::new(client.cat(&path).concat2()));
+    report.check("block/stat", client.block_stat(hash.as_str()));
+    report.check(
+        "block/get",
+        Box::new(client.block_get(hash.as_str()).concat2()),
+    );
+    report.check("object/stat", client.object_stat(&hash));
+    report.check("object/get", client.object_get(&hash));
+    report.check(
+        "object/get?encoding=protobuf",
+        client.object_get_node(&hash),
+    );
+    report.check("object/links", client.object_links(&hash));
+    report.check("object/new", client.object_new(None));
+    report.check("dag/get", client.dag_get(&path));
+    report.check("ls", client.ls(Some(&path)));
+    report.check("file/ls", client.file_ls(&path));
+    report.check("pin/ls", client.pin_ls(Some(&hash), None));
+    report.check("pin/rm", client.pin_rm(&hash, true));
+    report.check("pin/add", client.pin_add(&hash, true));
+    report.check("pin/rm", client.pin_rm(&hash, true));
+}
+
+fn check_files(report: &mut Report, client: &IpfsClient) {
+    let file = format!("{}/file", MFS_DIR);
+
+    report.check("files/mkdir", client.files_mkdir(MFS_DIR, true));
+    report.check(
+        "files/write",
+        client.files_write(&file, true, true, Cursor::new("ipfs-api conformance")),
+    );
+    report.check("files/ls", client.files_ls(Some(MFS_DIR)));
+    report.check("files/stat", client.files_stat(&file));
+    report.check("files/read", Box::new(client.files_read(&file).concat2()));
+    report.check("files/flush", client.files_flush(Some(MFS_DIR)));
+    report.check("files/rm", client.files_rm(MFS_DIR, true));
+}
+
+fn check_keys(report: &mut Report, client: &IpfsClient) {
+    report.check("key/gen", client.key_gen(KEY_NAME, KeyType::Ed25519, 0));
+    report.check("key/list", client.key_list());
+    report.check("key/rm", client.key_rm(KEY_NAME));
+}
@@ -29,7 +29,7 @@ impl CliCommand for Command {
         (args, client) => {
             let path = args.value_of("INPUT").unwrap();
             if !args.is_present("recursive") && Path::new(path).is_dir() {
-                panic!(EXPECTED_FILE);
+                panic!("{}", EXPECTED_FILE);
             }
             client
                 .add_path(path)
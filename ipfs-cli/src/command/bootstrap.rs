@@ -9,8 +9,9 @@
 use clap::App;
 use command::CliCommand;
 use futures::Future;
+use ipfs_api::Multiaddr;
 
-fn print_peers(peers: &[String]) {
+fn print_peers(peers: &[Multiaddr]) {
     println!();
     println!("  peers                  :");
     for peer in peers {
@@ -29,7 +29,7 @@ impl CliCommand for Command {
             let path = args.value_of("PATH").unwrap();
 
             client
-                .cat(&path)
+                .cat(path)
                 .for_each(|chunk| io::stdout().write_all(&chunk).map_err(From::from))
         }
     );
@@ -45,7 +45,7 @@ impl CliCommand for Command {
             client.config_replace(config).map(|_| ())
         },
         ("show", _args) => {
-            client.config_show().map(|config| println!("{}", config))
+            client.config_show().map(|config| println!("{:#?}", config))
         }
     );
 }
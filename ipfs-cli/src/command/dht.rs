@@ -87,7 +87,7 @@ impl CliCommand for Command {
         ("provide", args) => {
             let key = args.value_of("KEY").unwrap();
 
-            client.dht_provide(&key).for_each(print_dht_response)
+            client.dht_provide(key).for_each(print_dht_response)
         },
         ("put", args) => {
             let key = args.value_of("KEY").unwrap();
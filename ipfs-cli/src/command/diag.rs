@@ -51,20 +51,20 @@ impl CliCommand for Command {
                 client
                     .diag_cmds_clear()
                     .map(|_| {
-                        println!("");
+                        println!();
                         println!("  OK");
-                        println!("");
+                        println!();
                     })
             },
             ("set-time", args) => {
                 let time = args.value_of("TIME").unwrap();
 
                 client
-                    .diag_cmds_set_time(&time)
+                    .diag_cmds_set_time(time)
                     .map(|_| {
-                        println!("");
+                        println!();
                         println!("  OK");
-                        println!("");
+                        println!();
                     })
             }
         },
@@ -8,7 +8,7 @@
 
 use clap::App;
 use command::CliCommand;
-use futures::Future;
+use futures::{future::Either, Future, Stream};
 
 pub struct Command;
 
@@ -21,20 +21,28 @@ impl CliCommand for Command {
                 (about: "Resolve a DNS link")
                 (@arg PATH: +required "The domain name to resolve")
                 (@arg recursive: -r --recursive "Resolve until the result is not a DNS link")
+                (@arg stream: -s --stream "Print each intermediate answer")
         )
     }
 
     handle!(
         (args, client) => {
             let path = args.value_of("PATH").unwrap();
+            let recursive = args.is_present("recursive");
 
-            client
-                .dns(path, args.is_present("recursive"))
-                .map(|res| {
+            if args.is_present("stream") {
+                Either::A(client.dns_stream(path, recursive).for_each(|res| {
+                    println!("{}", res.path);
+
+                    Ok(())
+                }))
+            } else {
+                Either::B(client.dns(path, recursive).map(|res| {
                     println!();
                     println!("  path    : {}", res.path);
                     println!();
-                })
+                }))
+            }
         }
     );
 }
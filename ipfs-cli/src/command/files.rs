@@ -87,9 +87,12 @@ impl CliCommand for Command {
 
             client
                 .files_flush(path)
-                .map(|_| {
+                .map(|flush| {
                     println!();
-                    println!("  OK");
+                    match flush.cid {
+                        Some(cid) => println!("  {}", cid),
+                        None => println!("  OK"),
+                    }
                     println!();
                 })
         },
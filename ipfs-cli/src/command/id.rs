@@ -0,0 +1,44 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "id";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand id =>
+                (about: "Show ipfs node id info")
+                (@arg PEER: "Peer to show info for (defaults to this node)")
+        )
+    }
+
+    handle!(
+        (args, client) => {
+            let peer = args.value_of("PEER");
+
+            client.id(peer).map(|id| {
+                println!();
+                println!("  id               : {}", id.id);
+                println!("  public_key       : {}", id.public_key);
+                println!("  addresses        :");
+                for address in id.addresses {
+                    println!("    {}", address);
+                }
+                println!("  agent_version    : {}", id.agent_version);
+                println!("  protocol_version : {}", id.protocol_version);
+                println!();
+            })
+        }
+    );
+}
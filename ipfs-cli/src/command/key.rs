@@ -0,0 +1,88 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+use ipfs_api::KeyType;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "key";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand key =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand gen =>
+                    (about: "Create a new keypair")
+                    (@arg NAME: +required "The name of the key")
+                    (@arg type: -t --type +takes_value possible_value[rsa ed25519]
+                        default_value[ed25519] "The type of key to create")
+                    (@arg size: -s --size +takes_value
+                        "The size of the key to generate (rsa only, defaults to 2048)")
+                )
+                (@subcommand list =>
+                    (about: "List all local keypairs")
+                )
+                (@subcommand rm =>
+                    (about: "Remove a keypair")
+                    (@arg NAME: +required "The name of the key to remove")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("gen", args) => {
+            let name = args.value_of("NAME").unwrap();
+            let kind = match args.value_of("type") {
+                Some("rsa") => KeyType::Rsa,
+                _ => KeyType::Ed25519,
+            };
+            let size = if args.is_present("size") {
+                value_t!(args, "size", i32).unwrap_or_else(|e| e.exit())
+            } else {
+                2048
+            };
+
+            client
+                .key_gen(name, kind, size)
+                .map(|key| {
+                    println!();
+                    println!("  {} {}", key.id, key.name);
+                    println!();
+                })
+        },
+        ("list", _args) => {
+            client
+                .key_list()
+                .map(|keys| {
+                    println!();
+                    for key in keys.keys {
+                        println!("  {} {}", key.id, key.name);
+                    }
+                    println!();
+                })
+        },
+        ("rm", args) => {
+            let name = args.value_of("NAME").unwrap();
+
+            client
+                .key_rm(name)
+                .map(|keys| {
+                    println!();
+                    for key in keys.keys {
+                        println!("  removed {} {}", key.id, key.name);
+                    }
+                    println!();
+                })
+        }
+    );
+}
@@ -0,0 +1,42 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "ls";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand ls =>
+                (about: "List directory contents for Unix filesystem objects")
+                (@arg PATH: +required "The path of the IPFS object to list")
+        )
+    }
+
+    handle!(
+        (args, client) => {
+            let path = args.value_of("PATH").unwrap();
+
+            client.ls(Some(path)).map(|ls| {
+                println!();
+                for object in ls.objects {
+                    println!("  {}", object.hash);
+                    for link in object.links {
+                        println!("    {} {:>10} {}", link.hash, link.size, link.name);
+                    }
+                }
+                println!();
+            })
+        }
+    );
+}
@@ -9,11 +9,10 @@
 use clap::{App, ArgMatches};
 use futures::Future;
 use ipfs_api::IpfsClient;
-use std::error::Error;
 use std::fs;
 use std::path::Path;
 
-pub type CommandExecutable = Box<Future<Item = (), Error = ()> + 'static + Send>;
+pub type CommandExecutable = Box<dyn Future<Item = (), Error = ()> + 'static + Send>;
 
 pub const EXPECTED_FILE: &str = "expected to read input file";
 
@@ -26,7 +25,7 @@ where
     match fs::metadata(path) {
         Ok(ref metadata) if metadata.is_file() => Ok(()),
         Ok(_) => Err("file must not be a directory".into()),
-        Err(e) => Err(e.description().into()),
+        Err(e) => Err(e.to_string()),
     }
 }
 
@@ -156,5 +155,14 @@ pub mod dns;
 pub mod file;
 pub mod files;
 pub mod filestore;
+pub mod get;
+pub mod id;
+pub mod key;
+pub mod ls;
+pub mod object;
+pub mod pin;
+pub mod refs;
 pub mod shutdown;
+pub mod stats;
+pub mod swarm;
 pub mod version;
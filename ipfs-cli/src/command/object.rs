@@ -0,0 +1,77 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::{Future, Stream};
+use std::io::{self, Write};
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "object";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand object =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand data =>
+                    (about: "Output the raw bytes of an IPFS object")
+                    (@arg KEY: +required "Key of the object to retrieve")
+                )
+                (@subcommand links =>
+                    (about: "Output the links pointed to by an IPFS object")
+                    (@arg KEY: +required "Key of the object to retrieve")
+                )
+                (@subcommand stat =>
+                    (about: "Get stats for a DAG node")
+                    (@arg KEY: +required "Key of the object to retrieve")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("data", args) => {
+            let key = args.value_of("KEY").unwrap();
+
+            client
+                .object_data(key)
+                .for_each(|chunk| io::stdout().write_all(&chunk).map_err(From::from))
+        },
+        ("links", args) => {
+            let key = args.value_of("KEY").unwrap();
+
+            client
+                .object_links(key)
+                .map(|object| {
+                    println!();
+                    for link in object.links {
+                        println!("  {} {:>10} {}", link.hash, link.size, link.name);
+                    }
+                    println!();
+                })
+        },
+        ("stat", args) => {
+            let key = args.value_of("KEY").unwrap();
+
+            client
+                .object_stat(key)
+                .map(|stat| {
+                    println!();
+                    println!("  hash        : {}", stat.hash);
+                    println!("  num_links   : {}", stat.num_links);
+                    println!("  block_size  : {}", stat.block_size);
+                    println!("  links_size  : {}", stat.links_size);
+                    println!("  data_size   : {}", stat.data_size);
+                    println!("  cumulative  : {}", stat.cumulative_size);
+                    println!();
+                })
+        }
+    );
+}
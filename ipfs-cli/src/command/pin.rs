@@ -0,0 +1,84 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "pin";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand pin =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand add =>
+                    (about: "Pin an object to local storage")
+                    (@arg PATH: +required "The path of the object to pin")
+                    (@arg direct: -d --direct "Pin only the object itself, not its children")
+                )
+                (@subcommand ls =>
+                    (about: "List objects pinned to local storage")
+                    (@arg PATH: "The path of the object to list pins for")
+                    (@arg type: -t --type +takes_value "The type of pin to list \
+                        (direct, indirect, recursive or all)")
+                )
+                (@subcommand rm =>
+                    (about: "Remove a pinned object from local storage")
+                    (@arg PATH: +required "The path of the object to unpin")
+                    (@arg direct: -d --direct "Unpin only the object itself, not its children")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("add", args) => {
+            let path = args.value_of("PATH").unwrap();
+
+            client
+                .pin_add(path, !args.is_present("direct"))
+                .map(|res| {
+                    println!();
+                    for pin in res.pins {
+                        println!("  pinned {}", pin);
+                    }
+                    println!();
+                })
+        },
+        ("ls", args) => {
+            let path = args.value_of("PATH");
+            let typ = args.value_of("type");
+
+            client
+                .pin_ls(path, typ)
+                .map(|res| {
+                    println!();
+                    for (key, pin) in res.keys {
+                        println!("  {} {}", key, pin.typ);
+                    }
+                    println!();
+                })
+        },
+        ("rm", args) => {
+            let path = args.value_of("PATH").unwrap();
+
+            client
+                .pin_rm(path, !args.is_present("direct"))
+                .map(|res| {
+                    println!();
+                    for pin in res.pins {
+                        println!("  unpinned {}", pin);
+                    }
+                    println!();
+                })
+        }
+    );
+}
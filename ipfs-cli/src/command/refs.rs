@@ -0,0 +1,42 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::{Future, Stream};
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "refs";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand refs =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand local =>
+                    (about: "List all local references")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("local", _args) => {
+            client.refs_local().for_each(|local| {
+                if local.err.is_empty() {
+                    println!("{}", local.reference);
+                } else {
+                    eprintln!("{}: {}", local.reference, local.err);
+                }
+
+                Ok(())
+            })
+        }
+    );
+}
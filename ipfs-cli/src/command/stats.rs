@@ -0,0 +1,54 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "stats";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand stats =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand bw =>
+                    (about: "Print ipfs bandwidth information")
+                )
+                (@subcommand repo =>
+                    (about: "Get stats for the currently used repo")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("bw", _args) => {
+            client.stats_bw().map(|bw| {
+                println!();
+                println!("  total_in  : {}", bw.total_in);
+                println!("  total_out : {}", bw.total_out);
+                println!("  rate_in   : {}", bw.rate_in);
+                println!("  rate_out  : {}", bw.rate_out);
+                println!();
+            })
+        },
+        ("repo", _args) => {
+            client.stats_repo().map(|repo| {
+                println!();
+                println!("  num_objects : {}", repo.num_objects);
+                println!("  repo_size   : {}", repo.repo_size);
+                println!("  repo_path   : {}", repo.repo_path);
+                println!("  version     : {}", repo.version);
+                println!();
+            })
+        }
+    );
+}
@@ -0,0 +1,67 @@
+// Copyright 2017 rust-ipfs-api Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use clap::App;
+use command::CliCommand;
+use futures::Future;
+
+pub struct Command;
+
+impl CliCommand for Command {
+    const NAME: &'static str = "swarm";
+
+    fn signature<'a, 'b>() -> App<'a, 'b> {
+        clap_app!(
+            @subcommand swarm =>
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand addrs =>
+                    (about: "List local addresses")
+                )
+                (@subcommand connect =>
+                    (about: "Open a connection to a given address")
+                    (@arg ADDR: +required "Multiaddr of the peer to connect to")
+                )
+                (@subcommand peers =>
+                    (about: "List peers with open connections")
+                )
+        )
+    }
+
+    handle!(
+        client;
+        ("addrs", _args) => {
+            client.swarm_addrs_local().map(|addrs| {
+                println!();
+                for addr in addrs.strings {
+                    println!("  {}", addr);
+                }
+                println!();
+            })
+        },
+        ("connect", args) => {
+            let addr = args.value_of("ADDR").unwrap();
+
+            client.swarm_connect(addr).map(|res| {
+                println!();
+                for line in res.strings {
+                    println!("  {}", line);
+                }
+                println!();
+            })
+        },
+        ("peers", _args) => {
+            client.swarm_peers().map(|peers| {
+                println!();
+                for peer in peers.peers {
+                    println!("  {}/p2p/{} {}", peer.addr, peer.peer, peer.latency);
+                }
+                println!();
+            })
+        }
+    );
+}
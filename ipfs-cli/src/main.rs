@@ -12,11 +12,39 @@ extern crate futures;
 extern crate hyper;
 extern crate ipfs_api;
 
+use clap::ArgMatches;
 use command::CliCommand;
-use ipfs_api::IpfsClient;
+use ipfs_api::{IpfsClient, IpfsClientBuilder};
+use std::process;
 
 mod command;
 
+/// Creates a client for the api given with `--api`, so the CLI can be used
+/// with daemons on other hosts.
+///
+fn build_client(matches: &ArgMatches) -> IpfsClient {
+    let builder = match matches.value_of("api") {
+        Some(api) => {
+            let mut parts = api.rsplitn(2, ':');
+            let port = parts.next().and_then(|port| port.parse().ok());
+
+            match (parts.next(), port) {
+                (Some(host), Some(port)) => IpfsClientBuilder::new(host, port),
+                _ => {
+                    eprintln!("invalid api address '{}', expected host:port", api);
+                    process::exit(1);
+                }
+            }
+        }
+        None => IpfsClientBuilder::default(),
+    };
+
+    builder.build().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    })
+}
+
 macro_rules! main {
     ($($cmd:ident);*) => {
         fn main() {
@@ -27,10 +55,12 @@ macro_rules! main {
                     (version: crate_version!())
                     (author: "Ferris T. <ferristseng@fastmail.fm>")
                     (@setting SubcommandRequiredElseHelp)
+                    (@arg api: --api +takes_value "Address of the api, as host:port \
+                        (defaults to the address in ~/.ipfs/api, or localhost:5001)")
                     $((subcommand: <command::$cmd::Command>::signature()))*
             ).get_matches();
 
-            let client = IpfsClient::default();
+            let client = build_client(&matches);
             let command = match matches.subcommand() {
                 $(
                 (<command::$cmd::Command>::NAME, Some(args)) => {
@@ -51,6 +81,13 @@ main!(
     cat; commands; config;
     dag; dht; diag; dns;
     file; files; filestore;
-    shutdown;
+    get;
+    id;
+    key;
+    ls;
+    object;
+    pin;
+    refs;
+    shutdown; stats; swarm;
     version
 );